@@ -10,30 +10,38 @@
 //!   documentation for usage details. This is **not recommended on Windows**. See [here](https://rust-lang.github.io/rfcs/1974-global-allocators.html#jemalloc)
 //!   for more info.
 
-use std::net::SocketAddr;
+use std::net::{IpAddr, SocketAddr};
 use std::path::PathBuf;
 
 use clap::{Args, Parser, Subcommand};
 use clap_complete::Shell;
 use common::parse::parse_socket_address;
-use katana_core::backend::config::{Environment, StarknetConfig};
+use katana_core::backend::config::{dev_genesis, Environment, StarknetConfig};
 use katana_core::constants::{
-    DEFAULT_ETH_L1_GAS_PRICE, DEFAULT_INVOKE_MAX_STEPS, DEFAULT_SEQUENCER_ADDRESS,
-    DEFAULT_STRK_L1_GAS_PRICE, DEFAULT_VALIDATE_MAX_STEPS,
+    DEFAULT_ETH_L1_GAS_PRICE, DEFAULT_INVOKE_MAX_STEPS, DEFAULT_STRK_L1_GAS_PRICE,
+    DEFAULT_VALIDATE_MAX_STEPS,
 };
 use katana_core::sequencer::SequencerConfig;
-use katana_primitives::block::GasPrices;
+use katana_primitives::block::{BlockIdOrTag, GasPrices};
 use katana_primitives::chain::ChainId;
-use katana_primitives::genesis::allocation::DevAllocationsGenerator;
-use katana_primitives::genesis::constant::DEFAULT_PREFUNDED_ACCOUNT_BALANCE;
+use katana_primitives::genesis::allocation::{DevAllocationsGenerator, DevMnemonic};
+use katana_primitives::genesis::constant::{
+    DEFAULT_DEV_ACCOUNT_DERIVATION_PATH, DEFAULT_PREFUNDED_ACCOUNT_BALANCE,
+};
 use katana_primitives::genesis::Genesis;
-use katana_rpc::config::ServerConfig;
+use katana_rpc::config::{ServerConfig, DEFAULT_MAX_BODY_SIZE};
 use katana_rpc_api::ApiKind;
 use tracing::Subscriber;
 use tracing_subscriber::{fmt, EnvFilter};
 use url::Url;
 
-use crate::utils::{parse_genesis, parse_seed};
+use crate::config::ConfigArgs;
+use crate::genesis::GenesisArgs;
+use crate::replay::ReplayArgs;
+use crate::utils::{
+    parse_account_class, parse_block_id, parse_derivation_path, parse_genesis, parse_mnemonic,
+    parse_seed, AccountClass,
+};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -79,22 +87,54 @@ pub struct KatanaArgs {
     #[arg(long, value_name = "SOCKET", value_parser = parse_socket_address, help_heading = "Metrics")]
     pub metrics: Option<SocketAddr>,
 
-    #[arg(long)]
+    #[arg(long, alias = "fork-block-number")]
     #[arg(requires = "rpc_url")]
-    #[arg(value_name = "BLOCK_NUMBER")]
-    #[arg(help = "Fork the network at a specific block.")]
-    pub fork_block_number: Option<u64>,
+    #[arg(value_name = "BLOCK")]
+    #[arg(value_parser = parse_block_id)]
+    #[arg(help = "Fork the network at a specific block, given by its number, its hash or \
+                  `latest`.")]
+    pub fork_block: Option<BlockIdOrTag>,
+
+    #[arg(long)]
+    #[arg(value_name = "URL")]
+    #[arg(conflicts_with_all = ["rpc_url", "no_mining", "block_time", "dev"])]
+    #[arg(help = "Run as a read-only replica of the Katana node at the given URL.")]
+    #[arg(long_help = "Run as a read-only replica of the Katana node at the given URL. The \
+                       blocks, state updates and classes of the followed node are synced into \
+                       the local database and served by the read API, while transactions are \
+                       rejected and no block is produced locally.")]
+    pub follow: Option<Url>,
 
     #[cfg(feature = "messaging")]
     #[arg(long)]
     #[arg(value_name = "PATH")]
     #[arg(value_parser = katana_core::service::messaging::MessagingConfig::parse)]
+    #[arg(conflicts_with = "follow")]
     #[arg(help = "Configure the messaging with an other chain.")]
     #[arg(long_help = "Configure the messaging to allow Katana listening/sending messages on a \
                        settlement chain that can be Ethereum or an other Starknet sequencer. \
                        The configuration file details and examples can be found here: https://book.dojoengine.org/toolchain/katana/reference#messaging")]
     pub messaging: Option<katana_core::service::messaging::MessagingConfig>,
 
+    #[cfg(feature = "messaging")]
+    #[arg(long)]
+    #[arg(requires = "dev")]
+    #[arg(conflicts_with = "messaging")]
+    #[arg(help = "Simulate the settlement chain in-process for messaging.")]
+    #[arg(long_help = "Simulate the settlement chain in-process for messaging. Messages to \
+                       L2 are sent, and messages to L1 listed and consumed, using the `dev` \
+                       RPC methods.")]
+    pub simulated_messaging: bool,
+
+    #[arg(long)]
+    #[arg(value_name = "PATH")]
+    #[arg(help = "Path of a TOML configuration file.")]
+    #[arg(long_help = "Path of a TOML configuration file in which all the options can be set. \
+                       The options given on the command line take precedence over the ones of \
+                       the file. The effective configuration can be printed with `katana config \
+                       dump`.")]
+    pub config: Option<PathBuf>,
+
     #[command(flatten)]
     #[command(next_help_heading = "Server options")]
     pub server: ServerOptions,
@@ -111,6 +151,20 @@ pub struct KatanaArgs {
 pub enum Commands {
     #[command(about = "Generate shell completion file for specified shell")]
     Completions { shell: Shell },
+
+    #[command(about = "Manage genesis files")]
+    Genesis(GenesisArgs),
+
+    #[command(about = "Manage the configuration file")]
+    Config(ConfigArgs),
+
+    #[command(about = "Re-execute stored blocks and compare the outcome with the database")]
+    #[command(long_about = "Re-execute the transactions of a range of blocks of a database on \
+                            top of the state of their parent block, and compare the receipts, \
+                            events and state updates with the stored ones. The options the \
+                            blocks were produced with (eg `--disable-fee`, `--chain-id`, \
+                            `--genesis`) must be given to get the same outcome.")]
+    Replay(ReplayArgs),
 }
 
 #[derive(Debug, Args, Clone)]
@@ -128,6 +182,52 @@ pub struct ServerOptions {
     #[arg(default_value = "100")]
     #[arg(help = "Maximum number of concurrent connections allowed.")]
     pub max_connections: u32,
+
+    #[arg(long = "http.api")]
+    #[arg(value_name = "APIS")]
+    #[arg(value_delimiter = ',')]
+    #[arg(help = "Comma separated list of the APIs to expose: `starknet`, `katana`, `torii`, \
+                  `dev`.")]
+    #[arg(long_help = "Comma separated list of the APIs to expose: `starknet`, `katana`, \
+                       `torii`, `dev`. Defaults to all of them but `dev`, which is only \
+                       exposed with `--dev`.")]
+    pub http_api: Option<Vec<ApiKind>>,
+
+    #[arg(long = "http.rate-limit")]
+    #[arg(value_name = "REQUESTS")]
+    #[arg(help = "Maximum number of calls per second from a single client IP.")]
+    #[arg(long_help = "Maximum number of calls per second from a single client IP, the calls \
+                       of a batch request being counted individually. The client is the peer \
+                       of the connection, unless it's one of `--http.trusted-proxies`. \
+                       WebSocket connections aren't supported when rate limiting.")]
+    pub rate_limit: Option<u32>,
+
+    #[arg(long = "http.trusted-proxies")]
+    #[arg(value_name = "IPS")]
+    #[arg(value_delimiter = ',')]
+    #[arg(requires = "rate_limit")]
+    #[arg(help = "Comma separated list of the reverse proxies trusted to identify the clients.")]
+    #[arg(long_help = "Comma separated list of the IPs of the reverse proxies in front of the \
+                       node. The rate limited client of the requests made through them is taken \
+                       from their `X-Forwarded-For` or `X-Real-IP` header.")]
+    pub trusted_proxies: Vec<IpAddr>,
+
+    #[arg(long = "http.max-request-body-size")]
+    #[arg(value_name = "BYTES")]
+    #[arg(default_value_t = DEFAULT_MAX_BODY_SIZE)]
+    #[arg(help = "Maximum size of a request body.")]
+    pub max_request_body_size: u32,
+
+    #[arg(long = "http.max-response-body-size")]
+    #[arg(value_name = "BYTES")]
+    #[arg(default_value_t = DEFAULT_MAX_BODY_SIZE)]
+    #[arg(help = "Maximum size of a response body.")]
+    pub max_response_body_size: u32,
+
+    #[arg(long = "http.max-batch-size")]
+    #[arg(value_name = "CALLS")]
+    #[arg(help = "Maximum number of calls in a batch request.")]
+    pub max_batch_size: Option<u32>,
 }
 
 #[derive(Debug, Args, Clone)]
@@ -143,6 +243,40 @@ pub struct StarknetOptions {
     #[arg(help = "Number of pre-funded accounts to generate.")]
     pub total_accounts: u16,
 
+    #[arg(long)]
+    #[arg(value_name = "PHRASE")]
+    #[arg(conflicts_with = "seed")]
+    #[arg(value_parser = parse_mnemonic)]
+    #[arg(help = "BIP-39 mnemonic to derive the keys of the pre-funded accounts from.")]
+    #[arg(long_help = "BIP-39 mnemonic to derive the keys of the pre-funded accounts from, \
+                       instead of the seed. The keys are derived the same way as the Starknet \
+                       wallets do, so the accounts can be imported in them with the mnemonic.")]
+    pub mnemonic: Option<String>,
+
+    #[arg(long)]
+    #[arg(value_name = "DERIVATION_PATH")]
+    #[arg(requires = "mnemonic")]
+    #[arg(value_parser = parse_derivation_path)]
+    #[arg(help = "Derivation path of the keys of the pre-funded accounts.")]
+    #[arg(long_help = "Derivation path of the keys of the pre-funded accounts, to which the \
+                       index of the account is appended. Defaults to `m/44'/9004'/0'/0`.")]
+    pub derivation_path: Option<String>,
+
+    #[arg(long = "account-class")]
+    #[arg(value_name = "CLASS")]
+    #[arg(value_delimiter = ',')]
+    #[arg(value_parser = parse_account_class)]
+    #[arg(help = "Class of the pre-funded accounts: `oz`, `argent`, or the path of a Sierra \
+                  class artifact.")]
+    #[arg(long_help = "Class of the pre-funded accounts: `oz` (the default), `argent`, or the \
+                       path of a Sierra class artifact. A comma separated list of classes can \
+                       be given to use a different class for each account, the last class \
+                       being used for the remaining accounts. The constructor of a Sierra \
+                       class must only take the public key and store it in the \
+                       `Account_public_key` storage variable, as the OpenZeppelin account. \
+                       Other account classes must be allocated in a genesis file.")]
+    pub account_classes: Vec<AccountClass>,
+
     #[arg(long)]
     #[arg(help = "Disable charging fee when executing transactions.")]
     pub disable_fee: bool,
@@ -151,13 +285,23 @@ pub struct StarknetOptions {
     #[arg(help = "Disable validation when executing transactions.")]
     pub disable_validate: bool,
 
+    #[arg(long)]
+    #[arg(help = "Execute independent transactions of a block optimistically in parallel.")]
+    pub parallel_execution: bool,
+
     #[command(flatten)]
     #[command(next_help_heading = "Environment options")]
     pub environment: EnvironmentOptions,
 
     #[arg(long)]
     #[arg(value_parser = parse_genesis)]
-    #[arg(conflicts_with_all(["rpc_url", "seed", "total_accounts"]))]
+    #[arg(conflicts_with_all([
+        "rpc_url",
+        "seed",
+        "total_accounts",
+        "mnemonic",
+        "account_classes"
+    ]))]
     pub genesis: Option<Genesis>,
 }
 
@@ -213,22 +357,37 @@ impl KatanaArgs {
             block_time: self.block_time,
             no_mining: self.no_mining,
             #[cfg(feature = "messaging")]
-            messaging: self.messaging.clone(),
+            messaging: if self.simulated_messaging {
+                Some(katana_core::service::messaging::MessagingConfig::simulated())
+            } else {
+                self.messaging.clone()
+            },
         }
     }
 
     pub fn server_config(&self) -> ServerConfig {
-        let mut apis = vec![ApiKind::Starknet, ApiKind::Katana, ApiKind::Torii];
-        // only enable `katana` API in dev mode
-        if self.dev {
-            apis.push(ApiKind::Dev);
-        }
+        let apis = match &self.server.http_api {
+            Some(apis) => apis.clone(),
+            None => {
+                let mut apis = vec![ApiKind::Starknet, ApiKind::Katana, ApiKind::Torii];
+                // only enable `katana` API in dev mode
+                if self.dev {
+                    apis.push(ApiKind::Dev);
+                }
+                apis
+            }
+        };
 
         ServerConfig {
             apis,
             port: self.server.port,
             host: self.server.host.clone().unwrap_or("0.0.0.0".into()),
             max_connections: self.server.max_connections,
+            max_request_body_size: self.server.max_request_body_size,
+            max_response_body_size: self.server.max_response_body_size,
+            max_batch_size: self.server.max_batch_size,
+            rate_limit: self.server.rate_limit,
+            trusted_proxies: self.server.trusted_proxies.clone(),
         }
     }
 
@@ -241,19 +400,18 @@ impl KatanaArgs {
         let genesis = match self.starknet.genesis.clone() {
             Some(genesis) => genesis,
             None => {
-                let accounts = DevAllocationsGenerator::new(self.starknet.total_accounts)
+                let classes = &self.starknet.account_classes;
+                let mut generator = DevAllocationsGenerator::new(self.starknet.total_accounts)
                     .with_seed(parse_seed(&self.starknet.seed))
                     .with_balance(DEFAULT_PREFUNDED_ACCOUNT_BALANCE)
-                    .generate();
+                    .with_classes(classes.iter().map(AccountClass::dev_account_class).collect());
 
-                let mut genesis = Genesis {
-                    gas_prices: gas_price.clone(),
-                    sequencer_address: *DEFAULT_SEQUENCER_ADDRESS,
-                    ..Default::default()
-                };
+                if let Some(mnemonic) = self.dev_mnemonic() {
+                    generator = generator.with_mnemonic(mnemonic);
+                }
 
-                genesis.extend_allocations(accounts.into_iter().map(|(k, v)| (k, v.into())));
-                genesis
+                let classes = classes.iter().filter_map(AccountClass::genesis_class);
+                dev_genesis(&generator, gas_price.clone(), classes)
             }
         };
 
@@ -261,7 +419,8 @@ impl KatanaArgs {
             disable_fee: self.starknet.disable_fee,
             disable_validate: self.starknet.disable_validate,
             fork_rpc_url: self.rpc_url.clone(),
-            fork_block_number: self.fork_block_number,
+            fork_block: self.fork_block,
+            follow_rpc_url: self.follow.clone(),
             env: Environment {
                 gas_price,
                 chain_id: self.starknet.environment.chain_id,
@@ -278,8 +437,17 @@ impl KatanaArgs {
             },
             db_dir: self.db_dir.clone(),
             genesis,
+            parallel_execution: self.starknet.parallel_execution,
         }
     }
+
+    /// Get the mnemonic to derive the keys of the dev accounts from, if any.
+    pub fn dev_mnemonic(&self) -> Option<DevMnemonic> {
+        let phrase = self.starknet.mnemonic.as_deref()?;
+        let path =
+            self.starknet.derivation_path.as_deref().unwrap_or(DEFAULT_DEV_ACCOUNT_DERIVATION_PATH);
+        Some(DevMnemonic::new(phrase, path).expect("mnemonic and derivation path are validated"))
+    }
 }
 
 #[cfg(test)]
@@ -315,4 +483,16 @@ mod test {
         assert_eq!(block_context.l1_gas_prices.eth, 10);
         assert_eq!(block_context.l1_gas_prices.strk, 20);
     }
+
+    #[test]
+    fn server_apis_from_args() {
+        let args = KatanaArgs::parse_from(["katana", "--dev"]);
+        assert!(args.server_config().apis.contains(&ApiKind::Dev));
+
+        let args = KatanaArgs::parse_from(["katana", "--dev", "--http.api", "starknet,katana"]);
+        assert_eq!(args.server_config().apis, vec![ApiKind::Starknet, ApiKind::Katana]);
+
+        let result = KatanaArgs::try_parse_from(["katana", "--http.api", "starknet,admin"]);
+        assert!(result.is_err());
+    }
 }
@@ -0,0 +1,674 @@
+//! Configuration file of the node.
+//!
+//! Every option of the node can be set in a TOML file passed with `--config`, the options given on
+//! the command line taking precedence over the ones of the file. The sections of the file mirror
+//! the groups of options of the command line, and the keys are the names of the options in
+//! snake case:
+//!
+//! ```toml
+//! block_time = 1000
+//! dev = true
+//!
+//! [server]
+//! port = 5050
+//! http_api = ["starknet", "katana"]
+//!
+//! [starknet]
+//! accounts = 3
+//!
+//! [starknet.environment]
+//! chain_id = "SN_GOERLI"
+//!
+//! [messaging]
+//! chain = "ethereum"
+//! rpc_url = "http://127.0.0.1:8545"
+//! # ...
+//! ```
+//!
+//! Relative paths in the file are resolved from the current directory, the same way as the ones
+//! given on the command line.
+
+use std::net::{IpAddr, SocketAddr};
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use clap::parser::ValueSource;
+use clap::{ArgMatches, Args, CommandFactory, FromArgMatches, Subcommand};
+#[cfg(feature = "messaging")]
+use katana_core::service::messaging::MessagingConfig;
+use katana_primitives::chain::ChainId;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::args::KatanaArgs;
+use crate::utils::{
+    block_id_to_string, parse_account_class, parse_block_id, parse_derivation_path, parse_genesis,
+    parse_mnemonic,
+};
+
+/// The content of a configuration file. All the values are optional, the missing ones are taken
+/// from the command line or its defaults.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct KatanaConfig {
+    pub silent: Option<bool>,
+    pub no_mining: Option<bool>,
+    pub block_time: Option<u64>,
+    pub db_dir: Option<PathBuf>,
+    pub rpc_url: Option<Url>,
+    pub dev: Option<bool>,
+    pub json_log: Option<bool>,
+    pub metrics: Option<SocketAddr>,
+    /// The block to fork from, either a block number, a block hash or `latest`.
+    pub fork_block: Option<String>,
+    pub follow: Option<Url>,
+    #[cfg(feature = "messaging")]
+    pub simulated_messaging: Option<bool>,
+    #[serde(default)]
+    pub server: ServerSection,
+    #[serde(default)]
+    pub starknet: StarknetSection,
+    #[cfg(feature = "messaging")]
+    pub messaging: Option<MessagingConfig>,
+}
+
+/// The `[server]` section, see [ServerOptions](crate::args::ServerOptions).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ServerSection {
+    pub port: Option<u16>,
+    pub host: Option<String>,
+    pub max_connections: Option<u32>,
+    /// The APIs to expose: `starknet`, `katana`, `torii` or `dev`.
+    pub http_api: Option<Vec<String>>,
+    pub rate_limit: Option<u32>,
+    /// The reverse proxies trusted to identify the rate limited clients.
+    pub trusted_proxies: Option<Vec<IpAddr>>,
+    pub max_request_body_size: Option<u32>,
+    pub max_response_body_size: Option<u32>,
+    pub max_batch_size: Option<u32>,
+}
+
+/// The `[starknet]` section, see [StarknetOptions](crate::args::StarknetOptions).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StarknetSection {
+    pub seed: Option<String>,
+    pub accounts: Option<u16>,
+    pub mnemonic: Option<String>,
+    pub derivation_path: Option<String>,
+    /// The account classes, either `oz`, `argent`, or the path of a Sierra class artifact.
+    pub account_classes: Option<Vec<String>>,
+    pub disable_fee: Option<bool>,
+    pub disable_validate: Option<bool>,
+    pub parallel_execution: Option<bool>,
+    /// Path of the genesis file.
+    pub genesis: Option<PathBuf>,
+    #[serde(default)]
+    pub environment: EnvironmentSection,
+}
+
+/// The `[starknet.environment]` section, see
+/// [EnvironmentOptions](crate::args::EnvironmentOptions).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EnvironmentSection {
+    /// The chain id, either as a hex string or a Cairo short string.
+    pub chain_id: Option<String>,
+    pub validate_max_steps: Option<u32>,
+    pub invoke_max_steps: Option<u32>,
+    // TOML integers are 64 bits, which is enough for the gas prices in practice.
+    pub eth_gas_price: Option<u64>,
+    pub strk_gas_price: Option<u64>,
+}
+
+#[derive(Debug, Args)]
+pub struct ConfigArgs {
+    #[command(subcommand)]
+    pub command: ConfigCommands,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ConfigCommands {
+    #[command(about = "Print the effective configuration")]
+    #[command(long_about = "Print the effective configuration, ie the configuration file merged \
+                            with the command line options and the defaults, in the format of \
+                            the configuration file. The secrets (the mnemonic of the dev \
+                            accounts and the messaging private key) are redacted unless \
+                            `--include-secrets` is given.")]
+    Dump(DumpArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct DumpArgs {
+    #[arg(long)]
+    #[arg(help = "Print the secrets in clear text instead of redacting them.")]
+    pub include_secrets: bool,
+}
+
+/// The value replacing the secrets in the dumped configuration.
+const REDACTED: &str = "<redacted>";
+
+impl ConfigArgs {
+    /// Executes the command, `config` being the effective configuration of the node.
+    pub fn execute(self, config: &KatanaConfig) -> Result<()> {
+        match self.command {
+            ConfigCommands::Dump(args) => {
+                let mut config = config.clone();
+                if !args.include_secrets {
+                    config.redact_secrets();
+                }
+                print!("{}", toml::to_string_pretty(&config)?);
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Parses the command line arguments and merges them with the configuration file given with
+/// `--config`, if any.
+///
+/// Returns the resulting arguments along with the effective configuration.
+pub fn load_args() -> Result<(KatanaArgs, KatanaConfig)> {
+    load_args_from_matches(KatanaArgs::command().get_matches())
+}
+
+fn load_args_from_matches(matches: ArgMatches) -> Result<(KatanaArgs, KatanaConfig)> {
+    let mut args = KatanaArgs::from_arg_matches(&matches)?;
+
+    let file = match &args.config {
+        Some(path) => KatanaConfig::load(path)?,
+        None => KatanaConfig::default(),
+    };
+
+    // The genesis is parsed by clap, so its path has to be kept to be part of the effective
+    // configuration.
+    let genesis = match matches.get_raw("genesis").and_then(|mut values| values.next()) {
+        Some(path) => Some(PathBuf::from(path)),
+        None => file.starknet.genesis.clone(),
+    };
+
+    file.apply(&mut args, &matches)?;
+    let effective = KatanaConfig::from_args(&args, genesis)?;
+
+    Ok((args, effective))
+}
+
+impl KatanaConfig {
+    /// Load the config from a TOML file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file {}", path.display()))?;
+        toml::from_str(&content)
+            .with_context(|| format!("Failed to parse config file {}", path.display()))
+    }
+
+    /// Replaces the secrets of the config with a placeholder, so that it can be printed. The
+    /// redacted config can't be loaded back as is.
+    pub fn redact_secrets(&mut self) {
+        if let Some(mnemonic) = &mut self.starknet.mnemonic {
+            *mnemonic = REDACTED.to_string();
+        }
+
+        #[cfg(feature = "messaging")]
+        if let Some(messaging) = &mut self.messaging {
+            messaging.private_key = REDACTED.to_string();
+        }
+    }
+
+    /// Sets the values of the config on `args`, except for the ones that were explicitly given on
+    /// the command line.
+    ///
+    /// The conflicts between options are checked again afterwards, as clap is only aware of the
+    /// options given on the command line.
+    pub fn apply(self, args: &mut KatanaArgs, matches: &ArgMatches) -> Result<()> {
+        let Self { server, starknet, .. } = self;
+        let environment = starknet.environment;
+
+        let genesis_in_file = starknet.genesis.is_some();
+        let accounts_in_file = starknet.seed.is_some()
+            || starknet.accounts.is_some()
+            || starknet.mnemonic.is_some()
+            || starknet.account_classes.is_some();
+        let seed_in_file = starknet.seed.is_some();
+        let mnemonic_in_file = starknet.mnemonic.is_some();
+
+        merge(matches, "silent", &mut args.silent, self.silent);
+        merge(matches, "no_mining", &mut args.no_mining, self.no_mining);
+        merge(matches, "block_time", &mut args.block_time, self.block_time.map(Some));
+        merge(matches, "db_dir", &mut args.db_dir, self.db_dir.map(Some));
+        merge(matches, "rpc_url", &mut args.rpc_url, self.rpc_url.map(Some));
+        merge(matches, "dev", &mut args.dev, self.dev);
+        merge(matches, "json_log", &mut args.json_log, self.json_log);
+        merge(matches, "metrics", &mut args.metrics, self.metrics.map(Some));
+        let fork_block = self.fork_block.as_deref().map(parse_block_id).transpose()?;
+        merge(matches, "fork_block", &mut args.fork_block, fork_block.map(Some));
+        merge(matches, "follow", &mut args.follow, self.follow.map(Some));
+
+        #[cfg(feature = "messaging")]
+        {
+            merge(
+                matches,
+                "simulated_messaging",
+                &mut args.simulated_messaging,
+                self.simulated_messaging,
+            );
+            merge(matches, "messaging", &mut args.messaging, self.messaging.map(Some));
+        }
+
+        merge(matches, "port", &mut args.server.port, server.port);
+        merge(matches, "host", &mut args.server.host, server.host.map(Some));
+        merge(matches, "max_connections", &mut args.server.max_connections, server.max_connections);
+
+        let apis = server
+            .http_api
+            .map(|apis| apis.iter().map(|api| api.parse()).collect::<Result<_, _>>())
+            .transpose()?;
+        merge(matches, "http_api", &mut args.server.http_api, apis.map(Some));
+        merge(matches, "rate_limit", &mut args.server.rate_limit, server.rate_limit.map(Some));
+        merge(matches, "trusted_proxies", &mut args.server.trusted_proxies, server.trusted_proxies);
+        merge(
+            matches,
+            "max_request_body_size",
+            &mut args.server.max_request_body_size,
+            server.max_request_body_size,
+        );
+        merge(
+            matches,
+            "max_response_body_size",
+            &mut args.server.max_response_body_size,
+            server.max_response_body_size,
+        );
+        merge(
+            matches,
+            "max_batch_size",
+            &mut args.server.max_batch_size,
+            server.max_batch_size.map(Some),
+        );
+
+        let options = &mut args.starknet;
+        merge(matches, "seed", &mut options.seed, starknet.seed);
+        merge(matches, "total_accounts", &mut options.total_accounts, starknet.accounts);
+
+        let mnemonic = starknet.mnemonic.as_deref().map(parse_mnemonic).transpose()?;
+        merge(matches, "mnemonic", &mut options.mnemonic, mnemonic.map(Some));
+
+        let path = starknet.derivation_path.as_deref().map(parse_derivation_path).transpose()?;
+        merge(matches, "derivation_path", &mut options.derivation_path, path.map(Some));
+
+        let classes = starknet
+            .account_classes
+            .map(|classes| classes.iter().map(|c| parse_account_class(c)).collect::<Result<_>>())
+            .transpose()?;
+        merge(matches, "account_classes", &mut options.account_classes, classes);
+
+        merge(matches, "disable_fee", &mut options.disable_fee, starknet.disable_fee);
+        merge(
+            matches,
+            "disable_validate",
+            &mut options.disable_validate,
+            starknet.disable_validate,
+        );
+        merge(
+            matches,
+            "parallel_execution",
+            &mut options.parallel_execution,
+            starknet.parallel_execution,
+        );
+
+        if !is_set_on_cli(matches, "genesis") {
+            if let Some(path) = starknet.genesis {
+                let path = path.to_string_lossy();
+                options.genesis = Some(parse_genesis(&path).context("Failed to load genesis")?);
+            }
+        }
+
+        let chain_id = environment
+            .chain_id
+            .map(|id| ChainId::parse(&id).with_context(|| format!("Invalid chain id {id}")))
+            .transpose()?;
+
+        let env = &mut options.environment;
+        merge(matches, "chain_id", &mut env.chain_id, chain_id);
+        merge(
+            matches,
+            "validate_max_steps",
+            &mut env.validate_max_steps,
+            environment.validate_max_steps.map(Some),
+        );
+        merge(
+            matches,
+            "invoke_max_steps",
+            &mut env.invoke_max_steps,
+            environment.invoke_max_steps.map(Some),
+        );
+        merge(
+            matches,
+            "l1_eth_gas_price",
+            &mut env.l1_eth_gas_price,
+            environment.eth_gas_price.map(|price| Some(price.into())),
+        );
+        merge(
+            matches,
+            "l1_strk_gas_price",
+            &mut env.l1_strk_gas_price,
+            environment.strk_gas_price.map(|price| Some(price.into())),
+        );
+
+        if args.no_mining && args.block_time.is_some() {
+            bail!("`no_mining` and `block_time` cannot be used together");
+        }
+        if args.fork_block.is_some() && args.rpc_url.is_none() {
+            bail!("`fork_block` requires `rpc_url`");
+        }
+        if args.starknet.genesis.is_some() && args.rpc_url.is_some() {
+            bail!("`genesis` and `rpc_url` cannot be used together");
+        }
+        if (genesis_in_file && is_set_on_cli_any(matches, DEV_ACCOUNTS_OPTIONS))
+            || (accounts_in_file && args.starknet.genesis.is_some())
+        {
+            bail!("`genesis` cannot be used with the options of the generated dev accounts");
+        }
+        if (seed_in_file && args.starknet.mnemonic.is_some())
+            || (mnemonic_in_file && is_set_on_cli(matches, "seed"))
+        {
+            bail!("`mnemonic` and `seed` cannot be used together");
+        }
+        if args.starknet.derivation_path.is_some() && args.starknet.mnemonic.is_none() {
+            bail!("`derivation_path` requires `mnemonic`");
+        }
+        if args.follow.is_some() {
+            if args.rpc_url.is_some() {
+                bail!("`follow` and `rpc_url` cannot be used together");
+            }
+            if args.no_mining || args.block_time.is_some() {
+                bail!("`follow` cannot be used with the mining options");
+            }
+            if args.dev {
+                bail!("`follow` and `dev` cannot be used together");
+            }
+            #[cfg(feature = "messaging")]
+            if args.messaging.is_some() {
+                bail!("`follow` and `messaging` cannot be used together");
+            }
+        }
+
+        #[cfg(feature = "messaging")]
+        if args.simulated_messaging {
+            if !args.dev {
+                bail!("`simulated_messaging` requires `dev`");
+            }
+            if args.messaging.is_some() {
+                bail!("`simulated_messaging` and `messaging` cannot be used together");
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Creates the config holding all the values of `args`, `genesis` being the path of the
+    /// genesis file, if any.
+    pub fn from_args(args: &KatanaArgs, genesis: Option<PathBuf>) -> Result<Self> {
+        let env = &args.starknet.environment;
+        // the dev accounts are only generated when no genesis file is given
+        let dev_accounts = genesis.is_none();
+        let account_classes = args.starknet.account_classes.iter().map(|c| c.to_string());
+
+        let gas_price = |price: Option<u128>| -> Result<Option<u64>> {
+            price
+                .map(|price| u64::try_from(price).context("Gas price too large for the config"))
+                .transpose()
+        };
+
+        Ok(Self {
+            silent: Some(args.silent),
+            no_mining: Some(args.no_mining),
+            block_time: args.block_time,
+            db_dir: args.db_dir.clone(),
+            rpc_url: args.rpc_url.clone(),
+            dev: Some(args.dev),
+            json_log: Some(args.json_log),
+            metrics: args.metrics,
+            fork_block: args.fork_block.as_ref().map(block_id_to_string),
+            follow: args.follow.clone(),
+            #[cfg(feature = "messaging")]
+            simulated_messaging: Some(args.simulated_messaging),
+            server: ServerSection {
+                port: Some(args.server.port),
+                host: args.server.host.clone(),
+                max_connections: Some(args.server.max_connections),
+                http_api: args
+                    .server
+                    .http_api
+                    .as_ref()
+                    .map(|apis| apis.iter().map(|api| api.to_string()).collect()),
+                rate_limit: args.server.rate_limit,
+                trusted_proxies: (!args.server.trusted_proxies.is_empty())
+                    .then(|| args.server.trusted_proxies.clone()),
+                max_request_body_size: Some(args.server.max_request_body_size),
+                max_response_body_size: Some(args.server.max_response_body_size),
+                max_batch_size: args.server.max_batch_size,
+            },
+            starknet: StarknetSection {
+                seed: (dev_accounts && args.starknet.mnemonic.is_none())
+                    .then(|| args.starknet.seed.clone()),
+                accounts: dev_accounts.then_some(args.starknet.total_accounts),
+                mnemonic: args.starknet.mnemonic.clone(),
+                derivation_path: args.starknet.derivation_path.clone(),
+                account_classes: (dev_accounts && !args.starknet.account_classes.is_empty())
+                    .then(|| account_classes.collect()),
+                disable_fee: Some(args.starknet.disable_fee),
+                disable_validate: Some(args.starknet.disable_validate),
+                parallel_execution: Some(args.starknet.parallel_execution),
+                genesis,
+                environment: EnvironmentSection {
+                    // formatted such that it's parsed back to the same chain id
+                    chain_id: Some(env.chain_id.to_string()),
+                    validate_max_steps: env.validate_max_steps,
+                    invoke_max_steps: env.invoke_max_steps,
+                    eth_gas_price: gas_price(env.l1_eth_gas_price)?,
+                    strk_gas_price: gas_price(env.l1_strk_gas_price)?,
+                },
+            },
+            #[cfg(feature = "messaging")]
+            messaging: args.messaging.clone(),
+        })
+    }
+}
+
+/// Sets `target` to `value` unless the argument `id` was given on the command line.
+fn merge<T>(matches: &ArgMatches, id: &str, target: &mut T, value: Option<T>) {
+    if let Some(value) = value {
+        if !is_set_on_cli(matches, id) {
+            *target = value;
+        }
+    }
+}
+
+/// The options of the dev accounts generated when no genesis file is given.
+const DEV_ACCOUNTS_OPTIONS: &[&str] = &["seed", "total_accounts", "mnemonic", "account_classes"];
+
+fn is_set_on_cli(matches: &ArgMatches, id: &str) -> bool {
+    matches.value_source(id) == Some(ValueSource::CommandLine)
+}
+
+fn is_set_on_cli_any(matches: &ArgMatches, ids: &[&str]) -> bool {
+    ids.iter().any(|id| is_set_on_cli(matches, id))
+}
+
+#[cfg(test)]
+mod tests {
+    use katana_primitives::chain::NamedChainId;
+    use katana_primitives::genesis::constant::DEFAULT_ARGENT_ACCOUNT_CONTRACT_CLASS_HASH;
+    use katana_rpc_api::ApiKind;
+
+    use super::*;
+
+    const CONFIG: &str = r#"
+        block_time = 1000
+        dev = true
+
+        [server]
+        port = 6060
+        max_connections = 10
+        http_api = ["starknet", "torii"]
+        max_batch_size = 50
+
+        [starknet]
+        seed = "1"
+        accounts = 3
+        disable_fee = true
+
+        [starknet.environment]
+        chain_id = "SN_GOERLI"
+        eth_gas_price = 10
+    "#;
+
+    fn load(cli: &[&str]) -> (KatanaArgs, KatanaConfig) {
+        let matches = KatanaArgs::command().get_matches_from(cli);
+        let mut args = KatanaArgs::from_arg_matches(&matches).unwrap();
+        let config: KatanaConfig = toml::from_str(CONFIG).unwrap();
+        config.apply(&mut args, &matches).unwrap();
+        let effective = KatanaConfig::from_args(&args, None).unwrap();
+        (args, effective)
+    }
+
+    #[test]
+    fn args_from_config_file() {
+        let (args, _) = load(&["katana"]);
+
+        assert_eq!(args.block_time, Some(1000));
+        assert!(args.dev);
+        assert_eq!(args.server.port, 6060);
+        assert_eq!(args.server.max_connections, 10);
+        assert_eq!(args.server.http_api, Some(vec![ApiKind::Starknet, ApiKind::Torii]));
+        assert_eq!(args.server.max_batch_size, Some(50));
+        assert_eq!(args.server.rate_limit, None);
+        assert_eq!(args.starknet.seed, "1");
+        assert_eq!(args.starknet.total_accounts, 3);
+        assert!(args.starknet.disable_fee);
+        assert!(!args.starknet.disable_validate);
+        assert_eq!(args.starknet.environment.chain_id, ChainId::Named(NamedChainId::Goerli));
+        assert_eq!(args.starknet.environment.l1_eth_gas_price, Some(10));
+        assert_eq!(args.starknet.environment.l1_strk_gas_price, None);
+    }
+
+    #[test]
+    fn cli_overrides_config_file() {
+        let (args, _) = load(&["katana", "--port", "7070", "--accounts", "5", "--chain-id", "0x1"]);
+
+        assert_eq!(args.server.port, 7070);
+        assert_eq!(args.server.max_connections, 10);
+        assert_eq!(args.starknet.total_accounts, 5);
+        assert_eq!(args.starknet.seed, "1");
+        assert_eq!(args.starknet.environment.chain_id, ChainId::Id(1u8.into()));
+    }
+
+    #[test]
+    fn conflicting_options_in_config_file() {
+        let matches = KatanaArgs::command().get_matches_from(["katana", "--no-mining"]);
+        let mut args = KatanaArgs::from_arg_matches(&matches).unwrap();
+        let config: KatanaConfig = toml::from_str("block_time = 1000").unwrap();
+        assert!(config.apply(&mut args, &matches).is_err());
+
+        let matches = KatanaArgs::command().get_matches_from(["katana", "--dev"]);
+        let mut args = KatanaArgs::from_arg_matches(&matches).unwrap();
+        let config: KatanaConfig = toml::from_str(r#"follow = "http://localhost:5050""#).unwrap();
+        assert!(config.apply(&mut args, &matches).is_err());
+    }
+
+    #[test]
+    fn dumped_config_is_loaded_back() {
+        let (args, effective) = load(&["katana", "--port", "7070"]);
+        let dumped = toml::to_string_pretty(&effective).unwrap();
+
+        let matches = KatanaArgs::command().get_matches_from(["katana"]);
+        let mut loaded = KatanaArgs::from_arg_matches(&matches).unwrap();
+        toml::from_str::<KatanaConfig>(&dumped).unwrap().apply(&mut loaded, &matches).unwrap();
+
+        assert_eq!(loaded.server.port, args.server.port);
+        assert_eq!(loaded.starknet.total_accounts, args.starknet.total_accounts);
+        assert_eq!(loaded.starknet.environment.chain_id, args.starknet.environment.chain_id);
+        assert_eq!(
+            loaded.starknet.environment.l1_eth_gas_price,
+            args.starknet.environment.l1_eth_gas_price
+        );
+    }
+
+    #[test]
+    fn dev_accounts_from_mnemonic_in_config_file() {
+        let config = r#"
+            [starknet]
+            accounts = 2
+            mnemonic = "test test test test test test test test test test test junk"
+            account_classes = ["argent"]
+        "#;
+
+        let matches = KatanaArgs::command().get_matches_from(["katana"]);
+        let mut args = KatanaArgs::from_arg_matches(&matches).unwrap();
+        toml::from_str::<KatanaConfig>(config).unwrap().apply(&mut args, &matches).unwrap();
+
+        let genesis = args.starknet_config().genesis;
+        let mnemonic = args.dev_mnemonic().unwrap();
+
+        for (_, account) in genesis.accounts() {
+            assert_eq!(account.class_hash(), DEFAULT_ARGENT_ACCOUNT_CONTRACT_CLASS_HASH);
+            let private_key = account.private_key();
+            assert!((0..2).any(|i| mnemonic.private_key(i).ok() == private_key));
+        }
+        assert!(genesis.classes.contains_key(&DEFAULT_ARGENT_ACCOUNT_CONTRACT_CLASS_HASH));
+
+        // the seed is not used, and so not part of the effective config
+        let effective = KatanaConfig::from_args(&args, None).unwrap();
+        assert_eq!(effective.starknet.seed, None);
+        assert_eq!(effective.starknet.account_classes, Some(vec!["argent".to_string()]));
+    }
+
+    #[test]
+    fn secrets_are_redacted() {
+        let mnemonic = "test test test test test test test test test test test junk";
+        let config = format!("[starknet]\nmnemonic = \"{mnemonic}\"");
+        let mut config: KatanaConfig = toml::from_str(&config).unwrap();
+
+        config.redact_secrets();
+        let dumped = toml::to_string_pretty(&config).unwrap();
+
+        assert!(!dumped.contains(mnemonic));
+        assert_eq!(config.starknet.mnemonic.as_deref(), Some(REDACTED));
+    }
+
+    #[cfg(feature = "messaging")]
+    #[test]
+    fn messaging_private_key_is_redacted() {
+        let config = r#"
+            [messaging]
+            chain = "ethereum"
+            rpc_url = "http://127.0.0.1:8545"
+            contract_address = "0x1"
+            sender_address = "0x2"
+            private_key = "0xdeadbeef"
+            interval = 2
+            from_block = 0
+        "#;
+        let mut config: KatanaConfig = toml::from_str(config).unwrap();
+
+        config.redact_secrets();
+        let dumped = toml::to_string_pretty(&config).unwrap();
+
+        assert!(!dumped.contains("0xdeadbeef"));
+        assert_eq!(config.messaging.unwrap().private_key, REDACTED);
+    }
+
+    #[test]
+    fn mnemonic_and_seed_conflict() {
+        let matches = KatanaArgs::command().get_matches_from(["katana", "--seed", "1"]);
+        let mut args = KatanaArgs::from_arg_matches(&matches).unwrap();
+        let config = r#"
+            [starknet]
+            mnemonic = "test test test test test test test test test test test junk"
+        "#;
+        let config: KatanaConfig = toml::from_str(config).unwrap();
+        assert!(config.apply(&mut args, &matches).is_err());
+    }
+}
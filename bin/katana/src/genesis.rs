@@ -0,0 +1,414 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use anyhow::{bail, Result};
+use clap::{Args, Subcommand};
+use katana_core::backend::genesis::export_genesis;
+use katana_core::backend::storage::Blockchain;
+use katana_primitives::block::BlockNumber;
+use katana_primitives::contract::StorageKey;
+use katana_primitives::genesis::allocation::{DevAccountClass, GenesisAccount, GenesisAllocation};
+use katana_primitives::genesis::constant::{
+    get_fee_token_balance_base_storage_address, ERC20_DECIMAL_STORAGE_SLOT,
+    ERC20_NAME_STORAGE_SLOT, ERC20_SYMBOL_STORAGE_SLOT, ERC20_TOTAL_SUPPLY_STORAGE_SLOT,
+    OZ_ACCOUNT_CONTRACT_PUBKEY_STORAGE_SLOT,
+};
+use katana_primitives::genesis::json::GenesisJson;
+use katana_primitives::genesis::token::GenesisToken;
+use katana_primitives::genesis::Genesis;
+use katana_primitives::utils::class::is_account_class;
+use katana_provider::traits::block::BlockNumberProvider;
+use starknet::core::utils::cairo_short_string_to_felt;
+
+#[derive(Debug, Args)]
+pub struct GenesisArgs {
+    #[command(subcommand)]
+    pub command: GenesisCommands,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum GenesisCommands {
+    #[command(about = "Export the state of a database as a genesis file")]
+    #[command(long_about = "Export the state of a database at a given block as a genesis file \
+                            that can be loaded with `--genesis`. The declared classes are \
+                            written as artifact files next to the genesis file. The genesis \
+                            options (`--genesis`, `--seed`, `--accounts`) must be the same as \
+                            the ones used to initialize the database.")]
+    Export(ExportArgs),
+
+    #[command(about = "Validate a genesis file without starting the node")]
+    #[command(long_about = "Validate a genesis file without starting the node. The class \
+                            artifacts are resolved and the genesis is converted the same way \
+                            as with `--genesis`, then every allocation is checked and a summary \
+                            of the classes and contracts is printed.")]
+    Check(CheckArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct ExportArgs {
+    #[arg(long)]
+    #[arg(value_name = "PATH")]
+    #[arg(help = "Directory path of the database to export.")]
+    pub db_dir: PathBuf,
+
+    #[arg(long)]
+    #[arg(value_name = "BLOCK_NUMBER")]
+    #[arg(help = "The block at which the state is exported. Defaults to the latest block.")]
+    pub block: Option<BlockNumber>,
+
+    #[arg(short, long)]
+    #[arg(value_name = "PATH")]
+    #[arg(default_value = "genesis.json")]
+    #[arg(help = "Path of the genesis file to write.")]
+    pub output: PathBuf,
+}
+
+#[derive(Debug, Args)]
+pub struct CheckArgs {
+    #[arg(value_name = "PATH")]
+    #[arg(help = "Path of the genesis file to check.")]
+    pub file: PathBuf,
+}
+
+impl GenesisArgs {
+    /// Executes the command, using `genesis` as the genesis the database was initialized with.
+    pub fn execute(self, genesis: &Genesis) -> Result<()> {
+        match self.command {
+            GenesisCommands::Export(args) => args.execute(genesis),
+            GenesisCommands::Check(args) => args.execute(),
+        }
+    }
+}
+
+impl ExportArgs {
+    fn execute(self, genesis: &Genesis) -> Result<()> {
+        if !self.db_dir.is_dir() {
+            bail!("Database directory {} doesn't exist", self.db_dir.display());
+        }
+
+        let blockchain = Blockchain::new_with_db(&self.db_dir, genesis)?;
+        let provider = blockchain.provider();
+
+        let latest = provider.latest_number()?;
+        let block = self.block.unwrap_or(latest);
+        if block > latest {
+            bail!("Block {block} not found, the latest block is {latest}");
+        }
+
+        let genesis = export_genesis(provider, block, genesis)?;
+        genesis.save(&self.output)?;
+
+        println!("Exported the state at block {block} to {}", self.output.display());
+
+        Ok(())
+    }
+}
+
+impl CheckArgs {
+    fn execute(self) -> Result<()> {
+        let json = GenesisJson::load(&self.file)?;
+
+        let mut report = GenesisReport::default();
+
+        for class in &json.classes {
+            let computed = class.compute_class_hash()?;
+            if let Some(hash) = class.class_hash.filter(|hash| *hash != computed) {
+                report.warnings.push(format!(
+                    "Class {hash:#x} doesn't match the hash computed from its artifact \
+                     ({computed:#x})"
+                ));
+            }
+        }
+
+        let genesis = Genesis::try_from(json)?;
+        report.extend(check_genesis(&genesis));
+
+        print_summary(&genesis);
+
+        for warning in &report.warnings {
+            println!("warning: {warning}");
+        }
+        for error in &report.errors {
+            println!("error: {error}");
+        }
+
+        if !report.errors.is_empty() {
+            bail!("Invalid genesis file with {} error(s)", report.errors.len());
+        }
+
+        println!("\nGenesis file {} is valid", self.file.display());
+
+        Ok(())
+    }
+}
+
+/// The issues found in a genesis configuration. Errors make the node fail or misbehave at startup,
+/// while warnings are likely mistakes that don't prevent the node from starting.
+#[derive(Debug, Default)]
+struct GenesisReport {
+    errors: Vec<String>,
+    warnings: Vec<String>,
+}
+
+impl GenesisReport {
+    fn extend(&mut self, other: GenesisReport) {
+        self.errors.extend(other.errors);
+        self.warnings.extend(other.warnings);
+    }
+}
+
+/// Checks the allocations of a genesis against its fee token and universal deployer, and the
+/// storage initialized by the genesis itself.
+fn check_genesis(genesis: &Genesis) -> GenesisReport {
+    let mut report = GenesisReport::default();
+    let fee_token = &genesis.fee_token;
+
+    if cairo_short_string_to_felt(&fee_token.name).is_err() {
+        report
+            .errors
+            .push(format!("Fee token name `{}` is not a valid Cairo short string", fee_token.name));
+    }
+
+    if cairo_short_string_to_felt(&fee_token.symbol).is_err() {
+        report.errors.push(format!(
+            "Fee token symbol `{}` is not a valid Cairo short string",
+            fee_token.symbol
+        ));
+    }
+
+    // The fee token storage slots written by the genesis itself, which override the ones in the
+    // fee token storage.
+    let mut fee_token_slots: HashMap<StorageKey, String> = HashMap::from([
+        (ERC20_NAME_STORAGE_SLOT, "fee token name".to_string()),
+        (ERC20_SYMBOL_STORAGE_SLOT, "fee token symbol".to_string()),
+        (ERC20_DECIMAL_STORAGE_SLOT, "fee token decimals".to_string()),
+        (ERC20_TOTAL_SUPPLY_STORAGE_SLOT, "fee token total supply".to_string()),
+        (ERC20_TOTAL_SUPPLY_STORAGE_SLOT + 1u8.into(), "fee token total supply".to_string()),
+    ]);
+
+    for (address, alloc) in &genesis.allocations {
+        if *address == fee_token.address {
+            report.errors.push(format!("Allocation {address} overlaps with the fee token"));
+        }
+
+        if *address == genesis.strk_fee_token.address {
+            report.errors.push(format!("Allocation {address} overlaps with the STRK fee token"));
+        }
+
+        if genesis.universal_deployer.as_ref().is_some_and(|udc| udc.address == *address) {
+            report
+                .errors
+                .push(format!("Allocation {address} overlaps with the universal deployer"));
+        }
+
+        if alloc.balance().is_some() {
+            let base = get_fee_token_balance_base_storage_address(*address);
+            fee_token_slots.insert(base, format!("balance of {address}"));
+            fee_token_slots.insert(base + 1u8.into(), format!("balance of {address}"));
+        }
+
+        let GenesisAllocation::Account(account) = alloc else {
+            continue;
+        };
+
+        let class_hash = account.class_hash();
+        let public_key = account.public_key();
+
+        match genesis.classes.get(&class_hash) {
+            Some(class) if !is_account_class(&class.casm) => report.errors.push(format!(
+                "Account {address} uses class {class_hash:#x} which is not an account class"
+            )),
+            Some(_) => {}
+            None => report.errors.push(format!(
+                "Account {address} uses class {class_hash:#x} which is not declared"
+            )),
+        }
+
+        let slot = account.storage().and_then(|s| s.get(&OZ_ACCOUNT_CONTRACT_PUBKEY_STORAGE_SLOT));
+        if slot.is_some_and(|value| *value != public_key) {
+            report.errors.push(format!(
+                "Account {address} storage overlaps with its public key slot \
+                 {OZ_ACCOUNT_CONTRACT_PUBKEY_STORAGE_SLOT:#x}"
+            ));
+        }
+
+        let class = DevAccountClass::from_class_hash(class_hash);
+        let (expected, _) = GenesisAccount::new_with_class(public_key, class);
+        if expected != *address {
+            report.warnings.push(format!(
+                "Account {address} is not the address derived from its public key and class \
+                 ({expected})"
+            ));
+        }
+    }
+
+    for address in genesis.tokens.keys() {
+        if *address == fee_token.address {
+            report.errors.push(format!("Token {address} overlaps with the fee token"));
+        }
+
+        if *address == genesis.strk_fee_token.address {
+            report.errors.push(format!("Token {address} overlaps with the STRK fee token"));
+        }
+
+        if genesis.universal_deployer.as_ref().is_some_and(|udc| udc.address == *address) {
+            report.errors.push(format!("Token {address} overlaps with the universal deployer"));
+        }
+
+        if genesis.allocations.contains_key(address) {
+            report.errors.push(format!("Token {address} overlaps with an allocation"));
+        }
+    }
+
+    for key in fee_token.storage.iter().flat_map(|storage| storage.keys()) {
+        if let Some(slot) = fee_token_slots.get(key) {
+            report.errors.push(format!("Fee token storage {key:#x} overlaps with the {slot}"));
+        }
+    }
+
+    report
+}
+
+fn print_summary(genesis: &Genesis) {
+    println!(
+        r"
+CLASSES
+======="
+    );
+
+    for (hash, class) in &genesis.classes {
+        let kind = if class.sierra.is_some() { "Sierra" } else { "Legacy" };
+        println!(
+            r"
+| Class hash      | {hash:#064x}
+| Compiled hash   | {:#064x}
+| Kind            | {kind}",
+            class.compiled_class_hash
+        );
+    }
+
+    println!(
+        r"
+
+CONTRACTS
+=========
+
+| Contract        | Fee Token ({})
+| Address         | {}
+| Class hash      | {:#064x}
+| Total supply    | {}",
+        genesis.fee_token.symbol,
+        genesis.fee_token.address,
+        genesis.fee_token.class_hash,
+        genesis.fee_token.total_supply,
+    );
+
+    println!(
+        r"
+| Contract        | STRK Fee Token ({})
+| Address         | {}
+| Class hash      | {:#064x}
+| Total supply    | {}",
+        genesis.strk_fee_token.symbol,
+        genesis.strk_fee_token.address,
+        genesis.strk_fee_token.class_hash,
+        genesis.strk_fee_token.total_supply,
+    );
+
+    if let Some(udc) = &genesis.universal_deployer {
+        println!(
+            r"
+| Contract        | Universal Deployer
+| Address         | {}
+| Class hash      | {:#064x}",
+            udc.address, udc.class_hash
+        );
+    }
+
+    for (address, token) in &genesis.tokens {
+        let (kind, total_supply) = match token {
+            GenesisToken::Erc20(token) => {
+                let total_supply =
+                    token.total_supply().expect("checked when the genesis is loaded");
+                (format!("ERC20 Token ({})", token.symbol), total_supply.to_string())
+            }
+            GenesisToken::Erc721(token) => {
+                (format!("ERC721 Token ({})", token.symbol), token.total_supply().to_string())
+            }
+        };
+
+        println!(
+            r"
+| Contract        | {kind}
+| Address         | {address}
+| Class hash      | {:#064x}
+| Total supply    | {total_supply}",
+            token.class_hash()
+        );
+    }
+
+    for (address, alloc) in &genesis.allocations {
+        let kind = match alloc {
+            GenesisAllocation::Account(_) => "Account",
+            GenesisAllocation::Contract(_) => "Contract",
+        };
+        let class_hash =
+            alloc.class_hash().map(|hash| format!("{hash:#064x}")).unwrap_or("-".to_string());
+        let balance = alloc.balance().unwrap_or_default();
+
+        println!(
+            r"
+| Contract        | {kind}
+| Address         | {address}
+| Class hash      | {class_hash}
+| Balance         | {balance}"
+        );
+    }
+
+    println!();
+}
+
+#[cfg(test)]
+mod tests {
+    use katana_primitives::genesis::allocation::{GenesisAccountAlloc, GenesisContractAlloc};
+    use katana_primitives::genesis::constant::DEFAULT_LEGACY_ERC20_CONTRACT_CLASS_HASH;
+    use katana_primitives::FieldElement;
+
+    use super::*;
+
+    fn test_genesis() -> Genesis {
+        Genesis::try_from(GenesisJson::load("./tests/test-data/genesis.json").unwrap()).unwrap()
+    }
+
+    #[test]
+    fn check_valid_genesis() {
+        let report = check_genesis(&test_genesis());
+        assert!(report.errors.is_empty(), "{:?}", report.errors);
+    }
+
+    #[test]
+    fn check_genesis_with_invalid_allocations() {
+        let mut genesis = test_genesis();
+
+        // an account using a class which isn't an account class
+        let (address, mut account) =
+            GenesisAccount::new(FieldElement::ONE, DEFAULT_LEGACY_ERC20_CONTRACT_CLASS_HASH);
+        account.balance = Some(1u8.into());
+        let account = GenesisAllocation::Account(GenesisAccountAlloc::Account(account));
+        genesis.extend_allocations([(address, account)]);
+
+        // a contract deployed at the fee token address
+        let contract = GenesisAllocation::Contract(GenesisContractAlloc::default());
+        genesis.extend_allocations([(genesis.fee_token.address, contract)]);
+
+        // a fee token storage overriding the balance of the account
+        let balance_slot = get_fee_token_balance_base_storage_address(address);
+        genesis.fee_token.storage.get_or_insert_with(HashMap::new).insert(balance_slot, 1u8.into());
+
+        let report = check_genesis(&genesis);
+        assert_eq!(report.errors.len(), 3, "{:?}", report.errors);
+        assert!(report.errors.iter().any(|e| e.contains("not an account class")));
+        assert!(report.errors.iter().any(|e| e.contains("overlaps with the fee token")));
+        assert!(report.errors.iter().any(|e| e.contains(&format!("balance of {address}"))));
+    }
+}
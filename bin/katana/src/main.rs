@@ -1,36 +1,55 @@
+use std::collections::BTreeSet;
 use std::io;
 use std::net::SocketAddr;
-use std::sync::Arc;
 
-use clap::{CommandFactory, Parser};
+use clap::CommandFactory;
 use clap_complete::{generate, Shell};
 use console::Style;
-use katana_core::sequencer::KatanaSequencer;
 use katana_primitives::contract::{ClassHash, ContractAddress};
-use katana_primitives::genesis::allocation::GenesisAccountAlloc;
+use katana_primitives::genesis::allocation::{DevAccountClass, GenesisAccountAlloc};
 use katana_primitives::genesis::Genesis;
-use katana_rpc::{spawn, NodeHandle};
+use katana_rpc::node::Node;
 use metrics::prometheus_exporter;
 use tokio::signal::ctrl_c;
 use tracing::info;
 
 mod args;
+mod config;
+mod genesis;
+mod replay;
 mod utils;
 
-use args::Commands::Completions;
+use args::Commands::{
+    Completions, Config as ConfigCommand, Genesis as GenesisCommand, Replay as ReplayCommand,
+};
 use args::KatanaArgs;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let args = KatanaArgs::parse();
+    let (mut args, config) = config::load_args()?;
     args.init_logging()?;
 
-    if let Some(command) = args.command {
+    if let Some(command) = args.command.take() {
         match command {
             Completions { shell } => {
                 print_completion(shell);
                 return Ok(());
             }
+
+            GenesisCommand(command) => {
+                command.execute(&args.starknet_config().genesis)?;
+                return Ok(());
+            }
+
+            ConfigCommand(command) => {
+                command.execute(&config)?;
+                return Ok(());
+            }
+
+            ReplayCommand(command) => {
+                command.execute(args.starknet_config()).await?;
+                return Ok(());
+            }
         }
     }
 
@@ -38,17 +57,25 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let sequencer_config = args.sequencer_config();
     let starknet_config = args.starknet_config();
 
-    let sequencer = Arc::new(KatanaSequencer::new(sequencer_config, starknet_config).await?);
-    let NodeHandle { addr, handle, .. } = spawn(Arc::clone(&sequencer), server_config).await?;
+    // The recorder must be installed before the node is started for its metrics to be recorded.
+    let metrics = args
+        .metrics
+        .map(|addr| prometheus_exporter::install_recorder("katana").map(|handle| (addr, handle)))
+        .transpose()?;
+
+    let node = Node::builder()
+        .sequencer_config(sequencer_config)
+        .starknet_config(starknet_config)
+        .server_config(server_config)
+        .start()
+        .await?;
 
     if !args.silent {
-        let genesis = &sequencer.backend().config.genesis;
-        print_intro(&args, genesis, addr);
+        let genesis = &node.sequencer().backend().config.genesis;
+        print_intro(&args, genesis, node.addr());
     }
 
-    if let Some(listen_addr) = args.metrics {
-        let prometheus_handle = prometheus_exporter::install_recorder("katana")?;
-
+    if let Some((listen_addr, prometheus_handle)) = metrics {
         info!(target: "katana::cli", addr = %listen_addr, "Starting metrics endpoint");
         prometheus_exporter::serve(
             listen_addr,
@@ -60,7 +87,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Wait until Ctrl + C is pressed, then shutdown
     ctrl_c().await?;
-    handle.stop()?;
+    node.stop().await?;
 
     Ok(())
 }
@@ -72,9 +99,10 @@ fn print_completion(shell: Shell) {
 }
 
 fn print_intro(args: &KatanaArgs, genesis: &Genesis, address: SocketAddr) {
-    let mut accounts = genesis.accounts().peekable();
-    let account_class_hash = accounts.peek().map(|e| e.1.class_hash());
+    let accounts = genesis.accounts();
+    let account_class_hashes = genesis.accounts().map(|e| e.1.class_hash()).collect();
     let seed = &args.starknet.seed;
+    let mnemonic = args.dev_mnemonic();
 
     if args.json_log {
         info!(
@@ -82,6 +110,8 @@ fn print_intro(args: &KatanaArgs, genesis: &Genesis, address: SocketAddr) {
             serde_json::json!({
                 "accounts": accounts.map(|a| serde_json::json!(a)).collect::<Vec<_>>(),
                 "seed": format!("{}", seed),
+                "mnemonic": mnemonic.as_ref().map(|m| m.phrase()),
+                "follow": args.follow.as_ref().map(|url| url.to_string()),
                 "address": format!("{address}"),
             })
         )
@@ -102,17 +132,42 @@ fn print_intro(args: &KatanaArgs, genesis: &Genesis, address: SocketAddr) {
             )
         );
 
-        print_genesis_contracts(genesis, account_class_hash);
-        print_genesis_accounts(accounts);
+        if let Some(url) = &args.follow {
+            println!(
+                r"
 
-        println!(
-            r"
+READ-ONLY REPLICA
+=================
+Following {url}
+    "
+            );
+        } else {
+            print_genesis_contracts(genesis, account_class_hashes);
+            print_genesis_accounts(accounts);
+
+            if let Some(mnemonic) = mnemonic {
+                println!(
+                    r"
+
+ACCOUNTS MNEMONIC
+=================
+{}
+Derivation path: {}/<index>
+    ",
+                    mnemonic.phrase(),
+                    mnemonic.derivation_path()
+                );
+            } else {
+                println!(
+                    r"
 
 ACCOUNTS SEED
 =============
 {seed}
     "
-        );
+                );
+            }
+        }
 
         let addr = format!(
             "🚀 JSON-RPC server started: {}",
@@ -123,7 +178,7 @@ ACCOUNTS SEED
     }
 }
 
-fn print_genesis_contracts(genesis: &Genesis, account_class_hash: Option<ClassHash>) {
+fn print_genesis_contracts(genesis: &Genesis, account_class_hashes: BTreeSet<ClassHash>) {
     println!(
         r"
 PREDEPLOYED CONTRACTS
@@ -135,6 +190,14 @@ PREDEPLOYED CONTRACTS
         genesis.fee_token.address, genesis.fee_token.class_hash,
     );
 
+    println!(
+        r"
+| Contract        | STRK Fee Token
+| Address         | {}
+| Class Hash      | {:#064x}",
+        genesis.strk_fee_token.address, genesis.strk_fee_token.class_hash,
+    );
+
     if let Some(ref udc) = genesis.universal_deployer {
         println!(
             r"
@@ -145,10 +208,15 @@ PREDEPLOYED CONTRACTS
         )
     }
 
-    if let Some(hash) = account_class_hash {
+    for hash in account_class_hashes {
+        let name = match DevAccountClass::from_class_hash(hash).name() {
+            Some(name) => format!("Account Contract ({name})"),
+            None => "Account Contract".to_string(),
+        };
+
         println!(
             r"
-| Contract        | Account Contract
+| Contract        | {name}
 | Class Hash      | {hash:#064x}"
         )
     }
@@ -0,0 +1,99 @@
+use std::path::PathBuf;
+
+use anyhow::{bail, Result};
+use clap::Args;
+use katana_core::backend::config::StarknetConfig;
+use katana_core::backend::replay::replay_block;
+use katana_core::backend::Backend;
+use katana_executor::abstraction::ExecutionFlags;
+use katana_primitives::block::BlockNumber;
+use katana_provider::traits::block::BlockNumberProvider;
+
+#[derive(Debug, Args)]
+pub struct ReplayArgs {
+    #[arg(long)]
+    #[arg(value_name = "PATH")]
+    #[arg(help = "Directory path of the database to replay.")]
+    pub db_dir: PathBuf,
+
+    #[arg(long)]
+    #[arg(value_name = "BLOCK_NUMBER")]
+    #[arg(default_value_t = 1)]
+    #[arg(help = "The first block to replay.")]
+    pub from: BlockNumber,
+
+    #[arg(long)]
+    #[arg(value_name = "BLOCK_NUMBER")]
+    #[arg(help = "The last block to replay. Defaults to the latest block.")]
+    pub to: Option<BlockNumber>,
+}
+
+impl ReplayArgs {
+    /// Executes the command, using `config` as the configuration the blocks were produced with.
+    pub async fn execute(self, config: StarknetConfig) -> Result<()> {
+        if !self.db_dir.is_dir() {
+            bail!("Database directory {} doesn't exist", self.db_dir.display());
+        }
+
+        // the blocks are replayed the same way the node executes them
+        let config = StarknetConfig {
+            db_dir: Some(self.db_dir.clone()),
+            fork_rpc_url: None,
+            follow_rpc_url: None,
+            ..config
+        };
+        let backend = Backend::new(config).await;
+        let provider = backend.blockchain.provider();
+
+        let latest = provider.latest_number()?;
+        let to = self.to.unwrap_or(latest);
+        if self.from == 0 {
+            bail!("The genesis block can't be replayed, `--from` must be at least 1");
+        }
+        if to > latest {
+            bail!("Block {to} not found, the latest block is {latest}");
+        }
+        if self.from > to {
+            bail!("Invalid block range, `--from` ({}) is after `--to` ({to})", self.from);
+        }
+
+        let cfg_env = backend.chain_cfg_env();
+        let flags = ExecutionFlags {
+            charge_fee: !backend.config.disable_fee,
+            validate: !backend.config.disable_validate,
+        };
+
+        let mut diverged = 0;
+
+        for block in self.from..=to {
+            let replay = replay_block(provider, backend.executor.as_ref(), &cfg_env, flags, block)?;
+
+            if replay.is_consistent() {
+                println!("Block {block}: {} transactions replayed, ok", replay.transaction_count);
+                continue;
+            }
+
+            diverged += 1;
+            println!(
+                "Block {block}: {} transactions replayed, {} divergences",
+                replay.transaction_count,
+                replay.divergences.len()
+            );
+
+            for divergence in &replay.divergences {
+                for line in divergence.to_string().lines() {
+                    println!("    {line}");
+                }
+            }
+        }
+
+        let total = to - self.from + 1;
+        if diverged > 0 {
+            bail!("{diverged} of {total} replayed blocks diverged from the database");
+        }
+
+        println!("Replayed {total} blocks, no divergence found");
+
+        Ok(())
+    }
+}
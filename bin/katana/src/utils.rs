@@ -1,7 +1,17 @@
+use std::fmt::Display;
+use std::fs::File;
+use std::io::BufReader;
 use std::path::PathBuf;
 
-use katana_primitives::genesis::json::GenesisJson;
-use katana_primitives::genesis::Genesis;
+use anyhow::{bail, Context};
+use katana_primitives::block::{BlockIdOrTag, BlockTag};
+use katana_primitives::contract::ClassHash;
+use katana_primitives::genesis::allocation::{DevAccountClass, DevMnemonic};
+use katana_primitives::genesis::constant::DEFAULT_DEV_ACCOUNT_DERIVATION_PATH;
+use katana_primitives::genesis::json::{class_from_artifact, GenesisJson};
+use katana_primitives::genesis::{Genesis, GenesisClass};
+use katana_primitives::utils::class::is_account_class;
+use katana_primitives::FieldElement;
 
 pub fn parse_seed(seed: &str) -> [u8; 32] {
     let seed = seed.as_bytes();
@@ -22,8 +32,106 @@ pub fn parse_genesis(value: &str) -> Result<Genesis, anyhow::Error> {
     Ok(genesis)
 }
 
+/// The class of the dev accounts, either one of the built-in account classes or a Sierra class
+/// loaded from its artifact.
+#[derive(Debug, Clone)]
+pub enum AccountClass {
+    Builtin(DevAccountClass),
+    Sierra { path: PathBuf, class_hash: ClassHash, class: GenesisClass },
+}
+
+impl AccountClass {
+    pub fn dev_account_class(&self) -> DevAccountClass {
+        match self {
+            Self::Builtin(class) => *class,
+            Self::Sierra { class_hash, .. } => DevAccountClass::Custom(*class_hash),
+        }
+    }
+
+    /// Get the class to declare in the genesis, along with its class hash.
+    pub fn genesis_class(&self) -> Option<(ClassHash, GenesisClass)> {
+        match self {
+            Self::Builtin(class) => class.genesis_class().map(|c| (class.class_hash(), c)),
+            Self::Sierra { class_hash, class, .. } => Some((*class_hash, class.clone())),
+        }
+    }
+}
+
+impl Display for AccountClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Builtin(class) => write!(f, "{}", class.name().unwrap_or_default()),
+            Self::Sierra { path, .. } => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+/// Used as clap value parser for [AccountClass].
+pub fn parse_account_class(value: &str) -> Result<AccountClass, anyhow::Error> {
+    match value {
+        "oz" => Ok(AccountClass::Builtin(DevAccountClass::OpenZeppelin)),
+        "argent" => Ok(AccountClass::Builtin(DevAccountClass::Argent)),
+        path => {
+            let path = PathBuf::from(shellexpand::full(path)?.into_owned());
+            let file = File::open(&path)
+                .with_context(|| format!("Failed to open class artifact {}", path.display()))?;
+
+            let artifact = serde_json::from_reader(BufReader::new(file))?;
+            let (class_hash, class) = class_from_artifact(artifact, None)?;
+
+            if class.sierra.is_none() {
+                bail!("{} is not a Sierra class artifact", path.display());
+            }
+            if !is_account_class(&class.casm) {
+                bail!("{} is not an account class", path.display());
+            }
+
+            Ok(AccountClass::Sierra { path, class_hash, class })
+        }
+    }
+}
+
+/// Used as clap value parser for the BIP-39 mnemonic of the dev accounts.
+pub fn parse_mnemonic(value: &str) -> Result<String, anyhow::Error> {
+    DevMnemonic::new(value, DEFAULT_DEV_ACCOUNT_DERIVATION_PATH)?;
+    Ok(value.to_string())
+}
+
+/// Used as clap value parser for the derivation path of the dev accounts.
+pub fn parse_derivation_path(value: &str) -> Result<String, anyhow::Error> {
+    DevMnemonic::validate_derivation_path(value)?;
+    Ok(value.to_string())
+}
+
+/// Used as clap value parser for the block to fork from, either a block number, a block hash or
+/// `latest`.
+pub fn parse_block_id(value: &str) -> Result<BlockIdOrTag, anyhow::Error> {
+    if value == "latest" {
+        Ok(BlockIdOrTag::Tag(BlockTag::Latest))
+    } else if value.starts_with("0x") {
+        let hash = FieldElement::from_hex_be(value)
+            .with_context(|| format!("Invalid block hash {value}"))?;
+        Ok(BlockIdOrTag::Hash(hash))
+    } else {
+        let number = value.parse().with_context(|| format!("Invalid block number {value}"))?;
+        Ok(BlockIdOrTag::Number(number))
+    }
+}
+
+/// The inverse of [parse_block_id].
+pub fn block_id_to_string(block: &BlockIdOrTag) -> String {
+    match block {
+        BlockIdOrTag::Tag(BlockTag::Latest) => "latest".to_string(),
+        BlockIdOrTag::Tag(BlockTag::Pending) => "pending".to_string(),
+        BlockIdOrTag::Hash(hash) => format!("{hash:#x}"),
+        BlockIdOrTag::Number(number) => number.to_string(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use katana_primitives::genesis::constant::DEFAULT_OZ_ACCOUNT_CONTRACT_CLASS_HASH;
+
     use super::*;
 
     #[test]
@@ -31,4 +139,32 @@ mod tests {
         let path = "./tests/test-data/genesis.json";
         parse_genesis(path).unwrap();
     }
+
+    #[test]
+    fn parse_account_classes() {
+        let class = parse_account_class("argent").unwrap();
+        assert_eq!(class.dev_account_class(), DevAccountClass::Argent);
+        assert_eq!(class.to_string(), "argent");
+
+        let path = "../../crates/katana/primitives/contracts/compiled/oz_account_080.json";
+        let class = parse_account_class(path).unwrap();
+        assert_eq!(
+            class.dev_account_class(),
+            DevAccountClass::Custom(DEFAULT_OZ_ACCOUNT_CONTRACT_CLASS_HASH)
+        );
+
+        // legacy classes aren't supported
+        let path = "../../crates/katana/primitives/contracts/compiled/account.json";
+        assert!(parse_account_class(path).is_err());
+    }
+
+    #[test]
+    fn parse_block_ids() {
+        for value in ["latest", "0x1234abcd", "42"] {
+            assert_eq!(block_id_to_string(&parse_block_id(value).unwrap()), value);
+        }
+
+        assert!(parse_block_id("pending").is_err());
+        assert!(parse_block_id("0xnothex").is_err());
+    }
 }
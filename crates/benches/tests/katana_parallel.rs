@@ -0,0 +1,56 @@
+// Compares the sequential and the optimistic parallel execution of the transactions of a block.
+#[cfg(not(feature = "skip-katana-benchmarks"))]
+mod katana_parallel_benchmarks {
+    use benches::spammer::spam_katana;
+    use benches::summary::BenchSummary;
+    use benches::{deploy, BenchCall};
+    use katana_runner::KatanaRunner;
+    use starknet::core::types::FieldElement;
+
+    const KATANA: &str = "../../target/release/katana";
+
+    async fn run(n_accounts: u16, parallel_execution: bool) -> BenchSummary {
+        let (name, extra_args) = if parallel_execution {
+            (format!("katana_parallel_benchmark_{n_accounts}"), vec!["--parallel-execution"])
+        } else {
+            (format!("katana_sequential_benchmark_{n_accounts}"), vec![])
+        };
+
+        // plus one as the first account is used for deployment
+        let runner =
+            KatanaRunner::new_with_extra_args(KATANA, &name, n_accounts + 1, true, &extra_args)
+                .expect("failed to start katana");
+
+        let contract_address = deploy(&runner).await.unwrap();
+
+        // every account moves its own character, so the transactions of the benchmarked block
+        // don't touch each other's state
+        let spawn = BenchCall("spawn", vec![]);
+        let calldata_move = BenchCall("move", vec![FieldElement::from_hex_be("0x3").unwrap()]);
+
+        let mut summary =
+            spam_katana(runner, contract_address, vec![spawn, calldata_move], 0, false).await;
+        summary.name = format!("{} ({name})", summary.name);
+        summary
+    }
+
+    async fn compare(n_accounts: u16) {
+        run(n_accounts, false).await.dump().await;
+        run(n_accounts, true).await.dump().await;
+    }
+
+    #[tokio::test]
+    async fn katana_parallel_benchmark_100() {
+        compare(100).await;
+    }
+
+    #[tokio::test]
+    async fn katana_parallel_benchmark_500() {
+        compare(500).await;
+    }
+
+    #[tokio::test]
+    async fn katana_parallel_benchmark_1000() {
+        compare(1000).await;
+    }
+}
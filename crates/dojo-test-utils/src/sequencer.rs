@@ -5,9 +5,8 @@ pub use katana_core::backend::config::{Environment, StarknetConfig};
 use katana_core::sequencer::KatanaSequencer;
 pub use katana_core::sequencer::SequencerConfig;
 use katana_primitives::chain::ChainId;
-use katana_rpc::config::ServerConfig;
-use katana_rpc::{spawn, NodeHandle};
-use katana_rpc_api::ApiKind;
+use katana_rpc::node::Node;
+use katana_rpc::NodeHandle;
 use starknet::accounts::{ExecutionEncoding, SingleOwnerAccount};
 use starknet::core::chain_id;
 use starknet::core::types::FieldElement;
@@ -31,32 +30,24 @@ pub struct TestSequencer {
 
 impl TestSequencer {
     pub async fn start(config: SequencerConfig, starknet_config: StarknetConfig) -> Self {
-        let sequencer = Arc::new(
-            KatanaSequencer::new(config, starknet_config)
-                .await
-                .expect("Failed to create sequencer"),
-        );
+        let node = Node::builder()
+            .sequencer_config(config)
+            .starknet_config(starknet_config)
+            .start()
+            .await
+            .expect("Failed to start node");
 
-        let handle = spawn(
-            Arc::clone(&sequencer),
-            ServerConfig {
-                port: 0,
-                host: "127.0.0.1".into(),
-                max_connections: 100,
-                apis: vec![ApiKind::Starknet, ApiKind::Katana, ApiKind::Torii, ApiKind::Dev],
-            },
-        )
-        .await
-        .expect("Unable to spawn server");
-
-        let url = Url::parse(&format!("http://{}", handle.addr)).expect("Failed to parse URL");
+        let url = Url::parse(&format!("http://{}", node.addr())).expect("Failed to parse URL");
 
-        let account = sequencer.backend.config.genesis.accounts().next().unwrap();
+        let account = node.accounts().next().unwrap();
         let account = TestAccount {
             private_key: account.1.private_key().unwrap(),
             account_address: (*account.0).into(),
         };
 
+        let sequencer = Arc::clone(node.sequencer());
+        let handle = node.rpc().clone();
+
         TestSequencer { sequencer, account, handle, url }
     }
 
@@ -1,17 +1,18 @@
 use std::path::PathBuf;
 
 use ::primitive_types::U256;
-use katana_primitives::block::GasPrices;
+use katana_primitives::block::{BlockIdOrTag, GasPrices};
 use katana_primitives::chain::ChainId;
+use katana_primitives::contract::ClassHash;
 use katana_primitives::env::BlockEnv;
 use katana_primitives::genesis::allocation::DevAllocationsGenerator;
 use katana_primitives::genesis::constant::DEFAULT_PREFUNDED_ACCOUNT_BALANCE;
-use katana_primitives::genesis::Genesis;
+use katana_primitives::genesis::{Genesis, GenesisClass};
 use url::Url;
 
 use crate::constants::{
-    DEFAULT_ETH_L1_GAS_PRICE, DEFAULT_INVOKE_MAX_STEPS, DEFAULT_STRK_L1_GAS_PRICE,
-    DEFAULT_VALIDATE_MAX_STEPS,
+    DEFAULT_ETH_L1_GAS_PRICE, DEFAULT_INVOKE_MAX_STEPS, DEFAULT_SEQUENCER_ADDRESS,
+    DEFAULT_STRK_L1_GAS_PRICE, DEFAULT_VALIDATE_MAX_STEPS,
 };
 use crate::env::BlockContextGenerator;
 
@@ -20,10 +21,16 @@ pub struct StarknetConfig {
     pub disable_fee: bool,
     pub env: Environment,
     pub fork_rpc_url: Option<Url>,
-    pub fork_block_number: Option<u64>,
+    /// The block of the network at `fork_rpc_url` to fork from. Defaults to its latest block.
+    pub fork_block: Option<BlockIdOrTag>,
+    /// Url of the node to follow. If set, the node is a read-only replica of it that syncs its
+    /// blocks instead of producing its own.
+    pub follow_rpc_url: Option<Url>,
     pub disable_validate: bool,
     pub db_dir: Option<PathBuf>,
     pub genesis: Genesis,
+    /// Execute independent transactions of a block optimistically in parallel.
+    pub parallel_execution: bool,
 }
 
 impl StarknetConfig {
@@ -36,6 +43,26 @@ impl StarknetConfig {
     }
 }
 
+/// Creates the genesis of a development chain, with the prefunded accounts generated by `accounts`
+/// and the L1 gas prices `gas_prices`.
+///
+/// The account classes which aren't part of the default genesis must be declared with `classes`.
+pub fn dev_genesis(
+    accounts: &DevAllocationsGenerator,
+    gas_prices: GasPrices,
+    classes: impl IntoIterator<Item = (ClassHash, GenesisClass)>,
+) -> Genesis {
+    let mut genesis =
+        Genesis { gas_prices, sequencer_address: *DEFAULT_SEQUENCER_ADDRESS, ..Default::default() };
+
+    for (class_hash, class) in classes {
+        genesis.classes.entry(class_hash).or_insert(class);
+    }
+
+    genesis.extend_allocations(accounts.generate().into_iter().map(|(k, v)| (k, v.into())));
+    genesis
+}
+
 impl Default for StarknetConfig {
     fn default() -> Self {
         let accounts = DevAllocationsGenerator::new(10)
@@ -48,11 +75,13 @@ impl Default for StarknetConfig {
         Self {
             disable_fee: false,
             fork_rpc_url: None,
-            fork_block_number: None,
+            fork_block: None,
+            follow_rpc_url: None,
             env: Environment::default(),
             disable_validate: false,
             db_dir: None,
             genesis,
+            parallel_execution: false,
         }
     }
 }
@@ -0,0 +1,124 @@
+//! Forking of a remote network.
+
+use std::sync::Arc;
+
+use katana_primitives::block::{BlockIdOrTag, BlockNumber, BlockTag, FinalityStatus};
+use katana_primitives::chain::ChainId;
+use katana_primitives::genesis::Genesis;
+use katana_primitives::FieldElement;
+use katana_provider::error::ProviderError;
+use katana_provider::providers::fork::backend::ForkedBackendError;
+use katana_provider::providers::fork::ForkedProvider;
+use parking_lot::RwLock;
+use starknet::core::types::{BlockStatus, BlockWithTxHashes, MaybePendingBlockWithTxHashes};
+use starknet::providers::jsonrpc::HttpTransport;
+use starknet::providers::{JsonRpcClient, Provider, ProviderError as StarknetProviderError};
+use url::Url;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ForkError {
+    #[error("The node is not forked from a network")]
+    NotForked,
+    #[error("Forked network error: {0}")]
+    Network(#[from] StarknetProviderError),
+    #[error(transparent)]
+    Backend(#[from] ForkedBackendError),
+    #[error(transparent)]
+    Provider(#[from] ProviderError),
+    #[error("Block {0:?} of the forked network is not accepted")]
+    NotAccepted(BlockIdOrTag),
+    #[error("The network at {url} is not on the forked chain {expected}, but on {actual}")]
+    ChainIdMismatch { url: Url, expected: ChainId, actual: ChainId },
+    #[error("Unable to reset the fork while a block is being mined")]
+    MiningInProgress,
+    #[error("Invalid block {block} of the forked network: {reason}")]
+    InvalidBlock { block: BlockNumber, reason: String },
+}
+
+/// The network the chain is forked from.
+pub struct Fork {
+    /// The url of the forked network, which can change when the fork is reset.
+    url: RwLock<Url>,
+    /// The provider of the local chain, shared with the [Blockchain](super::storage::Blockchain).
+    provider: Arc<ForkedProvider>,
+}
+
+impl Fork {
+    pub(super) fn new(url: Url, provider: Arc<ForkedProvider>) -> Self {
+        Self { url: RwLock::new(url), provider }
+    }
+
+    /// Returns the url of the forked network.
+    pub fn url(&self) -> Url {
+        self.url.read().clone()
+    }
+
+    pub(super) fn set_url(&self, url: Url) {
+        *self.url.write() = url;
+    }
+
+    pub(super) fn provider(&self) -> &ForkedProvider {
+        &self.provider
+    }
+}
+
+/// A block of the forked network to fork the chain from again, see
+/// [Backend::reset_fork](super::Backend::reset_fork).
+pub struct ForkPoint {
+    pub(super) url: Url,
+    pub(super) client: Arc<JsonRpcClient<HttpTransport>>,
+    pub(super) block: BlockWithTxHashes,
+    pub(super) status: FinalityStatus,
+}
+
+impl ForkPoint {
+    pub fn block_number(&self) -> BlockNumber {
+        self.block.block_number
+    }
+}
+
+/// Fetches the block to fork from, along with its finality status. The latest block is used if
+/// `block` is `None`.
+pub(super) async fn fetch_forked_block(
+    client: &JsonRpcClient<HttpTransport>,
+    block: Option<BlockIdOrTag>,
+) -> Result<(BlockWithTxHashes, FinalityStatus), ForkError> {
+    let block_id = block.unwrap_or(BlockIdOrTag::Tag(BlockTag::Latest));
+
+    let MaybePendingBlockWithTxHashes::Block(block) =
+        client.get_block_with_tx_hashes(block_id).await?
+    else {
+        return Err(ForkError::NotAccepted(block_id));
+    };
+
+    let status = match block.status {
+        BlockStatus::AcceptedOnL1 => FinalityStatus::AcceptedOnL1,
+        BlockStatus::AcceptedOnL2 => FinalityStatus::AcceptedOnL2,
+        _ => return Err(ForkError::NotAccepted(block_id)),
+    };
+
+    Ok((block, status))
+}
+
+/// Adjusts the genesis so that its block matches the forked one.
+pub(super) fn adjust_genesis(
+    genesis: &mut Genesis,
+    block: &BlockWithTxHashes,
+) -> Result<(), ForkError> {
+    let gas_price = |price: FieldElement| {
+        u128::try_from(price).map_err(|_| ForkError::InvalidBlock {
+            block: block.block_number,
+            reason: format!("gas price {price} doesn't fit in 128 bits"),
+        })
+    };
+
+    genesis.gas_prices.eth = gas_price(block.l1_gas_price.price_in_wei)?;
+    genesis.gas_prices.strk = gas_price(block.l1_gas_price.price_in_fri)?;
+    genesis.number = block.block_number;
+    genesis.state_root = block.new_root;
+    genesis.parent_hash = block.parent_hash;
+    genesis.timestamp = block.timestamp;
+    genesis.sequencer_address = block.sequencer_address.into();
+
+    Ok(())
+}
@@ -0,0 +1,322 @@
+//! Export of the chain state at a given block as a genesis configuration.
+
+use std::collections::{BTreeSet, HashMap};
+
+use anyhow::{anyhow, bail, Result};
+use ethers::types::U256;
+use katana_primitives::block::BlockNumber;
+use katana_primitives::contract::{ClassHash, CompiledContractClass, ContractAddress, StorageKey};
+use katana_primitives::conversion::rpc::{
+    flattened_sierra_to_json_artifact, legacy_inner_to_json_artifact,
+};
+use katana_primitives::genesis::allocation::GenesisAllocation;
+use katana_primitives::genesis::constant::{
+    get_fee_token_balance_base_storage_address, ERC20_DECIMAL_STORAGE_SLOT,
+    ERC20_NAME_STORAGE_SLOT, ERC20_SYMBOL_STORAGE_SLOT, ERC20_TOTAL_SUPPLY_STORAGE_SLOT,
+    OZ_ACCOUNT_CONTRACT_PUBKEY_STORAGE_SLOT,
+};
+use katana_primitives::genesis::json::{
+    FeeTokenConfigJson, GenesisAccountJson, GenesisClassJson, GenesisContractJson, GenesisJson,
+    PathOrFullArtifact, UniversalDeployerConfigJson,
+};
+use katana_primitives::genesis::Genesis;
+use katana_primitives::FieldElement;
+use katana_provider::traits::block::HeaderProvider;
+use katana_provider::traits::contract::LegacyClassAbiProvider;
+use katana_provider::traits::state::{StateFactoryProvider, StateProvider};
+use katana_provider::traits::state_update::StateUpdateProvider;
+use starknet::core::utils::parse_cairo_short_string;
+
+/// Exports the state of the chain at block `block` as a [GenesisJson], with all the declared
+/// classes embedded as artifacts.
+///
+/// The state updates of all the blocks up to `block` are replayed to find the declared classes,
+/// the deployed contracts and their storage keys, whose values are then read from the state at
+/// `block`. The exported genesis block takes the place of `block`, ie it has the same number,
+/// parent hash, timestamp and gas prices.
+///
+/// The `genesis` used to start the chain is needed to locate the fee tokens and the universal
+/// deployer, and to know which contracts are the prefunded accounts. The balances of the
+/// contracts are read from the fee token storage.
+pub fn export_genesis<P>(provider: &P, block: BlockNumber, genesis: &Genesis) -> Result<GenesisJson>
+where
+    P: HeaderProvider + StateUpdateProvider + StateFactoryProvider + LegacyClassAbiProvider,
+{
+    let header =
+        provider.header(block.into())?.ok_or_else(|| anyhow!("Block {block} not found"))?;
+    let state = provider
+        .historical(block.into())?
+        .ok_or_else(|| anyhow!("State at block {block} not found"))?;
+
+    let mut declared_classes: BTreeSet<ClassHash> = BTreeSet::new();
+    let mut contracts: BTreeSet<ContractAddress> = BTreeSet::new();
+    let mut storage_keys: HashMap<ContractAddress, BTreeSet<StorageKey>> = HashMap::new();
+
+    for num in 0..=block {
+        let Some(updates) = provider.state_update(num.into())? else {
+            continue;
+        };
+
+        declared_classes.extend(updates.declared_classes.into_keys());
+        contracts.extend(updates.contract_updates.into_keys());
+        contracts.extend(updates.nonce_updates.into_keys());
+
+        for (address, storage) in updates.storage_updates {
+            contracts.insert(address);
+            storage_keys.entry(address).or_default().extend(storage.into_keys());
+        }
+    }
+
+    let classes = declared_classes
+        .into_iter()
+        .map(|hash| {
+            let artifact = class_artifact(provider, state.as_ref(), hash)?;
+            Ok(GenesisClassJson {
+                class: PathOrFullArtifact::Artifact(artifact),
+                class_hash: Some(hash),
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let contract_storage = |address: ContractAddress| -> Result<HashMap<_, _>> {
+        let mut storage = HashMap::new();
+        for key in storage_keys.get(&address).into_iter().flatten() {
+            let value = state.storage(address, *key)?.unwrap_or_default();
+            if value != FieldElement::ZERO {
+                storage.insert(*key, value);
+            }
+        }
+        Ok(storage)
+    };
+
+    let fee_token_address = genesis.fee_token.address;
+    let mut fee_token_storage = contract_storage(fee_token_address)?;
+
+    // The balances are derived from the allocations when the genesis is loaded, so they are moved
+    // from the fee token storage to the accounts and contracts.
+    let mut balance_of = |address: ContractAddress| -> Option<U256> {
+        let base = get_fee_token_balance_base_storage_address(address);
+        let low = fee_token_storage.remove(&base).unwrap_or_default();
+        let high = fee_token_storage.remove(&(base + 1u8.into())).unwrap_or_default();
+        let balance = felt_to_u256(low) + (felt_to_u256(high) << 128);
+        (!balance.is_zero()).then_some(balance)
+    };
+
+    let strk_fee_token_address = genesis.strk_fee_token.address;
+    let udc_address = genesis.universal_deployer.as_ref().map(|udc| udc.address);
+
+    let mut accounts = HashMap::new();
+    let mut contracts_json = HashMap::new();
+
+    for address in contracts {
+        if address == fee_token_address
+            || address == strk_fee_token_address
+            || Some(address) == udc_address
+        {
+            continue;
+        }
+
+        let class = state.class_hash_of_contract(address)?;
+        let nonce = state.nonce(address)?.filter(|nonce| *nonce != FieldElement::ZERO);
+        let mut storage = contract_storage(address)?;
+        let balance = balance_of(address);
+
+        match genesis.allocations.get(&address) {
+            Some(alloc) if alloc.public_key().is_some() && class.is_some() => {
+                let public_key = storage
+                    .remove(&OZ_ACCOUNT_CONTRACT_PUBKEY_STORAGE_SLOT)
+                    .or(alloc.public_key())
+                    .unwrap_or_default();
+
+                let private_key = match alloc {
+                    GenesisAllocation::Account(account) => account.private_key(),
+                    GenesisAllocation::Contract(_) => None,
+                };
+
+                accounts.insert(
+                    address,
+                    GenesisAccountJson {
+                        public_key,
+                        balance,
+                        nonce,
+                        class,
+                        storage: (!storage.is_empty()).then_some(storage),
+                        private_key,
+                    },
+                );
+            }
+
+            _ => {
+                contracts_json.insert(
+                    address,
+                    GenesisContractJson {
+                        class,
+                        balance,
+                        nonce,
+                        storage: (!storage.is_empty()).then_some(storage),
+                    },
+                );
+            }
+        }
+    }
+
+    let universal_deployer = match udc_address {
+        Some(address) => match state.class_hash_of_contract(address)? {
+            Some(class) => {
+                let storage = contract_storage(address)?;
+                Some(UniversalDeployerConfigJson {
+                    address: Some(address),
+                    class: Some(class),
+                    storage: (!storage.is_empty()).then_some(storage),
+                })
+            }
+            None => None,
+        },
+        None => None,
+    };
+
+    // the total supply is computed from the balances of the allocations
+    fee_token_storage.remove(&ERC20_TOTAL_SUPPLY_STORAGE_SLOT);
+    fee_token_storage.remove(&(ERC20_TOTAL_SUPPLY_STORAGE_SLOT + 1u8.into()));
+    let fee_token = fee_token_json(state.as_ref(), fee_token_address, fee_token_storage)?;
+
+    // The balances of the allocations are the ETH ones. As the STRK fee token storage takes
+    // precedence over them when the genesis is loaded, the STRK balances are kept there, including
+    // the zero ones, along with the total supply.
+    let mut strk_fee_token_storage = contract_storage(strk_fee_token_address)?;
+    let balances = accounts
+        .iter()
+        .map(|(address, account)| (address, account.balance))
+        .chain(contracts_json.iter().map(|(address, contract)| (address, contract.balance)));
+
+    for (address, _) in balances.filter(|(_, balance)| balance.is_some()) {
+        let base = get_fee_token_balance_base_storage_address(*address);
+        strk_fee_token_storage.entry(base).or_default();
+        strk_fee_token_storage.entry(base + 1u8.into()).or_default();
+    }
+
+    let strk_fee_token =
+        fee_token_json(state.as_ref(), strk_fee_token_address, strk_fee_token_storage)?;
+
+    Ok(GenesisJson {
+        parent_hash: header.parent_hash,
+        state_root: header.state_root,
+        number: header.number,
+        timestamp: header.timestamp,
+        sequencer_address: header.sequencer_address,
+        gas_prices: header.gas_prices,
+        classes,
+        fee_token,
+        strk_fee_token: Some(strk_fee_token),
+        universal_deployer,
+        accounts,
+        contracts: contracts_json,
+        tokens: HashMap::new(),
+    })
+}
+
+/// Returns the configuration of the fee token at `address`, whose metadata is moved out of its
+/// `storage`.
+fn fee_token_json(
+    state: &dyn StateProvider,
+    address: ContractAddress,
+    mut storage: HashMap<StorageKey, FieldElement>,
+) -> Result<FeeTokenConfigJson> {
+    let class = state
+        .class_hash_of_contract(address)?
+        .ok_or_else(|| anyhow!("Fee token contract {address} is not deployed"))?;
+
+    let name = storage.remove(&ERC20_NAME_STORAGE_SLOT).unwrap_or_default();
+    let symbol = storage.remove(&ERC20_SYMBOL_STORAGE_SLOT).unwrap_or_default();
+    let decimals = storage.remove(&ERC20_DECIMAL_STORAGE_SLOT).unwrap_or_default();
+
+    Ok(FeeTokenConfigJson {
+        name: parse_cairo_short_string(&name)?,
+        symbol: parse_cairo_short_string(&symbol)?,
+        address: Some(address),
+        decimals: u128::try_from(decimals)
+            .ok()
+            .and_then(|decimals| u8::try_from(decimals).ok())
+            .ok_or_else(|| anyhow!("Invalid fee token decimals {decimals:#x}"))?,
+        class: Some(class),
+        storage: (!storage.is_empty()).then_some(storage),
+    })
+}
+
+/// Returns the artifact of the class `hash`. The ABI of a legacy class isn't part of its compiled
+/// class, so it's read from the ABIs stored alongside the classes.
+fn class_artifact(
+    abis: &impl LegacyClassAbiProvider,
+    state: &dyn StateProvider,
+    hash: ClassHash,
+) -> Result<serde_json::Value> {
+    if let Some(sierra) = state.sierra_class(hash)? {
+        return flattened_sierra_to_json_artifact(&sierra);
+    }
+
+    match state.class(hash)? {
+        Some(CompiledContractClass::V0(class)) => {
+            let abi = abis.legacy_class_abi(hash)?.unwrap_or_default();
+            legacy_inner_to_json_artifact(class, &abi)
+        }
+        Some(CompiledContractClass::V1(_)) => bail!("Missing Sierra class of class {hash:#x}"),
+        None => bail!("Missing class {hash:#x}"),
+    }
+}
+
+fn felt_to_u256(value: FieldElement) -> U256 {
+    U256::from_big_endian(&value.to_bytes_be())
+}
+
+#[cfg(test)]
+mod tests {
+    use katana_primitives::contract::ClassHash;
+    use katana_primitives::genesis::allocation::DevAllocationsGenerator;
+    use katana_primitives::genesis::constant::{
+        DEFAULT_LEGACY_ERC20_CONTRACT_ABI, DEFAULT_LEGACY_ERC20_CONTRACT_CLASS_HASH,
+        DEFAULT_LEGACY_UDC_ABI, DEFAULT_LEGACY_UDC_CLASS_HASH, DEFAULT_PREFUNDED_ACCOUNT_BALANCE,
+    };
+    use katana_primitives::genesis::Genesis;
+    use katana_provider::providers::in_memory::InMemoryProvider;
+
+    use super::export_genesis;
+    use crate::backend::storage::Blockchain;
+
+    #[test]
+    fn export_and_load_genesis() {
+        let mut genesis = Genesis::default();
+        let accounts = DevAllocationsGenerator::new(3)
+            .with_balance(DEFAULT_PREFUNDED_ACCOUNT_BALANCE)
+            .generate();
+        genesis.extend_allocations(accounts.into_iter().map(|(k, v)| (k, v.into())));
+
+        let blockchain = Blockchain::new_with_genesis(InMemoryProvider::new(), &genesis).unwrap();
+        let exported = export_genesis(blockchain.provider(), 0, &genesis).unwrap();
+
+        assert_eq!(exported.accounts.len(), 3);
+        assert!(exported.contracts.is_empty());
+
+        let loaded = Genesis::try_from(exported).unwrap();
+        assert_eq!(loaded.allocations, genesis.allocations);
+        assert_eq!(loaded.universal_deployer, genesis.universal_deployer);
+        assert_eq!(loaded.fee_token.name, genesis.fee_token.name);
+        assert_eq!(loaded.fee_token.symbol, genesis.fee_token.symbol);
+        assert_eq!(loaded.fee_token.decimals, genesis.fee_token.decimals);
+        assert_eq!(loaded.fee_token.class_hash, genesis.fee_token.class_hash);
+        assert_eq!(loaded.strk_fee_token.address, genesis.strk_fee_token.address);
+        assert_eq!(loaded.strk_fee_token.symbol, genesis.strk_fee_token.symbol);
+        assert_eq!(
+            loaded.state_updates().state_updates.declared_classes,
+            genesis.state_updates().state_updates.declared_classes
+        );
+
+        // the ABIs of the legacy classes are exported along with their definitions
+        let legacy_abi =
+            |hash: ClassHash| loaded.classes.get(&hash).and_then(|c| c.legacy_abi.as_deref());
+        assert_eq!(legacy_abi(DEFAULT_LEGACY_UDC_CLASS_HASH), Some(&*DEFAULT_LEGACY_UDC_ABI));
+        assert_eq!(
+            legacy_abi(DEFAULT_LEGACY_ERC20_CONTRACT_CLASS_HASH),
+            Some(&*DEFAULT_LEGACY_ERC20_CONTRACT_ABI)
+        );
+    }
+}
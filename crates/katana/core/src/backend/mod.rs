@@ -1,7 +1,11 @@
 use std::sync::Arc;
+use std::time::Instant;
 
+use katana_db::init_db;
+use katana_executor::abstraction::Executor;
+use katana_executor::blockifier::BlockifierExecutor;
 use katana_primitives::block::{
-    Block, FinalityStatus, GasPrices, Header, PartialHeader, SealedBlockWithStatus,
+    Block, BlockIdOrTag, FinalityStatus, GasPrices, Header, PartialHeader, SealedBlockWithStatus,
 };
 use katana_primitives::chain::ChainId;
 use katana_primitives::env::{BlockEnv, CfgEnv, FeeTokenAddressses};
@@ -10,22 +14,28 @@ use katana_primitives::state::StateUpdatesWithDeclaredClasses;
 use katana_primitives::transaction::TxWithHash;
 use katana_primitives::version::CURRENT_STARKNET_VERSION;
 use katana_primitives::FieldElement;
+use katana_provider::providers::db::DbProvider;
 use katana_provider::providers::fork::ForkedProvider;
 use katana_provider::providers::in_memory::InMemoryProvider;
 use katana_provider::traits::block::{BlockHashProvider, BlockWriter};
 use katana_provider::traits::state::{StateFactoryProvider, StateProvider};
+use metrics::{gauge, histogram};
 use parking_lot::RwLock;
-use starknet::core::types::{BlockId, BlockStatus, MaybePendingBlockWithTxHashes};
 use starknet::core::utils::parse_cairo_short_string;
 use starknet::providers::jsonrpc::HttpTransport;
 use starknet::providers::{JsonRpcClient, Provider};
 use tracing::{info, trace};
+use url::Url;
 
 pub mod config;
 pub mod contract;
+pub mod fork;
+pub mod genesis;
+pub mod replay;
 pub mod storage;
 
 use self::config::StarknetConfig;
+use self::fork::{Fork, ForkError, ForkPoint};
 use self::storage::Blockchain;
 use crate::constants::MAX_RECURSION_DEPTH;
 use crate::env::{get_default_vm_resource_fee_cost, BlockContextGenerator};
@@ -41,43 +51,40 @@ pub struct Backend {
     pub chain_id: ChainId,
     /// The block context generator.
     pub block_context_generator: RwLock<BlockContextGenerator>,
+    /// The executor used to execute transactions.
+    pub executor: Arc<dyn Executor>,
+    /// The network the chain is forked from, if any.
+    pub fork: Option<Fork>,
 }
 
 impl Backend {
-    pub async fn new(mut config: StarknetConfig) -> Self {
+    /// Creates a backend that executes the transactions with the blockifier.
+    pub async fn new(config: StarknetConfig) -> Self {
+        let executor =
+            BlockifierExecutor::default().with_parallel_execution(config.parallel_execution);
+        Self::new_with_executor(config, Arc::new(executor)).await
+    }
+
+    /// Creates a backend that executes the transactions with `executor`.
+    pub async fn new_with_executor(
+        mut config: StarknetConfig,
+        executor: Arc<dyn Executor>,
+    ) -> Self {
         let block_context_generator = config.block_context_generator();
 
-        let (blockchain, chain_id): (Blockchain, ChainId) = if let Some(forked_url) =
+        let (blockchain, chain_id, fork): (Blockchain, ChainId, _) = if let Some(forked_url) =
             &config.fork_rpc_url
         {
-            let provider = Arc::new(JsonRpcClient::new(HttpTransport::new(forked_url.clone())));
-            let forked_chain_id = provider.chain_id().await.unwrap();
-
-            let forked_block_num = if let Some(num) = config.fork_block_number {
-                num
-            } else {
-                provider
-                    .block_number()
-                    .await
-                    .expect("failed to fetch block number from forked network")
-            };
+            let client = Arc::new(JsonRpcClient::new(HttpTransport::new(forked_url.clone())));
+            let forked_chain_id = client.chain_id().await.unwrap();
 
-            let block =
-                provider.get_block_with_tx_hashes(BlockId::Number(forked_block_num)).await.unwrap();
-            let MaybePendingBlockWithTxHashes::Block(block) = block else {
-                panic!("block to be forked is a pending block")
-            };
+            let (block, status) = fork::fetch_forked_block(&client, config.fork_block)
+                .await
+                .expect("failed to fetch the block to fork from");
 
             // adjust the genesis to match the forked block
-            config.genesis.number = block.block_number;
-            config.genesis.state_root = block.new_root;
-            config.genesis.parent_hash = block.parent_hash;
-            config.genesis.timestamp = block.timestamp;
-            config.genesis.sequencer_address = block.sequencer_address.into();
-            config.genesis.gas_prices.eth =
-                block.l1_gas_price.price_in_wei.try_into().expect("should fit in u128");
-            config.genesis.gas_prices.strk =
-                block.l1_gas_price.price_in_fri.try_into().expect("should fit in u128");
+            fork::adjust_genesis(&mut config.genesis, &block)
+                .expect("failed to adjust the genesis to the forked block");
 
             trace!(
                 target: "backend",
@@ -87,30 +94,51 @@ impl Backend {
                 forked_url
             );
 
+            let provider =
+                Arc::new(ForkedProvider::new(client, block.block_number.into()).unwrap());
             let blockchain = Blockchain::new_from_forked(
-                ForkedProvider::new(provider, forked_block_num.into()).unwrap(),
+                Arc::clone(&provider),
                 block.block_hash,
                 &config.genesis,
-                match block.status {
-                    BlockStatus::AcceptedOnL1 => FinalityStatus::AcceptedOnL1,
-                    BlockStatus::AcceptedOnL2 => FinalityStatus::AcceptedOnL2,
-                    _ => panic!("unable to fork for non-accepted block"),
-                },
+                status,
             )
             .expect("able to create forked blockchain");
 
-            (blockchain, forked_chain_id.into())
+            let fork = Fork::new(forked_url.clone(), provider);
+            (blockchain, forked_chain_id.into(), Some(fork))
+        } else if let Some(follow_url) = &config.follow_rpc_url {
+            let provider = JsonRpcClient::new(HttpTransport::new(follow_url.clone()));
+            let chain_id =
+                provider.chain_id().await.expect("failed to fetch chain id from followed node");
+
+            trace!(
+                target: "backend",
+                "following chain `{}` from {}",
+                parse_cairo_short_string(&chain_id).unwrap(),
+                follow_url
+            );
+
+            // The blocks, including the genesis one, are synced from the followed node.
+            let blockchain = if let Some(db_path) = &config.db_dir {
+                let db = init_db(db_path).expect("able to open database");
+                Blockchain::new(DbProvider::new(db))
+            } else {
+                Blockchain::new(InMemoryProvider::new())
+            };
+
+            (blockchain, chain_id.into(), None)
         } else if let Some(db_path) = &config.db_dir {
             (
                 Blockchain::new_with_db(db_path, &config.genesis)
                     .expect("able to create blockchain from db"),
                 config.env.chain_id,
+                None,
             )
         } else {
             let blockchain = Blockchain::new_with_genesis(InMemoryProvider::new(), &config.genesis)
                 .expect("able to create blockchain from genesis block");
 
-            (blockchain, config.env.chain_id)
+            (blockchain, config.env.chain_id, None)
         };
 
         Self {
@@ -118,9 +146,62 @@ impl Backend {
             blockchain,
             config,
             block_context_generator: RwLock::new(block_context_generator),
+            executor,
+            fork,
         }
     }
 
+    /// Fetches the block to fork the chain from again, `block` of the network at `url`. The
+    /// currently forked network and its latest block are used by default.
+    ///
+    /// The network has to be on the same chain as the one the node has been started with.
+    pub async fn fork_point(
+        &self,
+        url: Option<Url>,
+        block: Option<BlockIdOrTag>,
+    ) -> Result<ForkPoint, ForkError> {
+        let fork = self.fork.as_ref().ok_or(ForkError::NotForked)?;
+        let url = url.unwrap_or_else(|| fork.url());
+        let client = Arc::new(JsonRpcClient::new(HttpTransport::new(url.clone())));
+
+        let chain_id = ChainId::from(client.chain_id().await?);
+        if chain_id != self.chain_id {
+            let expected = self.chain_id;
+            return Err(ForkError::ChainIdMismatch { url, expected, actual: chain_id });
+        }
+
+        let (block, status) = fork::fetch_forked_block(&client, block).await?;
+        Ok(ForkPoint { url, client, block, status })
+    }
+
+    /// Drops all the blocks of the chain and forks it again at the given point, as if the node
+    /// had been restarted from it.
+    ///
+    /// No block must be mined during the reset, see
+    /// [BlockProducer::reset_fork](crate::service::block_producer::BlockProducer::reset_fork).
+    pub fn reset_fork(&self, point: ForkPoint) -> Result<(), ForkError> {
+        let fork = self.fork.as_ref().ok_or(ForkError::NotForked)?;
+        let ForkPoint { url, client, block, status } = point;
+
+        let mut genesis = self.config.genesis.clone();
+        fork::adjust_genesis(&mut genesis, &block)?;
+
+        fork.provider().reset(client, block.block_number.into())?;
+        BlockWriter::insert_block_with_states_and_receipts(
+            fork.provider(),
+            genesis.block().seal_with_hash_and_status(block.block_hash, status),
+            genesis.state_updates(),
+            vec![],
+        )?;
+
+        *self.block_context_generator.write() = self.config.block_context_generator();
+
+        info!(target: "backend", "🍴 Forked block {} from {url}", block.block_number);
+        fork.set_url(url);
+
+        Ok(())
+    }
+
     /// Mines a new block based on the provided execution outcome.
     /// This method should only be called by the
     /// [IntervalBlockProducer](crate::service::block_producer::IntervalBlockProducer) when the node
@@ -164,12 +245,22 @@ impl Backend {
         let block = Block { header, body: txs }.seal();
         let block = SealedBlockWithStatus { block, status: FinalityStatus::AcceptedOnL2 };
 
+        let started_at = Instant::now();
         BlockWriter::insert_block_with_states_and_receipts(
             self.blockchain.provider(),
             block,
             state_updates,
             receipts,
         )?;
+        histogram!("db.commit_duration_seconds", started_at.elapsed());
+        histogram!("block_producer.block_transactions", tx_count as f64);
+
+        if let Some(db_dir) = &self.config.db_dir {
+            // the data of an MDBX environment is stored in a single file
+            if let Ok(metadata) = std::fs::metadata(db_dir.join("mdbx.dat")) {
+                gauge!("db.size", metadata.len() as f64);
+            }
+        }
 
         info!(target: "backend", "⛏️ Block {block_number} mined with {tx_count} transactions");
 
@@ -194,8 +285,8 @@ impl Backend {
         block_env.l1_gas_prices = self.config.env.gas_price.clone();
     }
 
-    /// Retrieves the chain configuration environment values.
-    pub(crate) fn chain_cfg_env(&self) -> CfgEnv {
+    /// Retrieves the chain configuration environment values, used to execute the transactions.
+    pub fn chain_cfg_env(&self) -> CfgEnv {
         CfgEnv {
             chain_id: self.chain_id,
             vm_resource_fee_cost: get_default_vm_resource_fee_cost(),
@@ -204,7 +295,7 @@ impl Backend {
             max_recursion_depth: MAX_RECURSION_DEPTH,
             fee_token_addresses: FeeTokenAddressses {
                 eth: self.config.genesis.fee_token.address,
-                strk: Default::default(),
+                strk: self.config.genesis.strk_fee_token.address,
             },
         }
     }
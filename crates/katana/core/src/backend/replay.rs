@@ -0,0 +1,558 @@
+//! Re-execution of stored blocks, to verify that executing their transactions again produces the
+//! same receipts and state updates as the ones stored in the database.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use anyhow::{anyhow, bail, Result};
+use katana_executor::abstraction::{BlockExecutionOutput, ExecutionFlags, Executor};
+use katana_primitives::block::BlockNumber;
+use katana_primitives::env::CfgEnv;
+use katana_primitives::receipt::{Event, MessageToL1, Receipt};
+use katana_primitives::state::StateUpdates;
+use katana_primitives::transaction::{
+    DeclareTxWithClass, ExecutableTx, ExecutableTxWithHash, Tx, TxHash, TxWithHash,
+};
+use katana_primitives::FieldElement;
+use katana_provider::traits::env::BlockEnvProvider;
+use katana_provider::traits::state::{StateFactoryProvider, StateProvider};
+use katana_provider::traits::state_update::StateUpdateProvider;
+use katana_provider::traits::transaction::{ReceiptProvider, TransactionProvider};
+
+/// The outcome of the replay of a block.
+#[derive(Debug)]
+pub struct BlockReplay {
+    /// The number of the replayed block.
+    pub block: BlockNumber,
+    /// The number of transactions re-executed.
+    pub transaction_count: usize,
+    /// The differences between the stored block and its replay.
+    pub divergences: Vec<Divergence>,
+}
+
+impl BlockReplay {
+    /// Returns `true` if the replay produced exactly what is stored for the block.
+    pub fn is_consistent(&self) -> bool {
+        self.divergences.is_empty()
+    }
+}
+
+/// A value that differs between the stored block and its replay.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Divergence {
+    /// What diverged, eg the fee of a transaction or a storage slot.
+    pub subject: String,
+    /// The stored value, `None` if there is no such value in the database.
+    pub stored: Option<String>,
+    /// The replayed value, `None` if the replay didn't produce such value.
+    pub replayed: Option<String>,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let stored = self.stored.as_deref().unwrap_or("<none>");
+        let replayed = self.replayed.as_deref().unwrap_or("<none>");
+        writeln!(f, "{}", self.subject)?;
+        writeln!(f, "  - stored:   {stored}")?;
+        write!(f, "  + replayed: {replayed}")
+    }
+}
+
+/// Re-executes the transactions of `block` on top of the state of its parent block, and compares
+/// the resulting receipts, events and state updates with the ones stored for the block.
+///
+/// `cfg_env` and `flags` must be the same as the ones the block was originally executed with,
+/// otherwise fees and validation related state changes will diverge.
+pub fn replay_block<P>(
+    provider: &P,
+    executor: &dyn Executor,
+    cfg_env: &CfgEnv,
+    flags: ExecutionFlags,
+    block: BlockNumber,
+) -> Result<BlockReplay>
+where
+    P: BlockEnvProvider
+        + TransactionProvider
+        + ReceiptProvider
+        + StateUpdateProvider
+        + StateFactoryProvider,
+{
+    if block == 0 {
+        bail!("The genesis block can't be replayed");
+    }
+
+    let not_found = || anyhow!("Block {block} not found");
+
+    let block_env = provider.block_env_at(block.into())?.ok_or_else(not_found)?;
+    let transactions = provider.transactions_by_block(block.into())?.ok_or_else(not_found)?;
+    let receipts = provider.receipts_by_block(block.into())?.ok_or_else(not_found)?;
+    let stored_states = provider.state_update(block.into())?.ok_or_else(not_found)?;
+
+    if receipts.len() != transactions.len() {
+        bail!(
+            "Block {block} has {} transactions but {} receipts",
+            transactions.len(),
+            receipts.len()
+        );
+    }
+
+    let parent_state = provider
+        .historical((block - 1).into())?
+        .ok_or_else(|| anyhow!("State at block {} not found", block - 1))?;
+    // The classes declared in the block are only available from the state of the block itself.
+    let block_state = provider
+        .historical(block.into())?
+        .ok_or_else(|| anyhow!("State at block {block} not found"))?;
+
+    let executables = transactions
+        .iter()
+        .cloned()
+        .map(|tx| executable_tx(block_state.as_ref(), tx))
+        .collect::<Result<Vec<_>>>()?;
+
+    let BlockExecutionOutput { transactions: executed, rejected, states } =
+        executor.execute_block(&block_env, cfg_env, parent_state, executables, flags)?;
+
+    let executed: BTreeMap<TxHash, Receipt> =
+        executed.into_iter().map(|(tx, receipt)| (tx.hash, receipt)).collect();
+    let rejected: BTreeMap<TxHash, String> =
+        rejected.into_iter().map(|(tx, err)| (tx.hash, err.to_string())).collect();
+
+    let mut divergences = Vec::new();
+
+    for (idx, (tx, stored)) in transactions.iter().zip(&receipts).enumerate() {
+        let subject = format!("transaction #{idx} ({:#x})", tx.hash);
+
+        if let Some(replayed) = executed.get(&tx.hash) {
+            diff_receipts(&subject, stored, replayed, &mut divergences);
+        } else {
+            divergences.push(Divergence {
+                subject,
+                stored: Some("included in the block".to_string()),
+                replayed: rejected.get(&tx.hash).map(|err| format!("rejected: {err}")),
+            });
+        }
+    }
+
+    diff_state_updates(&stored_states, &states.state_updates, &mut divergences);
+
+    Ok(BlockReplay { block, transaction_count: transactions.len(), divergences })
+}
+
+/// Attaches the classes declared by `tx` to it, so that it can be executed again.
+fn executable_tx(state: &dyn StateProvider, tx: TxWithHash) -> Result<ExecutableTxWithHash> {
+    let transaction = match tx.transaction {
+        Tx::Invoke(tx) => ExecutableTx::Invoke(tx),
+        Tx::L1Handler(tx) => ExecutableTx::L1Handler(tx),
+        Tx::DeployAccount(tx) => ExecutableTx::DeployAccount(tx),
+        Tx::Declare(declare) => {
+            let class_hash = declare.class_hash();
+            let compiled_class = state.class(class_hash)?.ok_or_else(|| {
+                anyhow!("Class {class_hash:#x} declared by transaction {:#x} not found", tx.hash)
+            })?;
+            let sierra_class = state.sierra_class(class_hash)?;
+
+            // the ABI of a legacy class isn't needed to execute the transaction
+            ExecutableTx::Declare(DeclareTxWithClass {
+                sierra_class,
+                compiled_class,
+                legacy_abi: None,
+                transaction: declare,
+            })
+        }
+    };
+
+    Ok(ExecutableTxWithHash { hash: tx.hash, transaction })
+}
+
+fn diff_receipts(subject: &str, stored: &Receipt, replayed: &Receipt, out: &mut Vec<Divergence>) {
+    diff_values(
+        format!("{subject}: actual fee"),
+        stored.actual_fee(),
+        replayed.actual_fee(),
+        |fee| fee.to_string(),
+        out,
+    );
+
+    if stored.revert_error() != replayed.revert_error() {
+        out.push(Divergence {
+            subject: format!("{subject}: revert error"),
+            stored: stored.revert_error().map(ToString::to_string),
+            replayed: replayed.revert_error().map(ToString::to_string),
+        });
+    }
+
+    diff_entries(
+        indexed(stored.events()),
+        indexed(replayed.events()),
+        |idx| format!("{subject}: event #{idx}"),
+        |event| format_event(event),
+        out,
+    );
+
+    diff_entries(
+        indexed(stored.messages_sent()),
+        indexed(replayed.messages_sent()),
+        |idx| format!("{subject}: message #{idx}"),
+        |message| format_message(message),
+        out,
+    );
+
+    diff_values(
+        format!("{subject}: execution resources"),
+        stored.execution_resources(),
+        replayed.execution_resources(),
+        |resources| format!("{resources:?}"),
+        out,
+    );
+}
+
+fn diff_state_updates(stored: &StateUpdates, replayed: &StateUpdates, out: &mut Vec<Divergence>) {
+    diff_entries(
+        stored.nonce_updates.iter().collect(),
+        replayed.nonce_updates.iter().collect(),
+        |address| format!("nonce of {address}"),
+        |nonce| format!("{nonce:#x}"),
+        out,
+    );
+
+    let storage_entries = |updates: &StateUpdates| {
+        updates
+            .storage_updates
+            .iter()
+            .flat_map(|(address, storage)| {
+                storage.iter().map(move |(key, value)| ((*address, *key), *value))
+            })
+            .collect::<BTreeMap<_, _>>()
+    };
+
+    diff_entries(
+        storage_entries(stored),
+        storage_entries(replayed),
+        |(address, key)| format!("storage of {address} at key {key:#x}"),
+        |value| format!("{value:#x}"),
+        out,
+    );
+
+    diff_entries(
+        stored.contract_updates.iter().collect(),
+        replayed.contract_updates.iter().collect(),
+        |address| format!("class hash of contract {address}"),
+        |hash| format!("{hash:#x}"),
+        out,
+    );
+
+    diff_entries(
+        stored.declared_classes.iter().collect(),
+        replayed.declared_classes.iter().collect(),
+        |hash| format!("compiled class hash of declared class {hash:#x}"),
+        |hash| format!("{hash:#x}"),
+        out,
+    );
+}
+
+fn diff_values<T: PartialEq>(
+    subject: String,
+    stored: T,
+    replayed: T,
+    display: impl Fn(&T) -> String,
+    out: &mut Vec<Divergence>,
+) {
+    if stored != replayed {
+        out.push(Divergence {
+            subject,
+            stored: Some(display(&stored)),
+            replayed: Some(display(&replayed)),
+        });
+    }
+}
+
+/// Compares two sets of entries, reporting the ones that differ or only exist on one side.
+fn diff_entries<K: Ord, V: PartialEq>(
+    stored: BTreeMap<K, V>,
+    mut replayed: BTreeMap<K, V>,
+    subject: impl Fn(&K) -> String,
+    display: impl Fn(&V) -> String,
+    out: &mut Vec<Divergence>,
+) {
+    let mut divergences = BTreeMap::new();
+
+    for (key, stored) in stored {
+        match replayed.remove(&key) {
+            Some(replayed) if replayed == stored => {}
+            replayed => {
+                let replayed = replayed.as_ref().map(&display);
+                divergences.insert(key, (Some(display(&stored)), replayed));
+            }
+        }
+    }
+
+    for (key, replayed) in replayed {
+        divergences.insert(key, (None, Some(display(&replayed))));
+    }
+
+    out.extend(divergences.into_iter().map(|(key, (stored, replayed))| Divergence {
+        subject: subject(&key),
+        stored,
+        replayed,
+    }));
+}
+
+fn indexed<T>(items: &[T]) -> BTreeMap<usize, &T> {
+    items.iter().enumerate().collect()
+}
+
+fn format_event(event: &Event) -> String {
+    format!(
+        "from {} keys [{}] data [{}]",
+        event.from_address,
+        format_felts(&event.keys),
+        format_felts(&event.data)
+    )
+}
+
+fn format_message(message: &MessageToL1) -> String {
+    format!(
+        "from {} to {:#x} payload [{}]",
+        message.from_address,
+        message.to_address,
+        format_felts(&message.payload)
+    )
+}
+
+fn format_felts(felts: &[FieldElement]) -> String {
+    felts.iter().map(|felt| format!("{felt:#x}")).collect::<Vec<_>>().join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use katana_executor::abstraction::{BlockExecutionOutput, ExecutionFlags};
+    use katana_primitives::contract::ContractAddress;
+    use katana_primitives::genesis::constant::{
+        DEFAULT_ARGENT_ACCOUNT_CONTRACT, DEFAULT_ARGENT_ACCOUNT_CONTRACT_CASM,
+        DEFAULT_ARGENT_ACCOUNT_CONTRACT_CLASS_HASH,
+        DEFAULT_ARGENT_ACCOUNT_CONTRACT_COMPILED_CLASS_HASH,
+        DEFAULT_OZ_ACCOUNT_CONTRACT_CLASS_HASH,
+    };
+    use katana_primitives::receipt::{InvokeTxReceipt, Receipt};
+    use katana_primitives::state::{StateUpdates, StateUpdatesWithDeclaredClasses};
+    use katana_primitives::transaction::{
+        DeclareTx, DeclareTxV2, DeclareTxWithClass, DeployAccountTx, DeployAccountTxV1,
+        ExecutableTx, ExecutableTxWithHash, InvokeTx, InvokeTxV1,
+    };
+    use katana_primitives::FieldElement;
+    use katana_provider::traits::block::BlockNumberProvider;
+    use katana_provider::traits::env::BlockEnvProvider;
+    use katana_provider::traits::state::StateFactoryProvider;
+    use starknet::core::utils::{get_contract_address, get_selector_from_name};
+
+    use super::{diff_receipts, diff_state_updates, replay_block, Divergence};
+    use crate::backend::config::StarknetConfig;
+    use crate::backend::Backend;
+
+    fn felt(value: u64) -> FieldElement {
+        FieldElement::from(value)
+    }
+
+    #[test]
+    fn receipts_divergences() {
+        let stored = Receipt::Invoke(InvokeTxReceipt { actual_fee: 100, ..Default::default() });
+        let replayed = Receipt::Invoke(InvokeTxReceipt {
+            actual_fee: 120,
+            revert_error: Some("Out of gas".to_string()),
+            ..Default::default()
+        });
+
+        let mut divergences = Vec::new();
+        diff_receipts("transaction #0 (0x1)", &stored, &replayed, &mut divergences);
+
+        assert_eq!(
+            divergences,
+            vec![
+                Divergence {
+                    subject: "transaction #0 (0x1): actual fee".to_string(),
+                    stored: Some("100".to_string()),
+                    replayed: Some("120".to_string()),
+                },
+                Divergence {
+                    subject: "transaction #0 (0x1): revert error".to_string(),
+                    stored: None,
+                    replayed: Some("Out of gas".to_string()),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn state_updates_divergences() {
+        let address = ContractAddress::from(felt(0x1));
+
+        let mut stored = StateUpdates::default();
+        stored.nonce_updates.insert(address, felt(1));
+        stored
+            .storage_updates
+            .insert(address, [(felt(0x10), felt(5)), (felt(0x11), felt(6))].into());
+
+        let mut replayed = StateUpdates::default();
+        replayed.nonce_updates.insert(address, felt(1));
+        replayed.storage_updates.insert(address, [(felt(0x10), felt(7))].into());
+        replayed.contract_updates.insert(address, felt(0x123));
+
+        let mut divergences = Vec::new();
+        diff_state_updates(&stored, &replayed, &mut divergences);
+
+        assert_eq!(
+            divergences,
+            vec![
+                Divergence {
+                    subject: "storage of 0x1 at key 0x10".to_string(),
+                    stored: Some("0x5".to_string()),
+                    replayed: Some("0x7".to_string()),
+                },
+                Divergence {
+                    subject: "storage of 0x1 at key 0x11".to_string(),
+                    stored: Some("0x6".to_string()),
+                    replayed: None,
+                },
+                Divergence {
+                    subject: "class hash of contract 0x1".to_string(),
+                    stored: None,
+                    replayed: Some("0x123".to_string()),
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn replay_stored_blocks() {
+        let backend = Backend::new(StarknetConfig::default()).await;
+        let provider = backend.blockchain.provider();
+        let cfg_env = backend.chain_cfg_env();
+        let flags = ExecutionFlags::default();
+
+        let mut block_env = provider.block_env_at(0.into()).unwrap().unwrap();
+        backend.update_block_env(&mut block_env);
+        backend.mine_empty_block(&block_env).unwrap();
+
+        let replay = replay_block(provider, backend.executor.as_ref(), &cfg_env, flags, 1).unwrap();
+        assert_eq!(replay.transaction_count, 0);
+        assert!(replay.is_consistent());
+
+        // A block whose stored state update can't be the result of executing its transactions.
+        let address = ContractAddress::from(felt(0x1));
+        let mut states = StateUpdatesWithDeclaredClasses::default();
+        states.state_updates.storage_updates.insert(address, [(felt(0x10), felt(5))].into());
+
+        let mut block_env = provider.block_env_at(1.into()).unwrap().unwrap();
+        backend.update_block_env(&mut block_env);
+        backend.do_mine_block(&block_env, Default::default(), states).unwrap();
+        assert_eq!(provider.latest_number().unwrap(), 2);
+
+        let replay = replay_block(provider, backend.executor.as_ref(), &cfg_env, flags, 2).unwrap();
+        assert_eq!(
+            replay.divergences,
+            vec![Divergence {
+                subject: "storage of 0x1 at key 0x10".to_string(),
+                stored: Some("0x5".to_string()),
+                replayed: None,
+            }]
+        );
+
+        assert!(replay_block(provider, backend.executor.as_ref(), &cfg_env, flags, 0).is_err());
+    }
+
+    #[tokio::test]
+    async fn replay_block_with_transactions() {
+        const MAX_FEE: u128 = 1_000_000_000_000_000_000;
+
+        let config = StarknetConfig { disable_validate: true, ..Default::default() };
+        let backend = Backend::new(config).await;
+        let provider = backend.blockchain.provider();
+        let cfg_env = backend.chain_cfg_env();
+        let chain_id = backend.chain_id;
+        // the transactions aren't signed
+        let flags = ExecutionFlags { charge_fee: true, validate: false };
+
+        let mut accounts = backend.config.genesis.accounts().map(|(a, _)| *a).collect::<Vec<_>>();
+        accounts.sort();
+
+        let declare = DeclareTxWithClass {
+            sierra_class: Some(DEFAULT_ARGENT_ACCOUNT_CONTRACT.clone().flatten().unwrap()),
+            compiled_class: DEFAULT_ARGENT_ACCOUNT_CONTRACT_CASM.clone(),
+            legacy_abi: None,
+            transaction: DeclareTx::V2(DeclareTxV2 {
+                chain_id,
+                sender_address: accounts[0],
+                nonce: FieldElement::ZERO,
+                signature: Vec::new(),
+                class_hash: DEFAULT_ARGENT_ACCOUNT_CONTRACT_CLASS_HASH,
+                compiled_class_hash: *DEFAULT_ARGENT_ACCOUNT_CONTRACT_COMPILED_CLASS_HASH,
+                max_fee: MAX_FEE,
+            }),
+        };
+
+        // the account to deploy is funded in the same block
+        let salt = felt(0x5a17);
+        let constructor_calldata = vec![felt(0x1234)];
+        let new_account = ContractAddress::from(get_contract_address(
+            salt,
+            DEFAULT_OZ_ACCOUNT_CONTRACT_CLASS_HASH,
+            &constructor_calldata,
+            FieldElement::ZERO,
+        ));
+
+        let transfer = InvokeTx::V1(InvokeTxV1 {
+            chain_id,
+            sender_address: accounts[1],
+            nonce: FieldElement::ZERO,
+            calldata: vec![
+                FieldElement::ONE,
+                backend.config.genesis.fee_token.address.into(),
+                get_selector_from_name("transfer").unwrap(),
+                FieldElement::THREE,
+                new_account.into(),
+                felt(2 * MAX_FEE as u64),
+                FieldElement::ZERO,
+            ],
+            signature: Vec::new(),
+            max_fee: MAX_FEE,
+        });
+
+        let deploy_account = DeployAccountTx::V1(DeployAccountTxV1 {
+            chain_id,
+            nonce: FieldElement::ZERO,
+            signature: Vec::new(),
+            class_hash: DEFAULT_OZ_ACCOUNT_CONTRACT_CLASS_HASH,
+            contract_address: new_account,
+            contract_address_salt: salt,
+            constructor_calldata,
+            max_fee: MAX_FEE,
+        });
+
+        let transactions = vec![
+            ExecutableTxWithHash::new(ExecutableTx::Declare(declare)),
+            ExecutableTxWithHash::new(ExecutableTx::Invoke(transfer)),
+            ExecutableTxWithHash::new(ExecutableTx::DeployAccount(deploy_account)),
+        ];
+
+        let mut block_env = provider.block_env_at(0.into()).unwrap().unwrap();
+        backend.update_block_env(&mut block_env);
+
+        let state = provider.latest().unwrap();
+        let BlockExecutionOutput { transactions: executed, rejected, states } = backend
+            .executor
+            .execute_block(&block_env, &cfg_env, state, transactions, flags)
+            .unwrap();
+
+        assert!(rejected.is_empty(), "rejected transactions: {rejected:?}");
+        assert_eq!(executed.len(), 3);
+        assert!(executed.iter().all(|(_, receipt)| receipt.revert_error().is_none()));
+        assert!(executed.iter().all(|(_, receipt)| receipt.actual_fee() > 0));
+
+        backend.do_mine_block(&block_env, executed, states).unwrap();
+
+        let replay = replay_block(provider, backend.executor.as_ref(), &cfg_env, flags, 1).unwrap();
+        assert_eq!(replay.transaction_count, 3);
+        assert!(replay.is_consistent(), "divergences: {:?}", replay.divergences);
+    }
+}
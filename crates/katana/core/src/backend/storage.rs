@@ -7,8 +7,9 @@ use katana_primitives::genesis::Genesis;
 use katana_primitives::state::StateUpdatesWithDeclaredClasses;
 use katana_provider::providers::db::DbProvider;
 use katana_provider::traits::block::{BlockProvider, BlockWriter};
-use katana_provider::traits::contract::ContractClassWriter;
+use katana_provider::traits::contract::{ContractClassWriter, LegacyClassAbiProvider};
 use katana_provider::traits::env::BlockEnvProvider;
+use katana_provider::traits::messaging::{MessagingCheckpointProvider, MessagingCheckpointWriter};
 use katana_provider::traits::state::{StateFactoryProvider, StateRootProvider, StateWriter};
 use katana_provider::traits::state_update::StateUpdateProvider;
 use katana_provider::traits::transaction::{
@@ -27,8 +28,11 @@ pub trait Database:
     + StateRootProvider
     + StateWriter
     + ContractClassWriter
+    + LegacyClassAbiProvider
     + StateFactoryProvider
     + BlockEnvProvider
+    + MessagingCheckpointProvider
+    + MessagingCheckpointWriter
     + 'static
     + Send
     + Sync
@@ -46,8 +50,11 @@ impl<T> Database for T where
         + StateRootProvider
         + StateWriter
         + ContractClassWriter
+        + LegacyClassAbiProvider
         + StateFactoryProvider
         + BlockEnvProvider
+        + MessagingCheckpointProvider
+        + MessagingCheckpointWriter
         + 'static
         + Send
         + Sync
@@ -2,6 +2,7 @@
 
 use futures::channel::mpsc::{channel, Receiver, Sender};
 use katana_primitives::transaction::ExecutableTxWithHash;
+use metrics::gauge;
 use parking_lot::RwLock;
 use starknet::core::types::FieldElement;
 use tracing::{info, warn};
@@ -21,7 +22,11 @@ impl TransactionPool {
 impl TransactionPool {
     pub fn add_transaction(&self, transaction: ExecutableTxWithHash) {
         let hash = transaction.hash;
-        self.transactions.write().push(transaction);
+        {
+            let mut txs = self.transactions.write();
+            txs.push(transaction);
+            gauge!("txpool.size", txs.len() as f64);
+        }
 
         info!(target: "txpool", "Transaction received | Hash: {hash:#x}");
 
@@ -41,6 +46,7 @@ impl TransactionPool {
         let mut txs = self.transactions.write();
         let transactions = txs.clone();
         txs.clear();
+        gauge!("txpool.size", 0.0);
         transactions
     }
 
@@ -5,18 +5,16 @@ use std::sync::Arc;
 
 use anyhow::Result;
 use blockifier::block_context::BlockContext;
-use blockifier::execution::errors::{EntryPointExecutionError, PreExecutionError};
-use blockifier::transaction::errors::TransactionExecutionError;
-use katana_executor::blockifier::state::StateRefDb;
-use katana_executor::blockifier::utils::{block_context_from_envs, EntryPointCall};
-use katana_executor::blockifier::PendingState;
+use katana_executor::abstraction::{EntryPointCall, ExecutionFlags, ExecutorError};
+use katana_executor::blockifier::utils::block_context_from_envs;
 use katana_primitives::block::{BlockHash, BlockHashOrNumber, BlockIdOrTag, BlockNumber};
 use katana_primitives::chain::ChainId;
 use katana_primitives::contract::{
     ClassHash, CompiledContractClass, ContractAddress, Nonce, StorageKey, StorageValue,
 };
+use katana_primitives::env::BlockEnv;
 use katana_primitives::event::{ContinuationToken, ContinuationTokenError};
-use katana_primitives::receipt::Event;
+use katana_primitives::receipt::{Event, Receipt};
 use katana_primitives::transaction::{ExecutableTxWithHash, TxHash, TxWithHash};
 use katana_primitives::FieldElement;
 use katana_provider::traits::block::{
@@ -28,19 +26,27 @@ use katana_provider::traits::state::{StateFactoryProvider, StateProvider};
 use katana_provider::traits::transaction::{
     ReceiptProvider, TransactionProvider, TransactionsProviderExt,
 };
+use parking_lot::Mutex;
 use starknet::core::types::{BlockTag, EmittedEvent, EventsPage, FeeEstimate};
+use tokio::task::JoinHandle;
+use url::Url;
 
 use crate::backend::config::StarknetConfig;
 use crate::backend::contract::StarknetContract;
+use crate::backend::fork::ForkError;
 use crate::backend::Backend;
 use crate::pool::TransactionPool;
 use crate::sequencer_error::SequencerError;
 use crate::service::block_producer::{BlockProducer, BlockProducerMode};
+use crate::service::follower::{BlockFollower, FollowerStatus};
 #[cfg(feature = "messaging")]
 use crate::service::messaging::MessagingConfig;
 #[cfg(feature = "messaging")]
-use crate::service::messaging::MessagingService;
-use crate::service::{NodeService, TransactionMiner};
+use crate::service::messaging::{
+    DeadLetters, GatheredMessages, MessagingService, SimulatedMessaging,
+};
+use crate::service::pending::PendingState;
+use crate::service::{metrics, NodeService, TransactionMiner};
 
 type SequencerResult<T> = Result<T, SequencerError>;
 
@@ -57,6 +63,19 @@ pub struct KatanaSequencer {
     pub pool: Arc<TransactionPool>,
     pub backend: Arc<Backend>,
     pub block_producer: BlockProducer,
+    /// The status of the synchronization with the followed node, if the node is a replica.
+    pub follower_status: Option<Arc<FollowerStatus>>,
+    /// The settlement chain simulated in-process, if the messaging is configured with one.
+    #[cfg(feature = "messaging")]
+    pub simulated_messaging: Option<Arc<SimulatedMessaging>>,
+    /// The messages gathered from the settlement chain, if the messaging is enabled.
+    #[cfg(feature = "messaging")]
+    pub gathered_messages: Option<Arc<GatheredMessages>>,
+    /// The messages that couldn't be sent to the settlement chain, if the messaging is enabled.
+    #[cfg(feature = "messaging")]
+    pub dead_letters: Option<Arc<DeadLetters>>,
+    /// The tasks running in the background, until the sequencer is stopped.
+    tasks: Mutex<Vec<JoinHandle<()>>>,
 }
 
 impl KatanaSequencer {
@@ -64,14 +83,41 @@ impl KatanaSequencer {
         config: SequencerConfig,
         starknet_config: StarknetConfig,
     ) -> anyhow::Result<Self> {
+        metrics::describe();
+
         let backend = Arc::new(Backend::new(starknet_config).await);
 
         let pool = Arc::new(TransactionPool::new());
+
+        // A replica doesn't produce blocks, it syncs them from the followed node instead. The
+        // block producer is only kept to notify its listeners about the synced transactions.
+        // The blocks are synced in the background, the replica serves them as they are synced.
+        if let Some(url) = backend.config.follow_rpc_url.clone() {
+            let block_producer = BlockProducer::instant(Arc::clone(&backend));
+            let follower = BlockFollower::new(url, Arc::clone(&backend), block_producer.clone())?;
+            let follower_status = Some(follower.status());
+
+            let follower = tokio::spawn(follower.run());
+
+            return Ok(Self {
+                pool,
+                config,
+                backend,
+                block_producer,
+                follower_status,
+                #[cfg(feature = "messaging")]
+                simulated_messaging: None,
+                #[cfg(feature = "messaging")]
+                gathered_messages: None,
+                #[cfg(feature = "messaging")]
+                dead_letters: None,
+                tasks: Mutex::new(vec![follower]),
+            });
+        }
+
         let miner = TransactionMiner::new(pool.add_listener());
 
-        let state = StateFactoryProvider::latest(backend.blockchain.provider())
-            .map(StateRefDb::new)
-            .unwrap();
+        let state = StateFactoryProvider::latest(backend.blockchain.provider())?;
 
         let block_producer = if config.block_time.is_some() || config.no_mining {
             let block_num = backend.blockchain.provider().latest_number()?;
@@ -92,12 +138,21 @@ impl KatanaSequencer {
 
         #[cfg(feature = "messaging")]
         let messaging = if let Some(config) = config.messaging.clone() {
-            MessagingService::new(config, Arc::clone(&pool), Arc::clone(&backend)).await.ok()
+            MessagingService::new(config, Arc::clone(&pool), Arc::clone(&backend), &block_producer)
+                .await
+                .ok()
         } else {
             None
         };
 
-        tokio::spawn(NodeService {
+        #[cfg(feature = "messaging")]
+        let simulated_messaging = messaging.as_ref().and_then(|m| m.simulated_messaging());
+        #[cfg(feature = "messaging")]
+        let gathered_messages = messaging.as_ref().map(|m| m.gathered_messages());
+        #[cfg(feature = "messaging")]
+        let dead_letters = messaging.as_ref().map(|m| m.dead_letters());
+
+        let node_service = tokio::spawn(NodeService {
             miner,
             pool: Arc::clone(&pool),
             block_producer: block_producer.clone(),
@@ -105,7 +160,30 @@ impl KatanaSequencer {
             messaging,
         });
 
-        Ok(Self { pool, config, backend, block_producer })
+        Ok(Self {
+            pool,
+            config,
+            backend,
+            block_producer,
+            follower_status: None,
+            #[cfg(feature = "messaging")]
+            simulated_messaging,
+            #[cfg(feature = "messaging")]
+            gathered_messages,
+            #[cfg(feature = "messaging")]
+            dead_letters,
+            tasks: Mutex::new(vec![node_service]),
+        })
+    }
+
+    /// Stops the tasks running in the background, ie. the mining of the transactions of the pool,
+    /// the messaging and the synchronization with the followed node, and waits for them to finish.
+    pub async fn stop(&self) {
+        let tasks = std::mem::take(&mut *self.tasks.lock());
+        for task in tasks {
+            task.abort();
+            let _ = task.await;
+        }
     }
 
     /// Returns the pending state if the sequencer is running in _interval_ mode. Otherwise `None`.
@@ -124,17 +202,51 @@ impl KatanaSequencer {
         &self.backend
     }
 
+    /// Returns the status of the synchronization with the followed node, if the node is a
+    /// replica.
+    pub fn follower_status(&self) -> Option<&FollowerStatus> {
+        self.follower_status.as_deref()
+    }
+
+    /// Returns the settlement chain simulated in-process, if the messaging is configured with one.
+    #[cfg(feature = "messaging")]
+    pub fn simulated_messaging(&self) -> Option<&SimulatedMessaging> {
+        self.simulated_messaging.as_deref()
+    }
+
+    /// Returns the messages gathered from the settlement chain, if the messaging is enabled.
+    #[cfg(feature = "messaging")]
+    pub fn gathered_messages(&self) -> Option<&GatheredMessages> {
+        self.gathered_messages.as_deref()
+    }
+
+    /// Returns the messages that couldn't be sent to the settlement chain, if the messaging is
+    /// enabled.
+    #[cfg(feature = "messaging")]
+    pub fn dead_letters(&self) -> Option<&DeadLetters> {
+        self.dead_letters.as_deref()
+    }
+
     pub fn block_execution_context_at(
         &self,
         block_id: BlockIdOrTag,
     ) -> SequencerResult<Option<BlockContext>> {
-        let provider = self.backend.blockchain.provider();
         let cfg_env = self.backend().chain_cfg_env();
+        let block_env =
+            self.block_env_at(block_id)?.ok_or(SequencerError::BlockNotFound(block_id))?;
+        Ok(Some(block_context_from_envs(&block_env, &cfg_env)))
+    }
+
+    /// Returns the block environment of the given block. For the pending block, this is the
+    /// environment of the block that is currently being built if the sequencer is running in
+    /// _interval_ mode.
+    pub fn block_env_at(&self, block_id: BlockIdOrTag) -> SequencerResult<Option<BlockEnv>> {
+        let provider = self.backend.blockchain.provider();
 
         if let BlockIdOrTag::Tag(BlockTag::Pending) = block_id {
             if let Some(state) = self.pending_state() {
                 let (block_env, _) = state.block_execution_envs();
-                return Ok(Some(block_context_from_envs(&block_env, &cfg_env)));
+                return Ok(Some(block_env));
             }
         }
 
@@ -150,10 +262,7 @@ impl KatanaSequencer {
             BlockIdOrTag::Number(num) => num,
         };
 
-        provider
-            .block_env_at(block_num.into())?
-            .map(|block_env| Some(block_context_from_envs(&block_env, &cfg_env)))
-            .ok_or(SequencerError::BlockNotFound(block_id))
+        Ok(provider.block_env_at(block_num.into())?)
     }
 
     pub fn state(&self, block_id: &BlockIdOrTag) -> SequencerResult<Box<dyn StateProvider>> {
@@ -167,7 +276,7 @@ impl KatanaSequencer {
 
             BlockIdOrTag::Tag(BlockTag::Pending) => {
                 if let Some(state) = self.pending_state() {
-                    Ok(Box::new(state.state.clone()))
+                    Ok(Box::new(state))
                 } else {
                     let state = StateFactoryProvider::latest(provider)?;
                     Ok(state)
@@ -186,8 +295,37 @@ impl KatanaSequencer {
         }
     }
 
-    pub fn add_transaction_to_pool(&self, tx: ExecutableTxWithHash) {
+    /// Returns `true` if the node is a read-only replica following another node.
+    pub fn is_replica(&self) -> bool {
+        self.backend.config.follow_rpc_url.is_some()
+    }
+
+    pub fn add_transaction_to_pool(&self, tx: ExecutableTxWithHash) -> SequencerResult<()> {
+        if self.is_replica() {
+            return Err(SequencerError::ReadOnly);
+        }
+
+        // The fee of V3 transactions is paid in STRK, so their L1 gas price bound must cover the
+        // price of the block they will be included in.
+        if let Some(bounds) = tx.tx_ref().resource_bounds() {
+            if !self.backend.config.disable_fee {
+                let block_id = BlockIdOrTag::Tag(BlockTag::Pending);
+                let block_env =
+                    self.block_env_at(block_id)?.ok_or(SequencerError::BlockNotFound(block_id))?;
+
+                let gas_price = block_env.l1_gas_prices.strk;
+                let max_price_per_unit = bounds.l1_gas.max_price_per_unit;
+                if max_price_per_unit < gas_price {
+                    return Err(SequencerError::InsufficientResourceBounds {
+                        max_price_per_unit,
+                        gas_price,
+                    });
+                }
+            }
+        }
+
         self.pool.add_transaction(tx);
+        Ok(())
     }
 
     pub fn estimate_fee(
@@ -198,21 +336,18 @@ impl KatanaSequencer {
     ) -> SequencerResult<Vec<FeeEstimate>> {
         let state = self.state(&block_id)?;
 
-        let block_context = self
-            .block_execution_context_at(block_id)?
-            .ok_or_else(|| SequencerError::BlockNotFound(block_id))?;
+        let cfg_env = self.backend.chain_cfg_env();
+        let block_env =
+            self.block_env_at(block_id)?.ok_or(SequencerError::BlockNotFound(block_id))?;
 
         // If the node is run with transaction validation disabled, then we should not validate
         // transactions when estimating the fee even if the `SKIP_VALIDATE` flag is not set.
         let should_validate = !(skip_validate || self.backend.config.disable_validate);
+        let flags = ExecutionFlags { validate: should_validate, ..Default::default() };
 
-        katana_executor::blockifier::utils::estimate_fee(
-            transactions.into_iter(),
-            block_context,
-            state,
-            should_validate,
-        )
-        .map_err(SequencerError::TransactionExecution)
+        let estimates =
+            self.backend.executor.estimate_fee(&block_env, &cfg_env, state, transactions, flags)?;
+        Ok(estimates)
     }
 
     pub fn block_hash_and_number(&self) -> SequencerResult<(BlockHash, BlockNumber)> {
@@ -222,6 +357,24 @@ impl KatanaSequencer {
         Ok((hash, number))
     }
 
+    /// Returns the hash and number of the last block mined at or before `timestamp`, if any.
+    pub fn block_hash_and_number_at_timestamp(
+        &self,
+        timestamp: u64,
+    ) -> SequencerResult<Option<(BlockHash, BlockNumber)>> {
+        let provider = self.backend.blockchain.provider();
+
+        let Some(number) = BlockNumberProvider::block_number_by_timestamp(provider, timestamp)?
+        else {
+            return Ok(None);
+        };
+
+        let hash = BlockHashProvider::block_hash_by_num(provider, number)?
+            .ok_or(SequencerError::BlockNotFound(BlockIdOrTag::Number(number)))?;
+
+        Ok(Some((hash, number)))
+    }
+
     pub fn class_hash_at(
         &self,
         block_id: BlockIdOrTag,
@@ -253,6 +406,16 @@ impl KatanaSequencer {
         }
     }
 
+    /// Returns the compiled class of the given class hash, as of the pending state.
+    pub fn compiled_class(
+        &self,
+        class_hash: ClassHash,
+    ) -> SequencerResult<Option<CompiledContractClass>> {
+        let state = self.state(&BlockIdOrTag::Tag(BlockTag::Pending))?;
+        let class = ContractClassProvider::class(&state, class_hash)?;
+        Ok(class)
+    }
+
     pub fn storage_at(
         &self,
         contract_address: ContractAddress,
@@ -325,20 +488,16 @@ impl KatanaSequencer {
     ) -> SequencerResult<Vec<FieldElement>> {
         let state = self.state(&block_id)?;
 
-        let block_context = self
-            .block_execution_context_at(block_id)?
-            .ok_or_else(|| SequencerError::BlockNotFound(block_id))?;
-
-        let retdata = katana_executor::blockifier::utils::call(request, block_context, state)
-            .map_err(|e| match e {
-                TransactionExecutionError::ExecutionError(exe) => match exe {
-                    EntryPointExecutionError::PreExecutionError(
-                        PreExecutionError::UninitializedStorageAddress(addr),
-                    ) => SequencerError::ContractNotFound(addr.into()),
-                    _ => SequencerError::EntryPointExecution(exe),
-                },
-                _ => SequencerError::TransactionExecution(e),
-            })?;
+        let cfg_env = self.backend.chain_cfg_env();
+        let block_env =
+            self.block_env_at(block_id)?.ok_or(SequencerError::BlockNotFound(block_id))?;
+
+        let retdata = self.backend.executor.call(&block_env, &cfg_env, state, request).map_err(
+            |e| match e {
+                ExecutorError::ContractNotDeployed(addr) => SequencerError::ContractNotFound(addr),
+                e => SequencerError::Executor(e),
+            },
+        )?;
 
         Ok(retdata)
     }
@@ -360,6 +519,20 @@ impl KatanaSequencer {
         Ok(tx)
     }
 
+    pub fn receipt(&self, hash: &TxHash) -> SequencerResult<Option<Receipt>> {
+        let receipt = ReceiptProvider::receipt_by_hash(self.backend.blockchain.provider(), *hash)?;
+
+        let receipt @ Some(_) = receipt else {
+            return Ok(self.pending_state().as_ref().and_then(|state| {
+                state.executed_txs.read().iter().find_map(|(tx, receipt)| {
+                    if tx.hash == *hash { Some(receipt.clone()) } else { None }
+                })
+            }));
+        };
+
+        Ok(receipt)
+    }
+
     pub fn events(
         &self,
         from_block: BlockIdOrTag,
@@ -475,6 +648,25 @@ impl KatanaSequencer {
         Ok(EventsPage { events: filtered_events, continuation_token: None })
     }
 
+    /// Drops the local chain and forks the network again, at `block` of the network at `url`. The
+    /// currently forked network and its latest block are used by default. Returns the number of
+    /// the forked block.
+    ///
+    /// The transactions that haven't been mined yet are discarded.
+    pub async fn reset_fork(
+        &self,
+        url: Option<Url>,
+        block: Option<BlockIdOrTag>,
+    ) -> Result<BlockNumber, ForkError> {
+        let point = self.backend.fork_point(url, block).await?;
+        let block_number = point.block_number();
+
+        self.block_producer.reset_fork(point)?;
+        let _ = self.pool.get_transactions();
+
+        Ok(block_number)
+    }
+
     pub fn set_next_block_timestamp(&self, timestamp: u64) -> Result<(), SequencerError> {
         if self.has_pending_transactions() {
             return Err(SequencerError::PendingTransactions);
@@ -1,5 +1,6 @@
 use blockifier::execution::errors::EntryPointExecutionError;
 use blockifier::transaction::errors::TransactionExecutionError;
+use katana_executor::abstraction::ExecutorError;
 use katana_primitives::block::BlockIdOrTag;
 use katana_primitives::contract::ContractAddress;
 use katana_primitives::event::ContinuationTokenError;
@@ -17,10 +18,19 @@ pub enum SequencerError {
     TransactionExecution(#[from] TransactionExecutionError),
     #[error(transparent)]
     EntryPointExecution(#[from] EntryPointExecutionError),
+    #[error(transparent)]
+    Executor(#[from] ExecutorError),
     #[error("Wait for pending transactions.")]
     PendingTransactions,
     #[error(transparent)]
     ContinuationToken(#[from] ContinuationTokenError),
     #[error(transparent)]
     Provider(#[from] ProviderError),
+    #[error("The node is a read-only replica, transactions must be sent to the followed node.")]
+    ReadOnly,
+    #[error(
+        "The max price per unit of L1 gas ({max_price_per_unit}) is lower than the STRK L1 gas \
+         price ({gas_price})."
+    )]
+    InsufficientResourceBounds { max_price_per_unit: u128, gas_price: u128 },
 }
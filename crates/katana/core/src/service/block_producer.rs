@@ -8,31 +8,31 @@ use std::time::Duration;
 use futures::channel::mpsc::{channel, Receiver, Sender};
 use futures::stream::{Stream, StreamExt};
 use futures::FutureExt;
-use katana_executor::blockifier::outcome::TxReceiptWithExecInfo;
-use katana_executor::blockifier::state::{CachedStateWrapper, StateRefDb};
-use katana_executor::blockifier::utils::{
-    block_context_from_envs, get_state_update_from_cached_state,
-};
-use katana_executor::blockifier::{PendingState, TransactionExecutor};
+use katana_executor::abstraction::{BlockExecutionOutput, ExecutionFlags, ExecutorError};
 use katana_primitives::block::BlockHashOrNumber;
 use katana_primitives::env::{BlockEnv, CfgEnv};
 use katana_primitives::receipt::Receipt;
-use katana_primitives::state::StateUpdatesWithDeclaredClasses;
-use katana_primitives::transaction::{ExecutableTxWithHash, TxWithHash};
+use katana_primitives::transaction::{ExecutableTxWithHash, TxHash, TxWithHash};
 use katana_provider::error::ProviderError;
 use katana_provider::traits::block::BlockNumberProvider;
 use katana_provider::traits::env::BlockEnvProvider;
-use katana_provider::traits::state::StateFactoryProvider;
+use katana_provider::traits::state::{StateFactoryProvider, StateProvider};
+use metrics::{histogram, increment_counter};
 use parking_lot::RwLock;
 use tokio::time::{interval_at, Instant, Interval};
 use tracing::{trace, warn};
 
+use super::metrics::rejection_reason;
+use super::pending::PendingState;
+use crate::backend::fork::{ForkError, ForkPoint};
 use crate::backend::Backend;
 
 #[derive(Debug, thiserror::Error)]
 pub enum BlockProductionError {
     #[error(transparent)]
     Provider(#[from] ProviderError),
+    #[error(transparent)]
+    Executor(#[from] ExecutorError),
 }
 
 pub struct MinedBlockOutcome {
@@ -44,8 +44,11 @@ type ServiceFuture<T> = Pin<Box<dyn Future<Output = T> + Send + Sync>>;
 type BlockProductionResult = Result<MinedBlockOutcome, BlockProductionError>;
 type BlockProductionFuture = ServiceFuture<BlockProductionResult>;
 type BlockProductionWithTxnsFuture =
-    ServiceFuture<Result<(Vec<TxWithHashAndReceiptPair>, MinedBlockOutcome), BlockProductionError>>;
+    ServiceFuture<Result<InstantMiningOutput, BlockProductionError>>;
+type InstantMiningOutput = (Vec<TxWithHashAndReceiptPair>, Vec<RejectedTx>, MinedBlockOutcome);
 pub type TxWithHashAndReceiptPair = (TxWithHash, Receipt);
+/// The hash of a transaction rejected by the block producer, along with the rejection reason.
+pub type RejectedTx = (TxHash, String);
 
 /// The type which responsible for block production.
 #[must_use = "BlockProducer does nothing unless polled"]
@@ -59,7 +62,7 @@ impl BlockProducer {
     /// Creates a block producer that mines a new block every `interval` milliseconds.
     pub fn interval(
         backend: Arc<Backend>,
-        initial_state: StateRefDb,
+        initial_state: Box<dyn StateProvider>,
         interval: u64,
         block_exec_envs: (BlockEnv, CfgEnv),
     ) -> Self {
@@ -77,7 +80,7 @@ impl BlockProducer {
     /// `katana_generateBlock` RPC method.
     pub fn on_demand(
         backend: Arc<Backend>,
-        initial_state: StateRefDb,
+        initial_state: Box<dyn StateProvider>,
         block_exec_envs: (BlockEnv, CfgEnv),
     ) -> Self {
         Self {
@@ -115,6 +118,24 @@ impl BlockProducer {
         matches!(*self.inner.read(), BlockProducerMode::Instant(_))
     }
 
+    /// Returns a receiver of the transactions rejected by the block producer, ie. that couldn't be
+    /// executed and therefore won't be included in a block.
+    pub fn add_rejection_listener(&self) -> Receiver<Vec<RejectedTx>> {
+        match &*self.inner.read() {
+            BlockProducerMode::Instant(producer) => producer.add_rejection_listener(),
+            BlockProducerMode::Interval(producer) => producer.add_rejection_listener(),
+        }
+    }
+
+    /// Notifies the listeners of the block producer about transactions that have been included
+    /// in a block without being mined by it, ie when the blocks are synced from another node.
+    pub(crate) fn notify_listeners(&self, txs: Vec<TxWithHashAndReceiptPair>) {
+        match &*self.inner.read() {
+            BlockProducerMode::Instant(producer) => producer.notify_listener(txs),
+            BlockProducerMode::Interval(producer) => producer.notify_listener(txs),
+        }
+    }
+
     // Handler for the `katana_generateBlock` RPC method.
     pub fn force_mine(&self) {
         trace!(target: "miner", "force mining");
@@ -124,6 +145,73 @@ impl BlockProducer {
             BlockProducerMode::Interval(producer) => producer.force_mine(),
         }
     }
+
+    /// Mines all the transactions that have been accepted by the block producer but are not yet
+    /// included in a block. Returns the last mined block, or `None` if there was nothing to mine.
+    ///
+    /// If a block is being mined, it is waited for before the pending transactions are mined on
+    /// top of it.
+    ///
+    /// This is meant to be called before the node shuts down so that transactions which have
+    /// already been acknowledged to clients are persisted in the database.
+    pub async fn mine_pending(&self) -> Result<Option<MinedBlockOutcome>, BlockProductionError> {
+        trace!(target: "miner", "mining pending transactions");
+        let mut mined = None;
+
+        loop {
+            // the lock can't be held while waiting for the ongoing mining process
+            let mining = {
+                let mut mode = self.inner.write();
+                let mining = match &mut *mode {
+                    BlockProducerMode::Instant(producer) => {
+                        producer.block_mining.take().map(OngoingMining::Instant)
+                    }
+                    BlockProducerMode::Interval(producer) => {
+                        producer.block_mining.take().map(OngoingMining::Interval)
+                    }
+                };
+
+                match mining {
+                    Some(mining) => mining,
+                    None => {
+                        let outcome = match &mut *mode {
+                            BlockProducerMode::Instant(producer) => producer.mine_pending(),
+                            BlockProducerMode::Interval(producer) => producer.mine_pending(),
+                        }?;
+                        return Ok(outcome.or(mined));
+                    }
+                }
+            };
+
+            trace!(target: "miner", "waiting for the ongoing mining process");
+
+            let outcome = match mining {
+                OngoingMining::Interval(mining) => mining.await?,
+                OngoingMining::Instant(mining) => {
+                    let (txs, rejected, outcome) = mining.await?;
+                    if let BlockProducerMode::Instant(producer) = &*self.inner.read() {
+                        producer.notify_listener(txs);
+                        notify_rejection_listeners(&producer.rejection_listeners, rejected);
+                    }
+                    outcome
+                }
+            };
+
+            mined = Some(outcome);
+        }
+    }
+
+    /// Drops the chain and forks the network again at `point`, see [Backend::reset_fork].
+    ///
+    /// The transactions that are queued or executed in the pending block are discarded. Fails if a
+    /// block is being mined.
+    pub fn reset_fork(&self, point: ForkPoint) -> Result<(), ForkError> {
+        let mut mode = self.inner.write();
+        match &mut *mode {
+            BlockProducerMode::Instant(producer) => producer.reset_fork(point),
+            BlockProducerMode::Interval(producer) => producer.reset_fork(point),
+        }
+    }
 }
 
 impl Stream for BlockProducer {
@@ -137,6 +225,12 @@ impl Stream for BlockProducer {
     }
 }
 
+/// A block being mined, taken out of the block producer to be waited for.
+enum OngoingMining {
+    Interval(BlockProductionFuture),
+    Instant(BlockProductionWithTxnsFuture),
+}
+
 /// The inner type of [BlockProducer].
 ///
 /// On _interval_ mining, a new block is opened for a fixed amount of interval. Within this
@@ -167,12 +261,14 @@ pub struct IntervalBlockProducer {
     state: Arc<PendingState>,
     /// Listeners notified when a new executed tx is added.
     tx_execution_listeners: RwLock<Vec<Sender<Vec<TxWithHashAndReceiptPair>>>>,
+    /// Listeners notified when transactions are rejected.
+    rejection_listeners: RwLock<Vec<Sender<Vec<RejectedTx>>>>,
 }
 
 impl IntervalBlockProducer {
     pub fn new(
         backend: Arc<Backend>,
-        db: StateRefDb,
+        db: Box<dyn StateProvider>,
         interval: u64,
         block_exec_envs: (BlockEnv, CfgEnv),
     ) -> Self {
@@ -192,6 +288,7 @@ impl IntervalBlockProducer {
             interval: Some(interval),
             queued: VecDeque::default(),
             tx_execution_listeners: RwLock::new(vec![]),
+            rejection_listeners: RwLock::new(vec![]),
         }
     }
 
@@ -200,7 +297,7 @@ impl IntervalBlockProducer {
     /// keep hold of the pending state.
     pub fn new_no_mining(
         backend: Arc<Backend>,
-        db: StateRefDb,
+        db: Box<dyn StateProvider>,
         block_exec_envs: (BlockEnv, CfgEnv),
     ) -> Self {
         let state = Arc::new(PendingState::new(db, block_exec_envs.0, block_exec_envs.1));
@@ -212,6 +309,7 @@ impl IntervalBlockProducer {
             block_mining: None,
             queued: VecDeque::default(),
             tx_execution_listeners: RwLock::new(vec![]),
+            rejection_listeners: RwLock::new(vec![]),
         }
     }
 
@@ -222,23 +320,63 @@ impl IntervalBlockProducer {
     /// Force mine a new block. It will only able to mine if there is no ongoing mining process.
     pub fn force_mine(&self) {
         if self.block_mining.is_none() {
-            let outcome = self.outcome();
-            let _ = Self::do_mine(outcome, self.backend.clone(), self.state.clone());
+            let _ = Self::do_mine(self.backend.clone(), self.state.clone());
         } else {
             trace!(target: "miner", "unable to force mine while a mining process is running")
         }
     }
 
-    fn do_mine(
-        state_updates: StateUpdatesWithDeclaredClasses,
-        backend: Arc<Backend>,
-        pending_state: Arc<PendingState>,
-    ) -> BlockProductionResult {
+    /// Executes all the queued transactions and mines the pending block if it contains any
+    /// executed transactions.
+    pub fn mine_pending(&mut self) -> Result<Option<MinedBlockOutcome>, BlockProductionError> {
+        if self.block_mining.is_some() {
+            // the ongoing mining process already includes all the executed transactions
+            warn!(
+                target: "miner",
+                queued = self.queued.len(),
+                "unable to mine pending block while a mining process is running"
+            );
+            return Ok(None);
+        }
+
+        while let Some(transactions) = self.queued.pop_front() {
+            self.execute_transactions(transactions)?;
+        }
+
+        if self.state.executed_txs.read().is_empty() {
+            return Ok(None);
+        }
+
+        Self::do_mine(self.backend.clone(), self.state.clone()).map(Some)
+    }
+
+    fn reset_fork(&mut self, point: ForkPoint) -> Result<(), ForkError> {
+        if self.block_mining.is_some() {
+            return Err(ForkError::MiningInProgress);
+        }
+
+        self.backend.reset_fork(point)?;
+
+        self.queued.clear();
+        let _ = self.state.take_txs_all();
+
+        // open a new pending block on top of the forked one
+        let provider = self.backend.blockchain.provider();
+        let latest_num = provider.latest_number()?;
+        let mut block_env = provider.block_env_at(latest_num.into())?.expect("forked block exists");
+        self.backend.update_block_env(&mut block_env);
+
+        self.state.reset_state(provider.latest()?, block_env, self.backend.chain_cfg_env());
+
+        Ok(())
+    }
+
+    fn do_mine(backend: Arc<Backend>, pending_state: Arc<PendingState>) -> BlockProductionResult {
         trace!(target: "miner", "creating new block");
 
-        let (txs, _) = pending_state.take_txs_all();
-        let tx_receipt_pairs =
-            txs.into_iter().map(|(tx, rct)| (tx, rct.receipt)).collect::<Vec<_>>();
+        let started_at = std::time::Instant::now();
+        let state_updates = pending_state.state_updates();
+        let (tx_receipt_pairs, _) = pending_state.take_txs_all();
 
         let (mut block_env, cfg_env) = pending_state.block_execution_envs();
 
@@ -248,41 +386,50 @@ impl IntervalBlockProducer {
         trace!(target: "miner", "created new block: {}", outcome.block_number);
 
         backend.update_block_env(&mut block_env);
-        pending_state.reset_state(StateRefDb(new_state), block_env, cfg_env);
+        pending_state.reset_state(new_state, block_env, cfg_env);
+
+        let elapsed = started_at.elapsed();
+        histogram!("block_producer.block_production_duration_seconds", elapsed, "mode" => "interval");
 
         Ok(outcome)
     }
 
-    fn execute_transactions(&self, transactions: Vec<ExecutableTxWithHash>) {
-        let txs = transactions.iter().map(TxWithHash::from);
-
-        let block_context = block_context_from_envs(
-            &self.state.block_envs.read().0,
-            &self.state.block_envs.read().1,
-        );
-
-        let results = {
-            TransactionExecutor::new(
-                &self.state.state,
-                &block_context,
-                !self.backend.config.disable_fee,
-                !self.backend.config.disable_validate,
-                transactions.clone().into_iter(),
-            )
-            .with_error_log()
-            .with_events_log()
-            .with_resources_log()
-            .zip(txs)
-            .filter_map(|(res, tx)| {
-                let Ok(info) = res else { return None };
-                let receipt = TxReceiptWithExecInfo::new(&tx, info);
-                Some((tx, receipt))
-            })
-            .collect::<Vec<_>>()
+    /// Executes the transactions on top of the pending state and adds them to the pending block.
+    fn execute_transactions(
+        &self,
+        transactions: Vec<ExecutableTxWithHash>,
+    ) -> Result<(), BlockProductionError> {
+        let (block_env, cfg_env) = self.state.block_execution_envs();
+        let flags = ExecutionFlags {
+            charge_fee: !self.backend.config.disable_fee,
+            validate: !self.backend.config.disable_validate,
         };
 
-        self.state.executed_txs.write().extend(results.clone());
-        self.notify_listener(results.into_iter().map(|(tx, info)| (tx, info.receipt)).collect());
+        let state = Box::new(Arc::clone(&self.state));
+        let output = self.backend.executor.execute_block(
+            &block_env,
+            &cfg_env,
+            state,
+            transactions,
+            flags,
+        )?;
+
+        let executed = output.transactions.clone();
+        let rejected = output
+            .rejected
+            .iter()
+            .map(|(tx, err)| {
+                let reason = rejection_reason(err);
+                increment_counter!("block_producer.rejected_transactions", "reason" => reason);
+                (tx.hash, err.to_string())
+            })
+            .collect::<Vec<_>>();
+
+        self.state.add_executed_txs(output);
+        self.notify_listener(executed);
+        notify_rejection_listeners(&self.rejection_listeners, rejected);
+
+        Ok(())
     }
 
     pub fn add_listener(&self) -> Receiver<Vec<TxWithHashAndReceiptPair>> {
@@ -292,6 +439,13 @@ impl IntervalBlockProducer {
         rx
     }
 
+    pub fn add_rejection_listener(&self) -> Receiver<Vec<RejectedTx>> {
+        const REJECTION_LISTENER_BUFFER_SIZE: usize = 2048;
+        let (tx, rx) = channel(REJECTION_LISTENER_BUFFER_SIZE);
+        self.rejection_listeners.write().push(tx);
+        rx
+    }
+
     /// notifies all listeners about the transaction
     fn notify_listener(&self, txs: Vec<TxWithHashAndReceiptPair>) {
         let mut listener = self.tx_execution_listeners.write();
@@ -317,10 +471,6 @@ impl IntervalBlockProducer {
             }
         }
     }
-
-    fn outcome(&self) -> StateUpdatesWithDeclaredClasses {
-        get_state_update_from_cached_state(&self.state.state)
-    }
 }
 
 impl Stream for IntervalBlockProducer {
@@ -333,13 +483,10 @@ impl Stream for IntervalBlockProducer {
         if let Some(interval) = &mut pin.interval {
             if interval.poll_tick(cx).is_ready() && pin.block_mining.is_none() {
                 let backend = pin.backend.clone();
-                let outcome = pin.outcome();
                 let state = pin.state.clone();
 
                 pin.block_mining = Some(Box::pin(async move {
-                    tokio::task::spawn_blocking(|| Self::do_mine(outcome, backend, state))
-                        .await
-                        .unwrap()
+                    tokio::task::spawn_blocking(|| Self::do_mine(backend, state)).await.unwrap()
                 }));
             }
         }
@@ -347,7 +494,9 @@ impl Stream for IntervalBlockProducer {
         // only execute transactions if there is no mining in progress
         if !pin.queued.is_empty() && pin.block_mining.is_none() {
             let transactions = pin.queued.pop_front().expect("not empty; qed");
-            pin.execute_transactions(transactions);
+            if let Err(err) = pin.execute_transactions(transactions) {
+                return Poll::Ready(Some(Err(err)));
+            }
         }
 
         // poll the mining future
@@ -373,6 +522,8 @@ pub struct InstantBlockProducer {
     queued: VecDeque<Vec<ExecutableTxWithHash>>,
     /// Listeners notified when a new executed tx is added.
     tx_execution_listeners: RwLock<Vec<Sender<Vec<TxWithHashAndReceiptPair>>>>,
+    /// Listeners notified when transactions are rejected.
+    rejection_listeners: RwLock<Vec<Sender<Vec<RejectedTx>>>>,
 }
 
 impl InstantBlockProducer {
@@ -382,24 +533,60 @@ impl InstantBlockProducer {
             block_mining: None,
             queued: VecDeque::default(),
             tx_execution_listeners: RwLock::new(vec![]),
+            rejection_listeners: RwLock::new(vec![]),
         }
     }
 
     pub fn force_mine(&mut self) {
         if self.block_mining.is_none() {
             let txs = self.queued.pop_front().unwrap_or_default();
-            let _ = Self::do_mine(self.backend.clone(), txs);
+            if let Ok((_, rejected, _)) = Self::do_mine(self.backend.clone(), txs) {
+                notify_rejection_listeners(&self.rejection_listeners, rejected);
+            }
         } else {
             trace!(target: "miner", "unable to force mine while a mining process is running")
         }
     }
 
+    /// Mines all the queued transactions in a single block.
+    pub fn mine_pending(&mut self) -> Result<Option<MinedBlockOutcome>, BlockProductionError> {
+        if self.block_mining.is_some() {
+            warn!(
+                target: "miner",
+                queued = self.queued.len(),
+                "unable to mine pending block while a mining process is running"
+            );
+            return Ok(None);
+        }
+
+        let transactions = self.queued.drain(..).flatten().collect::<Vec<_>>();
+        if transactions.is_empty() {
+            return Ok(None);
+        }
+
+        let (_, rejected, outcome) = Self::do_mine(self.backend.clone(), transactions)?;
+        notify_rejection_listeners(&self.rejection_listeners, rejected);
+        Ok(Some(outcome))
+    }
+
+    fn reset_fork(&mut self, point: ForkPoint) -> Result<(), ForkError> {
+        if self.block_mining.is_some() {
+            return Err(ForkError::MiningInProgress);
+        }
+
+        self.backend.reset_fork(point)?;
+        self.queued.clear();
+
+        Ok(())
+    }
+
     fn do_mine(
         backend: Arc<Backend>,
         transactions: Vec<ExecutableTxWithHash>,
-    ) -> Result<(Vec<TxWithHashAndReceiptPair>, MinedBlockOutcome), BlockProductionError> {
+    ) -> Result<InstantMiningOutput, BlockProductionError> {
         trace!(target: "miner", "creating new block");
 
+        let started_at = std::time::Instant::now();
         let provider = backend.blockchain.provider();
 
         let cfg_env = backend.chain_cfg_env();
@@ -407,43 +594,34 @@ impl InstantBlockProducer {
         let mut block_env = provider.block_env_at(BlockHashOrNumber::Num(latest_num))?.unwrap();
         backend.update_block_env(&mut block_env);
 
-        let block_context = block_context_from_envs(&block_env, &cfg_env);
-
         let latest_state = StateFactoryProvider::latest(backend.blockchain.provider())?;
-        let state = CachedStateWrapper::new(StateRefDb(latest_state));
-
-        let txs = transactions.iter().map(TxWithHash::from);
-
-        let tx_receipt_pairs: Vec<TxWithHashAndReceiptPair> = TransactionExecutor::new(
-            &state,
-            &block_context,
-            !backend.config.disable_fee,
-            !backend.config.disable_validate,
-            transactions.clone().into_iter(),
-        )
-        .with_error_log()
-        .with_events_log()
-        .with_resources_log()
-        .zip(txs)
-        .filter_map(|(res, tx)| {
-            if let Ok(info) = res {
-                let info = TxReceiptWithExecInfo::new(&tx, info);
-                Some((tx, info.receipt))
-            } else {
-                None
-            }
-        })
-        .collect();
 
-        let outcome = backend.do_mine_block(
-            &block_env,
-            tx_receipt_pairs.clone(),
-            get_state_update_from_cached_state(&state),
-        )?;
+        let flags = ExecutionFlags {
+            charge_fee: !backend.config.disable_fee,
+            validate: !backend.config.disable_validate,
+        };
+
+        let BlockExecutionOutput { transactions: tx_receipt_pairs, rejected, states } = backend
+            .executor
+            .execute_block(&block_env, &cfg_env, latest_state, transactions, flags)?;
+
+        let rejected = rejected
+            .into_iter()
+            .map(|(tx, err)| {
+                let reason = rejection_reason(&err);
+                increment_counter!("block_producer.rejected_transactions", "reason" => reason);
+                (tx.hash, err.to_string())
+            })
+            .collect::<Vec<_>>();
+
+        let outcome = backend.do_mine_block(&block_env, tx_receipt_pairs.clone(), states)?;
+
+        let elapsed = started_at.elapsed();
+        histogram!("block_producer.block_production_duration_seconds", elapsed, "mode" => "instant");
 
         trace!(target: "miner", "created new block: {}", outcome.block_number);
 
-        Ok((tx_receipt_pairs, outcome))
+        Ok((tx_receipt_pairs, rejected, outcome))
     }
 
     pub fn add_listener(&self) -> Receiver<Vec<TxWithHashAndReceiptPair>> {
@@ -453,6 +631,13 @@ impl InstantBlockProducer {
         rx
     }
 
+    pub fn add_rejection_listener(&self) -> Receiver<Vec<RejectedTx>> {
+        const REJECTION_LISTENER_BUFFER_SIZE: usize = 2048;
+        let (tx, rx) = channel(REJECTION_LISTENER_BUFFER_SIZE);
+        self.rejection_listeners.write().push(tx);
+        rx
+    }
+
     /// notifies all listeners about the transaction
     fn notify_listener(&self, txs: Vec<TxWithHashAndReceiptPair>) {
         let mut listener = self.tx_execution_listeners.write();
@@ -499,8 +684,9 @@ impl Stream for InstantBlockProducer {
         // poll the mining future
         if let Some(mut mining) = pin.block_mining.take() {
             match mining.poll_unpin(cx) {
-                Poll::Ready(Ok((txs, outcome))) => {
+                Poll::Ready(Ok((txs, rejected, outcome))) => {
                     pin.notify_listener(txs);
+                    notify_rejection_listeners(&pin.rejection_listeners, rejected);
                     return Poll::Ready(Some(Ok(outcome)));
                 }
 
@@ -517,3 +703,226 @@ impl Stream for InstantBlockProducer {
         Poll::Pending
     }
 }
+
+/// Notifies the listeners about the rejected transactions, dropping the closed listeners.
+fn notify_rejection_listeners(
+    listeners: &RwLock<Vec<Sender<Vec<RejectedTx>>>>,
+    rejected: Vec<RejectedTx>,
+) {
+    if rejected.is_empty() {
+        return;
+    }
+
+    listeners.write().retain_mut(|listener| match listener.try_send(rejected.clone()) {
+        Ok(()) => true,
+        Err(e) if e.is_full() => {
+            warn!(target: "miner", "failed to send rejected txs notification because channel is full");
+            true
+        }
+        Err(_) => false,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use katana_executor::abstraction::{EntryPointCall, Executor, ExecutorResult};
+    use katana_primitives::contract::ContractAddress;
+    use katana_primitives::receipt::InvokeTxReceipt;
+    use katana_primitives::transaction::{ExecutableTx, InvokeTx, InvokeTxV1};
+    use katana_primitives::FieldElement;
+    use parking_lot::Mutex;
+    use starknet::core::types::FeeEstimate;
+
+    use super::*;
+    use crate::backend::config::StarknetConfig;
+
+    /// An executor that accepts all the transactions, each of them incrementing a counter stored in
+    /// the state.
+    #[derive(Default)]
+    struct MockExecutor {
+        /// The number of transactions of each call to `execute_block`.
+        batches: Mutex<Vec<usize>>,
+    }
+
+    impl Executor for MockExecutor {
+        fn execute_block(
+            &self,
+            _: &BlockEnv,
+            _: &CfgEnv,
+            state: Box<dyn StateProvider>,
+            transactions: Vec<ExecutableTxWithHash>,
+            _: ExecutionFlags,
+        ) -> ExecutorResult<BlockExecutionOutput> {
+            self.batches.lock().push(transactions.len());
+
+            let address = counter_address();
+            let counter = state.storage(address, FieldElement::ZERO).unwrap().unwrap_or_default();
+
+            let mut output = BlockExecutionOutput::default();
+            for tx in &transactions {
+                let receipt = Receipt::Invoke(InvokeTxReceipt::default());
+                output.transactions.push((TxWithHash::from(tx), receipt));
+            }
+
+            let counter = counter + FieldElement::from(transactions.len());
+            let storage = [(FieldElement::ZERO, counter)].into();
+            output.states.state_updates.storage_updates.insert(address, storage);
+
+            Ok(output)
+        }
+
+        fn simulate(
+            &self,
+            _: &BlockEnv,
+            _: &CfgEnv,
+            _: Box<dyn StateProvider>,
+            _: Vec<ExecutableTxWithHash>,
+            _: ExecutionFlags,
+        ) -> ExecutorResult<Vec<(TxWithHash, Receipt)>> {
+            unimplemented!()
+        }
+
+        fn estimate_fee(
+            &self,
+            _: &BlockEnv,
+            _: &CfgEnv,
+            _: Box<dyn StateProvider>,
+            _: Vec<ExecutableTxWithHash>,
+            _: ExecutionFlags,
+        ) -> ExecutorResult<Vec<FeeEstimate>> {
+            unimplemented!()
+        }
+
+        fn call(
+            &self,
+            _: &BlockEnv,
+            _: &CfgEnv,
+            _: Box<dyn StateProvider>,
+            _: EntryPointCall,
+        ) -> ExecutorResult<Vec<FieldElement>> {
+            unimplemented!()
+        }
+    }
+
+    fn counter_address() -> ContractAddress {
+        ContractAddress::from(FieldElement::from(0x1234u64))
+    }
+
+    async fn backend(executor: Arc<MockExecutor>) -> Arc<Backend> {
+        Arc::new(Backend::new_with_executor(StarknetConfig::default(), executor).await)
+    }
+
+    fn transactions(nonces: std::ops::Range<u64>) -> Vec<ExecutableTxWithHash> {
+        nonces
+            .map(|nonce| {
+                let tx = InvokeTxV1 { nonce: nonce.into(), ..Default::default() };
+                ExecutableTxWithHash::new(ExecutableTx::Invoke(InvokeTx::V1(tx)))
+            })
+            .collect()
+    }
+
+    fn counter(backend: &Backend) -> FieldElement {
+        let state = backend.blockchain.provider().latest().unwrap();
+        state.storage(counter_address(), FieldElement::ZERO).unwrap().unwrap_or_default()
+    }
+
+    #[tokio::test]
+    async fn instant_mining_uses_backend_executor() {
+        let executor = Arc::new(MockExecutor::default());
+        let backend = backend(Arc::clone(&executor)).await;
+        let producer = BlockProducer::instant(Arc::clone(&backend));
+
+        producer.queue(transactions(0..3));
+        let outcome = producer.mine_pending().await.unwrap().expect("block mined");
+
+        assert_eq!(outcome.block_number, 1);
+        assert_eq!(*executor.batches.lock(), vec![3]);
+        assert_eq!(counter(&backend), FieldElement::THREE);
+    }
+
+    #[tokio::test]
+    async fn interval_mining_uses_backend_executor() {
+        let executor = Arc::new(MockExecutor::default());
+        let backend = backend(Arc::clone(&executor)).await;
+
+        let producer = on_demand_producer(&backend);
+
+        producer.queue(transactions(0..1));
+        producer.queue(transactions(1..3));
+        let outcome = producer.mine_pending().await.unwrap().expect("block mined");
+
+        // each batch is executed on top of the state left by the previous ones
+        assert_eq!(outcome.block_number, 1);
+        assert_eq!(*executor.batches.lock(), vec![1, 2]);
+        assert_eq!(counter(&backend), FieldElement::THREE);
+    }
+
+    fn on_demand_producer(backend: &Arc<Backend>) -> BlockProducer {
+        let provider = backend.blockchain.provider();
+        let mut block_env = provider.block_env_at(BlockHashOrNumber::Num(0)).unwrap().unwrap();
+        backend.update_block_env(&mut block_env);
+        let envs = (block_env, backend.chain_cfg_env());
+        BlockProducer::on_demand(Arc::clone(backend), provider.latest().unwrap(), envs)
+    }
+
+    #[tokio::test]
+    async fn mine_pending_seals_executed_transactions() {
+        let executor = Arc::new(MockExecutor::default());
+        let backend = backend(Arc::clone(&executor)).await;
+        let producer = on_demand_producer(&backend);
+
+        if let BlockProducerMode::Interval(producer) = &*producer.inner.read() {
+            producer.execute_transactions(transactions(0..2)).unwrap();
+        }
+
+        let outcome = producer.mine_pending().await.unwrap().expect("block mined");
+
+        assert_eq!(outcome.block_number, 1);
+        assert_eq!(*executor.batches.lock(), vec![2]);
+        assert_eq!(counter(&backend), FieldElement::TWO);
+    }
+
+    #[tokio::test]
+    async fn instant_mine_pending_waits_for_ongoing_mining() {
+        let executor = Arc::new(MockExecutor::default());
+        let backend = backend(Arc::clone(&executor)).await;
+        let producer = BlockProducer::instant(Arc::clone(&backend));
+
+        if let BlockProducerMode::Instant(producer) = &mut *producer.inner.write() {
+            let backend = Arc::clone(&backend);
+            let mining = async move { InstantBlockProducer::do_mine(backend, transactions(0..2)) };
+            producer.block_mining = Some(Box::pin(mining));
+        }
+        producer.queue(transactions(2..3));
+
+        let outcome = producer.mine_pending().await.unwrap().expect("block mined");
+
+        // the queued transactions are mined on top of the block that was being mined
+        assert_eq!(outcome.block_number, 2);
+        assert_eq!(*executor.batches.lock(), vec![2, 1]);
+        assert_eq!(counter(&backend), FieldElement::THREE);
+    }
+
+    #[tokio::test]
+    async fn interval_mine_pending_waits_for_ongoing_mining() {
+        let executor = Arc::new(MockExecutor::default());
+        let backend = backend(Arc::clone(&executor)).await;
+        let producer = on_demand_producer(&backend);
+
+        if let BlockProducerMode::Interval(producer) = &mut *producer.inner.write() {
+            producer.execute_transactions(transactions(0..1)).unwrap();
+
+            let (backend, state) = (Arc::clone(&backend), producer.state());
+            let mining = async move { IntervalBlockProducer::do_mine(backend, state) };
+            producer.block_mining = Some(Box::pin(mining));
+        }
+        producer.queue(transactions(1..3));
+
+        let outcome = producer.mine_pending().await.unwrap().expect("block mined");
+
+        assert_eq!(outcome.block_number, 2);
+        assert_eq!(*executor.batches.lock(), vec![1, 2]);
+        assert_eq!(counter(&backend), FieldElement::THREE);
+        assert!(producer.mine_pending().await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,736 @@
+//! Synchronization of the chain of another node, for running Katana as a read-only replica.
+//!
+//! The [BlockFollower] pulls the blocks, their receipts, state updates and declared classes from
+//! the upstream node through its JSON-RPC API, and writes them as-is in the local database. No
+//! transaction is executed locally, the replica only serves what the upstream node has produced.
+//!
+//! The Starknet API doesn't expose all the data of the transactions, like the entry point calls
+//! they made or the fee paid on L1 for the L1 handler ones. If the upstream node is a Katana node
+//! exposing the `katana` API, this data is fetched from it. Otherwise, the replica serves the
+//! transactions without it.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use ethers::types::H256;
+use futures::{StreamExt, TryStreamExt};
+use jsonrpsee::core::client::ClientT;
+use jsonrpsee::core::Error as JsonRpcError;
+use jsonrpsee::http_client::{HttpClient, HttpClientBuilder};
+use jsonrpsee::rpc_params;
+use jsonrpsee::types::error::{CallError, METHOD_NOT_FOUND_CODE};
+use katana_primitives::block::{Block, BlockHash, BlockNumber, FinalityStatus, GasPrices, Header};
+use katana_primitives::chain::ChainId;
+use katana_primitives::conversion::rpc::{
+    flattened_sierra_to_compiled_class, legacy_rpc_to_inner_compiled_class,
+};
+use katana_primitives::receipt::{
+    DeclareTxReceipt, DeployAccountTxReceipt, Event, InvokeTxReceipt, L1HandlerTxReceipt,
+    MessageToL1, Receipt, TxExecutionResources,
+};
+use katana_primitives::state::StateUpdatesWithDeclaredClasses;
+use katana_primitives::transaction::{
+    DeclareTx, DeclareTxV1, DeclareTxV2, DeclareTxV3, DeployAccountTx, DeployAccountTxV1,
+    DeployAccountTxV3, InvokeTx, InvokeTxV1, InvokeTxV3, L1HandlerTx, Tx, TxHash, TxWithHash,
+};
+use katana_primitives::version::Version;
+use katana_primitives::FieldElement;
+use katana_provider::error::ProviderError;
+use katana_provider::traits::block::{BlockHashProvider, BlockNumberProvider, BlockWriter};
+use parking_lot::RwLock;
+use serde::de::DeserializeOwned;
+use starknet::core::types::{
+    BlockId, BlockStatus, ContractClass, DeclareTransaction, DeployAccountTransaction,
+    ExecutionResult, InvokeTransaction, MaybePendingBlockWithTxHashes, MaybePendingBlockWithTxs,
+    MaybePendingStateUpdate, MaybePendingTransactionReceipt, StateDiff, Transaction,
+    TransactionReceipt,
+};
+use starknet::core::utils::get_contract_address;
+use starknet::providers::jsonrpc::HttpTransport;
+use starknet::providers::{JsonRpcClient, Provider, ProviderError as StarknetProviderError};
+use tracing::{error, info, trace, warn};
+use url::Url;
+
+use super::block_producer::{BlockProducer, TxWithHashAndReceiptPair};
+use crate::backend::Backend;
+
+/// The interval at which the upstream node is polled for new blocks.
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The maximum number of transactions of a block whose receipts are fetched concurrently.
+const TX_FETCH_CONCURRENCY: usize = 32;
+
+type FollowerResult<T> = Result<T, FollowerError>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum FollowerError {
+    #[error("Upstream node error: {0}")]
+    Upstream(#[from] StarknetProviderError),
+    #[error("Upstream node error: {0}")]
+    UpstreamKatana(#[from] JsonRpcError),
+    #[error(transparent)]
+    Provider(#[from] ProviderError),
+    #[error("Block {0} is not yet accepted by the upstream node")]
+    PendingBlock(BlockNumber),
+    #[error(
+        "Block {block} diverged from the upstream node: local hash {local:#x}, upstream hash \
+         {upstream:#x}"
+    )]
+    Diverged { block: BlockNumber, local: BlockHash, upstream: BlockHash },
+    #[error("Invalid block {block}: {reason}")]
+    InvalidBlock { block: BlockNumber, reason: String },
+    #[error("Invalid transaction {hash:#x}: {reason}")]
+    InvalidTransaction { hash: TxHash, reason: String },
+    #[error("Invalid class {hash:#x}: {reason}")]
+    InvalidClass { hash: FieldElement, reason: String },
+}
+
+/// The status of the synchronization with the upstream node.
+#[derive(Debug, Default)]
+pub struct FollowerStatus {
+    diverged: RwLock<Option<String>>,
+}
+
+impl FollowerStatus {
+    /// Returns why the follower stopped, if the local chain diverged from the upstream one.
+    pub fn diverged(&self) -> Option<String> {
+        self.diverged.read().clone()
+    }
+}
+
+/// Keeps the local chain in sync with the chain of an upstream node.
+pub struct BlockFollower {
+    backend: Arc<Backend>,
+    /// Notified of the synced transactions, so that its listeners are served the same way as
+    /// when the blocks are mined locally.
+    block_producer: BlockProducer,
+    upstream: JsonRpcClient<HttpTransport>,
+    /// Client of the `katana` API of the upstream node.
+    upstream_katana: HttpClient,
+    /// Whether the upstream node exposes the `katana` API, until a call tells otherwise.
+    katana_api: AtomicBool,
+    poll_interval: Duration,
+    status: Arc<FollowerStatus>,
+}
+
+impl BlockFollower {
+    pub fn new(
+        upstream: Url,
+        backend: Arc<Backend>,
+        block_producer: BlockProducer,
+    ) -> FollowerResult<Self> {
+        Ok(Self {
+            backend,
+            block_producer,
+            upstream_katana: HttpClientBuilder::default().build(&upstream)?,
+            upstream: JsonRpcClient::new(HttpTransport::new(upstream)),
+            katana_api: AtomicBool::new(true),
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            status: Arc::new(FollowerStatus::default()),
+        })
+    }
+
+    pub fn status(&self) -> Arc<FollowerStatus> {
+        Arc::clone(&self.status)
+    }
+
+    /// Fetches the blocks of the upstream node that are missing from the local chain. Returns the
+    /// number of synced blocks.
+    pub async fn sync(&self) -> FollowerResult<u64> {
+        let provider = self.backend.blockchain.provider();
+
+        let next = if provider.block_hash_by_num(0)?.is_some() {
+            let latest = provider.latest_number()?;
+            let local = provider.latest_hash()?;
+            let upstream = self.upstream_block_hash(latest).await?;
+
+            if local != upstream {
+                return Err(FollowerError::Diverged { block: latest, local, upstream });
+            }
+
+            latest + 1
+        } else {
+            0
+        };
+
+        let upstream_latest = self.upstream.block_number().await?;
+        for block in next..=upstream_latest {
+            self.sync_block(block).await?;
+        }
+
+        Ok((upstream_latest + 1).saturating_sub(next))
+    }
+
+    /// Polls the upstream node for new blocks until the local chain diverges from it, in which
+    /// case the divergence is reported in the [FollowerStatus].
+    pub async fn run(self) {
+        loop {
+            match self.sync().await {
+                Ok(0) => {}
+                Ok(count) => trace!(target: "follower", "synced {count} blocks"),
+                Err(err @ FollowerError::Diverged { .. }) => {
+                    error!(target: "follower", "stopped following the upstream node: {err}");
+                    *self.status.diverged.write() = Some(err.to_string());
+                    return;
+                }
+                Err(err) => error!(target: "follower", "failed to sync blocks: {err}"),
+            }
+
+            tokio::time::sleep(self.poll_interval).await;
+        }
+    }
+
+    async fn upstream_block_hash(&self, block: BlockNumber) -> FollowerResult<BlockHash> {
+        match self.upstream.get_block_with_tx_hashes(BlockId::Number(block)).await? {
+            MaybePendingBlockWithTxHashes::Block(block) => Ok(block.block_hash),
+            MaybePendingBlockWithTxHashes::PendingBlock(_) => {
+                Err(FollowerError::PendingBlock(block))
+            }
+        }
+    }
+
+    async fn sync_block(&self, number: BlockNumber) -> FollowerResult<()> {
+        let block = match self.upstream.get_block_with_txs(BlockId::Number(number)).await? {
+            MaybePendingBlockWithTxs::Block(block) => block,
+            MaybePendingBlockWithTxs::PendingBlock(_) => {
+                return Err(FollowerError::PendingBlock(number));
+            }
+        };
+
+        let state_diff = match self.upstream.get_state_update(BlockId::Number(number)).await? {
+            MaybePendingStateUpdate::Update(update) => update.state_diff,
+            MaybePendingStateUpdate::PendingUpdate(_) => {
+                return Err(FollowerError::PendingBlock(number));
+            }
+        };
+
+        let (txs, receipts): (Vec<_>, Vec<_>) = futures::stream::iter(block.transactions)
+            .map(|tx| self.sync_transaction(number, tx))
+            .buffered(TX_FETCH_CONCURRENCY)
+            .try_collect::<Vec<_>>()
+            .await?
+            .into_iter()
+            .unzip();
+
+        let states = self.state_updates(number, state_diff).await?;
+
+        let invalid_block = |reason: String| FollowerError::InvalidBlock { block: number, reason };
+        let gas_price = |price: FieldElement| {
+            u128::try_from(price).map_err(|_| invalid_block(format!("gas price {price} overflows")))
+        };
+
+        let header = Header {
+            parent_hash: block.parent_hash,
+            number: block.block_number,
+            gas_prices: GasPrices::new(
+                gas_price(block.l1_gas_price.price_in_wei)?,
+                gas_price(block.l1_gas_price.price_in_fri)?,
+            ),
+            timestamp: block.timestamp,
+            state_root: block.new_root,
+            sequencer_address: block.sequencer_address.into(),
+            version: Version::parse(&block.starknet_version)
+                .map_err(|e| invalid_block(e.to_string()))?,
+        };
+
+        let status = match block.status {
+            BlockStatus::AcceptedOnL1 => FinalityStatus::AcceptedOnL1,
+            _ => FinalityStatus::AcceptedOnL2,
+        };
+
+        let block =
+            Block { header, body: txs.clone() }.seal_with_hash_and_status(block.block_hash, status);
+
+        let tx_count = txs.len();
+        BlockWriter::insert_block_with_states_and_receipts(
+            self.backend.blockchain.provider(),
+            block,
+            states,
+            receipts.clone(),
+        )?;
+
+        info!(target: "follower", "🔄 Block {number} synced with {tx_count} transactions");
+
+        let synced: Vec<TxWithHashAndReceiptPair> = txs.into_iter().zip(receipts).collect();
+        if !synced.is_empty() {
+            self.block_producer.notify_listeners(synced);
+        }
+
+        Ok(())
+    }
+
+    /// Fetches the receipt of a transaction of block `number`, and the data of the transaction
+    /// only exposed by the `katana` API.
+    async fn sync_transaction(
+        &self,
+        number: BlockNumber,
+        tx: Transaction,
+    ) -> FollowerResult<TxWithHashAndReceiptPair> {
+        let hash = *tx.transaction_hash();
+        let receipt = match self.upstream.get_transaction_receipt(hash).await? {
+            MaybePendingTransactionReceipt::Receipt(receipt) => receipt,
+            MaybePendingTransactionReceipt::PendingReceipt(_) => {
+                return Err(FollowerError::PendingBlock(number));
+            }
+        };
+
+        let resources = self.katana_request("katana_transactionExecutionResources", hash).await?;
+        let receipt = receipt_from_rpc(hash, receipt, resources)?;
+
+        let paid_fee_on_l1 = match tx {
+            Transaction::L1Handler(_) => {
+                let fee: Option<FieldElement> =
+                    self.katana_request("katana_paidFeeOnL1", hash).await?;
+                fee.map(u128::try_from).transpose().map_err(|_| {
+                    FollowerError::InvalidTransaction {
+                        hash,
+                        reason: "paid fee on L1 doesn't fit in 128 bits".into(),
+                    }
+                })?
+            }
+            _ => None,
+        };
+
+        let tx = tx_from_rpc(tx, self.backend.chain_id, &receipt, paid_fee_on_l1)?;
+        Ok((tx, receipt))
+    }
+
+    /// Calls a method of the `katana` API of the upstream node about the transaction `hash`.
+    /// Returns `None` if the upstream node doesn't expose the API.
+    async fn katana_request<R: DeserializeOwned>(
+        &self,
+        method: &str,
+        hash: TxHash,
+    ) -> FollowerResult<Option<R>> {
+        if !self.katana_api.load(Ordering::Relaxed) {
+            return Ok(None);
+        }
+
+        match self.upstream_katana.request(method, rpc_params![hash]).await {
+            Ok(value) => Ok(Some(value)),
+            Err(JsonRpcError::Call(CallError::Custom(err)))
+                if err.code() == METHOD_NOT_FOUND_CODE =>
+            {
+                warn!(
+                    target: "follower",
+                    "upstream node doesn't expose the katana API, syncing the transactions \
+                     without the data it provides"
+                );
+                self.katana_api.store(false, Ordering::Relaxed);
+                Ok(None)
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Converts the state diff of a block, fetching the classes declared in it.
+    async fn state_updates(
+        &self,
+        block: BlockNumber,
+        diff: StateDiff,
+    ) -> FollowerResult<StateUpdatesWithDeclaredClasses> {
+        let mut states = StateUpdatesWithDeclaredClasses::default();
+        let updates = &mut states.state_updates;
+
+        for item in diff.nonces {
+            updates.nonce_updates.insert(item.contract_address.into(), item.nonce);
+        }
+
+        for item in diff.storage_diffs {
+            let storage = updates.storage_updates.entry(item.address.into()).or_default();
+            storage.extend(item.storage_entries.into_iter().map(|entry| (entry.key, entry.value)));
+        }
+
+        for item in diff.deployed_contracts {
+            updates.contract_updates.insert(item.address.into(), item.class_hash);
+        }
+
+        for item in diff.replaced_classes {
+            updates.contract_updates.insert(item.contract_address.into(), item.class_hash);
+        }
+
+        // Legacy classes don't have a compiled class hash, their class hash is used instead.
+        let declared = diff
+            .declared_classes
+            .into_iter()
+            .map(|item| (item.class_hash, item.compiled_class_hash))
+            .chain(diff.deprecated_declared_classes.into_iter().map(|hash| (hash, hash)));
+
+        for (hash, compiled_class_hash) in declared {
+            states.state_updates.declared_classes.insert(hash, compiled_class_hash);
+
+            let invalid_class =
+                |e: anyhow::Error| FollowerError::InvalidClass { hash, reason: e.to_string() };
+
+            match self.upstream.get_class(BlockId::Number(block), hash).await? {
+                ContractClass::Legacy(class) => {
+                    let (_, compiled) =
+                        legacy_rpc_to_inner_compiled_class(&class).map_err(invalid_class)?;
+                    states.declared_compiled_classes.insert(hash, compiled);
+                    states.declared_legacy_class_abis.insert(hash, class.abi.unwrap_or_default());
+                }
+
+                ContractClass::Sierra(class) => {
+                    let (_, _, compiled) =
+                        flattened_sierra_to_compiled_class(&class).map_err(invalid_class)?;
+                    states.declared_compiled_classes.insert(hash, compiled);
+                    states.declared_sierra_classes.insert(hash, class);
+                }
+            }
+        }
+
+        Ok(states)
+    }
+}
+
+/// Converts a transaction fetched from the upstream node. Its `receipt` provides the data that
+/// is not part of the RPC transaction, like the message hash of an L1 handler transaction.
+///
+/// The fee paid on L1 of an L1 handler transaction isn't part of it either, and is set to zero
+/// if `paid_fee_on_l1` isn't provided.
+fn tx_from_rpc(
+    tx: Transaction,
+    chain_id: ChainId,
+    receipt: &Receipt,
+    paid_fee_on_l1: Option<u128>,
+) -> FollowerResult<TxWithHash> {
+    let hash = *tx.transaction_hash();
+    let invalid = |reason: &str| FollowerError::InvalidTransaction { hash, reason: reason.into() };
+    let max_fee = |fee: FieldElement| {
+        u128::try_from(fee).map_err(|_| invalid("max fee doesn't fit in 128 bits"))
+    };
+
+    let transaction = match tx {
+        Transaction::Invoke(InvokeTransaction::V1(tx)) => Tx::Invoke(InvokeTx::V1(InvokeTxV1 {
+            chain_id,
+            sender_address: tx.sender_address.into(),
+            nonce: tx.nonce,
+            calldata: tx.calldata,
+            signature: tx.signature,
+            max_fee: max_fee(tx.max_fee)?,
+        })),
+
+        Transaction::Invoke(InvokeTransaction::V3(tx)) => Tx::Invoke(InvokeTx::V3(InvokeTxV3 {
+            chain_id,
+            sender_address: tx.sender_address.into(),
+            nonce: tx.nonce,
+            calldata: tx.calldata,
+            signature: tx.signature,
+            resource_bounds: tx.resource_bounds,
+            tip: tx.tip,
+            paymaster_data: tx.paymaster_data,
+            account_deployment_data: tx.account_deployment_data,
+            nonce_data_availability_mode: tx.nonce_data_availability_mode,
+            fee_data_availability_mode: tx.fee_data_availability_mode,
+        })),
+
+        Transaction::Declare(DeclareTransaction::V1(tx)) => {
+            Tx::Declare(DeclareTx::V1(DeclareTxV1 {
+                chain_id,
+                sender_address: tx.sender_address.into(),
+                nonce: tx.nonce,
+                signature: tx.signature,
+                class_hash: tx.class_hash,
+                max_fee: max_fee(tx.max_fee)?,
+            }))
+        }
+
+        Transaction::Declare(DeclareTransaction::V2(tx)) => {
+            Tx::Declare(DeclareTx::V2(DeclareTxV2 {
+                chain_id,
+                sender_address: tx.sender_address.into(),
+                nonce: tx.nonce,
+                signature: tx.signature,
+                class_hash: tx.class_hash,
+                compiled_class_hash: tx.compiled_class_hash,
+                max_fee: max_fee(tx.max_fee)?,
+            }))
+        }
+
+        Transaction::Declare(DeclareTransaction::V3(tx)) => {
+            Tx::Declare(DeclareTx::V3(DeclareTxV3 {
+                chain_id,
+                sender_address: tx.sender_address.into(),
+                nonce: tx.nonce,
+                signature: tx.signature,
+                class_hash: tx.class_hash,
+                compiled_class_hash: tx.compiled_class_hash,
+                resource_bounds: tx.resource_bounds,
+                tip: tx.tip,
+                paymaster_data: tx.paymaster_data,
+                account_deployment_data: tx.account_deployment_data,
+                nonce_data_availability_mode: tx.nonce_data_availability_mode,
+                fee_data_availability_mode: tx.fee_data_availability_mode,
+            }))
+        }
+
+        Transaction::L1Handler(tx) => {
+            let Receipt::L1Handler(receipt) = receipt else {
+                return Err(invalid("receipt is not the one of an L1 handler transaction"));
+            };
+
+            Tx::L1Handler(L1HandlerTx {
+                nonce: tx.nonce.into(),
+                chain_id,
+                paid_fee_on_l1: paid_fee_on_l1.unwrap_or_default(),
+                version: tx.version,
+                message_hash: receipt.message_hash,
+                calldata: tx.calldata,
+                contract_address: tx.contract_address.into(),
+                entry_point_selector: tx.entry_point_selector,
+            })
+        }
+
+        Transaction::DeployAccount(DeployAccountTransaction::V1(tx)) => {
+            let contract_address = get_contract_address(
+                tx.contract_address_salt,
+                tx.class_hash,
+                &tx.constructor_calldata,
+                FieldElement::ZERO,
+            );
+
+            Tx::DeployAccount(DeployAccountTx::V1(DeployAccountTxV1 {
+                chain_id,
+                nonce: tx.nonce,
+                signature: tx.signature,
+                class_hash: tx.class_hash,
+                contract_address: contract_address.into(),
+                contract_address_salt: tx.contract_address_salt,
+                constructor_calldata: tx.constructor_calldata,
+                max_fee: max_fee(tx.max_fee)?,
+            }))
+        }
+
+        Transaction::DeployAccount(DeployAccountTransaction::V3(tx)) => {
+            let contract_address = get_contract_address(
+                tx.contract_address_salt,
+                tx.class_hash,
+                &tx.constructor_calldata,
+                FieldElement::ZERO,
+            );
+
+            Tx::DeployAccount(DeployAccountTx::V3(DeployAccountTxV3 {
+                chain_id,
+                nonce: tx.nonce,
+                signature: tx.signature,
+                class_hash: tx.class_hash,
+                contract_address: contract_address.into(),
+                contract_address_salt: tx.contract_address_salt,
+                constructor_calldata: tx.constructor_calldata,
+                resource_bounds: tx.resource_bounds,
+                tip: tx.tip,
+                paymaster_data: tx.paymaster_data,
+                nonce_data_availability_mode: tx.nonce_data_availability_mode,
+                fee_data_availability_mode: tx.fee_data_availability_mode,
+            }))
+        }
+
+        Transaction::Invoke(InvokeTransaction::V0(_))
+        | Transaction::Declare(DeclareTransaction::V0(_))
+        | Transaction::Deploy(_) => return Err(invalid("unsupported transaction version")),
+    };
+
+    Ok(TxWithHash { hash, transaction })
+}
+
+/// Converts a receipt fetched from the upstream node. The execution resources of the RPC receipt
+/// are used if the complete `resources`, including the calls made by the transaction, aren't
+/// provided.
+fn receipt_from_rpc(
+    hash: TxHash,
+    receipt: TransactionReceipt,
+    resources: Option<TxExecutionResources>,
+) -> FollowerResult<Receipt> {
+    let invalid = |reason: &str| FollowerError::InvalidTransaction { hash, reason: reason.into() };
+    let actual_fee = |fee: FieldElement| {
+        u128::try_from(fee).map_err(|_| invalid("actual fee doesn't fit in 128 bits"))
+    };
+
+    let receipt = match receipt {
+        TransactionReceipt::Invoke(rct) => Receipt::Invoke(InvokeTxReceipt {
+            actual_fee: actual_fee(rct.actual_fee.amount)?,
+            fee_unit: rct.actual_fee.unit.into(),
+            events: events_from_rpc(rct.events),
+            messages_sent: messages_from_rpc(rct.messages_sent),
+            revert_error: revert_error(rct.execution_result),
+            revert_reason: None,
+            execution_resources: resources
+                .unwrap_or_else(|| resources_from_rpc(rct.execution_resources)),
+        }),
+
+        TransactionReceipt::Declare(rct) => Receipt::Declare(DeclareTxReceipt {
+            actual_fee: actual_fee(rct.actual_fee.amount)?,
+            fee_unit: rct.actual_fee.unit.into(),
+            events: events_from_rpc(rct.events),
+            messages_sent: messages_from_rpc(rct.messages_sent),
+            revert_error: revert_error(rct.execution_result),
+            revert_reason: None,
+            execution_resources: resources
+                .unwrap_or_else(|| resources_from_rpc(rct.execution_resources)),
+        }),
+
+        TransactionReceipt::L1Handler(rct) => Receipt::L1Handler(L1HandlerTxReceipt {
+            actual_fee: actual_fee(rct.actual_fee.amount)?,
+            events: events_from_rpc(rct.events),
+            message_hash: H256::from(*rct.message_hash.as_bytes()),
+            messages_sent: messages_from_rpc(rct.messages_sent),
+            revert_error: revert_error(rct.execution_result),
+            revert_reason: None,
+            execution_resources: resources
+                .unwrap_or_else(|| resources_from_rpc(rct.execution_resources)),
+        }),
+
+        TransactionReceipt::DeployAccount(rct) => Receipt::DeployAccount(DeployAccountTxReceipt {
+            actual_fee: actual_fee(rct.actual_fee.amount)?,
+            fee_unit: rct.actual_fee.unit.into(),
+            events: events_from_rpc(rct.events),
+            messages_sent: messages_from_rpc(rct.messages_sent),
+            revert_error: revert_error(rct.execution_result),
+            revert_reason: None,
+            execution_resources: resources
+                .unwrap_or_else(|| resources_from_rpc(rct.execution_resources)),
+            contract_address: rct.contract_address.into(),
+        }),
+
+        TransactionReceipt::Deploy(_) => return Err(invalid("unsupported transaction version")),
+    };
+
+    Ok(receipt)
+}
+
+fn events_from_rpc(events: Vec<starknet::core::types::Event>) -> Vec<Event> {
+    events
+        .into_iter()
+        .map(|e| Event { from_address: e.from_address.into(), keys: e.keys, data: e.data })
+        .collect()
+}
+
+fn messages_from_rpc(messages: Vec<starknet::core::types::MsgToL1>) -> Vec<MessageToL1> {
+    messages
+        .into_iter()
+        .map(|m| MessageToL1 {
+            from_address: m.from_address.into(),
+            to_address: m.to_address,
+            payload: m.payload,
+        })
+        .collect()
+}
+
+fn resources_from_rpc(
+    resources: starknet::core::types::ExecutionResources,
+) -> TxExecutionResources {
+    TxExecutionResources {
+        steps: resources.steps,
+        memory_holes: resources.memory_holes,
+        range_check_builtin: resources.range_check_builtin_applications,
+        pedersen_builtin: resources.pedersen_builtin_applications,
+        poseidon_builtin: resources.poseidon_builtin_applications,
+        ec_op_builtin: resources.ec_op_builtin_applications,
+        ecdsa_builtin: resources.ecdsa_builtin_applications,
+        bitwise_builtin: resources.bitwise_builtin_applications,
+        keccak_builtin: resources.keccak_builtin_applications,
+        segment_arena_builtin: resources.segment_arena_builtin,
+        ..Default::default()
+    }
+}
+
+fn revert_error(result: ExecutionResult) -> Option<String> {
+    match result {
+        ExecutionResult::Succeeded => None,
+        ExecutionResult::Reverted { reason } => Some(reason),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use katana_primitives::chain::ChainId;
+    use katana_primitives::receipt::{InvokeTxReceipt, Receipt};
+    use katana_primitives::transaction::{DeployAccountTx, DeployAccountTxV1, Tx};
+    use starknet::core::types::{
+        DeployAccountTransaction, DeployAccountTransactionV1, ExecutionResources, ExecutionResult,
+        FeePayment, InvokeTransactionReceipt, PriceUnit, Transaction, TransactionFinalityStatus,
+        TransactionReceipt,
+    };
+    use starknet::core::utils::get_contract_address;
+    use starknet::macros::felt;
+
+    use super::{receipt_from_rpc, tx_from_rpc};
+
+    #[test]
+    fn deploy_account_tx_from_rpc() {
+        let class_hash = felt!("0x123");
+        let salt = felt!("0x456");
+        let calldata = vec![felt!("0x789")];
+        let contract_address = get_contract_address(salt, class_hash, &calldata, 0u8.into());
+
+        let expected = DeployAccountTx::V1(DeployAccountTxV1 {
+            chain_id: ChainId::GOERLI,
+            nonce: 0u8.into(),
+            signature: vec![felt!("0x1"), felt!("0x2")],
+            class_hash,
+            contract_address: contract_address.into(),
+            contract_address_salt: salt,
+            constructor_calldata: calldata.clone(),
+            max_fee: 1000,
+        });
+        let hash = expected.calculate_hash(false);
+
+        let rpc_tx =
+            Transaction::DeployAccount(DeployAccountTransaction::V1(DeployAccountTransactionV1 {
+                transaction_hash: hash,
+                max_fee: 1000u32.into(),
+                signature: vec![felt!("0x1"), felt!("0x2")],
+                nonce: 0u8.into(),
+                contract_address_salt: salt,
+                constructor_calldata: calldata,
+                class_hash,
+            }));
+
+        let receipt = Receipt::Invoke(Default::default());
+        let tx = tx_from_rpc(rpc_tx, ChainId::GOERLI, &receipt, None).unwrap();
+
+        assert_eq!(tx.hash, hash);
+        assert_eq!(tx.transaction, Tx::DeployAccount(expected));
+    }
+
+    #[test]
+    fn reverted_receipt_from_rpc() {
+        let hash = felt!("0xabc");
+        let rpc_receipt = TransactionReceipt::Invoke(InvokeTransactionReceipt {
+            transaction_hash: hash,
+            actual_fee: FeePayment { amount: 42u8.into(), unit: PriceUnit::Wei },
+            finality_status: TransactionFinalityStatus::AcceptedOnL2,
+            block_hash: felt!("0x1"),
+            block_number: 1,
+            messages_sent: vec![],
+            events: vec![],
+            execution_resources: ExecutionResources {
+                steps: 100,
+                memory_holes: Some(2),
+                range_check_builtin_applications: Some(3),
+                pedersen_builtin_applications: None,
+                poseidon_builtin_applications: None,
+                ec_op_builtin_applications: None,
+                ecdsa_builtin_applications: None,
+                bitwise_builtin_applications: None,
+                keccak_builtin_applications: None,
+                segment_arena_builtin: None,
+            },
+            execution_result: ExecutionResult::Reverted { reason: "Out of gas".into() },
+        });
+
+        let Receipt::Invoke(InvokeTxReceipt {
+            actual_fee, revert_error, execution_resources, ..
+        }) = receipt_from_rpc(hash, rpc_receipt, None).unwrap()
+        else {
+            panic!("expected an invoke receipt");
+        };
+
+        assert_eq!(actual_fee, 42);
+        assert_eq!(revert_error.as_deref(), Some("Out of gas"));
+        assert_eq!(execution_resources.steps, 100);
+        assert_eq!(execution_resources.memory_holes, Some(2));
+        assert_eq!(execution_resources.range_check_builtin, Some(3));
+    }
+}
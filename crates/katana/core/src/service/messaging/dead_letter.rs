@@ -0,0 +1,30 @@
+use std::sync::Arc;
+
+pub use katana_db::models::messaging::DeadLetter;
+use katana_provider::traits::messaging::{MessagingCheckpointProvider, MessagingCheckpointWriter};
+use katana_provider::ProviderResult;
+
+use crate::backend::Backend;
+
+/// The messages to L1 that permanently failed to be sent to the settlement chain.
+///
+/// The messages are persisted in the storage, they have to be settled manually using the
+/// information listed here.
+pub struct DeadLetters {
+    backend: Arc<Backend>,
+}
+
+impl DeadLetters {
+    pub(super) fn new(backend: Arc<Backend>) -> Self {
+        Self { backend }
+    }
+
+    /// Returns all the dead-lettered messages, in the order they were given up on.
+    pub fn all(&self) -> ProviderResult<Vec<DeadLetter>> {
+        self.backend.blockchain.provider().dead_letters()
+    }
+
+    pub(super) fn insert(&self, letter: DeadLetter) -> ProviderResult<()> {
+        self.backend.blockchain.provider().insert_dead_letter(letter)
+    }
+}
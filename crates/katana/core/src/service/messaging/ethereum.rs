@@ -10,8 +10,10 @@ use ethers::types::{Address, BlockNumber, Log};
 use k256::ecdsa::SigningKey;
 use katana_primitives::chain::ChainId;
 use katana_primitives::receipt::MessageToL1;
-use katana_primitives::transaction::L1HandlerTx;
-use katana_primitives::utils::transaction::compute_l1_message_hash;
+use katana_primitives::transaction::{L1HandlerTx, L1TxHash};
+use katana_primitives::utils::transaction::{
+    compute_l1_message_hash, compute_l1_to_l2_message_hash,
+};
 use katana_primitives::FieldElement;
 use tracing::{debug, error, trace, warn};
 
@@ -129,7 +131,7 @@ impl Messenger for EthereumMessaging {
         from_block: u64,
         max_blocks: u64,
         chain_id: ChainId,
-    ) -> MessengerResult<(u64, Vec<Self::MessageTransaction>)> {
+    ) -> MessengerResult<(u64, Vec<(L1TxHash, Self::MessageTransaction)>)> {
         let chain_latest_block: u64 = self
             .provider
             .get_block_number()
@@ -155,8 +157,10 @@ impl Messenger for EthereumMessaging {
                 );
 
                 block_logs.into_iter().for_each(|log| {
+                    // Logs of mined blocks always have the hash of their transaction.
+                    let l1_tx_hash = log.transaction_hash.unwrap_or_default();
                     if let Ok(tx) = l1_handler_tx_from_log(log, chain_id) {
-                        l1_handler_txs.push(tx)
+                        l1_handler_txs.push((l1_tx_hash, tx))
                     }
                 })
             },
@@ -189,6 +193,10 @@ impl Messenger for EthereumMessaging {
             // wait for the tx to be mined
             .await?
         {
+            // A status of 0 means that the transaction reverted.
+            Some(receipt) if receipt.status == Some(U64::zero()) => {
+                Err(Error::TransactionReverted(format!("{:#x}", receipt.transaction_hash)))
+            }
             Some(receipt) => {
                 trace!(
                     target: LOG_TARGET,
@@ -222,7 +230,13 @@ fn l1_handler_tx_from_log(log: Log, chain_id: ChainId) -> MessengerResult<L1Hand
     let mut calldata = vec![from_address];
     calldata.extend(parsed_log.payload.into_iter().map(felt_from_u256));
 
-    let message_hash = compute_l1_message_hash(from_address, contract_address, &calldata);
+    let message_hash = compute_l1_to_l2_message_hash(
+        from_address,
+        contract_address,
+        entry_point_selector,
+        &calldata[1..],
+        nonce,
+    );
 
     Ok(L1HandlerTx {
         nonce,
@@ -305,17 +319,26 @@ mod tests {
         let to_address = FieldElement::from_hex_be(to_address).unwrap();
         let from_address = FieldElement::from_hex_be(from_address).unwrap();
 
-        let message_hash = compute_l1_message_hash(from_address, to_address, &calldata);
+        let selector = FieldElement::from_hex_be(selector).unwrap();
+        let nonce = FieldElement::from(nonce);
+
+        let message_hash = compute_l1_to_l2_message_hash(
+            from_address,
+            to_address,
+            selector,
+            &calldata[1..],
+            nonce,
+        );
 
         let expected = L1HandlerTx {
+            nonce,
             calldata,
             chain_id,
             message_hash,
             paid_fee_on_l1: fee,
             version: FieldElement::ZERO,
-            nonce: FieldElement::from(nonce),
             contract_address: to_address.into(),
-            entry_point_selector: FieldElement::from_hex_be(selector).unwrap(),
+            entry_point_selector: selector,
         };
         let tx_hash = expected.calculate_hash();
 
@@ -9,6 +9,25 @@
 //! `L1HandlerTransaction`. There is no account involved to execute this transaction, fees are
 //! charged on the settlement layer.
 //!
+//! When `check_paid_fee` is enabled in the `MessagingConfig`, the fee paid on the settlement chain
+//! for each message is compared to the estimated fee of its `L1HandlerTransaction`, and messages
+//! that didn't pay enough are rejected. The check is skipped when the settlement chain is a
+//! Starknet chain, as its messaging contract doesn't charge a fee for the messages. The status of
+//! the gathered messages is persisted, and can be looked up by the hash of the settlement chain
+//! transaction that sent them.
+//!
+//! When the settlement chain can't be reached, gathering and sending are retried with an
+//! exponential backoff. The messages are sent one at a time, and a message that still can't be
+//! sent after `max_retries` retries is moved to a dead-letter store persisted in the storage, so
+//! that the following messages can be sent. The dead-lettered messages can be listed through the
+//! `katana` RPC API.
+//!
+//! The messaging progress is persisted in the storage, so that Katana resumes from where it stopped
+//! after a restart. L1 messages are deduplicated by their hash, meaning that a message gathered
+//! more than once is only executed by the first `L1HandlerTransaction` that processed it. A message
+//! whose `L1HandlerTransaction` is rejected by the block producer is marked as failed, and isn't
+//! retried.
+//!
 //! The sending of the messages is realized by collecting all the `messages_sent` from local
 //! execution of smart contracts using the `send_message_to_l1_syscall`. Once messages are
 //! collected, the hash of each message is computed and then registered on the settlement layer to
@@ -17,6 +36,9 @@
 //! updates on Ethereum, since the process of proving and verifying of state updates, and then
 //! posting in on the settlement layer are not yet present in Katana.
 //!
+//! For testing purposes, the settlement chain can also be simulated in-process. Messages to L2 are
+//! then injected, and messages to L1 listed and consumed, through the `dev` RPC API.
+//!
 //! Katana also has a `starknet-messaging` feature, where an opiniated implementation of L2 <-> L3
 //! messaging is implemented using Starknet as settlement chain.
 //!
@@ -32,12 +54,16 @@
 //! configuration file following the `MessagingConfig` format. An example of this file can be found
 //! in the messaging contracts.
 
+mod dead_letter;
 mod ethereum;
 mod service;
+mod simulated;
 #[cfg(feature = "starknet-messaging")]
 mod starknet;
+mod status;
 
 use std::path::Path;
+use std::sync::Arc;
 
 use ::starknet::providers::ProviderError as StarknetProviderError;
 use anyhow::Result;
@@ -46,18 +72,27 @@ use ethereum::EthereumMessaging;
 use ethers::providers::ProviderError as EthereumProviderError;
 use katana_primitives::chain::ChainId;
 use katana_primitives::receipt::MessageToL1;
-use serde::Deserialize;
+use katana_primitives::transaction::L1TxHash;
+use serde::{Deserialize, Serialize};
 use tracing::{error, info};
 
+pub use self::dead_letter::{DeadLetter, DeadLetters};
 pub use self::service::{MessagingOutcome, MessagingService};
+pub use self::simulated::{SimulatedMessageToL1, SimulatedMessaging};
 #[cfg(feature = "starknet-messaging")]
 use self::starknet::StarknetMessaging;
+pub use self::status::{GatheredMessage, GatheredMessageStatus, GatheredMessages};
 
 pub(crate) const LOG_TARGET: &str = "messaging";
 pub(crate) const CONFIG_CHAIN_ETHEREUM: &str = "ethereum";
+pub(crate) const CONFIG_CHAIN_SIMULATED: &str = "simulated";
 #[cfg(feature = "starknet-messaging")]
 pub(crate) const CONFIG_CHAIN_STARKNET: &str = "starknet";
 
+/// The default number of times the sending of messages is retried before the messages are
+/// dead-lettered.
+pub const DEFAULT_MAX_RETRIES: u32 = 5;
+
 type MessengerResult<T> = Result<T, Error>;
 
 #[derive(Debug, thiserror::Error)]
@@ -70,8 +105,12 @@ pub enum Error {
     GatherError,
     #[error("Failed to send messages to settlement chain")]
     SendError,
+    #[error("Transaction reverted on settlement chain: {0}")]
+    TransactionReverted(String),
     #[error(transparent)]
     Provider(ProviderError),
+    #[error(transparent)]
+    Storage(#[from] katana_provider::error::ProviderError),
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -89,7 +128,7 @@ impl From<EthereumProviderError> for Error {
 }
 
 /// The config used to initialize the messaging service.
-#[derive(Debug, Default, Deserialize, Clone)]
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
 pub struct MessagingConfig {
     /// The settlement chain.
     pub chain: String,
@@ -105,8 +144,19 @@ pub struct MessagingConfig {
     /// The interval, in seconds, at which the messaging service will fetch and settle messages
     /// from/to the settlement chain.
     pub interval: u64,
-    /// The block on settlement chain from where Katana will start fetching messages.
+    /// The block on settlement chain from where Katana will start fetching messages. Ignored
+    /// when resuming from a previously persisted messaging checkpoint.
     pub from_block: u64,
+    /// Whether the messages that didn't pay enough fee on the settlement chain to cover the
+    /// execution of their L1 handler transaction must be rejected. Ignored when the settlement
+    /// chain is a Starknet chain, which doesn't report the fee paid for the messages.
+    #[serde(default)]
+    pub check_paid_fee: bool,
+    /// The number of times the sending of a message is retried, with an exponential backoff,
+    /// before the message is moved to the dead-letter store. Defaults to
+    /// [DEFAULT_MAX_RETRIES].
+    #[serde(default)]
+    pub max_retries: Option<u32>,
 }
 
 impl MessagingConfig {
@@ -116,6 +166,16 @@ impl MessagingConfig {
         serde_json::from_slice(&buf).map_err(|e| e.into())
     }
 
+    /// Creates the config to use a settlement chain simulated in-process.
+    pub fn simulated() -> Self {
+        Self {
+            chain: CONFIG_CHAIN_SIMULATED.into(),
+            interval: 1,
+            check_paid_fee: true,
+            ..Default::default()
+        }
+    }
+
     /// This is used as the clap `value_parser` implementation
     pub fn parse(path: &str) -> Result<Self, String> {
         Self::load(path).map_err(|e| e.to_string())
@@ -132,8 +192,9 @@ pub trait Messenger {
     type MessageTransaction;
 
     /// Gathers messages emitted on the settlement chain and convert them to their
-    /// corresponding transaction type on Starknet, and the latest block on the settlement until
-    /// which the messages were collected.
+    /// corresponding transaction type on Starknet, along with the hash of the settlement chain
+    /// transaction that emitted them, and the latest block on the settlement until which the
+    /// messages were collected.
     ///
     /// # Arguments
     ///
@@ -146,7 +207,7 @@ pub trait Messenger {
         from_block: u64,
         max_blocks: u64,
         chain_id: ChainId,
-    ) -> MessengerResult<(u64, Vec<Self::MessageTransaction>)>;
+    ) -> MessengerResult<(u64, Vec<(L1TxHash, Self::MessageTransaction)>)>;
 
     /// Computes the hash of the given messages and sends them to the settlement chain.
     ///
@@ -164,11 +225,23 @@ pub trait Messenger {
 
 pub enum MessengerMode {
     Ethereum(EthereumMessaging),
+    Simulated(Arc<SimulatedMessaging>),
     #[cfg(feature = "starknet-messaging")]
     Starknet(StarknetMessaging),
 }
 
 impl MessengerMode {
+    /// Whether the messages gathered from the settlement chain carry the fee paid for them. The
+    /// messaging contract on Starknet doesn't charge a fee for the messages, so there is no paid
+    /// fee to check in this mode.
+    pub fn reports_paid_fee(&self) -> bool {
+        match self {
+            MessengerMode::Ethereum(_) | MessengerMode::Simulated(_) => true,
+            #[cfg(feature = "starknet-messaging")]
+            MessengerMode::Starknet(_) => false,
+        }
+    }
+
     pub async fn from_config(config: MessagingConfig) -> MessengerResult<Self> {
         match config.chain.as_str() {
             CONFIG_CHAIN_ETHEREUM => match EthereumMessaging::new(config).await {
@@ -182,6 +255,11 @@ impl MessengerMode {
                 }
             },
 
+            CONFIG_CHAIN_SIMULATED => {
+                info!(target: LOG_TARGET, "Messaging enabled [Simulated]");
+                Ok(MessengerMode::Simulated(Arc::new(SimulatedMessaging::default())))
+            }
+
             #[cfg(feature = "starknet-messaging")]
             CONFIG_CHAIN_STARKNET => match StarknetMessaging::new(config).await {
                 Ok(m_sn) => {
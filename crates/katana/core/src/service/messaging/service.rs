@@ -1,24 +1,42 @@
+use std::collections::{HashSet, VecDeque};
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
 use std::time::Duration;
 
+use futures::channel::mpsc::Receiver;
 use futures::{Future, FutureExt, Stream};
+use katana_executor::abstraction::ExecutionFlags;
 use katana_primitives::block::BlockHashOrNumber;
+use katana_primitives::chain::ChainId;
 use katana_primitives::receipt::MessageToL1;
-use katana_primitives::transaction::{ExecutableTxWithHash, L1HandlerTx, TxHash};
+use katana_primitives::transaction::{
+    ExecutableTxWithHash, L1HandlerTx, L1MessageHash, L1TxHash, TxHash,
+};
 use katana_provider::traits::block::BlockNumberProvider;
+use katana_provider::traits::env::BlockEnvProvider;
+use katana_provider::traits::messaging::{MessagingCheckpointProvider, MessagingCheckpointWriter};
+use katana_provider::traits::state::StateFactoryProvider;
 use katana_provider::traits::transaction::ReceiptProvider;
+use metrics::increment_counter;
 use tokio::time::{interval_at, Instant, Interval};
-use tracing::{error, info};
+use tracing::{debug, error, info, warn};
 
-use super::{MessagingConfig, Messenger, MessengerMode, MessengerResult, LOG_TARGET};
+use super::{
+    DeadLetter, DeadLetters, Error, GatheredMessage, GatheredMessageStatus, GatheredMessages,
+    MessagingConfig, Messenger, MessengerMode, MessengerResult, SimulatedMessaging,
+    DEFAULT_MAX_RETRIES, LOG_TARGET,
+};
 use crate::backend::Backend;
 use crate::pool::TransactionPool;
+use crate::service::block_producer::{BlockProducer, RejectedTx};
 
 type MessagingFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
-type MessageGatheringFuture = MessagingFuture<MessengerResult<(u64, usize)>>;
-type MessageSettlingFuture = MessagingFuture<MessengerResult<Option<(u64, usize)>>>;
+type MessageGatheringFuture = MessagingFuture<MessengerResult<(u64, Vec<(L1TxHash, L1HandlerTx)>)>>;
+type MessageSettlingFuture = MessagingFuture<MessengerResult<Option<MessageSending>>>;
+
+/// The maximum delay between two attempts of a failing messaging operation.
+const MAX_RETRY_BACKOFF: Duration = Duration::from_secs(300);
 
 pub struct MessagingService {
     /// The interval at which the service will perform the messaging operations.
@@ -27,26 +45,65 @@ pub struct MessagingService {
     pool: Arc<TransactionPool>,
     /// The messenger mode the service is running in.
     messenger: Arc<MessengerMode>,
+    /// Whether the messages that didn't pay enough fee on the settlement chain are rejected.
+    check_paid_fee: bool,
+    /// The messages gathered from the settlement chain.
+    gathered_messages: Arc<GatheredMessages>,
     /// The block number of the settlement chain from which messages will be gathered.
     gather_from_block: u64,
     /// The message gathering future.
     msg_gather_fut: Option<MessageGatheringFuture>,
+    /// The backoff of the message gathering, when the settlement chain can't be reached.
+    gather_backoff: Backoff,
+    /// The settlement chain blocks that have been gathered, along with their messages that are
+    /// not yet included in a local block.
+    pending_gathers: VecDeque<(u64, Vec<PendingMessage>)>,
+    /// The hashes of the L1 handler transactions of the messages in `pending_gathers`.
+    in_flight: HashSet<TxHash>,
+    /// Receives the transactions rejected by the block producer.
+    rejected_txs: Receiver<Vec<RejectedTx>>,
     /// The block number of the local blockchain from which messages will be sent.
     send_from_block: u64,
+    /// The index of the next message of `send_from_block` to send.
+    send_from_index: usize,
     /// The message sending future.
     msg_send_fut: Option<MessageSettlingFuture>,
+    /// The backoff of the sending of the next message of `send_from_block`.
+    send_backoff: Backoff,
+    /// The number of times the sending of a message is retried before it is dead-lettered.
+    max_retries: u32,
+    /// The messages that couldn't be sent to the settlement chain.
+    dead_letters: Arc<DeadLetters>,
 }
 
 impl MessagingService {
     /// Initializes a new instance from a configuration file's path.
     /// Will panic on failure to avoid continuing with invalid configuration.
+    ///
+    /// The L1 handler transactions are included by `block_producer`, which notifies the service
+    /// about the ones it rejects.
     pub async fn new(
         config: MessagingConfig,
         pool: Arc<TransactionPool>,
         backend: Arc<Backend>,
+        block_producer: &BlockProducer,
     ) -> anyhow::Result<Self> {
-        let gather_from_block = config.from_block;
+        let provider = backend.blockchain.provider();
+
+        let gather_from_block = match provider.gather_from_block()? {
+            Some(block) => {
+                info!(target: LOG_TARGET, "Resuming messages gathering from block {block}");
+                block
+            }
+            None => config.from_block,
+        };
+        let send_from_block = provider.send_from_block()?.unwrap_or_default();
+        let send_from_index = provider.send_from_index()?.unwrap_or_default() as usize;
+
         let interval = interval_from_seconds(config.interval);
+        let check_paid_fee = config.check_paid_fee;
+        let max_retries = config.max_retries.unwrap_or(DEFAULT_MAX_RETRIES);
+        let retry_backoff = Duration::from_secs(config.interval);
         let messenger = match MessengerMode::from_config(config).await {
             Ok(m) => Arc::new(m),
             Err(_) => {
@@ -57,101 +114,370 @@ impl MessagingService {
             }
         };
 
+        if check_paid_fee && !messenger.reports_paid_fee() {
+            warn!(
+                target: LOG_TARGET,
+                "The settlement chain doesn't report the fee paid for the messages, the paid fee \
+                 won't be checked"
+            );
+        }
+        let check_paid_fee = check_paid_fee && messenger.reports_paid_fee();
+
         Ok(Self {
             pool,
             backend,
             interval,
             messenger,
+            max_retries,
+            check_paid_fee,
             gather_from_block,
-            send_from_block: 0,
+            send_from_block,
+            send_from_index,
             msg_gather_fut: None,
             msg_send_fut: None,
+            pending_gathers: VecDeque::new(),
+            in_flight: HashSet::new(),
+            rejected_txs: block_producer.add_rejection_listener(),
+            gather_backoff: Backoff::new(retry_backoff),
+            send_backoff: Backoff::new(retry_backoff),
+            dead_letters: Arc::new(DeadLetters::new(Arc::clone(&backend))),
+            gathered_messages: Arc::new(GatheredMessages::new(Arc::clone(&backend))),
         })
     }
 
     async fn gather_messages(
         messenger: Arc<MessengerMode>,
-        pool: Arc<TransactionPool>,
-        backend: Arc<Backend>,
+        chain_id: ChainId,
         from_block: u64,
-    ) -> MessengerResult<(u64, usize)> {
+    ) -> MessengerResult<(u64, Vec<(L1TxHash, L1HandlerTx)>)> {
         // 200 avoids any possible rejection from RPC with possibly lot's of messages.
         // TODO: May this be configurable?
         let max_block = 200;
 
         match messenger.as_ref() {
             MessengerMode::Ethereum(inner) => {
-                let (block_num, txs) =
-                    inner.gather_messages(from_block, max_block, backend.chain_id).await?;
-                let txs_count = txs.len();
-
-                txs.into_iter().for_each(|tx| {
-                    let hash = tx.calculate_hash();
-                    trace_l1_handler_tx_exec(hash, &tx);
-                    pool.add_transaction(ExecutableTxWithHash { hash, transaction: tx.into() })
-                });
+                inner.gather_messages(from_block, max_block, chain_id).await
+            }
 
-                Ok((block_num, txs_count))
+            MessengerMode::Simulated(inner) => {
+                inner.gather_messages(from_block, max_block, chain_id).await
             }
 
             #[cfg(feature = "starknet-messaging")]
             MessengerMode::Starknet(inner) => {
-                let (block_num, txs) =
-                    inner.gather_messages(from_block, max_block, backend.chain_id).await?;
-                let txs_count = txs.len();
-
-                txs.into_iter().for_each(|tx| {
-                    let hash = tx.calculate_hash();
-                    trace_l1_handler_tx_exec(hash, &tx);
-                    pool.add_transaction(ExecutableTxWithHash { hash, transaction: tx.into() })
-                });
+                inner.gather_messages(from_block, max_block, chain_id).await
+            }
+        }
+    }
+
+    /// Returns the simulated settlement chain, if the service is running with one.
+    pub fn simulated_messaging(&self) -> Option<Arc<SimulatedMessaging>> {
+        match self.messenger.as_ref() {
+            MessengerMode::Simulated(inner) => Some(Arc::clone(inner)),
+            _ => None,
+        }
+    }
+
+    /// Returns the messages gathered from the settlement chain.
+    pub fn gathered_messages(&self) -> Arc<GatheredMessages> {
+        Arc::clone(&self.gathered_messages)
+    }
+
+    /// Returns the messages that couldn't be sent to the settlement chain.
+    pub fn dead_letters(&self) -> Arc<DeadLetters> {
+        Arc::clone(&self.dead_letters)
+    }
+
+    /// Adds the L1 handler transactions of the messages gathered up until the settlement chain
+    /// block `block_num` to the pool. Returns the number of transactions added.
+    ///
+    /// The messages that have already been processed, or whose transaction is already in the pool
+    /// or in the pending block, are skipped. If `check_paid_fee` is set, the messages that didn't
+    /// pay enough fee on the settlement chain are rejected.
+    fn add_l1_handler_txs(
+        &mut self,
+        block_num: u64,
+        txs: Vec<(L1TxHash, L1HandlerTx)>,
+    ) -> MessengerResult<usize> {
+        let provider = self.backend.blockchain.provider();
 
-                Ok((block_num, txs_count))
+        // look up the processed messages first, so that no transaction is added if it fails
+        let txs = txs
+            .into_iter()
+            .map(|(l1_tx_hash, tx)| {
+                let processed_by = provider.l1_handler_tx_by_message_hash(tx.message_hash)?;
+                Ok((l1_tx_hash, tx, processed_by))
+            })
+            .collect::<MessengerResult<Vec<_>>>()?;
+
+        let mut gathered = Vec::with_capacity(txs.len());
+        let mut accepted: Vec<(PendingMessage, L1HandlerTx)> = Vec::with_capacity(txs.len());
+
+        for (l1_tx_hash, tx, processed_by) in txs {
+            let message_hash = tx.message_hash;
+
+            if let Some(tx_hash) = processed_by {
+                debug!(
+                    target: LOG_TARGET,
+                    "Skipping L1 message {message_hash:#x} already processed by transaction \
+                     {tx_hash:#x}",
+                );
+
+                let status = GatheredMessageStatus::Accepted { transaction_hash: tx_hash };
+                gathered.push((l1_tx_hash, GatheredMessage { message_hash, status }));
+                continue;
             }
+
+            let hash = tx.calculate_hash();
+
+            if self.in_flight.contains(&hash) || accepted.iter().any(|(m, _)| m.tx_hash == hash) {
+                debug!(
+                    target: LOG_TARGET,
+                    "Skipping L1 message {message_hash:#x} already being processed by transaction \
+                     {hash:#x}",
+                );
+
+                let status = GatheredMessageStatus::Accepted { transaction_hash: hash };
+                gathered.push((l1_tx_hash, GatheredMessage { message_hash, status }));
+                continue;
+            }
+
+            let paid_fee_on_l1 = tx.paid_fee_on_l1;
+
+            if self.check_paid_fee {
+                let executable = ExecutableTxWithHash { hash, transaction: tx.clone().into() };
+
+                match estimate_l1_handler_fee(&self.backend, executable) {
+                    Ok(estimated_fee) if paid_fee_on_l1 < estimated_fee => {
+                        warn!(
+                            target: LOG_TARGET,
+                            "Rejecting L1 message {message_hash:#x}: paid fee {paid_fee_on_l1} is \
+                             lower than the estimated fee {estimated_fee}",
+                        );
+
+                        let status = GatheredMessageStatus::Rejected {
+                            transaction_hash: hash,
+                            paid_fee_on_l1,
+                            estimated_fee,
+                        };
+                        gathered.push((l1_tx_hash, GatheredMessage { message_hash, status }));
+                        continue;
+                    }
+
+                    Ok(_) => {}
+
+                    // The execution failure will be reported by the transaction receipt.
+                    Err(e) => warn!(
+                        target: LOG_TARGET,
+                        "Failed to estimate fee of L1 message {message_hash:#x}: {e}"
+                    ),
+                }
+            }
+
+            let status = GatheredMessageStatus::Accepted { transaction_hash: hash };
+            gathered.push((l1_tx_hash, GatheredMessage { message_hash, status }));
+            accepted.push((PendingMessage { l1_tx_hash, message_hash, tx_hash: hash }, tx));
+        }
+
+        // the statuses are persisted before any transaction is added, for the same reason
+        for (l1_tx_hash, message) in gathered {
+            self.gathered_messages.insert(l1_tx_hash, message)?;
+        }
+
+        let mut pending = Vec::with_capacity(accepted.len());
+
+        for (message, tx) in accepted {
+            let hash = message.tx_hash;
+            trace_l1_handler_tx_exec(hash, &tx);
+            self.in_flight.insert(hash);
+            pending.push(message);
+            self.pool.add_transaction(ExecutableTxWithHash { hash, transaction: tx.into() })
         }
+
+        let msg_count = pending.len();
+        self.pending_gathers.push_back((block_num, pending));
+        Ok(msg_count)
     }
 
+    /// Persists the gathering cursor past the settlement chain blocks whose messages have all
+    /// been processed, ie. included in a local block or rejected by the block producer. The
+    /// messages that are still in the pool will then be gathered again after a restart.
+    fn checkpoint_gathered_messages(&mut self) -> MessengerResult<()> {
+        // the rejected transactions won't ever be included, their messages are done with
+        let mut rejected = Vec::new();
+        while let Ok(Some(txs)) = self.rejected_txs.try_next() {
+            rejected.extend(txs.into_iter().filter(|(hash, _)| self.in_flight.contains(hash)));
+        }
+
+        for (tx_hash, reason) in rejected {
+            for (_, messages) in self.pending_gathers.iter_mut() {
+                let Some(index) = messages.iter().position(|m| m.tx_hash == tx_hash) else {
+                    continue;
+                };
+
+                let PendingMessage { l1_tx_hash, message_hash, .. } = messages[index];
+                warn!(
+                    target: LOG_TARGET,
+                    "L1 handler transaction {tx_hash:#x} of message {message_hash:#x} rejected: \
+                     {reason}",
+                );
+
+                let status = GatheredMessageStatus::Failed { transaction_hash: tx_hash, reason };
+                self.gathered_messages
+                    .insert(l1_tx_hash, GatheredMessage { message_hash, status })?;
+                messages.remove(index);
+                self.in_flight.remove(&tx_hash);
+                break;
+            }
+        }
+
+        let provider = self.backend.blockchain.provider();
+        let mut checkpoint = None;
+
+        while let Some((block_num, messages)) = self.pending_gathers.front_mut() {
+            for message in std::mem::take(messages) {
+                if provider.l1_handler_tx_by_message_hash(message.message_hash)?.is_some() {
+                    self.in_flight.remove(&message.tx_hash);
+                } else {
+                    messages.push(message);
+                }
+            }
+
+            if !messages.is_empty() {
+                break;
+            }
+
+            checkpoint = Some(*block_num + 1);
+            self.pending_gathers.pop_front();
+        }
+
+        if let Some(block_num) = checkpoint {
+            provider.set_gather_from_block(block_num)?;
+        }
+
+        Ok(())
+    }
+
+    /// Moves the next message of `send_from_block`, which failed to be sent `attempts` times, to
+    /// the dead-letter store, so that the following messages can be sent.
+    fn dead_letter_message(
+        &mut self,
+        message: MessageToL1,
+        attempts: u32,
+        error: String,
+    ) -> MessengerResult<()> {
+        let block_number = self.send_from_block;
+
+        error!(
+            target: LOG_TARGET,
+            "Giving up sending message {} of block {block_number} after {attempts} attempts: \
+             {error}",
+            self.send_from_index,
+        );
+
+        // the message must be persisted before moving past it
+        self.dead_letters.insert(DeadLetter { block_number, message, attempts, error })?;
+        increment_counter!("messaging.dead_letters");
+
+        self.send_from_index += 1;
+        self.send_backoff.reset();
+
+        let provider = self.backend.blockchain.provider();
+        provider.set_send_from(block_number, self.send_from_index as u64)?;
+        Ok(())
+    }
+
+    /// Moves on to the next local block once all the messages of `send_from_block` have been
+    /// sent or dead-lettered.
+    fn complete_sending_block(&mut self) {
+        self.send_from_block += 1;
+        self.send_from_index = 0;
+        self.send_backoff.reset();
+
+        let provider = self.backend.blockchain.provider();
+        if let Err(e) = provider.set_send_from(self.send_from_block, 0) {
+            error!(target: LOG_TARGET, "error saving messages sending checkpoint: {e}");
+        }
+    }
+
+    /// Sends the messages of the local block `block_num` one at a time, starting from the message
+    /// at `from_index`, until one of them fails to be sent. Returns `None` if the block doesn't
+    /// exist yet.
+    ///
+    /// The sending cursor is persisted after every message sent, so that the messages already sent
+    /// aren't sent again after a restart.
     async fn send_messages(
         block_num: u64,
+        from_index: usize,
         backend: Arc<Backend>,
         messenger: Arc<MessengerMode>,
-    ) -> MessengerResult<Option<(u64, usize)>> {
-        let Some(messages) = ReceiptProvider::receipts_by_block(
-            backend.blockchain.provider(),
-            BlockHashOrNumber::Num(block_num),
-        )
-        .unwrap()
-        .map(|r| r.iter().flat_map(|r| r.messages_sent().to_vec()).collect::<Vec<MessageToL1>>()) else {
+    ) -> MessengerResult<Option<MessageSending>> {
+        let Some(messages) = messages_sent_in_block(&backend, block_num)? else {
             return Ok(None);
         };
 
-        if messages.is_empty() {
-            Ok(Some((block_num, 0)))
-        } else {
-            match messenger.as_ref() {
-                MessengerMode::Ethereum(inner) => {
-                    let hashes = inner
-                        .send_messages(&messages)
-                        .await
-                        .map(|hashes| hashes.iter().map(|h| format!("{h:#x}")).collect())?;
-                    trace_msg_to_l1_sent(&messages, &hashes);
-                    Ok(Some((block_num, hashes.len())))
-                }
+        let mut sent = 0;
+        let mut error = None;
+
+        let provider = backend.blockchain.provider();
+
+        for message in messages.iter().skip(from_index) {
+            match Self::send_message(&messenger, message).await {
+                Ok(hash) => {
+                    trace_msg_to_l1_sent(std::slice::from_ref(message), &[hash]);
+                    sent += 1;
 
-                #[cfg(feature = "starknet-messaging")]
-                MessengerMode::Starknet(inner) => {
-                    let hashes = inner
-                        .send_messages(&messages)
-                        .await
-                        .map(|hashes| hashes.iter().map(|h| format!("{h:#x}")).collect())?;
-                    trace_msg_to_l1_sent(&messages, &hashes);
-                    Ok(Some((block_num, hashes.len())))
+                    let index = (from_index + sent) as u64;
+                    if let Err(e) = provider.set_send_from(block_num, index) {
+                        error!(target: LOG_TARGET, "error saving messages sending checkpoint: {e}");
+                    }
+                }
+                Err(e) => {
+                    error = Some(e);
+                    break;
                 }
             }
         }
+
+        Ok(Some(MessageSending { messages, sent, error }))
+    }
+
+    /// Sends a single message to the settlement chain, returning its hash.
+    async fn send_message(
+        messenger: &MessengerMode,
+        message: &MessageToL1,
+    ) -> MessengerResult<String> {
+        let messages = std::slice::from_ref(message);
+
+        let hashes: Vec<String> = match messenger {
+            MessengerMode::Ethereum(inner) => {
+                inner.send_messages(messages).await?.iter().map(|h| format!("{h:#x}")).collect()
+            }
+
+            MessengerMode::Simulated(inner) => {
+                inner.send_messages(messages).await?.iter().map(|h| format!("{h:#x}")).collect()
+            }
+
+            #[cfg(feature = "starknet-messaging")]
+            MessengerMode::Starknet(inner) => {
+                inner.send_messages(messages).await?.iter().map(|h| format!("{h:#x}")).collect()
+            }
+        };
+
+        hashes.into_iter().next().ok_or(Error::SendError)
     }
 }
 
+/// The progress of sending the messages of a local block.
+struct MessageSending {
+    /// All the messages of the block.
+    messages: Vec<MessageToL1>,
+    /// The number of messages sent by this attempt.
+    sent: usize,
+    /// The error that stopped the sending before all the messages were sent.
+    error: Option<Error>,
+}
+
 pub enum MessagingOutcome {
     Gather {
         /// The latest block number of the settlement chain from which messages were gathered.
@@ -165,6 +491,12 @@ pub enum MessagingOutcome {
         /// The number of messages sent on `block_num`.
         msg_count: usize,
     },
+    DeadLetter {
+        /// The local block number whose messages couldn't be sent.
+        block_num: u64,
+        /// The number of messages of `block_num` moved to the dead-letter store.
+        msg_count: usize,
+    },
 }
 
 impl Stream for MessagingService {
@@ -174,42 +506,67 @@ impl Stream for MessagingService {
         let pin = self.get_mut();
 
         if pin.interval.poll_tick(cx).is_ready() {
-            if pin.msg_gather_fut.is_none() {
+            if let Err(e) = pin.checkpoint_gathered_messages() {
+                error!(target: LOG_TARGET, "error saving messages gathering checkpoint: {e}");
+            }
+
+            if pin.msg_gather_fut.is_none() && pin.gather_backoff.is_ready() {
                 pin.msg_gather_fut = Some(Box::pin(Self::gather_messages(
                     pin.messenger.clone(),
-                    pin.pool.clone(),
-                    pin.backend.clone(),
+                    pin.backend.chain_id,
                     pin.gather_from_block,
                 )));
             }
 
-            if pin.msg_send_fut.is_none() {
-                let local_latest_block_num =
-                    BlockNumberProvider::latest_number(pin.backend.blockchain.provider()).unwrap();
-                if pin.send_from_block <= local_latest_block_num {
-                    pin.msg_send_fut = Some(Box::pin(Self::send_messages(
-                        pin.send_from_block,
-                        pin.backend.clone(),
-                        pin.messenger.clone(),
-                    )))
+            if pin.msg_send_fut.is_none() && pin.send_backoff.is_ready() {
+                match BlockNumberProvider::latest_number(pin.backend.blockchain.provider()) {
+                    Ok(latest_block_num) if pin.send_from_block <= latest_block_num => {
+                        pin.msg_send_fut = Some(Box::pin(Self::send_messages(
+                            pin.send_from_block,
+                            pin.send_from_index,
+                            pin.backend.clone(),
+                            pin.messenger.clone(),
+                        )))
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        error!(target: LOG_TARGET, "error reading the latest local block: {e}")
+                    }
                 }
             }
         }
 
         // Poll the gathering future.
         if let Some(mut gather_fut) = pin.msg_gather_fut.take() {
-            match gather_fut.poll_unpin(cx) {
+            let gathered = gather_fut.poll_unpin(cx).map(|result| {
+                let (last_block, txs) = result?;
+                let msg_count = pin.add_l1_handler_txs(last_block, txs)?;
+                Ok::<_, Error>((last_block, msg_count))
+            });
+
+            match gathered {
                 Poll::Ready(Ok((last_block, msg_count))) => {
+                    pin.gather_backoff.reset();
                     pin.gather_from_block = last_block + 1;
+
+                    if let Err(e) = pin.checkpoint_gathered_messages() {
+                        error!(target: LOG_TARGET, "error saving messages gathering checkpoint: {e}");
+                    }
+
                     return Poll::Ready(Some(MessagingOutcome::Gather {
                         lastest_block: last_block,
                         msg_count,
                     }));
                 }
                 Poll::Ready(Err(e)) => {
+                    increment_counter!("messaging.gather_failures");
+                    pin.gather_backoff.failed();
+
                     error!(
                         target: LOG_TARGET,
-                        "error gathering messages for block {}: {e}", pin.gather_from_block
+                        "error gathering messages for block {}, retrying in {:?}: {e}",
+                        pin.gather_from_block,
+                        pin.gather_backoff.delay(),
                     );
                     return Poll::Pending;
                 }
@@ -220,20 +577,60 @@ impl Stream for MessagingService {
         // Poll the message sending future.
         if let Some(mut send_fut) = pin.msg_send_fut.take() {
             match send_fut.poll_unpin(cx) {
-                Poll::Ready(Ok(Some((block_num, msg_count)))) => {
-                    // +1 to move to the next local block to check messages to be
-                    // sent on the settlement chain.
-                    pin.send_from_block += 1;
-                    return Poll::Ready(Some(MessagingOutcome::Send { block_num, msg_count }));
+                Poll::Ready(Ok(Some(MessageSending { messages, sent, error }))) => {
+                    let block_num = pin.send_from_block;
+
+                    // the failures are counted for the next message to send
+                    if sent > 0 {
+                        pin.send_backoff.reset();
+                    }
+                    pin.send_from_index += sent;
+
+                    let Some(e) = error else {
+                        pin.complete_sending_block();
+                        return Poll::Ready(Some(MessagingOutcome::Send {
+                            block_num,
+                            msg_count: sent,
+                        }));
+                    };
+
+                    increment_counter!("messaging.send_failures");
+                    let attempts = pin.send_backoff.failed();
+
+                    if attempts > pin.max_retries {
+                        let message = messages[pin.send_from_index].clone();
+                        if let Err(e) = pin.dead_letter_message(message, attempts, e.to_string()) {
+                            error!(target: LOG_TARGET, "error saving dead-lettered message: {e}");
+                            return Poll::Pending;
+                        }
+
+                        if pin.send_from_index == messages.len() {
+                            pin.complete_sending_block();
+                        }
+
+                        return Poll::Ready(Some(MessagingOutcome::DeadLetter {
+                            block_num,
+                            msg_count: 1,
+                        }));
+                    }
+
+                    error!(
+                        target: LOG_TARGET,
+                        "error settling message {} of block {block_num}, retrying in {:?}: {e}",
+                        pin.send_from_index,
+                        pin.send_backoff.delay(),
+                    );
+                    return Poll::Pending;
                 }
+                Poll::Ready(Ok(None)) => return Poll::Pending,
                 Poll::Ready(Err(e)) => {
                     error!(
                         target: LOG_TARGET,
-                        "error settling messages for block {}: {e}", pin.send_from_block
+                        "error reading messages of block {}: {e}",
+                        pin.send_from_block,
                     );
                     return Poll::Pending;
                 }
-                Poll::Ready(_) => return Poll::Pending,
                 Poll::Pending => pin.msg_send_fut = Some(send_fut),
             }
         }
@@ -242,6 +639,84 @@ impl Stream for MessagingService {
     }
 }
 
+/// Estimates the fee of executing the L1 handler transaction on top of the latest block.
+fn estimate_l1_handler_fee(backend: &Backend, tx: ExecutableTxWithHash) -> anyhow::Result<u128> {
+    let provider = backend.blockchain.provider();
+
+    let block_num = provider.latest_number()?;
+    let block_env = provider
+        .block_env_at(block_num.into())?
+        .ok_or_else(|| anyhow::anyhow!("missing block env of the latest block {block_num}"))?;
+    let cfg_env = backend.chain_cfg_env();
+    let state = StateFactoryProvider::latest(provider)?;
+
+    let flags = ExecutionFlags::default();
+    let fees = backend.executor.estimate_fee(&block_env, &cfg_env, state, vec![tx], flags)?;
+
+    Ok(u128::try_from(fees[0].overall_fee).unwrap_or(u128::MAX))
+}
+
+/// Returns the messages sent to L1 by the transactions of the given local block, or `None` if the
+/// block doesn't exist yet.
+fn messages_sent_in_block(
+    backend: &Backend,
+    block_num: u64,
+) -> MessengerResult<Option<Vec<MessageToL1>>> {
+    let provider = backend.blockchain.provider();
+    let receipts = ReceiptProvider::receipts_by_block(provider, BlockHashOrNumber::Num(block_num))?;
+    Ok(receipts.map(|r| r.iter().flat_map(|r| r.messages_sent().to_vec()).collect()))
+}
+
+/// A gathered message whose L1 handler transaction has been added to the pool.
+#[derive(Debug)]
+struct PendingMessage {
+    /// The hash of the settlement chain transaction that sent the message.
+    l1_tx_hash: L1TxHash,
+    message_hash: L1MessageHash,
+    /// The hash of the L1 handler transaction.
+    tx_hash: TxHash,
+}
+
+/// Exponential backoff of a failing messaging operation. The delay starts at `base` and doubles
+/// after each consecutive failure, up to [MAX_RETRY_BACKOFF].
+#[derive(Debug)]
+struct Backoff {
+    base: Duration,
+    failures: u32,
+    retry_at: Option<Instant>,
+}
+
+impl Backoff {
+    fn new(base: Duration) -> Self {
+        Self { base, failures: 0, retry_at: None }
+    }
+
+    /// Returns true if the operation can be attempted.
+    fn is_ready(&self) -> bool {
+        self.retry_at.map_or(true, |at| Instant::now() >= at)
+    }
+
+    /// The delay before the next attempt.
+    fn delay(&self) -> Duration {
+        match self.failures {
+            0 => Duration::ZERO,
+            n => self.base.saturating_mul(2u32.saturating_pow(n - 1)).min(MAX_RETRY_BACKOFF),
+        }
+    }
+
+    /// Records a failure of the operation. Returns the number of consecutive failures.
+    fn failed(&mut self) -> u32 {
+        self.failures += 1;
+        self.retry_at = Some(Instant::now() + self.delay());
+        self.failures
+    }
+
+    fn reset(&mut self) {
+        self.failures = 0;
+        self.retry_at = None;
+    }
+}
+
 /// Returns an `Interval` from the given seconds.
 fn interval_from_seconds(secs: u64) -> Interval {
     let duration = Duration::from_secs(secs);
@@ -250,7 +725,7 @@ fn interval_from_seconds(secs: u64) -> Interval {
     interval
 }
 
-fn trace_msg_to_l1_sent(messages: &Vec<MessageToL1>, hashes: &Vec<String>) {
+fn trace_msg_to_l1_sent(messages: &[MessageToL1], hashes: &[String]) {
     assert_eq!(messages.len(), hashes.len());
 
     #[cfg(feature = "starknet-messaging")]
@@ -330,3 +805,261 @@ hash,
         calldata_str.join(", ")
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+    use katana_primitives::receipt::{L1HandlerTxReceipt, Receipt};
+    use katana_primitives::transaction::{ExecutableTx, Tx, TxWithHash};
+    use starknet::core::types::{EthAddress, MsgFromL1};
+    use starknet::macros::felt;
+
+    use super::*;
+    use crate::backend::config::StarknetConfig;
+
+    async fn messaging_service(
+        backend: &Arc<Backend>,
+        block_producer: &BlockProducer,
+    ) -> MessagingService {
+        let config = MessagingConfig { check_paid_fee: false, ..MessagingConfig::simulated() };
+        let pool = Arc::new(TransactionPool::new());
+        MessagingService::new(config, pool, Arc::clone(backend), block_producer).await.unwrap()
+    }
+
+    /// Sends a message to a contract that isn't deployed from the simulated settlement chain.
+    fn send_message(service: &MessagingService) -> L1HandlerTx {
+        let message = MsgFromL1 {
+            from_address: EthAddress::from_felt(&felt!("0x1")).unwrap(),
+            to_address: felt!("0x2"),
+            entry_point_selector: felt!("0x3"),
+            payload: vec![felt!("0x4")],
+        };
+
+        let messaging = service.simulated_messaging().unwrap();
+        messaging.send_message_to_l2(message, 0, service.backend.chain_id, |_| false)
+    }
+
+    /// Gathers the messages of the simulated settlement chain. Returns the number of transactions
+    /// added to the pool.
+    async fn gather(service: &mut MessagingService) -> usize {
+        let (messenger, chain_id) = (service.messenger.clone(), service.backend.chain_id);
+        let (block_num, txs) =
+            MessagingService::gather_messages(messenger, chain_id, service.gather_from_block)
+                .await
+                .unwrap();
+
+        service.gather_from_block = block_num + 1;
+        service.add_l1_handler_txs(block_num, txs).unwrap()
+    }
+
+    /// Includes the transactions of the pool in a new block, as if they were successfully
+    /// executed.
+    fn mine(backend: &Backend, pool: &TransactionPool) {
+        let tx_receipt_pairs = pool
+            .get_transactions()
+            .iter()
+            .map(|tx| {
+                let ExecutableTx::L1Handler(l1_tx) = &tx.transaction else {
+                    panic!("expected an L1 handler transaction");
+                };
+
+                let receipt = Receipt::L1Handler(L1HandlerTxReceipt {
+                    actual_fee: 0,
+                    events: Vec::new(),
+                    message_hash: l1_tx.message_hash,
+                    messages_sent: Vec::new(),
+                    revert_error: None,
+                    revert_reason: None,
+                    execution_resources: Default::default(),
+                });
+
+                (TxWithHash::from(tx), receipt)
+            })
+            .collect();
+
+        let provider = backend.blockchain.provider();
+        let mut block_env =
+            provider.block_env_at(provider.latest_number().unwrap().into()).unwrap().unwrap();
+        backend.update_block_env(&mut block_env);
+        backend.do_mine_block(&block_env, tx_receipt_pairs, Default::default()).unwrap();
+    }
+
+    /// Includes an L1 handler transaction sending `messages` to the settlement chain in a new
+    /// block. Returns the number of the block.
+    fn mine_messages_to_l1(
+        backend: &Backend,
+        service: &MessagingService,
+        messages: Vec<MessageToL1>,
+    ) -> u64 {
+        let tx = send_message(service);
+        let receipt = Receipt::L1Handler(L1HandlerTxReceipt {
+            actual_fee: 0,
+            events: Vec::new(),
+            message_hash: tx.message_hash,
+            messages_sent: messages,
+            revert_error: None,
+            revert_reason: None,
+            execution_resources: Default::default(),
+        });
+        let tx = TxWithHash { hash: tx.calculate_hash(), transaction: Tx::L1Handler(tx) };
+
+        let provider = backend.blockchain.provider();
+        let mut block_env =
+            provider.block_env_at(provider.latest_number().unwrap().into()).unwrap().unwrap();
+        backend.update_block_env(&mut block_env);
+        backend.do_mine_block(&block_env, vec![(tx, receipt)], Default::default()).unwrap();
+        provider.latest_number().unwrap()
+    }
+
+    /// Sends the messages of the local block `block_num`, starting from the message at
+    /// `from_index`.
+    async fn send(service: &MessagingService, block_num: u64, from_index: usize) -> MessageSending {
+        let (backend, messenger) = (service.backend.clone(), service.messenger.clone());
+        MessagingService::send_messages(block_num, from_index, backend, messenger)
+            .await
+            .unwrap()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn resume_gathering_after_restart() {
+        let backend = Arc::new(Backend::new(StarknetConfig::default()).await);
+        let block_producer = BlockProducer::instant(Arc::clone(&backend));
+        let provider = backend.blockchain.provider();
+
+        let mut service = messaging_service(&backend, &block_producer).await;
+        let tx = send_message(&service);
+        assert_eq!(gather(&mut service).await, 1);
+
+        // the message is not included in a block yet
+        service.checkpoint_gathered_messages().unwrap();
+        assert_eq!(provider.gather_from_block().unwrap(), None);
+
+        mine(&backend, &service.pool);
+        service.checkpoint_gathered_messages().unwrap();
+        assert_eq!(provider.gather_from_block().unwrap(), Some(1));
+        assert!(service.in_flight.is_empty());
+
+        // the settlement chain doesn't survive the restart and sends the same message again
+        let mut restarted = messaging_service(&backend, &block_producer).await;
+        assert_eq!(restarted.gather_from_block, 1);
+        // the status of the message is still known before it is gathered again
+        assert!(restarted.gathered_messages.by_l1_tx_hash(tx.message_hash).unwrap().is_some());
+        assert_eq!(send_message(&restarted), tx);
+        assert_eq!(gather(&mut restarted).await, 0);
+        assert!(restarted.pool.get_transactions().is_empty());
+
+        let status = GatheredMessageStatus::Accepted { transaction_hash: tx.calculate_hash() };
+        let gathered = restarted.gathered_messages.by_l1_tx_hash(tx.message_hash).unwrap();
+        assert_eq!(gathered, Some(vec![GatheredMessage { message_hash: tx.message_hash, status }]));
+    }
+
+    #[tokio::test]
+    async fn skip_messages_being_processed() {
+        let backend = Arc::new(Backend::new(StarknetConfig::default()).await);
+        let block_producer = BlockProducer::instant(Arc::clone(&backend));
+
+        let mut service = messaging_service(&backend, &block_producer).await;
+        let tx = send_message(&service);
+        assert_eq!(gather(&mut service).await, 1);
+
+        // gathered again while its transaction is still waiting to be included in a block
+        let l1_tx_hash = tx.message_hash;
+        let txs = vec![(l1_tx_hash, tx.clone()), (l1_tx_hash, tx)];
+        assert_eq!(service.add_l1_handler_txs(1, txs).unwrap(), 0);
+        assert_eq!(service.pool.get_transactions().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn rejected_messages_do_not_block_the_checkpoint() {
+        let backend = Arc::new(Backend::new(StarknetConfig::default()).await);
+        let mut block_producer = BlockProducer::instant(Arc::clone(&backend));
+        let provider = backend.blockchain.provider();
+
+        let mut service = messaging_service(&backend, &block_producer).await;
+        let tx = send_message(&service);
+        assert_eq!(gather(&mut service).await, 1);
+
+        // the L1 handler can't be executed as the target contract isn't deployed
+        block_producer.queue(service.pool.get_transactions());
+        block_producer.next().await.unwrap().unwrap();
+
+        service.checkpoint_gathered_messages().unwrap();
+        assert_eq!(provider.gather_from_block().unwrap(), Some(1));
+        assert!(service.pending_gathers.is_empty());
+        assert!(service.in_flight.is_empty());
+
+        let gathered = service.gathered_messages.by_l1_tx_hash(tx.message_hash).unwrap().unwrap();
+        assert!(matches!(
+            &gathered[0].status,
+            GatheredMessageStatus::Failed { transaction_hash, .. } if *transaction_hash == tx.calculate_hash()
+        ));
+    }
+
+    #[tokio::test]
+    async fn dead_letters_survive_restart() {
+        let backend = Arc::new(Backend::new(StarknetConfig::default()).await);
+        let block_producer = BlockProducer::instant(Arc::clone(&backend));
+
+        let messages = (1u8..=3)
+            .map(|i| MessageToL1 {
+                from_address: felt!("0x1").into(),
+                to_address: felt!("0x2"),
+                payload: vec![i.into()],
+            })
+            .collect::<Vec<_>>();
+
+        let mut service = messaging_service(&backend, &block_producer).await;
+        let block_num = mine_messages_to_l1(&backend, &service, messages.clone());
+        service.send_from_block = block_num;
+
+        // the first message is dead-lettered and the others are sent, then the node stops before
+        // moving on to the next block
+        service.dead_letter_message(messages[0].clone(), 6, "unreachable".into()).unwrap();
+        assert_eq!(service.send_from_index, 1);
+
+        let sending = send(&service, block_num, service.send_from_index).await;
+        assert_eq!(sending.sent, 2);
+        assert_eq!(service.simulated_messaging().unwrap().messages_to_l1().len(), 2);
+
+        let restarted = messaging_service(&backend, &block_producer).await;
+        assert_eq!(restarted.send_from_block, block_num);
+        assert_eq!(restarted.send_from_index, 3);
+
+        // nothing is left to send, and the dead-lettered message isn't retried
+        let sending = send(&restarted, block_num, restarted.send_from_index).await;
+        assert_eq!(sending.sent, 0);
+        assert!(restarted.simulated_messaging().unwrap().messages_to_l1().is_empty());
+
+        let letter = DeadLetter {
+            block_number: block_num,
+            message: messages[0].clone(),
+            attempts: 6,
+            error: "unreachable".into(),
+        };
+        assert_eq!(restarted.dead_letters().all().unwrap(), vec![letter]);
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_max_delay() {
+        let mut backoff = Backoff::new(Duration::from_secs(2));
+        assert!(backoff.is_ready());
+
+        assert_eq!(backoff.failed(), 1);
+        assert_eq!(backoff.delay(), Duration::from_secs(2));
+        assert!(!backoff.is_ready());
+
+        backoff.failed();
+        backoff.failed();
+        assert_eq!(backoff.delay(), Duration::from_secs(8));
+
+        for _ in 0..10 {
+            backoff.failed();
+        }
+        assert_eq!(backoff.delay(), MAX_RETRY_BACKOFF);
+
+        backoff.reset();
+        assert!(backoff.is_ready());
+        assert_eq!(backoff.delay(), Duration::ZERO);
+    }
+}
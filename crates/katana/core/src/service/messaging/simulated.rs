@@ -0,0 +1,238 @@
+use async_trait::async_trait;
+use ethers::types::H256;
+use katana_primitives::chain::ChainId;
+use katana_primitives::receipt::MessageToL1;
+use katana_primitives::transaction::{L1HandlerTx, L1MessageHash, L1TxHash};
+use katana_primitives::utils::transaction::{
+    compute_l1_message_hash, compute_l1_to_l2_message_hash,
+};
+use katana_primitives::FieldElement;
+use parking_lot::Mutex;
+use starknet::core::types::MsgFromL1;
+use tracing::debug;
+
+use super::{Messenger, MessengerResult, LOG_TARGET};
+
+/// A message sent to the simulated settlement chain.
+#[derive(Debug, Clone)]
+pub struct SimulatedMessageToL1 {
+    /// The hash of the message, used to consume it on the settlement chain.
+    pub hash: H256,
+    /// The message content.
+    pub message: MessageToL1,
+    /// Whether the message has been consumed on the settlement chain.
+    pub consumed: bool,
+}
+
+#[derive(Debug, Default)]
+struct SimulatedSettlementChain {
+    /// The nonce of the next message sent to L2.
+    nonce: u64,
+    /// The messages sent to L2 that have not been gathered yet.
+    messages_to_l2: Vec<(L1TxHash, L1HandlerTx)>,
+    /// All the messages sent to the settlement chain, in the order they were settled.
+    messages_to_l1: Vec<SimulatedMessageToL1>,
+}
+
+/// Messaging with a settlement chain simulated in-process.
+///
+/// Instead of being emitted by a contract on the settlement chain, messages to L2 are injected
+/// with [SimulatedMessaging::send_message_to_l2]. The messages to L1 are kept until they are
+/// consumed with [SimulatedMessaging::consume_message_to_l1].
+#[derive(Debug, Default)]
+pub struct SimulatedMessaging {
+    chain: Mutex<SimulatedSettlementChain>,
+}
+
+impl SimulatedMessaging {
+    /// Sends a message from the settlement chain to L2, returning the L1 handler transaction
+    /// that will be executed once the message is gathered.
+    ///
+    /// Each message is sent by its own settlement chain transaction, whose hash is the hash of
+    /// the message.
+    ///
+    /// As the simulated chain doesn't survive a restart, the nonces for which `is_processed`
+    /// returns true are skipped so that the message doesn't collide with an already processed
+    /// message.
+    pub fn send_message_to_l2(
+        &self,
+        message: MsgFromL1,
+        paid_fee_on_l1: u128,
+        chain_id: ChainId,
+        is_processed: impl Fn(L1MessageHash) -> bool,
+    ) -> L1HandlerTx {
+        let mut chain = self.chain.lock();
+
+        // This conversion will never fail as `from_address` is 20 bytes.
+        let from_address =
+            FieldElement::from_byte_slice_be(message.from_address.as_bytes()).unwrap();
+
+        let (nonce, message_hash) = loop {
+            let nonce = FieldElement::from(chain.nonce);
+            chain.nonce += 1;
+
+            let message_hash = compute_l1_to_l2_message_hash(
+                from_address,
+                message.to_address,
+                message.entry_point_selector,
+                &message.payload,
+                nonce,
+            );
+
+            if !is_processed(message_hash) {
+                break (nonce, message_hash);
+            }
+        };
+
+        let mut calldata = vec![from_address];
+        calldata.extend(message.payload);
+
+        let tx = L1HandlerTx {
+            nonce,
+            calldata,
+            chain_id,
+            message_hash,
+            paid_fee_on_l1,
+            version: FieldElement::ZERO,
+            contract_address: message.to_address.into(),
+            entry_point_selector: message.entry_point_selector,
+        };
+
+        chain.messages_to_l2.push((message_hash, tx.clone()));
+        tx
+    }
+
+    /// Returns all the messages sent to the settlement chain.
+    pub fn messages_to_l1(&self) -> Vec<SimulatedMessageToL1> {
+        self.chain.lock().messages_to_l1.clone()
+    }
+
+    /// Consumes a message sent to the settlement chain. Returns false if there is no message with
+    /// the given hash left to consume.
+    pub fn consume_message_to_l1(&self, hash: H256) -> bool {
+        let mut chain = self.chain.lock();
+        let message = chain
+            .messages_to_l1
+            .iter_mut()
+            .find(|message| message.hash == hash && !message.consumed);
+
+        match message {
+            Some(message) => {
+                message.consumed = true;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[async_trait]
+impl Messenger for SimulatedMessaging {
+    type MessageHash = H256;
+    type MessageTransaction = L1HandlerTx;
+
+    async fn gather_messages(
+        &self,
+        from_block: u64,
+        _max_blocks: u64,
+        _chain_id: ChainId,
+    ) -> MessengerResult<(u64, Vec<(L1TxHash, Self::MessageTransaction)>)> {
+        // The simulated chain has no blocks, all the pending messages are gathered at once.
+        let txs = std::mem::take(&mut self.chain.lock().messages_to_l2);
+        Ok((from_block, txs))
+    }
+
+    async fn send_messages(
+        &self,
+        messages: &[MessageToL1],
+    ) -> MessengerResult<Vec<Self::MessageHash>> {
+        let mut chain = self.chain.lock();
+        let mut hashes = Vec::with_capacity(messages.len());
+
+        for message in messages {
+            let hash = compute_l1_message_hash(
+                message.from_address.into(),
+                message.to_address,
+                &message.payload,
+            );
+
+            debug!(target: LOG_TARGET, "Message {hash:#x} settled on simulated chain");
+
+            hashes.push(hash);
+            chain.messages_to_l1.push(SimulatedMessageToL1 {
+                hash,
+                message: message.clone(),
+                consumed: false,
+            });
+        }
+
+        Ok(hashes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use starknet::core::types::EthAddress;
+    use starknet::macros::felt;
+
+    use super::*;
+
+    fn message() -> MsgFromL1 {
+        MsgFromL1 {
+            from_address: EthAddress::from_felt(&felt!("0x1")).unwrap(),
+            to_address: felt!("0x2"),
+            entry_point_selector: felt!("0x3"),
+            payload: vec![felt!("0x4")],
+        }
+    }
+
+    #[tokio::test]
+    async fn gather_messages_sent_to_l2() {
+        let messaging = SimulatedMessaging::default();
+
+        let tx1 = messaging.send_message_to_l2(message(), 10, ChainId::default(), |_| false);
+        let tx2 = messaging.send_message_to_l2(message(), 20, ChainId::default(), |_| false);
+        assert_ne!(tx1.message_hash, tx2.message_hash);
+        assert_eq!(tx1.calldata, vec![felt!("0x1"), felt!("0x4")]);
+        assert_eq!(tx2.paid_fee_on_l1, 20);
+
+        let (block, txs) = messaging.gather_messages(7, 200, ChainId::default()).await.unwrap();
+        assert_eq!(block, 7);
+        assert_eq!(txs, vec![(tx1.message_hash, tx1), (tx2.message_hash, tx2)]);
+
+        let (_, txs) = messaging.gather_messages(8, 200, ChainId::default()).await.unwrap();
+        assert!(txs.is_empty());
+    }
+
+    #[test]
+    fn skip_processed_nonces() {
+        let messaging = SimulatedMessaging::default();
+        let processed = messaging.send_message_to_l2(message(), 0, ChainId::default(), |_| false);
+
+        let messaging = SimulatedMessaging::default();
+        let tx = messaging.send_message_to_l2(message(), 0, ChainId::default(), |hash| {
+            hash == processed.message_hash
+        });
+
+        assert_eq!(tx.nonce, FieldElement::ONE);
+    }
+
+    #[tokio::test]
+    async fn consume_messages_sent_to_l1() {
+        let messaging = SimulatedMessaging::default();
+        let message = MessageToL1 {
+            from_address: felt!("0x1").into(),
+            to_address: felt!("0x2"),
+            payload: vec![felt!("0x3")],
+        };
+
+        let hashes = messaging.send_messages(&[message.clone(), message]).await.unwrap();
+        assert_eq!(hashes[0], hashes[1]);
+
+        assert!(messaging.consume_message_to_l1(hashes[0]));
+        assert!(messaging.consume_message_to_l1(hashes[0]));
+        assert!(!messaging.consume_message_to_l1(hashes[0]));
+
+        assert!(messaging.messages_to_l1().iter().all(|message| message.consumed));
+    }
+}
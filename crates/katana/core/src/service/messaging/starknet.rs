@@ -1,14 +1,18 @@
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::Result;
 use async_trait::async_trait;
 use katana_primitives::chain::ChainId;
 use katana_primitives::receipt::MessageToL1;
-use katana_primitives::transaction::L1HandlerTx;
-use katana_primitives::utils::transaction::compute_l1_message_hash;
+use katana_primitives::transaction::{L1HandlerTx, L1TxHash};
+use katana_primitives::utils::transaction::compute_l1_to_l2_message_hash;
 use starknet::accounts::{Account, Call, ExecutionEncoding, SingleOwnerAccount};
-use starknet::core::types::{BlockId, BlockTag, EmittedEvent, EventFilter, FieldElement};
+use starknet::core::types::{
+    BlockId, BlockTag, EmittedEvent, EventFilter, ExecutionResult, FieldElement,
+    MaybePendingTransactionReceipt, PendingTransactionReceipt, TransactionReceipt,
+};
 use starknet::core::utils::starknet_keccak;
 use starknet::macros::{felt, selector};
 use starknet::providers::jsonrpc::HttpTransport;
@@ -29,6 +33,12 @@ const EXE_MAGIC: FieldElement = felt!("0x455845");
 
 pub const HASH_EXEC: FieldElement = felt!("0xee");
 
+/// The interval at which the receipt of a transaction sent to the settlement chain is polled.
+const TX_RECEIPT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// The number of times the receipt of a transaction sent to the settlement chain is polled before
+/// giving up.
+const TX_RECEIPT_MAX_POLLS: usize = 60;
+
 pub struct StarknetMessaging {
     chain_id: FieldElement,
     provider: AnyProvider,
@@ -128,6 +138,41 @@ impl StarknetMessaging {
         Ok(tx.transaction_hash)
     }
 
+    /// Waits for a transaction sent to the settlement chain to be executed, and fails if it
+    /// reverted.
+    async fn wait_for_tx(&self, tx_hash: FieldElement) -> MessengerResult<()> {
+        for _ in 0..TX_RECEIPT_MAX_POLLS {
+            let receipt = match self.provider.get_transaction_receipt(tx_hash).await {
+                Ok(receipt) => receipt,
+                Err(_) => {
+                    tokio::time::sleep(TX_RECEIPT_POLL_INTERVAL).await;
+                    continue;
+                }
+            };
+
+            let execution_result = match receipt {
+                MaybePendingTransactionReceipt::Receipt(TransactionReceipt::Invoke(r)) => {
+                    r.execution_result
+                }
+                MaybePendingTransactionReceipt::PendingReceipt(
+                    PendingTransactionReceipt::Invoke(r),
+                ) => r.execution_result,
+                _ => return Err(Error::SendError),
+            };
+
+            return match execution_result {
+                ExecutionResult::Succeeded => Ok(()),
+                ExecutionResult::Reverted { reason } => {
+                    error!(target: LOG_TARGET, "Transaction {tx_hash:#064x} reverted: {reason}");
+                    Err(Error::TransactionReverted(reason))
+                }
+            };
+        }
+
+        warn!(target: LOG_TARGET, "No receipt for transaction {tx_hash:#064x}.");
+        Err(Error::SendError)
+    }
+
     /// Sends messages hashes to settlement layer by sending a transaction.
     async fn send_hashes(&self, mut hashes: Vec<FieldElement>) -> MessengerResult<FieldElement> {
         hashes.retain(|&x| x != HASH_EXEC);
@@ -148,6 +193,7 @@ impl StarknetMessaging {
         match self.send_invoke_tx(vec![call]).await {
             Ok(tx_hash) => {
                 trace!(target: LOG_TARGET, "Hashes sending transaction {:#064x}", tx_hash);
+                self.wait_for_tx(tx_hash).await?;
                 Ok(tx_hash)
             }
             Err(e) => {
@@ -168,7 +214,7 @@ impl Messenger for StarknetMessaging {
         from_block: u64,
         max_blocks: u64,
         chain_id: ChainId,
-    ) -> MessengerResult<(u64, Vec<Self::MessageTransaction>)> {
+    ) -> MessengerResult<(u64, Vec<(L1TxHash, Self::MessageTransaction)>)> {
         let chain_latest_block: u64 = match self.provider.block_number().await {
             Ok(n) => n,
             Err(_) => {
@@ -192,7 +238,7 @@ impl Messenger for StarknetMessaging {
             chain_latest_block
         };
 
-        let mut l1_handler_txs: Vec<L1HandlerTx> = vec![];
+        let mut l1_handler_txs: Vec<(L1TxHash, L1HandlerTx)> = vec![];
 
         self.fetch_events(BlockId::Number(from_block), BlockId::Number(to_block))
             .await
@@ -209,7 +255,8 @@ impl Messenger for StarknetMessaging {
 
                 block_events.iter().for_each(|e| {
                     if let Ok(tx) = l1_handler_tx_from_event(e, chain_id) {
-                        l1_handler_txs.push(tx)
+                        let l1_tx_hash = L1TxHash::from(e.transaction_hash.to_bytes_be());
+                        l1_handler_txs.push((l1_tx_hash, tx))
                     }
                 })
             });
@@ -231,6 +278,7 @@ impl Messenger for StarknetMessaging {
             match self.send_invoke_tx(calls).await {
                 Ok(tx_hash) => {
                     trace!(target: LOG_TARGET, "Invoke transaction hash {:#064x}", tx_hash);
+                    self.wait_for_tx(tx_hash).await?;
                 }
                 Err(e) => {
                     error!("Error sending invoke tx on Starknet: {:?}", e);
@@ -310,6 +358,14 @@ fn parse_messages(messages: &[MessageToL1]) -> MessengerResult<(Vec<FieldElement
     Ok((hashes, calls))
 }
 
+/// The fee paid on L1 given to the L1 handler transactions of the messages gathered from Starknet.
+///
+/// The messaging contract doesn't charge a fee for the messages, so the `MessageSentToAppchain`
+/// event doesn't carry any. The paid fee isn't checked in this mode, see
+/// [MessengerMode::reports_paid_fee](super::MessengerMode::reports_paid_fee), this value is only
+/// used to execute the transactions.
+const PAID_FEE_ON_L1: u128 = 30000;
+
 fn l1_handler_tx_from_event(event: &EmittedEvent, chain_id: ChainId) -> Result<L1HandlerTx> {
     if event.keys[0] != selector!("MessageSentToAppchain") {
         debug!(
@@ -334,15 +390,20 @@ fn l1_handler_tx_from_event(event: &EmittedEvent, chain_id: ChainId) -> Result<L
     let mut calldata = vec![from_address];
     calldata.extend(&event.data[3..]);
 
-    let message_hash = compute_l1_message_hash(from_address, to_address, &calldata);
+    let message_hash = compute_l1_to_l2_message_hash(
+        from_address,
+        to_address,
+        entry_point_selector,
+        &calldata[1..],
+        nonce,
+    );
 
     Ok(L1HandlerTx {
         nonce,
         calldata,
         chain_id,
         message_hash,
-        // This is the min value paid on L1 for the message to be sent to L2.
-        paid_fee_on_l1: 30000_u128,
+        paid_fee_on_l1: PAID_FEE_ON_L1,
         entry_point_selector,
         version: FieldElement::ZERO,
         contract_address: to_address.into(),
@@ -467,14 +528,20 @@ mod tests {
             transaction_hash,
         };
 
-        let message_hash = compute_l1_message_hash(from_address, to_address, &calldata);
+        let message_hash = compute_l1_to_l2_message_hash(
+            from_address,
+            to_address,
+            selector,
+            &calldata[1..],
+            nonce,
+        );
 
         let expected = L1HandlerTx {
             nonce,
             calldata,
             chain_id,
             message_hash,
-            paid_fee_on_l1: 30000_u128,
+            paid_fee_on_l1: PAID_FEE_ON_L1,
             version: FieldElement::ZERO,
             entry_point_selector: selector,
             contract_address: to_address.into(),
@@ -0,0 +1,38 @@
+use std::sync::Arc;
+
+pub use katana_db::models::messaging::{GatheredMessage, GatheredMessageStatus};
+use katana_primitives::transaction::L1TxHash;
+use katana_provider::traits::messaging::{MessagingCheckpointProvider, MessagingCheckpointWriter};
+use katana_provider::ProviderResult;
+
+use crate::backend::Backend;
+
+/// The messages gathered from the settlement chain, indexed by the hash of the settlement chain
+/// transaction that sent them.
+///
+/// The messages are persisted in the storage, so their status can still be looked up after a
+/// restart.
+pub struct GatheredMessages {
+    backend: Arc<Backend>,
+}
+
+impl GatheredMessages {
+    pub(super) fn new(backend: Arc<Backend>) -> Self {
+        Self { backend }
+    }
+
+    /// Returns the messages sent by the given settlement chain transaction, in the order they were
+    /// gathered.
+    pub fn by_l1_tx_hash(&self, hash: L1TxHash) -> ProviderResult<Option<Vec<GatheredMessage>>> {
+        self.backend.blockchain.provider().gathered_messages(hash)
+    }
+
+    /// Records a gathered message, replacing the status of the message if it was already gathered.
+    pub(super) fn insert(
+        &self,
+        l1_tx_hash: L1TxHash,
+        message: GatheredMessage,
+    ) -> ProviderResult<()> {
+        self.backend.blockchain.provider().insert_gathered_message(l1_tx_hash, message)
+    }
+}
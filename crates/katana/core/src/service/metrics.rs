@@ -0,0 +1,56 @@
+//! Metrics of the block production.
+//!
+//! The metrics recorded are:
+//! - `block_producer.block_production_duration_seconds`: the time taken to produce a block, from
+//!   the execution of its transactions (in _instant_ mode only) to its commitment, per mode.
+//! - `block_producer.block_transactions`: the number of transactions included in a block.
+//! - `block_producer.rejected_transactions`: the number of transactions that couldn't be included
+//!   in a block because their execution failed, per reason.
+//! - `txpool.size`: the number of transactions waiting in the pool to be picked by the miner.
+//! - `executor.transaction_execution_duration_seconds`: the time taken to execute a transaction.
+//! - `db.commit_duration_seconds`: the time taken to write a mined block to the storage.
+//! - `db.size`: the size of the MDBX database file, if the node is backed by a database.
+
+use katana_executor::abstraction::ExecutorError;
+use metrics::{describe_counter, describe_gauge, describe_histogram, Unit};
+
+/// Describes the metrics recorded by the sequencer so that they are exported along with their
+/// unit and description.
+pub(crate) fn describe() {
+    describe_histogram!(
+        "block_producer.block_production_duration_seconds",
+        Unit::Seconds,
+        "Time taken to produce a block"
+    );
+    describe_histogram!(
+        "block_producer.block_transactions",
+        Unit::Count,
+        "Number of transactions included in a block"
+    );
+    describe_counter!(
+        "block_producer.rejected_transactions",
+        Unit::Count,
+        "Number of transactions rejected per reason"
+    );
+    describe_gauge!("txpool.size", Unit::Count, "Number of transactions in the pool");
+    describe_histogram!(
+        "executor.transaction_execution_duration_seconds",
+        Unit::Seconds,
+        "Time taken to execute a transaction"
+    );
+    describe_histogram!(
+        "db.commit_duration_seconds",
+        Unit::Seconds,
+        "Time taken to write a mined block to the storage"
+    );
+    describe_gauge!("db.size", Unit::Bytes, "Size of the database file");
+}
+
+/// Returns the label of the reason why a transaction has been rejected.
+pub(crate) fn rejection_reason(error: &ExecutorError) -> &'static str {
+    match error {
+        ExecutorError::ContractNotDeployed(_) => "contract_not_deployed",
+        ExecutorError::TransactionExecution(_) => "execution_failed",
+        ExecutorError::Other(_) => "other",
+    }
+}
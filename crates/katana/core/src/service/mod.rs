@@ -15,8 +15,11 @@ use self::block_producer::BlockProducer;
 use crate::pool::TransactionPool;
 
 pub mod block_producer;
+pub mod follower;
 #[cfg(feature = "messaging")]
 pub mod messaging;
+pub(crate) mod metrics;
+pub mod pending;
 
 #[cfg(feature = "messaging")]
 use self::messaging::{MessagingOutcome, MessagingService};
@@ -54,6 +57,9 @@ impl Future for NodeService {
                     MessagingOutcome::Send { msg_count, .. } => {
                         trace!(target: "node", "sent {msg_count} messages to the settlement chain");
                     }
+                    MessagingOutcome::DeadLetter { block_num, msg_count } => {
+                        trace!(target: "node", "dead-lettered {msg_count} messages of block {block_num}");
+                    }
                 }
             }
         }
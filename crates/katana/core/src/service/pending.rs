@@ -0,0 +1,166 @@
+use std::sync::Arc;
+
+use katana_executor::abstraction::{BlockExecutionOutput, ExecutorError};
+use katana_primitives::contract::{
+    ClassHash, CompiledClassHash, CompiledContractClass, ContractAddress, FlattenedSierraClass,
+    Nonce, StorageKey, StorageValue,
+};
+use katana_primitives::env::{BlockEnv, CfgEnv};
+use katana_primitives::receipt::Receipt;
+use katana_primitives::state::StateUpdatesWithDeclaredClasses;
+use katana_primitives::transaction::TxWithHash;
+use katana_provider::traits::contract::ContractClassProvider;
+use katana_provider::traits::state::StateProvider;
+use katana_provider::ProviderResult;
+use parking_lot::RwLock;
+
+pub type AcceptedTxPair = (TxWithHash, Receipt);
+pub type RejectedTxPair = (TxWithHash, ExecutorError);
+
+/// The block being built by the _interval_ block producer.
+///
+/// The transactions of the block are executed in batches, each one on top of the state left by the
+/// previous ones. This type implements [StateProvider] so that it can be given to the executor to
+/// execute the next batch, and to read the state of the pending block.
+pub struct PendingState {
+    /// The block context of the pending block.
+    pub block_envs: RwLock<(BlockEnv, CfgEnv)>,
+    /// The state the pending block is built on.
+    base: RwLock<Arc<dyn StateProvider>>,
+    /// The state changes of the transactions executed in the pending block.
+    state_updates: RwLock<StateUpdatesWithDeclaredClasses>,
+    /// The transactions that have been executed.
+    pub executed_txs: RwLock<Vec<AcceptedTxPair>>,
+    /// The transactions that have been rejected.
+    pub rejected_txs: RwLock<Vec<RejectedTxPair>>,
+}
+
+impl PendingState {
+    pub fn new(state: Box<dyn StateProvider>, block_env: BlockEnv, cfg_env: CfgEnv) -> Self {
+        Self {
+            block_envs: RwLock::new((block_env, cfg_env)),
+            base: RwLock::new(Arc::from(state)),
+            state_updates: RwLock::new(StateUpdatesWithDeclaredClasses::default()),
+            executed_txs: RwLock::new(Vec::new()),
+            rejected_txs: RwLock::new(Vec::new()),
+        }
+    }
+
+    /// Opens a new pending block on top of `state`, discarding the state changes of the current
+    /// one.
+    pub fn reset_state(&self, state: Box<dyn StateProvider>, block_env: BlockEnv, cfg_env: CfgEnv) {
+        *self.block_envs.write() = (block_env, cfg_env);
+        *self.base.write() = Arc::from(state);
+        *self.state_updates.write() = StateUpdatesWithDeclaredClasses::default();
+    }
+
+    /// Adds the transactions executed on top of the pending state to the pending block.
+    pub fn add_executed_txs(&self, output: BlockExecutionOutput) {
+        let BlockExecutionOutput { transactions, rejected, states } = output;
+        merge_state_updates(&mut self.state_updates.write(), states);
+        self.executed_txs.write().extend(transactions);
+        self.rejected_txs.write().extend(rejected);
+    }
+
+    /// Returns the state changes of all the transactions executed in the pending block.
+    pub fn state_updates(&self) -> StateUpdatesWithDeclaredClasses {
+        self.state_updates.read().clone()
+    }
+
+    /// Drain the pending transactions, returning the executed and rejected transactions.
+    pub fn take_txs_all(&self) -> (Vec<AcceptedTxPair>, Vec<RejectedTxPair>) {
+        let executed_txs = std::mem::take(&mut *self.executed_txs.write());
+        let rejected_txs = std::mem::take(&mut *self.rejected_txs.write());
+        (executed_txs, rejected_txs)
+    }
+
+    pub fn block_execution_envs(&self) -> (BlockEnv, CfgEnv) {
+        self.block_envs.read().clone()
+    }
+
+    fn base(&self) -> Arc<dyn StateProvider> {
+        Arc::clone(&self.base.read())
+    }
+}
+
+impl ContractClassProvider for PendingState {
+    fn compiled_class_hash_of_class_hash(
+        &self,
+        hash: ClassHash,
+    ) -> ProviderResult<Option<CompiledClassHash>> {
+        if let Some(hash) = self.state_updates.read().state_updates.declared_classes.get(&hash) {
+            return Ok(Some(*hash));
+        }
+        self.base().compiled_class_hash_of_class_hash(hash)
+    }
+
+    fn class(&self, hash: ClassHash) -> ProviderResult<Option<CompiledContractClass>> {
+        if let Some(class) = self.state_updates.read().declared_compiled_classes.get(&hash) {
+            return Ok(Some(class.clone()));
+        }
+        self.base().class(hash)
+    }
+
+    fn sierra_class(&self, hash: ClassHash) -> ProviderResult<Option<FlattenedSierraClass>> {
+        if let Some(class) = self.state_updates.read().declared_sierra_classes.get(&hash) {
+            return Ok(Some(class.clone()));
+        }
+        self.base().sierra_class(hash)
+    }
+}
+
+impl StateProvider for PendingState {
+    fn nonce(&self, address: ContractAddress) -> ProviderResult<Option<Nonce>> {
+        if let Some(nonce) = self.state_updates.read().state_updates.nonce_updates.get(&address) {
+            return Ok(Some(*nonce));
+        }
+        self.base().nonce(address)
+    }
+
+    fn storage(
+        &self,
+        address: ContractAddress,
+        storage_key: StorageKey,
+    ) -> ProviderResult<Option<StorageValue>> {
+        let value = self
+            .state_updates
+            .read()
+            .state_updates
+            .storage_updates
+            .get(&address)
+            .and_then(|storage| storage.get(&storage_key).copied());
+
+        match value {
+            Some(value) => Ok(Some(value)),
+            None => self.base().storage(address, storage_key),
+        }
+    }
+
+    fn class_hash_of_contract(
+        &self,
+        address: ContractAddress,
+    ) -> ProviderResult<Option<ClassHash>> {
+        if let Some(hash) = self.state_updates.read().state_updates.contract_updates.get(&address) {
+            return Ok(Some(*hash));
+        }
+        self.base().class_hash_of_contract(address)
+    }
+}
+
+/// Applies the state changes `new` on top of `state`.
+fn merge_state_updates(
+    state: &mut StateUpdatesWithDeclaredClasses,
+    new: StateUpdatesWithDeclaredClasses,
+) {
+    let updates = &mut state.state_updates;
+    updates.nonce_updates.extend(new.state_updates.nonce_updates);
+    updates.contract_updates.extend(new.state_updates.contract_updates);
+    updates.declared_classes.extend(new.state_updates.declared_classes);
+    for (address, storage) in new.state_updates.storage_updates {
+        updates.storage_updates.entry(address).or_default().extend(storage);
+    }
+
+    state.declared_sierra_classes.extend(new.declared_sierra_classes);
+    state.declared_compiled_classes.extend(new.declared_compiled_classes);
+    state.declared_legacy_class_abis.extend(new.declared_legacy_class_abis);
+}
@@ -0,0 +1,125 @@
+use katana_core::backend::config::StarknetConfig;
+use katana_core::backend::Backend;
+use katana_executor::abstraction::{BlockExecutionOutput, ExecutionFlags, Executor};
+use katana_executor::blockifier::BlockifierExecutor;
+use katana_primitives::block::GasPrices;
+use katana_primitives::contract::ContractAddress;
+use katana_primitives::env::BlockEnv;
+use katana_primitives::transaction::{ExecutableTx, ExecutableTxWithHash, InvokeTx, InvokeTxV1};
+use katana_primitives::FieldElement;
+use katana_provider::traits::state::StateFactoryProvider;
+use starknet::core::utils::get_selector_from_name;
+
+const MAX_FEE: u128 = 1_000_000_000_000_000_000;
+
+struct Setup {
+    backend: Backend,
+    block_env: BlockEnv,
+    accounts: Vec<ContractAddress>,
+}
+
+impl Setup {
+    async fn new() -> Self {
+        let backend = Backend::new(StarknetConfig::default()).await;
+
+        let block_env = BlockEnv {
+            number: 1,
+            timestamp: 0,
+            l1_gas_prices: GasPrices { eth: 100_000_000_000, strk: 100_000_000_000 },
+            sequencer_address: FieldElement::from(0x5e9u64).into(),
+        };
+
+        let mut accounts = backend.config.genesis.accounts().map(|(a, _)| *a).collect::<Vec<_>>();
+        accounts.sort();
+
+        Self { backend, block_env, accounts }
+    }
+
+    /// Transfers `amount` of the fee token from `sender` to `recipient`.
+    fn transfer(
+        &self,
+        sender: ContractAddress,
+        nonce: u64,
+        recipient: ContractAddress,
+        amount: u64,
+    ) -> ExecutableTxWithHash {
+        let calldata = vec![
+            FieldElement::ONE,
+            self.backend.config.genesis.fee_token.address.into(),
+            get_selector_from_name("transfer").unwrap(),
+            FieldElement::THREE,
+            recipient.into(),
+            FieldElement::from(amount),
+            FieldElement::ZERO,
+        ];
+
+        ExecutableTxWithHash::new(ExecutableTx::Invoke(InvokeTx::V1(InvokeTxV1 {
+            chain_id: self.backend.chain_id,
+            sender_address: sender,
+            nonce: nonce.into(),
+            calldata,
+            signature: vec![],
+            max_fee: MAX_FEE,
+        })))
+    }
+
+    fn execute(&self, parallel: bool, txs: Vec<ExecutableTxWithHash>) -> BlockExecutionOutput {
+        let state = self.backend.blockchain.provider().latest().unwrap();
+        let flags = ExecutionFlags { charge_fee: true, validate: false };
+        BlockifierExecutor::default()
+            .with_parallel_execution(parallel)
+            .execute_block(&self.block_env, &self.backend.chain_cfg_env(), state, txs, flags)
+            .unwrap()
+    }
+
+    /// Executes the transactions sequentially and in parallel, and checks that both give the same
+    /// receipts and state changes.
+    fn assert_same_output(&self, txs: Vec<ExecutableTxWithHash>) {
+        let sequential = self.execute(false, txs.clone());
+        let parallel = self.execute(true, txs);
+
+        assert_eq!(parallel.transactions, sequential.transactions);
+        assert_eq!(
+            parallel.rejected.iter().map(|(tx, _)| tx.hash).collect::<Vec<_>>(),
+            sequential.rejected.iter().map(|(tx, _)| tx.hash).collect::<Vec<_>>()
+        );
+        assert_eq!(parallel.states.state_updates, sequential.states.state_updates);
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn parallel_execution_of_independent_transactions() {
+    let setup = Setup::new().await;
+    let recipient = |i: u64| ContractAddress::from(FieldElement::from(0x1000 + i));
+
+    let txs =
+        (0..4).map(|i| setup.transfer(setup.accounts[i], 0, recipient(i as u64), 10)).collect();
+    setup.assert_same_output(txs);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn parallel_execution_of_conflicting_transactions() {
+    let setup = Setup::new().await;
+    let [a, b, c, d] = [0, 1, 2, 3].map(|i| setup.accounts[i]);
+    let recipient = ContractAddress::from(FieldElement::from(0x1000u64));
+
+    let txs = vec![
+        // both write the balance of the same recipient
+        setup.transfer(a, 0, recipient, 10),
+        setup.transfer(b, 0, recipient, 20),
+        // depends on the nonce and the balance written by the first transaction
+        setup.transfer(a, 1, c, 30),
+        // reads the balance of the sequencer, written by the fee transfers
+        setup.transfer(d, 0, setup.block_env.sequencer_address, 40),
+        // rejected, the nonce is ahead of the one of the sender
+        setup.transfer(b, 5, d, 50),
+        // spends the balance received from the third transaction
+        setup.transfer(c, 0, a, 60),
+    ];
+
+    let output = setup.execute(true, txs.clone());
+    assert_eq!(output.transactions.len(), 5);
+    assert_eq!(output.rejected.len(), 1);
+
+    setup.assert_same_output(txs);
+}
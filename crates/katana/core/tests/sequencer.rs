@@ -109,6 +109,21 @@ async fn test_increase_next_block_timestamp() {
     assert_eq!(block2_timestamp, block1_timestamp + 1000, "timestamp should be updated");
 }
 
+#[tokio::test]
+async fn test_mine_pending_without_executed_transactions() {
+    let (mut sequencer_config, starknet_config) = create_test_sequencer_config();
+    sequencer_config.no_mining = true;
+
+    let sequencer = KatanaSequencer::new(sequencer_config, starknet_config).await.unwrap();
+    let provider = sequencer.backend.blockchain.provider();
+
+    let block_num = provider.latest_number().unwrap();
+    let outcome = sequencer.block_producer().mine_pending().await.unwrap();
+
+    assert!(outcome.is_none(), "empty pending block should not be mined");
+    assert_eq!(provider.latest_number().unwrap(), block_num);
+}
+
 // #[tokio::test]
 // async fn test_set_storage_at_on_instant_mode() {
 //     let sequencer = create_test_sequencer().await;
@@ -0,0 +1,116 @@
+//! Executor-agnostic interfaces for executing transactions.
+//!
+//! The [Executor] trait is what the rest of Katana is built on, so that the underlying VM can be
+//! swapped without touching the node implementation. The default implementation is backed by the
+//! blockifier and is available under the `blockifier` feature.
+
+use katana_primitives::contract::ContractAddress;
+use katana_primitives::env::{BlockEnv, CfgEnv};
+use katana_primitives::receipt::{Receipt, RevertReason};
+use katana_primitives::state::StateUpdatesWithDeclaredClasses;
+use katana_primitives::transaction::{ExecutableTxWithHash, TxWithHash};
+use katana_primitives::FieldElement;
+use katana_provider::traits::state::StateProvider;
+use starknet::core::types::FeeEstimate;
+
+pub type ExecutorResult<T> = Result<T, ExecutorError>;
+
+/// Errors that can be returned by an [Executor].
+#[derive(Debug, thiserror::Error)]
+pub enum ExecutorError {
+    /// The contract that is being called is not deployed.
+    #[error("Contract {0} is not deployed")]
+    ContractNotDeployed(ContractAddress),
+
+    /// The transaction or call failed to execute.
+    #[error("{0}")]
+    TransactionExecution(RevertReason),
+
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+/// Flags to control how transactions are executed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExecutionFlags {
+    /// Charge the transaction fee from the sender.
+    pub charge_fee: bool,
+    /// Run the account validation logic of the transaction.
+    pub validate: bool,
+}
+
+impl Default for ExecutionFlags {
+    fn default() -> Self {
+        Self { charge_fee: true, validate: true }
+    }
+}
+
+#[derive(Debug)]
+pub struct EntryPointCall {
+    /// The address of the contract whose function you're calling.
+    pub contract_address: ContractAddress,
+    /// The input to the function.
+    pub calldata: Vec<FieldElement>,
+    /// The function selector.
+    pub entry_point_selector: FieldElement,
+}
+
+/// The output of executing a list of transactions on top of a state.
+#[derive(Debug, Default)]
+pub struct BlockExecutionOutput {
+    /// The transactions that were successfully executed, paired with their receipts.
+    pub transactions: Vec<(TxWithHash, Receipt)>,
+    /// The transactions that were rejected, paired with the reason of the rejection.
+    pub rejected: Vec<(TxWithHash, ExecutorError)>,
+    /// The state changes resulting from executing the transactions.
+    pub states: StateUpdatesWithDeclaredClasses,
+}
+
+/// A transaction executor.
+///
+/// None of the methods commit anything to the given state. It is up to the caller to
+/// persist the resulting state changes.
+pub trait Executor: Send + Sync {
+    /// Executes the transactions, in order, as part of the block described by `block_env`. A
+    /// transaction that fails to execute does not abort the execution of the rest of the
+    /// transactions, instead it will be included in the list of rejected transactions.
+    fn execute_block(
+        &self,
+        block_env: &BlockEnv,
+        cfg_env: &CfgEnv,
+        state: Box<dyn StateProvider>,
+        transactions: Vec<ExecutableTxWithHash>,
+        flags: ExecutionFlags,
+    ) -> ExecutorResult<BlockExecutionOutput>;
+
+    /// Simulates the execution of the transactions, in order, and returns their receipts.
+    /// Fails if any of the transactions can't be executed.
+    fn simulate(
+        &self,
+        block_env: &BlockEnv,
+        cfg_env: &CfgEnv,
+        state: Box<dyn StateProvider>,
+        transactions: Vec<ExecutableTxWithHash>,
+        flags: ExecutionFlags,
+    ) -> ExecutorResult<Vec<(TxWithHash, Receipt)>>;
+
+    /// Estimates the fee of each of the transactions, executed in order. Fails if any of the
+    /// transactions can't be executed or is reverted.
+    fn estimate_fee(
+        &self,
+        block_env: &BlockEnv,
+        cfg_env: &CfgEnv,
+        state: Box<dyn StateProvider>,
+        transactions: Vec<ExecutableTxWithHash>,
+        flags: ExecutionFlags,
+    ) -> ExecutorResult<Vec<FeeEstimate>>;
+
+    /// Performs a function call on a contract and returns the return values.
+    fn call(
+        &self,
+        block_env: &BlockEnv,
+        cfg_env: &CfgEnv,
+        state: Box<dyn StateProvider>,
+        call: EntryPointCall,
+    ) -> ExecutorResult<Vec<FieldElement>>;
+}
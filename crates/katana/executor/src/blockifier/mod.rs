@@ -1,26 +1,41 @@
 pub mod outcome;
+pub mod parallel;
+pub mod revert;
 pub mod state;
 pub mod transactions;
 pub mod utils;
 
 use std::sync::Arc;
+use std::time::Instant;
 
 use blockifier::block_context::BlockContext;
+use blockifier::execution::errors::{EntryPointExecutionError, PreExecutionError};
 use blockifier::transaction::errors::TransactionExecutionError;
 use blockifier::transaction::objects::TransactionExecutionInfo;
 use blockifier::transaction::transaction_execution::Transaction;
 use blockifier::transaction::transactions::ExecutableTransaction;
 use katana_primitives::env::{BlockEnv, CfgEnv};
+use katana_primitives::receipt::{CallFrame, Receipt};
 use katana_primitives::transaction::{
     DeclareTxWithClass, ExecutableTx, ExecutableTxWithHash, TxWithHash,
 };
-use parking_lot::RwLock;
+use katana_primitives::FieldElement;
+use katana_provider::traits::state::StateProvider;
+use metrics::histogram;
+use starknet::core::types::FeeEstimate;
 use tracing::{trace, warn};
 
 use self::outcome::TxReceiptWithExecInfo;
+use self::parallel::ParallelTransactionExecutor;
+use self::revert::{parse_error_trace, revert_reason_from_error, FunctionMapCache};
 use self::state::{CachedStateWrapper, StateRefDb};
 use self::transactions::BlockifierTx;
-use self::utils::events_from_exec_info;
+use self::utils::{
+    block_context_from_envs, events_from_exec_info, get_state_update_from_cached_state,
+};
+use crate::abstraction::{
+    BlockExecutionOutput, EntryPointCall, ExecutionFlags, Executor, ExecutorError, ExecutorResult,
+};
 use crate::blockifier::utils::{
     pretty_print_resources, trace_events, warn_message_transaction_error_exec_error,
 };
@@ -156,6 +171,18 @@ fn execute_tx(
         None
     };
 
+    let legacy_abi = if let ExecutableTx::Declare(DeclareTxWithClass {
+        transaction,
+        legacy_abi: Some(abi),
+        ..
+    }) = tx.as_ref()
+    {
+        Some((transaction.class_hash(), abi.clone()))
+    } else {
+        None
+    };
+
+    let started_at = Instant::now();
     let res = match BlockifierTx::from(tx).0 {
         Transaction::AccountTransaction(tx) => {
             tx.execute(&mut state.inner(), block_context, charge_fee, validate)
@@ -164,69 +191,211 @@ fn execute_tx(
             tx.execute(&mut state.inner(), block_context, charge_fee, validate)
         }
     };
+    histogram!("executor.transaction_execution_duration_seconds", started_at.elapsed());
 
     if res.is_ok() {
         if let Some((class_hash, sierra_class)) = sierra {
             state.sierra_class_mut().insert(class_hash, sierra_class);
         }
+
+        if let Some((class_hash, abi)) = legacy_abi {
+            state.legacy_class_abis_mut().insert(class_hash, abi);
+        }
     }
 
     res
 }
 
-pub type AcceptedTxPair = (TxWithHash, TxReceiptWithExecInfo);
-pub type RejectedTxPair = (TxWithHash, TransactionExecutionError);
-
-pub struct PendingState {
-    /// The block context of the pending block.
-    pub block_envs: RwLock<(BlockEnv, CfgEnv)>,
-    /// The state of the pending block.
-    pub state: Arc<CachedStateWrapper>,
-    /// The transactions that have been executed.
-    pub executed_txs: RwLock<Vec<AcceptedTxPair>>,
-    /// The transactions that have been rejected.
-    pub rejected_txs: RwLock<Vec<RejectedTxPair>>,
+/// An [Executor] implementation backed by the blockifier.
+#[derive(Debug, Default, Clone)]
+pub struct BlockifierExecutor {
+    /// Execute the transactions of a block optimistically in parallel.
+    parallel_execution: bool,
+    /// The Sierra function maps used to resolve the call paths of the failures.
+    function_maps: FunctionMapCache,
 }
 
-impl PendingState {
-    pub fn new(state: StateRefDb, block_env: BlockEnv, cfg_env: CfgEnv) -> Self {
-        Self {
-            block_envs: RwLock::new((block_env, cfg_env)),
-            state: Arc::new(CachedStateWrapper::new(state)),
-            executed_txs: RwLock::new(Vec::new()),
-            rejected_txs: RwLock::new(Vec::new()),
-        }
+impl BlockifierExecutor {
+    pub fn with_parallel_execution(self, parallel_execution: bool) -> Self {
+        Self { parallel_execution, ..self }
     }
 
-    pub fn reset_state(&self, state: StateRefDb, block_env: BlockEnv, cfg_env: CfgEnv) {
-        *self.block_envs.write() = (block_env, cfg_env);
-        self.state.reset_with_new_state(state);
+    /// Converts an execution error into an [ExecutorError], resolving the functions of the call
+    /// path of the failure using the classes available in `state`.
+    fn executor_error(
+        &self,
+        error: TransactionExecutionError,
+        state: &dyn StateProvider,
+    ) -> ExecutorError {
+        match ExecutorError::from(error) {
+            ExecutorError::TransactionExecution(mut reason) => {
+                self.function_maps.resolve_call_path(&mut reason, state);
+                ExecutorError::TransactionExecution(reason)
+            }
+            err => err,
+        }
     }
+}
 
-    pub fn add_executed_txs(&self, transactions: Vec<(TxWithHash, TxExecutionResult)>) {
-        transactions.into_iter().for_each(|(tx, res)| self.add_executed_tx(tx, res));
-    }
+impl Executor for BlockifierExecutor {
+    fn execute_block(
+        &self,
+        block_env: &BlockEnv,
+        cfg_env: &CfgEnv,
+        state: Box<dyn StateProvider>,
+        transactions: Vec<ExecutableTxWithHash>,
+        flags: ExecutionFlags,
+    ) -> ExecutorResult<BlockExecutionOutput> {
+        let block_context = block_context_from_envs(block_env, cfg_env);
+        let state = CachedStateWrapper::new(StateRefDb(state));
+        let txs = transactions.iter().map(TxWithHash::from).collect::<Vec<_>>();
 
-    /// Drain the pending transactions, returning the executed and rejected transactions.
-    pub fn take_txs_all(&self) -> (Vec<AcceptedTxPair>, Vec<RejectedTxPair>) {
-        let executed_txs = std::mem::take(&mut *self.executed_txs.write());
-        let rejected_txs = std::mem::take(&mut *self.rejected_txs.write());
-        (executed_txs, rejected_txs)
+        let mut output = BlockExecutionOutput::default();
+
+        let results = if self.parallel_execution {
+            ParallelTransactionExecutor::new(
+                &state,
+                &block_context,
+                flags.charge_fee,
+                flags.validate,
+            )
+            .execute(transactions)
+        } else {
+            TransactionExecutor::new(
+                &state,
+                &block_context,
+                flags.charge_fee,
+                flags.validate,
+                transactions.into_iter(),
+            )
+            .with_error_log()
+            .with_events_log()
+            .with_resources_log()
+            .execute()
+        };
+
+        for (res, tx) in results.into_iter().zip(txs) {
+            match res {
+                Ok(info) => {
+                    let receipt =
+                        TxReceiptWithExecInfo::new(&tx, info, &state, &self.function_maps).receipt;
+                    output.transactions.push((tx, receipt));
+                }
+                Err(err) => output.rejected.push((tx, self.executor_error(err, &state))),
+            }
+        }
+
+        output.states = get_state_update_from_cached_state(&state);
+        Ok(output)
     }
 
-    pub fn block_execution_envs(&self) -> (BlockEnv, CfgEnv) {
-        self.block_envs.read().clone()
+    fn simulate(
+        &self,
+        block_env: &BlockEnv,
+        cfg_env: &CfgEnv,
+        state: Box<dyn StateProvider>,
+        transactions: Vec<ExecutableTxWithHash>,
+        flags: ExecutionFlags,
+    ) -> ExecutorResult<Vec<(TxWithHash, Receipt)>> {
+        let block_context = block_context_from_envs(block_env, cfg_env);
+        let state = CachedStateWrapper::new(StateRefDb(state));
+        let txs = transactions.iter().map(TxWithHash::from).collect::<Vec<_>>();
+
+        TransactionExecutor::new(
+            &state,
+            &block_context,
+            flags.charge_fee,
+            flags.validate,
+            transactions.into_iter(),
+        )
+        .with_error_log()
+        .zip(txs)
+        .map(|(res, tx)| -> ExecutorResult<_> {
+            let info = res.map_err(|err| self.executor_error(err, &state))?;
+            let receipt =
+                TxReceiptWithExecInfo::new(&tx, info, &state, &self.function_maps).receipt;
+            Ok((tx, receipt))
+        })
+        .collect()
     }
 
-    fn add_executed_tx(&self, tx: TxWithHash, execution_result: TxExecutionResult) {
-        match execution_result {
-            Ok(execution_info) => {
-                let receipt = TxReceiptWithExecInfo::new(&tx, execution_info);
-                self.executed_txs.write().push((tx, receipt));
+    fn estimate_fee(
+        &self,
+        block_env: &BlockEnv,
+        cfg_env: &CfgEnv,
+        state: Box<dyn StateProvider>,
+        transactions: Vec<ExecutableTxWithHash>,
+        flags: ExecutionFlags,
+    ) -> ExecutorResult<Vec<FeeEstimate>> {
+        let block_context = block_context_from_envs(block_env, cfg_env);
+        let state = CachedStateWrapper::new(StateRefDb(state));
+        let units = transactions.iter().map(|tx| tx.tx_ref().fee_unit()).collect::<Vec<_>>();
+
+        TransactionExecutor::new(
+            &state,
+            &block_context,
+            flags.charge_fee,
+            flags.validate,
+            transactions.into_iter(),
+        )
+        .with_error_log()
+        .zip(units)
+        .map(|(res, unit)| -> ExecutorResult<_> {
+            let info = res.map_err(|err| self.executor_error(err, &state))?;
+
+            if let Some(error) = &info.revert_error {
+                let mut reason = parse_error_trace(error);
+                self.function_maps.resolve_call_path(&mut reason, &state);
+                return Err(ExecutorError::TransactionExecution(reason));
             }
-            Err(err) => {
-                self.rejected_txs.write().push((tx, err));
+
+            utils::calculate_execution_fee(&block_context, &info, unit)
+                .map_err(|err| self.executor_error(err, &state))
+        })
+        .collect()
+    }
+
+    fn call(
+        &self,
+        block_env: &BlockEnv,
+        cfg_env: &CfgEnv,
+        state: Box<dyn StateProvider>,
+        call: EntryPointCall,
+    ) -> ExecutorResult<Vec<FieldElement>> {
+        let block_context = block_context_from_envs(block_env, cfg_env);
+        let state: Arc<dyn StateProvider> = Arc::from(state);
+        let (contract_address, selector) = (call.contract_address, call.entry_point_selector);
+
+        utils::call(call, block_context, Box::new(state.clone())).map_err(|err| {
+            match self.executor_error(err, &state) {
+                ExecutorError::TransactionExecution(mut reason) => {
+                    // The trace only contains the contracts called from the called contract.
+                    let frame = CallFrame {
+                        contract_address,
+                        selector: Some(selector),
+                        pc: None,
+                        function: None,
+                    };
+                    reason.call_path.insert(0, frame);
+                    reason.contract_address.get_or_insert(contract_address);
+                    reason.selector.get_or_insert(selector);
+                    ExecutorError::TransactionExecution(reason)
+                }
+                err => err,
             }
+        })
+    }
+}
+
+impl From<TransactionExecutionError> for ExecutorError {
+    fn from(value: TransactionExecutionError) -> Self {
+        match value {
+            TransactionExecutionError::ExecutionError(
+                EntryPointExecutionError::PreExecutionError(
+                    PreExecutionError::UninitializedStorageAddress(addr),
+                ),
+            ) => Self::ContractNotDeployed(addr.into()),
+            err => Self::TransactionExecution(revert_reason_from_error(&err)),
         }
     }
 }
@@ -1,12 +1,15 @@
 use std::collections::HashMap;
 
+use blockifier::execution::call_info::CallInfo;
 use blockifier::transaction::objects::TransactionExecutionInfo;
 use katana_primitives::receipt::{
-    DeclareTxReceipt, DeployAccountTxReceipt, InvokeTxReceipt, L1HandlerTxReceipt, Receipt,
-    TxExecutionResources,
+    CallProfile, DeclareTxReceipt, DeployAccountTxReceipt, InvokeTxReceipt, L1HandlerTxReceipt,
+    Receipt, TxExecutionResources,
 };
 use katana_primitives::transaction::Tx;
+use katana_provider::traits::state::StateProvider;
 
+use super::revert::{parse_error_trace, FunctionMapCache};
 use super::utils::{events_from_exec_info, l2_to_l1_messages_from_exec_info};
 
 #[derive(Clone)]
@@ -16,19 +19,43 @@ pub struct TxReceiptWithExecInfo {
 }
 
 impl TxReceiptWithExecInfo {
-    pub fn new(tx: impl AsRef<Tx>, execution_info: TransactionExecutionInfo) -> Self {
+    /// Creates the receipt of a transaction. The `state` is the state the transaction has been
+    /// executed on, and is used with `function_maps` to decode the revert error, if any.
+    pub fn new(
+        tx: impl AsRef<Tx>,
+        execution_info: TransactionExecutionInfo,
+        state: &dyn StateProvider,
+        function_maps: &FunctionMapCache,
+    ) -> Self {
         let actual_fee = execution_info.actual_fee.0;
+        let fee_unit = tx.as_ref().tx_ref().fee_unit();
 
         let events = events_from_exec_info(&execution_info);
         let revert_error = execution_info.revert_error.clone();
+        let revert_reason = revert_error.as_deref().map(|error| {
+            let mut reason = parse_error_trace(error);
+            function_maps.resolve_call_path(&mut reason, state);
+            reason
+        });
         let messages_sent = l2_to_l1_messages_from_exec_info(&execution_info);
-        let actual_resources = parse_actual_resources(&execution_info.actual_resources.0);
+        let mut actual_resources = parse_actual_resources(&execution_info.actual_resources.0);
+        actual_resources.calls = [
+            &execution_info.validate_call_info,
+            &execution_info.execute_call_info,
+            &execution_info.fee_transfer_call_info,
+        ]
+        .into_iter()
+        .flatten()
+        .map(call_profile)
+        .collect();
 
         let receipt = match tx.as_ref() {
             Tx::Invoke(_) => Receipt::Invoke(InvokeTxReceipt {
                 events,
                 actual_fee,
+                fee_unit,
                 revert_error,
+                revert_reason,
                 messages_sent,
                 execution_resources: actual_resources,
             }),
@@ -36,7 +63,9 @@ impl TxReceiptWithExecInfo {
             Tx::Declare(_) => Receipt::Declare(DeclareTxReceipt {
                 events,
                 actual_fee,
+                fee_unit,
                 revert_error,
+                revert_reason,
                 messages_sent,
                 execution_resources: actual_resources,
             }),
@@ -45,6 +74,7 @@ impl TxReceiptWithExecInfo {
                 events,
                 actual_fee,
                 revert_error,
+                revert_reason,
                 messages_sent,
                 message_hash: tx.message_hash,
                 execution_resources: actual_resources,
@@ -53,7 +83,9 @@ impl TxReceiptWithExecInfo {
             Tx::DeployAccount(tx) => Receipt::DeployAccount(DeployAccountTxReceipt {
                 events,
                 actual_fee,
+                fee_unit,
                 revert_error,
+                revert_reason,
                 messages_sent,
                 execution_resources: actual_resources,
                 contract_address: tx.contract_address(),
@@ -78,5 +110,22 @@ fn parse_actual_resources(resources: &HashMap<String, usize>) -> TxExecutionReso
         poseidon_builtin: resources.get("poseidon_builtin").map(|x| *x as u64),
         range_check_builtin: resources.get("range_check_builtin").map(|x| *x as u64),
         segment_arena_builtin: resources.get("segment_arena_builtin").map(|x| *x as u64),
+        l1_gas: resources.get("l1_gas_usage").map(|x| *x as u64),
+        l1_data_gas: resources.get("l1_blob_gas_usage").map(|x| *x as u64),
+        calls: Vec::new(),
+    }
+}
+
+/// Builds the [`CallProfile`] tree of a call. The VM resources of a call include the ones of its
+/// inner calls, so they are subtracted to get the steps of the call itself.
+fn call_profile(call: &CallInfo) -> CallProfile {
+    let inner_steps = call.inner_calls.iter().map(|call| call.vm_resources.n_steps).sum::<usize>();
+
+    CallProfile {
+        contract_address: call.call.storage_address.into(),
+        class_hash: call.call.class_hash.map(|hash| hash.0.into()),
+        selector: call.call.entry_point_selector.0.into(),
+        steps: call.vm_resources.n_steps.saturating_sub(inner_steps) as u64,
+        inner_calls: call.inner_calls.iter().map(call_profile).collect(),
     }
 }
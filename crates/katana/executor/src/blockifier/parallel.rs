@@ -0,0 +1,587 @@
+//! Optimistic parallel execution of transactions.
+//!
+//! The transactions are first executed speculatively, in parallel, each on its own view of a
+//! snapshot of the base state, which is read without locking the base state. Every speculative
+//! execution records the state keys that it has read and the state changes that it has produced.
+//! The results are then committed to the base state one by one, in the original order of the
+//! transactions. If a transaction has read a key that was written by a transaction committed
+//! before it, its speculative result is discarded and the transaction is re-executed on top of the
+//! committed state. The resulting state is therefore
+//! identical to the one produced by executing the transactions sequentially.
+//!
+//! Every transaction that pays a fee writes to the fee token balance of the sequencer. Treating
+//! those storage slots as regular keys would make every transaction conflict with the previous
+//! one, so when they are only accessed by the fee transfer, they are excluded from the conflict
+//! detection and the balance change of the transaction is applied as a delta on top of the
+//! committed balance instead. A transaction which otherwise reads the balance of the sequencer,
+//! is sent by the sequencer or is reverted is checked like any other.
+//!
+//! Declare transactions are executed sequentially, on the base state, as a barrier between the
+//! transactions that come before and after them.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Instant;
+
+use blockifier::block_context::BlockContext;
+use blockifier::execution::call_info::CallInfo;
+use blockifier::execution::contract_class::ContractClass;
+use blockifier::state::cached_state::{CachedState, GlobalContractCache};
+use blockifier::state::errors::StateError;
+use blockifier::state::state_api::{State, StateReader, StateResult};
+use blockifier::transaction::objects::TransactionExecutionInfo;
+use blockifier::transaction::transaction_execution::Transaction;
+use blockifier::transaction::transactions::ExecutableTransaction;
+use katana_primitives::transaction::{ExecutableTx, ExecutableTxWithHash};
+use katana_primitives::FieldElement;
+use katana_provider::traits::state::StateProvider;
+use metrics::histogram;
+use rayon::prelude::*;
+use starknet::core::utils::get_storage_var_address;
+use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce, PatriciaKey};
+use starknet_api::hash::{StarkFelt, StarkHash};
+use starknet_api::patricia_key;
+use starknet_api::state::StorageKey;
+use tracing::{trace, warn};
+
+use super::state::{CachedStateWrapper, StateRefDb};
+use super::transactions::BlockifierTx;
+use super::utils::warn_message_transaction_error_exec_error;
+use super::{execute_tx, TxExecutionResult};
+
+/// A transaction executor that executes independent transactions in parallel.
+///
+/// Unlike [`TransactionExecutor`](super::TransactionExecutor), all the transactions have to be
+/// known upfront. The results are returned in the exact order the transactions are provided.
+pub struct ParallelTransactionExecutor<'a> {
+    /// The state the transactions will be executed on.
+    state: &'a CachedStateWrapper,
+    /// The block context the transactions will be executed on.
+    block_context: &'a BlockContext,
+    /// A flag to enable/disable fee charging.
+    charge_fee: bool,
+    /// A flag to enable/disable transaction validation.
+    validate: bool,
+    /// The storage slots of the sequencer fee token balances.
+    fee_balances: Vec<FeeBalance>,
+    /// The compiled classes shared by the speculative executions.
+    classes: GlobalContractCache,
+}
+
+impl<'a> ParallelTransactionExecutor<'a> {
+    pub fn new(
+        state: &'a CachedStateWrapper,
+        block_context: &'a BlockContext,
+        charge_fee: bool,
+        validate: bool,
+    ) -> Self {
+        let fee_balances = sequencer_fee_balances(block_context);
+        let classes = GlobalContractCache::default();
+        Self { state, block_context, charge_fee, validate, fee_balances, classes }
+    }
+
+    /// Executes all the transactions and returns their results.
+    pub fn execute(&self, transactions: Vec<ExecutableTxWithHash>) -> Vec<TxExecutionResult> {
+        let mut results = Vec::with_capacity(transactions.len());
+        let mut batch = Vec::new();
+
+        for tx in transactions {
+            if matches!(tx.as_ref(), ExecutableTx::Declare(_)) {
+                results.extend(self.execute_batch(std::mem::take(&mut batch)));
+                let res =
+                    execute_tx(tx, self.state, self.block_context, self.charge_fee, self.validate);
+                results.push(res);
+            } else {
+                batch.push(tx);
+            }
+        }
+
+        results.extend(self.execute_batch(batch));
+        results.iter().for_each(log_execution_result);
+        results
+    }
+
+    fn execute_batch(&self, transactions: Vec<ExecutableTxWithHash>) -> Vec<TxExecutionResult> {
+        if transactions.is_empty() {
+            return Vec::new();
+        }
+
+        let snapshot = StateSnapshot::new(self.state);
+        let speculative = transactions
+            .clone()
+            .into_par_iter()
+            .map(|tx| self.execute_speculatively(tx, Some(&snapshot)))
+            .collect::<Vec<_>>();
+
+        let mut written = HashSet::new();
+        let mut results = Vec::with_capacity(speculative.len());
+        let mut reexecuted = 0;
+
+        for (tx, mut execution) in transactions.into_iter().zip(speculative) {
+            let conflict = execution.reads.iter().any(|key| {
+                written.contains(key)
+                    && !(execution.fee_transfer_only && self.is_fee_balance_key(key))
+            });
+
+            // The state that the transaction has read is stale, so the transaction has to be
+            // executed again on top of the transactions that have been committed before it.
+            if conflict {
+                reexecuted += 1;
+                execution = self.execute_speculatively(tx, None);
+            }
+
+            if execution.result.is_ok() {
+                if let Err(err) = self.commit(&execution) {
+                    results.push(Err(err.into()));
+                    continue;
+                }
+
+                written.extend(execution.writes.keys());
+            }
+
+            results.push(execution.result);
+        }
+
+        trace!(
+            target: "executor",
+            total = results.len(),
+            reexecuted,
+            "Executed transactions in parallel."
+        );
+
+        results
+    }
+
+    /// Executes the transaction on a private view of the base state, without committing any of
+    /// its changes. The base state is read through `snapshot` if given.
+    fn execute_speculatively(
+        &self,
+        tx: ExecutableTxWithHash,
+        snapshot: Option<&StateSnapshot>,
+    ) -> SpeculativeExecution {
+        let reader = TrackedStateReader::new(self.state, snapshot);
+        let mut state = CachedState::new(reader, self.classes.clone());
+
+        let (charge_fee, validate) = (self.charge_fee, self.validate);
+        let started_at = Instant::now();
+        let result = match BlockifierTx::from(tx).0 {
+            Transaction::AccountTransaction(tx) => {
+                tx.execute(&mut state, self.block_context, charge_fee, validate)
+            }
+            Transaction::L1HandlerTransaction(tx) => {
+                tx.execute(&mut state, self.block_context, charge_fee, validate)
+            }
+        };
+        histogram!("executor.transaction_execution_duration_seconds", started_at.elapsed());
+
+        let writes = if result.is_ok() { WriteSet::from(&state) } else { WriteSet::default() };
+        let fee_transfer_only = result.as_ref().is_ok_and(|info| self.is_fee_transfer_only(info));
+        let TrackedStateReader { reads, storage_values, .. } = state.state;
+
+        SpeculativeExecution { result, reads, storage_values, writes, fee_transfer_only }
+    }
+
+    /// Applies the changes of a speculative execution to the base state. The fallible reads are
+    /// all done before the first write, so that a failure leaves the base state untouched.
+    fn commit(&self, execution: &SpeculativeExecution) -> StateResult<()> {
+        let mut state = self.state.inner();
+
+        let mut balances = Vec::new();
+        for balance in &self.fee_balances {
+            let written = |key: StorageKey| {
+                execution.writes.storage.iter().find_map(|(address, k, value)| {
+                    (*address == balance.token && *k == key).then_some(*value)
+                })
+            };
+
+            let (written_low, written_high) = (written(balance.low), written(balance.high));
+            if written_low.is_none() && written_high.is_none() {
+                continue;
+            }
+
+            let read = |key: StorageKey| {
+                execution.storage_values.get(&(balance.token, key)).copied().unwrap_or_default()
+            };
+
+            let (read_low, read_high) = (read(balance.low), read(balance.high));
+            let before = u256_from_felts(read_low, read_high)?;
+            let after = u256_from_felts(
+                written_low.unwrap_or(read_low),
+                written_high.unwrap_or(read_high),
+            )?;
+
+            let current = u256_from_felts(
+                state.get_storage_at(balance.token, balance.low)?,
+                state.get_storage_at(balance.token, balance.high)?,
+            )?;
+
+            balances.push((balance, u256_add(current, u256_sub(after, before))));
+        }
+
+        // Executing a transaction increments the nonce of the sender by exactly one.
+        let mut incremented_nonces = Vec::new();
+        for (address, nonce) in &execution.writes.nonces {
+            if state.get_nonce_at(*address)? != *nonce {
+                incremented_nonces.push(*address);
+            }
+        }
+
+        for (address, key, value) in &execution.writes.storage {
+            if !self.is_fee_balance_key(&StateKey::Storage(*address, *key)) {
+                state.set_storage_at(*address, *key, *value);
+            }
+        }
+
+        for (balance, (low, high)) in balances {
+            state.set_storage_at(balance.token, balance.low, u128_to_felt(low));
+            state.set_storage_at(balance.token, balance.high, u128_to_felt(high));
+        }
+
+        // the execution has already applied those changes to the same values, so they can't fail
+        for address in incremented_nonces {
+            state.increment_nonce(address)?;
+        }
+
+        for (address, class_hash) in &execution.writes.class_hashes {
+            state.set_class_hash_at(*address, *class_hash)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns whether the fee balances of the sequencer have only been accessed by the fee
+    /// transfer of the transaction, in which case the value that has been read doesn't affect the
+    /// outcome of the transaction.
+    fn is_fee_transfer_only(&self, info: &TransactionExecutionInfo) -> bool {
+        if info.revert_error.is_some() {
+            return false;
+        }
+
+        // the balance of the sender is the one of the sequencer
+        let sequencer = self.block_context.block_info.sequencer_address;
+        if info.fee_transfer_call_info.as_ref().is_some_and(|c| c.call.caller_address == sequencer)
+        {
+            return false;
+        }
+
+        [&info.validate_call_info, &info.execute_call_info]
+            .into_iter()
+            .flatten()
+            .all(|call| !self.accesses_fee_balance(call))
+    }
+
+    fn accesses_fee_balance(&self, call: &CallInfo) -> bool {
+        let accessed = self.fee_balances.iter().any(|balance| {
+            call.call.storage_address == balance.token
+                && (call.accessed_storage_keys.contains(&balance.low)
+                    || call.accessed_storage_keys.contains(&balance.high))
+        });
+        accessed || call.inner_calls.iter().any(|call| self.accesses_fee_balance(call))
+    }
+
+    fn is_fee_balance_key(&self, key: &StateKey) -> bool {
+        match key {
+            StateKey::Storage(address, key) => self.fee_balances.iter().any(|balance| {
+                balance.token == *address && (balance.low == *key || balance.high == *key)
+            }),
+            _ => false,
+        }
+    }
+}
+
+/// A key in the state that can be read or written by a transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum StateKey {
+    Storage(ContractAddress, StorageKey),
+    Nonce(ContractAddress),
+    ClassHash(ContractAddress),
+    Class(ClassHash),
+}
+
+/// The outcome of executing a transaction on a private view of the base state.
+struct SpeculativeExecution {
+    result: TxExecutionResult,
+    /// The keys that were read from the base state.
+    reads: HashSet<StateKey>,
+    /// The storage values that were read from the base state.
+    storage_values: HashMap<(ContractAddress, StorageKey), StarkFelt>,
+    /// The state changes produced by the transaction.
+    writes: WriteSet,
+    /// Whether the fee balances of the sequencer have only been accessed by the fee transfer.
+    fee_transfer_only: bool,
+}
+
+#[derive(Debug, Default)]
+struct WriteSet {
+    storage: Vec<(ContractAddress, StorageKey, StarkFelt)>,
+    nonces: Vec<(ContractAddress, Nonce)>,
+    class_hashes: Vec<(ContractAddress, ClassHash)>,
+}
+
+impl WriteSet {
+    fn keys(&self) -> impl Iterator<Item = StateKey> + '_ {
+        let storage =
+            self.storage.iter().map(|(address, key, _)| StateKey::Storage(*address, *key));
+        let nonces = self.nonces.iter().map(|(address, _)| StateKey::Nonce(*address));
+        let class_hashes =
+            self.class_hashes.iter().map(|(address, _)| StateKey::ClassHash(*address));
+        storage.chain(nonces).chain(class_hashes)
+    }
+}
+
+impl<S: StateReader> From<&CachedState<S>> for WriteSet {
+    fn from(state: &CachedState<S>) -> Self {
+        let diff = state.to_state_diff();
+
+        let storage = diff
+            .storage_updates
+            .into_iter()
+            .flat_map(|(address, entries)| {
+                entries.into_iter().map(move |(key, value)| (address, key, value))
+            })
+            .collect();
+
+        Self {
+            storage,
+            nonces: diff.address_to_nonce.into_iter().collect(),
+            class_hashes: diff.address_to_class_hash.into_iter().collect(),
+        }
+    }
+}
+
+/// The base state as it was before executing a batch of transactions.
+///
+/// The changes made by the transactions executed before the batch are copied out of the base
+/// state, so that the speculative executions can read it in parallel without locking it.
+struct StateSnapshot {
+    /// The state the base state is built on.
+    db: Arc<dyn StateProvider>,
+    storage: HashMap<(ContractAddress, StorageKey), StarkFelt>,
+    nonces: HashMap<ContractAddress, Nonce>,
+    class_hashes: HashMap<ContractAddress, ClassHash>,
+    compiled_class_hashes: HashMap<ClassHash, CompiledClassHash>,
+    /// The classes declared by the transactions executed before the batch.
+    classes: HashMap<ClassHash, ContractClass>,
+}
+
+impl StateSnapshot {
+    fn new(base: &CachedStateWrapper) -> Self {
+        let mut state = base.inner();
+        let diff = state.to_state_diff();
+
+        let storage = diff
+            .storage_updates
+            .into_iter()
+            .flat_map(|(address, entries)| {
+                entries.into_iter().map(move |(key, value)| ((address, key), value))
+            })
+            .collect();
+
+        // the legacy classes declared before the batch are missing, they are read from the base
+        // state when not found in the underlying state
+        let classes = diff
+            .class_hash_to_compiled_class_hash
+            .keys()
+            .filter_map(|hash| Some((*hash, state.get_compiled_contract_class(*hash).ok()?)))
+            .collect();
+
+        Self {
+            db: base.db(),
+            storage,
+            nonces: diff.address_to_nonce.into_iter().collect(),
+            class_hashes: diff.address_to_class_hash.into_iter().collect(),
+            compiled_class_hashes: diff.class_hash_to_compiled_class_hash.into_iter().collect(),
+            classes,
+        }
+    }
+}
+
+/// A [StateReader] over a [StateSnapshot], falling back to the base state for the classes which
+/// are not in it.
+struct SnapshotReader<'a> {
+    snapshot: &'a StateSnapshot,
+    db: StateRefDb,
+    base: &'a CachedStateWrapper,
+}
+
+impl StateReader for SnapshotReader<'_> {
+    fn get_storage_at(
+        &mut self,
+        contract_address: ContractAddress,
+        key: StorageKey,
+    ) -> StateResult<StarkFelt> {
+        match self.snapshot.storage.get(&(contract_address, key)) {
+            Some(value) => Ok(*value),
+            None => self.db.get_storage_at(contract_address, key),
+        }
+    }
+
+    fn get_nonce_at(&mut self, contract_address: ContractAddress) -> StateResult<Nonce> {
+        match self.snapshot.nonces.get(&contract_address) {
+            Some(nonce) => Ok(*nonce),
+            None => self.db.get_nonce_at(contract_address),
+        }
+    }
+
+    fn get_class_hash_at(&mut self, contract_address: ContractAddress) -> StateResult<ClassHash> {
+        match self.snapshot.class_hashes.get(&contract_address) {
+            Some(class_hash) => Ok(*class_hash),
+            None => self.db.get_class_hash_at(contract_address),
+        }
+    }
+
+    fn get_compiled_class_hash(&mut self, class_hash: ClassHash) -> StateResult<CompiledClassHash> {
+        if let Some(hash) = self.snapshot.compiled_class_hashes.get(&class_hash) {
+            return Ok(*hash);
+        }
+
+        match self.db.get_compiled_class_hash(class_hash) {
+            Err(StateError::UndeclaredClassHash(_)) => {
+                self.base.inner().get_compiled_class_hash(class_hash)
+            }
+            res => res,
+        }
+    }
+
+    fn get_compiled_contract_class(&mut self, class_hash: ClassHash) -> StateResult<ContractClass> {
+        if let Some(class) = self.snapshot.classes.get(&class_hash) {
+            return Ok(class.clone());
+        }
+
+        match self.db.get_compiled_contract_class(class_hash) {
+            Err(StateError::UndeclaredClassHash(_)) => {
+                self.base.inner().get_compiled_contract_class(class_hash)
+            }
+            res => res,
+        }
+    }
+}
+
+/// A [StateReader] over the base state which records every key that is read through it.
+///
+/// The base state is read through its snapshot if there is one, or else directly, which requires
+/// locking it.
+struct TrackedStateReader<'a> {
+    base: &'a CachedStateWrapper,
+    snapshot: Option<SnapshotReader<'a>>,
+    reads: HashSet<StateKey>,
+    storage_values: HashMap<(ContractAddress, StorageKey), StarkFelt>,
+}
+
+impl<'a> TrackedStateReader<'a> {
+    fn new(base: &'a CachedStateWrapper, snapshot: Option<&'a StateSnapshot>) -> Self {
+        let snapshot = snapshot.map(|snapshot| SnapshotReader {
+            snapshot,
+            db: StateRefDb::new(Arc::clone(&snapshot.db)),
+            base,
+        });
+        Self { base, snapshot, reads: HashSet::new(), storage_values: HashMap::new() }
+    }
+}
+
+impl StateReader for TrackedStateReader<'_> {
+    fn get_storage_at(
+        &mut self,
+        contract_address: ContractAddress,
+        key: StorageKey,
+    ) -> StateResult<StarkFelt> {
+        self.reads.insert(StateKey::Storage(contract_address, key));
+        let value = match &mut self.snapshot {
+            Some(snapshot) => snapshot.get_storage_at(contract_address, key)?,
+            None => self.base.inner().get_storage_at(contract_address, key)?,
+        };
+        self.storage_values.insert((contract_address, key), value);
+        Ok(value)
+    }
+
+    fn get_nonce_at(&mut self, contract_address: ContractAddress) -> StateResult<Nonce> {
+        self.reads.insert(StateKey::Nonce(contract_address));
+        match &mut self.snapshot {
+            Some(snapshot) => snapshot.get_nonce_at(contract_address),
+            None => self.base.inner().get_nonce_at(contract_address),
+        }
+    }
+
+    fn get_class_hash_at(&mut self, contract_address: ContractAddress) -> StateResult<ClassHash> {
+        self.reads.insert(StateKey::ClassHash(contract_address));
+        match &mut self.snapshot {
+            Some(snapshot) => snapshot.get_class_hash_at(contract_address),
+            None => self.base.inner().get_class_hash_at(contract_address),
+        }
+    }
+
+    fn get_compiled_class_hash(&mut self, class_hash: ClassHash) -> StateResult<CompiledClassHash> {
+        self.reads.insert(StateKey::Class(class_hash));
+        match &mut self.snapshot {
+            Some(snapshot) => snapshot.get_compiled_class_hash(class_hash),
+            None => self.base.inner().get_compiled_class_hash(class_hash),
+        }
+    }
+
+    fn get_compiled_contract_class(&mut self, class_hash: ClassHash) -> StateResult<ContractClass> {
+        self.reads.insert(StateKey::Class(class_hash));
+        match &mut self.snapshot {
+            Some(snapshot) => snapshot.get_compiled_contract_class(class_hash),
+            None => self.base.inner().get_compiled_contract_class(class_hash),
+        }
+    }
+}
+
+/// The storage slots of the sequencer balance in a fee token contract. The balance is a `u256`
+/// stored as two consecutive felts.
+#[derive(Debug, Clone, Copy)]
+struct FeeBalance {
+    token: ContractAddress,
+    low: StorageKey,
+    high: StorageKey,
+}
+
+fn sequencer_fee_balances(block_context: &BlockContext) -> Vec<FeeBalance> {
+    let sequencer = FieldElement::from(*block_context.block_info.sequencer_address.0.key());
+    let base = get_storage_var_address("ERC20_balances", &[sequencer]).unwrap();
+    let base_high = base + FieldElement::ONE;
+
+    let low = StorageKey(patricia_key!(base));
+    let high = StorageKey(patricia_key!(base_high));
+
+    let tokens = &block_context.chain_info.fee_token_addresses;
+    [tokens.eth_fee_token_address, tokens.strk_fee_token_address]
+        .into_iter()
+        .map(|token| FeeBalance { token, low, high })
+        .collect()
+}
+
+fn u256_from_felts(low: StarkFelt, high: StarkFelt) -> StateResult<(u128, u128)> {
+    let to_u128 = |felt: StarkFelt| {
+        u128::try_from(FieldElement::from(felt)).map_err(|_| {
+            StateError::StateReadError(format!("Invalid fee token balance limb: {felt}"))
+        })
+    };
+    Ok((to_u128(low)?, to_u128(high)?))
+}
+
+fn u128_to_felt(value: u128) -> StarkFelt {
+    FieldElement::from(value).into()
+}
+
+fn u256_add((a_low, a_high): (u128, u128), (b_low, b_high): (u128, u128)) -> (u128, u128) {
+    let (low, carry) = a_low.overflowing_add(b_low);
+    (low, a_high.wrapping_add(b_high).wrapping_add(carry as u128))
+}
+
+fn u256_sub((a_low, a_high): (u128, u128), (b_low, b_high): (u128, u128)) -> (u128, u128) {
+    let (low, borrow) = a_low.overflowing_sub(b_low);
+    (low, a_high.wrapping_sub(b_high).wrapping_sub(borrow as u128))
+}
+
+fn log_execution_result(res: &TxExecutionResult) {
+    match res {
+        Ok(info) => {
+            if let Some(err) = &info.revert_error {
+                let formatted_err = format!("{err:?}").replace("\\n", "\n");
+                warn!(target: "executor", "Transaction execution error: {formatted_err}");
+            }
+        }
+        Err(err) => warn_message_transaction_error_exec_error(err),
+    }
+}
@@ -0,0 +1,363 @@
+//! Decoding of the blockifier execution errors into a [RevertReason].
+//!
+//! The panic data of a failed entry point is available in the error returned by the blockifier,
+//! and is decoded as Cairo short strings and `ByteArray`s. The location of a failure, and the calls
+//! that led to it, are only reported in the formatted trace of the VM error, as raw program
+//! counters, and so is the error of a reverted transaction. The trace is parsed back into the call
+//! path, and the program counters are mapped to the Sierra functions of the called contracts.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::Arc;
+
+use blockifier::execution::errors::EntryPointExecutionError;
+use blockifier::transaction::errors::TransactionExecutionError;
+use katana_primitives::contract::{ClassHash, ContractAddress};
+use katana_primitives::receipt::{CallFrame, RevertReason};
+use katana_primitives::utils::class::SierraFunctionMap;
+use katana_primitives::FieldElement;
+use katana_provider::traits::state::StateProvider;
+use parking_lot::RwLock;
+use starknet::core::utils::parse_cairo_short_string;
+use starknet::macros::felt;
+
+/// The value that prefixes a `ByteArray` serialized in panic data.
+const BYTE_ARRAY_MAGIC: FieldElement =
+    felt!("0x46a6158a16a947e5916b2a2ca68501a45e93d7110e81aa2d6438b1c57c879a3");
+
+/// The number of bytes stored in a full word of a `ByteArray`.
+const BYTES_IN_WORD: usize = 31;
+
+/// Creates a [RevertReason] from an execution error. The functions of the call path are not
+/// resolved, see [FunctionMapCache::resolve_call_path].
+pub fn revert_reason_from_error(error: &TransactionExecutionError) -> RevertReason {
+    if let TransactionExecutionError::ExecutionError(error) = error {
+        return revert_reason_from_entry_point_error(error);
+    }
+
+    // the other errors, eg. of the validation or of the constructor, wrap the error of the entry
+    // point that failed
+    let mut source = error.source();
+    while let Some(error) = source {
+        if let Some(error) = error.downcast_ref::<EntryPointExecutionError>() {
+            return revert_reason_from_entry_point_error(error);
+        }
+        source = error.source();
+    }
+
+    RevertReason { reason: error.to_string(), ..Default::default() }
+}
+
+fn revert_reason_from_entry_point_error(error: &EntryPointExecutionError) -> RevertReason {
+    match error {
+        EntryPointExecutionError::ExecutionFailed { error_data } => {
+            let data = error_data.iter().map(|felt| FieldElement::from(*felt)).collect::<Vec<_>>();
+            RevertReason { reason: decode_panic_data(&data), ..Default::default() }
+        }
+        EntryPointExecutionError::VirtualMachineExecutionErrorWithTrace { trace, .. } => {
+            parse_error_trace(trace)
+        }
+        error => RevertReason { reason: error.to_string(), ..Default::default() },
+    }
+}
+
+/// Parses an error trace, as formatted by the blockifier, into a [RevertReason].
+pub fn parse_error_trace(trace: &str) -> RevertReason {
+    let mut call_path: Vec<CallFrame> = Vec::new();
+    let mut reason = None;
+
+    for line in trace.lines().map(str::trim) {
+        if let Some(rest) = line.strip_prefix("Error in the called contract (") {
+            if let Some(address) = rest.split(')').next().and_then(parse_felt) {
+                call_path.push(CallFrame {
+                    contract_address: ContractAddress(address),
+                    selector: None,
+                    pc: None,
+                    function: None,
+                });
+            }
+        } else if let Some(rest) = line.strip_prefix("Error at pc=0:") {
+            let pc = rest.trim_end_matches(':').parse().ok();
+            if let Some(frame) = call_path.last_mut().filter(|frame| frame.pc.is_none()) {
+                frame.pc = pc;
+            }
+        } else if let Some((_, data)) =
+            line.split_once("Failure reason:").or_else(|| line.split_once("failure reason:"))
+        {
+            reason = Some(decode_failure_reason(data));
+        }
+    }
+
+    // Without any panic data, the most relevant message is the last one that is not part of the
+    // call path, eg. the exception raised by a hint.
+    let reason = reason.unwrap_or_else(|| {
+        const TRACE_PREFIXES: [&str; 4] =
+            ["Error in the called contract", "Error at pc", "Cairo traceback", "Unknown location"];
+
+        trace
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .filter(|line| !TRACE_PREFIXES.iter().any(|prefix| line.starts_with(prefix)))
+            .last()
+            .unwrap_or(trace)
+            .to_string()
+    });
+
+    let (contract_address, selector) = match call_path.last() {
+        Some(frame) => (Some(frame.contract_address), frame.selector),
+        None => (None, None),
+    };
+
+    RevertReason { contract_address, selector, reason, call_path }
+}
+
+/// The [SierraFunctionMap]s of the classes, so that a class is compiled at most once to resolve the
+/// call paths of its failures. A class hash always refers to the same class, so the maps never
+/// have to be invalidated.
+#[derive(Debug, Default, Clone)]
+pub struct FunctionMapCache {
+    maps: Arc<RwLock<HashMap<ClassHash, Option<Arc<SierraFunctionMap>>>>>,
+}
+
+impl FunctionMapCache {
+    /// Resolves the Sierra functions of the call path using the classes available in `state`.
+    pub fn resolve_call_path(&self, reason: &mut RevertReason, state: &dyn StateProvider) {
+        for frame in &mut reason.call_path {
+            let Some(pc) = frame.pc else { continue };
+            let Ok(Some(class_hash)) = state.class_hash_of_contract(frame.contract_address) else {
+                continue;
+            };
+
+            let map = self.get(class_hash, state);
+            if let Some(function) = map.as_ref().and_then(|map| map.function_at(pc)) {
+                frame.function = function.name.clone();
+                frame.selector = frame.selector.or(function.selector);
+            }
+        }
+
+        if let Some(frame) = reason.call_path.last() {
+            reason.selector = reason.selector.or(frame.selector);
+        }
+    }
+
+    fn get(
+        &self,
+        class_hash: ClassHash,
+        state: &dyn StateProvider,
+    ) -> Option<Arc<SierraFunctionMap>> {
+        if let Some(map) = self.maps.read().get(&class_hash) {
+            return map.clone();
+        }
+
+        // legacy classes don't have a Sierra program, which is cached as well
+        let class = state.sierra_class(class_hash).ok()?;
+        let map = class.and_then(|class| SierraFunctionMap::new(&class).ok()).map(Arc::new);
+        self.maps.write().insert(class_hash, map.clone());
+        map
+    }
+}
+
+/// Decodes panic data into a human readable string. Felts that are neither a Cairo short string
+/// nor part of a serialized `ByteArray` are kept in their hex representation.
+pub fn decode_panic_data(data: &[FieldElement]) -> String {
+    let mut decoded = Vec::new();
+    let mut rest = data;
+
+    while let Some((felt, tail)) = rest.split_first() {
+        if *felt == BYTE_ARRAY_MAGIC {
+            if let Some((string, tail)) = decode_byte_array(tail) {
+                decoded.push(string);
+                rest = tail;
+                continue;
+            }
+        }
+
+        match parse_cairo_short_string(felt) {
+            Ok(string) if is_printable(&string) => decoded.push(string),
+            _ => decoded.push(format!("{felt:#x}")),
+        }
+
+        rest = tail;
+    }
+
+    decoded.join(", ")
+}
+
+/// Decodes a serialized `ByteArray`, returning the decoded string and the remaining data.
+fn decode_byte_array(data: &[FieldElement]) -> Option<(String, &[FieldElement])> {
+    let (len, data) = data.split_first()?;
+    let len = usize::try_from(u64::try_from(*len).ok()?).ok()?;
+
+    // the length comes from the panic data of the transaction, so it can't be trusted
+    if len.checked_add(2)? > data.len() {
+        return None;
+    }
+
+    let (words, data) = data.split_at(len);
+    let (pending_word, data) = data.split_first()?;
+    let (pending_word_len, data) = data.split_first()?;
+
+    let pending_word_len = u64::try_from(*pending_word_len).ok()? as usize;
+    if pending_word_len >= BYTES_IN_WORD {
+        return None;
+    }
+
+    let capacity = len.checked_mul(BYTES_IN_WORD)?.checked_add(pending_word_len)?;
+    let mut bytes = Vec::with_capacity(capacity);
+    for word in words {
+        bytes.extend_from_slice(&word.to_bytes_be()[32 - BYTES_IN_WORD..]);
+    }
+    bytes.extend_from_slice(&pending_word.to_bytes_be()[32 - pending_word_len..]);
+
+    Some((String::from_utf8_lossy(&bytes).into_owned(), data))
+}
+
+/// Decodes the failure reason of an error trace, eg. `0x4e6f7420616c6c6f776564 ('Not allowed').`
+fn decode_failure_reason(data: &str) -> String {
+    let felts = data
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|token| token.starts_with("0x"))
+        .filter_map(parse_felt)
+        .collect::<Vec<_>>();
+
+    if felts.is_empty() {
+        data.trim().trim_end_matches('.').to_string()
+    } else {
+        decode_panic_data(&felts)
+    }
+}
+
+fn parse_felt(value: &str) -> Option<FieldElement> {
+    FieldElement::from_hex_be(value.trim()).ok()
+}
+
+fn is_printable(value: &str) -> bool {
+    !value.is_empty() && value.chars().all(|c| c.is_ascii_graphic() || c == ' ')
+}
+
+#[cfg(test)]
+mod tests {
+    use katana_primitives::genesis::constant::{
+        DEFAULT_OZ_ACCOUNT_CONTRACT, DEFAULT_OZ_ACCOUNT_CONTRACT_CLASS_HASH,
+    };
+    use katana_provider::providers::in_memory::InMemoryProvider;
+    use katana_provider::traits::contract::ContractClassWriter;
+    use katana_provider::traits::state::{StateFactoryProvider, StateWriter};
+    use starknet::core::utils::{cairo_short_string_to_felt, get_selector_from_name};
+
+    use super::*;
+
+    #[test]
+    fn decode_short_strings() {
+        let data = [
+            cairo_short_string_to_felt("Not allowed").unwrap(),
+            cairo_short_string_to_felt("ENTRYPOINT_FAILED").unwrap(),
+        ];
+        assert_eq!(decode_panic_data(&data), "Not allowed, ENTRYPOINT_FAILED");
+    }
+
+    #[test]
+    fn decode_byte_array_panic() {
+        let message = "This is a message longer than thirty one bytes";
+        let (full, pending) = message.as_bytes().split_at(BYTES_IN_WORD);
+
+        let data = [
+            BYTE_ARRAY_MAGIC,
+            FieldElement::ONE,
+            FieldElement::from_byte_slice_be(full).unwrap(),
+            FieldElement::from_byte_slice_be(pending).unwrap(),
+            FieldElement::from(pending.len() as u64),
+        ];
+
+        assert_eq!(decode_panic_data(&data), message);
+    }
+
+    #[test]
+    fn decode_byte_array_with_invalid_length() {
+        let message = cairo_short_string_to_felt("Not allowed").unwrap();
+
+        for len in [FieldElement::from(u64::MAX), FieldElement::from(3u8), FieldElement::MAX] {
+            let data = [BYTE_ARRAY_MAGIC, len, message, FieldElement::ZERO];
+            assert_eq!(decode_byte_array(&data[1..]), None);
+
+            // the felts are kept as is
+            let decoded = decode_panic_data(&data);
+            assert!(decoded.starts_with(&format!("{BYTE_ARRAY_MAGIC:#x}, {len:#x}")));
+            assert!(decoded.contains("Not allowed"));
+        }
+    }
+
+    #[test]
+    fn decode_execution_failure() {
+        let error_data = vec![cairo_short_string_to_felt("Not allowed").unwrap().into()];
+        let error = EntryPointExecutionError::ExecutionFailed { error_data };
+
+        let reason = revert_reason_from_error(&TransactionExecutionError::ExecutionError(error));
+
+        assert_eq!(reason.reason, "Not allowed");
+        assert!(reason.call_path.is_empty());
+    }
+
+    #[test]
+    fn parse_nested_error_trace() {
+        let trace = "Error in the called contract (0x1234):
+Error at pc=0:4302:
+Cairo traceback (most recent call last):
+Unknown location (pc=0:290)
+
+Error in the called contract (0x5678):
+Execution failed. Failure reason: 0x4e6f7420616c6c6f776564 ('Not allowed').";
+
+        let reason = parse_error_trace(trace);
+
+        assert_eq!(reason.reason, "Not allowed");
+        assert_eq!(reason.contract_address, Some(ContractAddress(felt!("0x5678"))));
+        assert_eq!(reason.call_path.len(), 2);
+        assert_eq!(reason.call_path[0].contract_address, ContractAddress(felt!("0x1234")));
+        assert_eq!(reason.call_path[0].pc, Some(4302));
+        assert_eq!(reason.call_path[1].pc, None);
+    }
+
+    #[test]
+    fn resolve_call_path_functions() {
+        let class_hash = DEFAULT_OZ_ACCOUNT_CONTRACT_CLASS_HASH;
+        let class = DEFAULT_OZ_ACCOUNT_CONTRACT.clone().flatten().unwrap();
+        let account = ContractAddress(felt!("0x1234"));
+
+        let provider = InMemoryProvider::new();
+        provider.set_sierra_class(class_hash, class.clone()).unwrap();
+        provider.set_class_hash_of_contract(account, class_hash).unwrap();
+        let state = provider.latest().unwrap();
+
+        let selector = get_selector_from_name("__execute__").unwrap();
+        let map = SierraFunctionMap::new(&class).unwrap();
+        let execute = map.functions().iter().find(|f| f.selector == Some(selector)).unwrap();
+        let offset = execute.offset as u64;
+
+        let frame = |contract_address, pc| CallFrame {
+            contract_address,
+            selector: None,
+            pc: Some(pc),
+            function: None,
+        };
+        let unknown = ContractAddress(felt!("0x5678"));
+        let mut reason = RevertReason {
+            contract_address: Some(account),
+            call_path: vec![frame(unknown, offset), frame(account, offset + 1)],
+            ..Default::default()
+        };
+
+        let cache = FunctionMapCache::default();
+        cache.resolve_call_path(&mut reason, state.as_ref());
+
+        // the contract isn't deployed, so its frame is left as is
+        assert_eq!(reason.call_path[0].function, None);
+        assert_eq!(reason.call_path[0].selector, None);
+
+        assert_eq!(reason.call_path[1].function.as_deref(), Some("__execute__"));
+        assert_eq!(reason.call_path[1].selector, Some(selector));
+        assert_eq!(reason.selector, Some(selector));
+        assert_eq!(cache.maps.read().len(), 1);
+    }
+}
@@ -1,9 +1,10 @@
 use std::collections::HashMap;
+use std::sync::Arc;
 
 use blockifier::state::cached_state::{CachedState, GlobalContractCache};
 use blockifier::state::errors::StateError;
 use blockifier::state::state_api::StateReader;
-use katana_primitives::contract::FlattenedSierraClass;
+use katana_primitives::contract::{FlattenedSierraClass, LegacyClassAbi};
 use katana_primitives::FieldElement;
 use katana_provider::traits::contract::ContractClassProvider;
 use katana_provider::traits::state::StateProvider;
@@ -133,20 +134,30 @@ impl StateReader for StateRefDb {
 
 pub struct CachedStateWrapper {
     inner: Mutex<CachedState<StateRefDb>>,
+    /// The state the cached state is built on, which can be read without locking the cache.
+    db: Arc<dyn StateProvider>,
     sierra_class: RwLock<HashMap<katana_primitives::contract::ClassHash, FlattenedSierraClass>>,
+    /// The ABIs of the legacy classes declared on the cached state.
+    legacy_class_abis: RwLock<HashMap<katana_primitives::contract::ClassHash, LegacyClassAbi>>,
 }
 
 impl CachedStateWrapper {
     pub fn new(db: StateRefDb) -> Self {
+        let db: Arc<dyn StateProvider> = Arc::from(db.0);
         Self {
             sierra_class: Default::default(),
-            inner: Mutex::new(CachedState::new(db, GlobalContractCache::default())),
+            legacy_class_abis: Default::default(),
+            inner: Mutex::new(CachedState::new(
+                StateRefDb::new(Arc::clone(&db)),
+                GlobalContractCache::default(),
+            )),
+            db,
         }
     }
 
-    pub(super) fn reset_with_new_state(&self, db: StateRefDb) {
-        *self.inner() = CachedState::new(db, GlobalContractCache::default());
-        self.sierra_class_mut().clear();
+    /// Returns the state the cached state is built on, without the uncommitted changes.
+    pub(super) fn db(&self) -> Arc<dyn StateProvider> {
+        Arc::clone(&self.db)
     }
 
     pub fn inner(
@@ -172,6 +183,24 @@ impl CachedStateWrapper {
     > {
         self.sierra_class.write()
     }
+
+    pub fn legacy_class_abis(
+        &self,
+    ) -> parking_lot::RwLockReadGuard<
+        '_,
+        HashMap<katana_primitives::contract::ClassHash, LegacyClassAbi>,
+    > {
+        self.legacy_class_abis.read()
+    }
+
+    pub fn legacy_class_abis_mut(
+        &self,
+    ) -> parking_lot::RwLockWriteGuard<
+        '_,
+        HashMap<katana_primitives::contract::ClassHash, LegacyClassAbi>,
+    > {
+        self.legacy_class_abis.write()
+    }
 }
 
 impl ContractClassProvider for CachedStateWrapper {
@@ -20,15 +20,15 @@ use blockifier::transaction::objects::{
 use convert_case::{Case, Casing};
 use katana_primitives::contract::ContractAddress;
 use katana_primitives::env::{BlockEnv, CfgEnv};
+use katana_primitives::fee::PriceUnit;
 use katana_primitives::receipt::{Event, MessageToL1};
 use katana_primitives::state::{StateUpdates, StateUpdatesWithDeclaredClasses};
 use katana_primitives::transaction::ExecutableTxWithHash;
 use katana_primitives::FieldElement;
 use katana_provider::traits::contract::ContractClassProvider;
 use katana_provider::traits::state::StateProvider;
-use starknet::core::types::{FeeEstimate, PriceUnit};
+use starknet::core::types::FeeEstimate;
 use starknet::core::utils::parse_cairo_short_string;
-use starknet::macros::felt;
 use starknet_api::block::{BlockNumber, BlockTimestamp};
 use starknet_api::core::EntryPointSelector;
 use starknet_api::transaction::Calldata;
@@ -36,16 +36,7 @@ use tracing::trace;
 
 use super::state::{CachedStateWrapper, StateRefDb};
 use super::TransactionExecutor;
-
-#[derive(Debug)]
-pub struct EntryPointCall {
-    /// The address of the contract whose function you're calling.
-    pub contract_address: ContractAddress,
-    /// The input to the function.
-    pub calldata: Vec<FieldElement>,
-    /// The function selector.
-    pub entry_point_selector: FieldElement,
-}
+pub use crate::abstraction::EntryPointCall;
 
 /// Perform a function call on a contract and retrieve the return values.
 pub fn call(
@@ -66,14 +57,19 @@ pub fn estimate_fee(
     state: Box<dyn StateProvider>,
     validate: bool,
 ) -> Result<Vec<FeeEstimate>, TransactionExecutionError> {
+    let transactions = transactions.collect::<Vec<_>>();
+    let units = transactions.iter().map(|tx| tx.tx_ref().fee_unit()).collect::<Vec<_>>();
+
     let state = CachedStateWrapper::new(StateRefDb(state));
-    let results = TransactionExecutor::new(&state, &block_context, true, validate, transactions)
-        .with_error_log()
-        .execute();
+    let results =
+        TransactionExecutor::new(&state, &block_context, true, validate, transactions.into_iter())
+            .with_error_log()
+            .execute();
 
     results
         .into_iter()
-        .map(|res| {
+        .zip(units)
+        .map(|(res, unit)| {
             let exec_info = res?;
 
             if exec_info.revert_error.is_some() {
@@ -82,7 +78,7 @@ pub fn estimate_fee(
                 ));
             }
 
-            calculate_execution_fee(&block_context, &exec_info)
+            calculate_execution_fee(&block_context, &exec_info, unit)
         })
         .collect::<Result<Vec<_>, _>>()
 }
@@ -129,29 +125,31 @@ pub fn raw_call(
     .map_err(TransactionExecutionError::ExecutionError)
 }
 
-/// Calculate the fee of a transaction execution.
+/// Calculate the fee of a transaction execution, in the token of the given `unit`.
 pub fn calculate_execution_fee(
     block_context: &BlockContext,
     exec_info: &TransactionExecutionInfo,
+    unit: PriceUnit,
 ) -> Result<FeeEstimate, TransactionExecutionError> {
     let (l1_gas_usage, vm_resources) = extract_l1_gas_and_vm_usage(&exec_info.actual_resources);
     let l1_gas_by_vm_usage = calculate_l1_gas_by_vm_usage(block_context, &vm_resources)?;
 
     let total_l1_gas_usage = l1_gas_usage as f64 + l1_gas_by_vm_usage;
 
-    // Gas prices are now in two currencies: eth and strk.
-    // For now let's only consider eth to be compatible with V2.
-    // https://github.com/starkware-libs/blockifier/blob/51b343fe38139a309a69b2482f4b484e8caa5edf/crates/blockifier/src/block_context.rs#L19C26-L19C26
-    // https://github.com/starkware-libs/blockifier/blob/51b343fe38139a309a69b2482f4b484e8caa5edf/crates/blockifier/src/block_context.rs#L49
-    let gas_price = block_context.block_info.gas_prices.eth_l1_gas_price as u64;
-    let gas_consumed = total_l1_gas_usage.ceil() as u64;
-    let overall_fee = total_l1_gas_usage.ceil() as u64 * gas_price;
+    // V3 transactions pay their fee in STRK, the others in ETH.
+    let gas_prices = &block_context.block_info.gas_prices;
+    let gas_price = match unit {
+        PriceUnit::Wei => gas_prices.eth_l1_gas_price,
+        PriceUnit::Fri => gas_prices.strk_l1_gas_price,
+    };
+    let gas_consumed = total_l1_gas_usage.ceil() as u128;
+    let overall_fee = gas_consumed * gas_price;
 
     Ok(FeeEstimate {
         gas_price: gas_price.into(),
         gas_consumed: gas_consumed.into(),
         overall_fee: overall_fee.into(),
-        unit: PriceUnit::Wei,
+        unit: unit.into(),
     })
 }
 
@@ -159,7 +157,7 @@ pub fn calculate_execution_fee(
 pub fn block_context_from_envs(block_env: &BlockEnv, cfg_env: &CfgEnv) -> BlockContext {
     let fee_token_addresses = FeeTokenAddresses {
         eth_fee_token_address: cfg_env.fee_token_addresses.eth.into(),
-        strk_fee_token_address: ContractAddress(felt!("0xb00b5")).into(),
+        strk_fee_token_address: cfg_env.fee_token_addresses.strk.into(),
     };
 
     let gas_prices = GasPrices {
@@ -240,6 +238,7 @@ pub fn get_state_update_from_cached_state(
     let state_diff = state.inner().to_state_diff();
 
     let declared_sierra_classes = state.sierra_class().clone();
+    let declared_legacy_class_abis = state.legacy_class_abis().clone();
 
     let declared_compiled_classes = state_diff
         .class_hash_to_compiled_class_hash
@@ -300,6 +299,7 @@ pub fn get_state_update_from_cached_state(
     StateUpdatesWithDeclaredClasses {
         declared_sierra_classes,
         declared_compiled_classes,
+        declared_legacy_class_abis,
         state_updates: StateUpdates {
             nonce_updates,
             storage_updates: storage_changes,
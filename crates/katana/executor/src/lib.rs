@@ -1 +1,4 @@
+pub mod abstraction;
+
+#[cfg(feature = "blockifier")]
 pub mod blockifier;
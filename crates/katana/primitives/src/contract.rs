@@ -21,6 +21,9 @@ pub type Nonce = FieldElement;
 pub type SierraClass = starknet::core::types::contract::SierraClass;
 pub type FlattenedSierraClass = starknet::core::types::FlattenedSierraClass;
 
+/// The ABI of a legacy contract class, which isn't part of its compiled class definition.
+pub type LegacyClassAbi = Vec<starknet::core::types::LegacyContractAbiEntry>;
+
 /// Represents a contract address.
 #[derive(Default, Clone, Copy, Eq, PartialEq, PartialOrd, Ord, Hash, Debug, Deref)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
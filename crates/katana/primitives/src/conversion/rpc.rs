@@ -6,6 +6,7 @@ use std::str::FromStr;
 use anyhow::{anyhow, Result};
 use blockifier::execution::contract_class::ContractClassV0;
 use cairo_lang_starknet::casm_contract_class::CasmContractClass;
+use cairo_lang_starknet::compiler_version::current_compiler_version_id;
 use cairo_vm::felt::Felt252;
 use cairo_vm::serde::deserialize_program::{
     serialize_program_data, ApTracking, OffsetValue, ProgramJson, ValueAddress,
@@ -29,8 +30,9 @@ use starknet_api::deprecated_contract_class::{EntryPoint, EntryPointType};
 
 use crate::contract::{
     ClassHash, CompiledClassHash, CompiledContractClass, CompiledContractClassV0,
-    FlattenedSierraClass,
+    CompiledContractClassV1, FlattenedSierraClass, LegacyClassAbi,
 };
+use crate::utils::class::rpc_to_cairo_contract_class;
 use crate::FieldElement;
 
 mod primitives {
@@ -85,6 +87,108 @@ pub fn legacy_inner_to_rpc_class(
     }))
 }
 
+/// Converts the legacy inner compiled class type [CompiledContractClassV0], along with its ABI
+/// which isn't part of the inner compiled class, into the JSON artifact format produced by the
+/// Cairo 0 compiler.
+pub fn legacy_inner_to_json_artifact(
+    legacy_contract_class: CompiledContractClassV0,
+    abi: &LegacyClassAbi,
+) -> Result<serde_json::Value> {
+    let ContractClass::Legacy(class) = legacy_inner_to_rpc_class(legacy_contract_class)? else {
+        unreachable!("legacy class should be converted to a legacy RPC class")
+    };
+
+    Ok(json!({
+        "abi": abi,
+        "entry_points_by_type": class.entry_points_by_type,
+        "program": decompress_legacy_program_data(&class.program)?,
+    }))
+}
+
+/// Converts the inner compiled class type [CompiledContractClassV1] into the CASM class format
+/// produced by the Sierra compiler.
+///
+/// The pythonic hints aren't part of the inner compiled class, so they are omitted from the CASM
+/// class. They aren't part of the compiled class hash.
+pub fn inner_to_casm_class(contract_class: &CompiledContractClassV1) -> Result<serde_json::Value> {
+    let program = &contract_class.program.shared_program_data;
+
+    let bytecode = program
+        .data
+        .iter()
+        .map(|value| match value {
+            MaybeRelocatable::Int(felt) => Ok(format!("{:#x}", felt.to_biguint())),
+            MaybeRelocatable::RelocatableValue(_) => Err(anyhow!("Relocatable value in bytecode")),
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    // The hints of the inner class are the JSON encoding of the CASM hints.
+    let mut hints = Vec::new();
+    for (pc, range) in program.hints_collection.hints_ranges.iter().enumerate() {
+        let Some((start, len)) = range else { continue };
+        let pc_hints = program.hints_collection.hints[*start..*start + len.get()]
+            .iter()
+            .map(|hint| serde_json::from_str::<serde_json::Value>(&hint.code))
+            .collect::<Result<Vec<_>, _>>()?;
+        hints.push(json!([pc, pc_hints]));
+    }
+
+    let entry_points = |entry_point_type: EntryPointType| -> Vec<serde_json::Value> {
+        contract_class
+            .entry_points_by_type
+            .get(&entry_point_type)
+            .into_iter()
+            .flatten()
+            .map(|entry_point| {
+                json!({
+                    "selector": format!("{:#x}", FieldElement::from(entry_point.selector.0)),
+                    "offset": entry_point.offset.0,
+                    "builtins": entry_point.builtins,
+                })
+            })
+            .collect()
+    };
+
+    Ok(json!({
+        "prime": format!("{:#x}", Felt252::prime()),
+        "compiler_version": current_compiler_version_id().to_string(),
+        "bytecode": bytecode,
+        "hints": hints,
+        "entry_points_by_type": {
+            "EXTERNAL": entry_points(EntryPointType::External),
+            "L1_HANDLER": entry_points(EntryPointType::L1Handler),
+            "CONSTRUCTOR": entry_points(EntryPointType::Constructor),
+        },
+    }))
+}
+
+/// Converts the given [`FlattenedSierraClass`] into the JSON artifact format produced by the
+/// Cairo 1 compiler.
+///
+/// The Sierra debug info isn't part of the flattened class, so the artifact is created with empty
+/// debug info.
+pub fn flattened_sierra_to_json_artifact(
+    contract_class: &FlattenedSierraClass,
+) -> Result<serde_json::Value> {
+    let abi = if contract_class.abi.is_empty() {
+        serde_json::Value::Array(Vec::new())
+    } else {
+        serde_json::from_str(&contract_class.abi)?
+    };
+
+    Ok(json!({
+        "sierra_program": contract_class.sierra_program,
+        "sierra_program_debug_info": {
+            "type_names": [],
+            "libfunc_names": [],
+            "user_func_names": [],
+        },
+        "contract_class_version": contract_class.contract_class_version,
+        "entry_points_by_type": contract_class.entry_points_by_type,
+        "abi": abi,
+    }))
+}
+
 /// Convert the given [`FlattenedSierraClass`] into the inner compiled class type
 /// [`CompiledContractClass`] along with its class hashes.
 pub fn flattened_sierra_to_compiled_class(
@@ -178,25 +282,6 @@ pub fn legacy_rpc_to_inner_compiled_class(
     Ok((class_hash, CompiledContractClass::V0(inner_class)))
 }
 
-/// Converts `starknet-rs` RPC [FlattenedSierraClass] type to Cairo's
-/// [ContractClass](cairo_lang_starknet::contract_class::ContractClass) type.
-fn rpc_to_cairo_contract_class(
-    contract_class: &FlattenedSierraClass,
-) -> Result<cairo_lang_starknet::contract_class::ContractClass, std::io::Error> {
-    let value = serde_json::to_value(contract_class)?;
-
-    Ok(cairo_lang_starknet::contract_class::ContractClass {
-        abi: serde_json::from_value(value["abi"].clone()).ok(),
-        sierra_program: serde_json::from_value(value["sierra_program"].clone())?,
-        entry_points_by_type: serde_json::from_value(value["entry_points_by_type"].clone())?,
-        contract_class_version: serde_json::from_value(value["contract_class_version"].clone())?,
-        sierra_program_debug_info: serde_json::from_value(
-            value["sierra_program_debug_info"].clone(),
-        )
-        .ok(),
-    })
-}
-
 fn compress_legacy_program_data(legacy_program: Program) -> Result<Vec<u8>, io::Error> {
     fn felt_as_dec_str<S: Serializer>(
         value: &Option<Felt252>,
@@ -406,8 +491,19 @@ fn parse_value_address_to_str(value_address: ValueAddress) -> String {
 mod tests {
     use starknet::core::types::ContractClass;
 
-    use super::{legacy_inner_to_rpc_class, legacy_rpc_to_inner_compiled_class};
-    use crate::utils::class::parse_compiled_class_v0;
+    use starknet::core::types::contract::legacy::LegacyContractClass;
+    use starknet::core::types::contract::{CompiledClass, SierraClass};
+
+    use super::{
+        flattened_sierra_to_json_artifact, inner_to_casm_class, legacy_inner_to_json_artifact,
+        legacy_inner_to_rpc_class, legacy_rpc_to_inner_compiled_class,
+    };
+    use crate::contract::{CompiledContractClass, CompiledContractClassV0};
+    use crate::genesis::constant::{
+        DEFAULT_OZ_ACCOUNT_CONTRACT, DEFAULT_OZ_ACCOUNT_CONTRACT_CASM,
+        DEFAULT_OZ_ACCOUNT_CONTRACT_COMPILED_CLASS_HASH,
+    };
+    use crate::utils::class::{parse_compiled_class_v0, parse_legacy_class_abi};
 
     // There are some discrepancies between the legacy RPC and the inner compiled class types which
     // results in some data lost during the conversion. Therefore, we are unable to assert for
@@ -425,4 +521,44 @@ mod tests {
 
         legacy_rpc_to_inner_compiled_class(&compressed_legacy_class).unwrap();
     }
+
+    #[test]
+    fn legacy_inner_to_json_artifact_and_back() {
+        let class_json = include_str!("../../contracts/compiled/account.json");
+        let class = parse_compiled_class_v0(class_json).unwrap();
+        let abi = parse_legacy_class_abi(class_json).unwrap();
+
+        let artifact = legacy_inner_to_json_artifact(class, &abi).unwrap();
+
+        let original: serde_json::Value = serde_json::from_str(class_json).unwrap();
+        assert_eq!(artifact["abi"], original["abi"]);
+
+        serde_json::from_value::<CompiledContractClassV0>(artifact.clone()).unwrap();
+        serde_json::from_value::<LegacyContractClass>(artifact).unwrap().class_hash().unwrap();
+    }
+
+    #[test]
+    fn flattened_sierra_to_json_artifact_and_back() {
+        let class = DEFAULT_OZ_ACCOUNT_CONTRACT.clone().flatten().unwrap();
+
+        let artifact = flattened_sierra_to_json_artifact(&class).unwrap();
+        let sierra: SierraClass = serde_json::from_value(artifact).unwrap();
+
+        assert_eq!(sierra.flatten().unwrap().class_hash(), class.class_hash());
+    }
+
+    #[test]
+    fn inner_to_casm_class_has_same_compiled_class_hash() {
+        let CompiledContractClass::V1(class) = DEFAULT_OZ_ACCOUNT_CONTRACT_CASM.clone() else {
+            panic!("Expected Sierra compiled class");
+        };
+
+        let casm = inner_to_casm_class(&class).unwrap();
+        let compiled_class: CompiledClass = serde_json::from_value(casm).unwrap();
+
+        assert_eq!(
+            compiled_class.class_hash().unwrap(),
+            DEFAULT_OZ_ACCOUNT_CONTRACT_COMPILED_CLASS_HASH
+        );
+    }
 }
@@ -0,0 +1,28 @@
+/// The unit of the fee paid for a transaction, ie. the token in which it is charged.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PriceUnit {
+    /// The fee is paid in ETH, as for the transactions up to V2.
+    #[default]
+    Wei,
+    /// The fee is paid in STRK, as for the V3 transactions.
+    Fri,
+}
+
+impl From<PriceUnit> for starknet::core::types::PriceUnit {
+    fn from(value: PriceUnit) -> Self {
+        match value {
+            PriceUnit::Wei => Self::Wei,
+            PriceUnit::Fri => Self::Fri,
+        }
+    }
+}
+
+impl From<starknet::core::types::PriceUnit> for PriceUnit {
+    fn from(value: starknet::core::types::PriceUnit) -> Self {
+        match value {
+            starknet::core::types::PriceUnit::Wei => Self::Wei,
+            starknet::core::types::PriceUnit::Fri => Self::Fri,
+        }
+    }
+}
@@ -2,15 +2,25 @@ use std::collections::HashMap;
 use std::fmt::Debug;
 
 use derive_more::{Deref, DerefMut};
+use ethers::signers::coins_bip39::English;
+use ethers::signers::{MnemonicBuilder, WalletError};
 use ethers::types::U256;
 use rand::rngs::SmallRng;
 use rand::{RngCore, SeedableRng};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use starknet::core::serde::unsigned_field_element::{UfeHex, UfeHexOption};
 use starknet::core::utils::get_contract_address;
 use starknet::signers::SigningKey;
 
-use super::constant::DEFAULT_OZ_ACCOUNT_CONTRACT_CLASS_HASH;
+use super::constant::{
+    ARGENT_ACCOUNT_CONTRACT_SIGNER_STORAGE_SLOT, DEFAULT_ARGENT_ACCOUNT_CONTRACT,
+    DEFAULT_ARGENT_ACCOUNT_CONTRACT_CASM, DEFAULT_ARGENT_ACCOUNT_CONTRACT_CLASS_HASH,
+    DEFAULT_ARGENT_ACCOUNT_CONTRACT_COMPILED_CLASS_HASH, DEFAULT_OZ_ACCOUNT_CONTRACT,
+    DEFAULT_OZ_ACCOUNT_CONTRACT_CASM, DEFAULT_OZ_ACCOUNT_CONTRACT_CLASS_HASH,
+    DEFAULT_OZ_ACCOUNT_CONTRACT_COMPILED_CLASS_HASH,
+};
+use super::GenesisClass;
 use crate::contract::{ClassHash, ContractAddress, StorageKey, StorageValue};
 use crate::FieldElement;
 
@@ -171,6 +181,16 @@ impl DevGenesisAccount {
         account.balance = Some(balance);
         (addr, account)
     }
+
+    /// Creates a new dev account of the given account `class`.
+    pub fn new_with_class(
+        private_key: FieldElement,
+        class: DevAccountClass,
+    ) -> (ContractAddress, Self) {
+        let public_key = public_key_from_private_key(private_key);
+        let (addr, inner) = GenesisAccount::new_with_class(public_key, class);
+        (addr, Self { private_key, inner })
+    }
 }
 
 /// Account contract allocated in the genesis block.
@@ -213,6 +233,166 @@ impl GenesisAccount {
         let (address, account) = Self::new(public_key, class_hash);
         (address, Self { balance: Some(balance), ..account })
     }
+
+    /// Creates a new account of the given account `class`, whose constructor arguments and
+    /// storage are initialized according to the class.
+    pub fn new_with_class(
+        public_key: FieldElement,
+        class: DevAccountClass,
+    ) -> (ContractAddress, Self) {
+        let class_hash = class.class_hash();
+        let address = get_contract_address(
+            FieldElement::from(666u32),
+            class_hash,
+            &class.constructor_calldata(public_key),
+            FieldElement::ZERO,
+        );
+
+        let account = Self {
+            public_key,
+            class_hash,
+            storage: class.storage(public_key),
+            ..Default::default()
+        };
+        (ContractAddress::from(address), account)
+    }
+}
+
+/// The account contract class of a dev account.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DevAccountClass {
+    /// The OpenZeppelin account contract.
+    #[default]
+    OpenZeppelin,
+    /// The Argent account contract, with no guardian.
+    Argent,
+    /// Any other account contract class, eg a Braavos-like account. It must follow the same layout
+    /// as the OpenZeppelin account, ie its constructor only takes the public key, which is stored
+    /// in the `Account_public_key` storage variable.
+    ///
+    /// The constructor isn't executed when the genesis is loaded, only its effect on the storage
+    /// is reproduced. So an account storing its public key elsewhere, or setting other storage
+    /// variables in its constructor, is deployed without them and can't validate its
+    /// transactions. Such accounts must be allocated in a genesis file, with their storage set
+    /// explicitly.
+    Custom(ClassHash),
+}
+
+impl DevAccountClass {
+    /// Get the account class from its class hash.
+    pub fn from_class_hash(class_hash: ClassHash) -> Self {
+        if class_hash == DEFAULT_OZ_ACCOUNT_CONTRACT_CLASS_HASH {
+            Self::OpenZeppelin
+        } else if class_hash == DEFAULT_ARGENT_ACCOUNT_CONTRACT_CLASS_HASH {
+            Self::Argent
+        } else {
+            Self::Custom(class_hash)
+        }
+    }
+
+    /// Get the class hash of the account contract.
+    pub fn class_hash(&self) -> ClassHash {
+        match self {
+            Self::OpenZeppelin => DEFAULT_OZ_ACCOUNT_CONTRACT_CLASS_HASH,
+            Self::Argent => DEFAULT_ARGENT_ACCOUNT_CONTRACT_CLASS_HASH,
+            Self::Custom(class_hash) => *class_hash,
+        }
+    }
+
+    /// Get the name of the account class, or `None` if it's a custom class.
+    pub fn name(&self) -> Option<&'static str> {
+        match self {
+            Self::OpenZeppelin => Some("oz"),
+            Self::Argent => Some("argent"),
+            Self::Custom(_) => None,
+        }
+    }
+
+    /// Get the definition of the class to declare in the genesis, or `None` if it's a custom
+    /// class.
+    pub fn genesis_class(&self) -> Option<GenesisClass> {
+        match self {
+            Self::OpenZeppelin => Some(GenesisClass {
+                sierra: Some(DEFAULT_OZ_ACCOUNT_CONTRACT.clone().flatten().unwrap().into()),
+                casm: DEFAULT_OZ_ACCOUNT_CONTRACT_CASM.clone().into(),
+                compiled_class_hash: DEFAULT_OZ_ACCOUNT_CONTRACT_COMPILED_CLASS_HASH,
+                legacy_abi: None,
+            }),
+            Self::Argent => Some(GenesisClass {
+                sierra: Some(DEFAULT_ARGENT_ACCOUNT_CONTRACT.clone().flatten().unwrap().into()),
+                casm: DEFAULT_ARGENT_ACCOUNT_CONTRACT_CASM.clone().into(),
+                compiled_class_hash: *DEFAULT_ARGENT_ACCOUNT_CONTRACT_COMPILED_CLASS_HASH,
+                legacy_abi: None,
+            }),
+            Self::Custom(_) => None,
+        }
+    }
+
+    fn constructor_calldata(&self, public_key: FieldElement) -> Vec<FieldElement> {
+        match self {
+            // the owner and the guardian
+            Self::Argent => vec![public_key, FieldElement::ZERO],
+            Self::OpenZeppelin | Self::Custom(_) => vec![public_key],
+        }
+    }
+
+    // The `Account_public_key` storage variable is always set when the genesis is loaded, so only
+    // the other variables set by the constructor are returned.
+    fn storage(&self, public_key: FieldElement) -> Option<HashMap<StorageKey, StorageValue>> {
+        match self {
+            Self::Argent => {
+                Some(HashMap::from([(ARGENT_ACCOUNT_CONTRACT_SIGNER_STORAGE_SLOT, public_key)]))
+            }
+            Self::OpenZeppelin | Self::Custom(_) => None,
+        }
+    }
+}
+
+/// A BIP-39 mnemonic from which the private keys of the dev accounts are derived.
+///
+/// The key of the account at index `i` is derived at the path `{derivation_path}/{i}` on the
+/// secp256k1 curve, and then ground to a key of the Stark curve the same way as the Starknet
+/// wallets do, so that the accounts can be imported in them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DevMnemonic {
+    phrase: String,
+    derivation_path: String,
+}
+
+impl DevMnemonic {
+    /// Creates a new mnemonic, failing if the phrase or the derivation path are invalid.
+    pub fn new(
+        phrase: impl Into<String>,
+        derivation_path: impl Into<String>,
+    ) -> Result<Self, WalletError> {
+        let mnemonic = Self { phrase: phrase.into(), derivation_path: derivation_path.into() };
+        mnemonic.private_key(0)?;
+        Ok(mnemonic)
+    }
+
+    /// Checks that `derivation_path` is a valid derivation path for the keys of the accounts.
+    pub fn validate_derivation_path(derivation_path: &str) -> Result<(), WalletError> {
+        MnemonicBuilder::<English>::default().derivation_path(&format!("{derivation_path}/0"))?;
+        Ok(())
+    }
+
+    pub fn phrase(&self) -> &str {
+        &self.phrase
+    }
+
+    pub fn derivation_path(&self) -> &str {
+        &self.derivation_path
+    }
+
+    /// Derive the private key of the account at `index`.
+    pub fn private_key(&self, index: u32) -> Result<FieldElement, WalletError> {
+        let wallet = MnemonicBuilder::<English>::default()
+            .phrase(self.phrase.as_str())
+            .derivation_path(&format!("{}/{index}", self.derivation_path))?
+            .build()?;
+
+        Ok(grind_key(wallet.signer().to_bytes().into()))
+    }
 }
 
 impl From<DevGenesisAccount> for GenesisAllocation {
@@ -226,8 +406,9 @@ impl From<DevGenesisAccount> for GenesisAllocation {
 pub struct DevAllocationsGenerator {
     total: u16,
     seed: [u8; 32],
+    mnemonic: Option<DevMnemonic>,
     balance: U256,
-    class_hash: FieldElement,
+    classes: Vec<DevAccountClass>,
 }
 
 impl DevAllocationsGenerator {
@@ -238,45 +419,180 @@ impl DevAllocationsGenerator {
         Self {
             total,
             seed: [0u8; 32],
+            mnemonic: None,
             balance: U256::zero(),
-            class_hash: DEFAULT_OZ_ACCOUNT_CONTRACT_CLASS_HASH,
+            classes: vec![DevAccountClass::OpenZeppelin],
         }
     }
 
     pub fn with_class(self, class_hash: ClassHash) -> Self {
-        Self { class_hash, ..self }
+        Self { classes: vec![DevAccountClass::from_class_hash(class_hash)], ..self }
+    }
+
+    /// Use a different account class for each account: the account at index `i` is of class
+    /// `classes[i]`, and the accounts past the end of `classes` are of its last class.
+    pub fn with_classes(self, classes: Vec<DevAccountClass>) -> Self {
+        if classes.is_empty() {
+            return self;
+        }
+        Self { classes, ..self }
     }
 
     pub fn with_seed<T: Into<[u8; 32]>>(self, seed: T) -> Self {
         Self { seed: seed.into(), ..self }
     }
 
+    /// Derive the private keys from `mnemonic` instead of the seed.
+    pub fn with_mnemonic(self, mnemonic: DevMnemonic) -> Self {
+        Self { mnemonic: Some(mnemonic), ..self }
+    }
+
     pub fn with_balance<T: Into<U256>>(self, balance: T) -> Self {
         Self { balance: balance.into(), ..self }
     }
 
-    /// Generate `total` number of accounts based on the `seed`.
+    /// Generate `total` number of accounts based on the `seed`, or the mnemonic if any.
     #[must_use]
     pub fn generate(&self) -> HashMap<ContractAddress, DevGenesisAccount> {
         let mut seed = self.seed;
         (0..self.total)
-            .map(|_| {
-                let mut rng = SmallRng::from_seed(seed);
-                let mut private_key_bytes = [0u8; 32];
-
-                rng.fill_bytes(&mut private_key_bytes);
-                private_key_bytes[0] %= 0x8;
-                seed = private_key_bytes;
-
-                let private_key = FieldElement::from_bytes_be(&private_key_bytes).unwrap();
-                DevGenesisAccount::new_with_balance(private_key, self.class_hash, self.balance)
+            .map(|index| {
+                let private_key = match &self.mnemonic {
+                    Some(mnemonic) => mnemonic
+                        .private_key(index.into())
+                        .expect("mnemonic should be validated on creation"),
+
+                    None => {
+                        let mut rng = SmallRng::from_seed(seed);
+                        let mut private_key_bytes = [0u8; 32];
+
+                        rng.fill_bytes(&mut private_key_bytes);
+                        private_key_bytes[0] %= 0x8;
+                        seed = private_key_bytes;
+
+                        FieldElement::from_bytes_be(&private_key_bytes).unwrap()
+                    }
+                };
+
+                let class = self.classes.get(usize::from(index)).or(self.classes.last());
+                let class = class.copied().unwrap_or_default();
+
+                let (address, mut account) = DevGenesisAccount::new_with_class(private_key, class);
+                account.balance = Some(self.balance);
+                (address, account)
             })
             .collect()
     }
 }
 
+/// Grind a key of the Stark curve from the 32 bytes `seed`, following the algorithm used by the
+/// Starknet wallets: the hashes of the seed and a counter are computed until one is below the
+/// largest multiple of the curve order that fits in 256 bits, and the key is its remainder.
+fn grind_key(seed: [u8; 32]) -> FieldElement {
+    let order = U256::from_str_radix(
+        "0800000000000010ffffffffffffffffb781126dcae7b2321e66a241adc64d2f",
+        16,
+    )
+    .unwrap();
+
+    // 2^256 - (2^256 % order)
+    let limit = U256::MAX - (U256::MAX % order + 1) % order + 1;
+
+    let mut index = 0u64;
+    loop {
+        // the counter is encoded as its minimal big-endian representation, and zero as one byte
+        let bytes = index.to_be_bytes();
+        let start = bytes.iter().position(|b| *b != 0).unwrap_or(bytes.len() - 1);
+
+        let hash = Sha256::new().chain_update(seed).chain_update(&bytes[start..]).finalize();
+        let key = U256::from_big_endian(&hash);
+
+        if key < limit {
+            let mut bytes = [0u8; 32];
+            (key % order).to_big_endian(&mut bytes);
+            return FieldElement::from_bytes_be(&bytes).unwrap();
+        }
+
+        index += 1;
+    }
+}
+
 /// Helper function for generating the public key from the `private_key` using
 /// the Stark curve.
 fn public_key_from_private_key(private_key: FieldElement) -> FieldElement {
     SigningKey::from_secret_scalar(private_key).verifying_key().scalar()
 }
+
+#[cfg(test)]
+mod tests {
+    use starknet::core::utils::get_storage_var_address;
+    use starknet::macros::felt;
+
+    use super::*;
+    use crate::genesis::constant::DEFAULT_DEV_ACCOUNT_DERIVATION_PATH;
+
+    const MNEMONIC: &str = "test test test test test test test test test test test junk";
+
+    #[test]
+    fn grind_stark_key() {
+        let seed = felt!("0x86f3e7293141f20a8baff320e8ee4accb9d4a4bf2b4d295e8cee784db46e0519");
+        assert_eq!(
+            grind_key(seed.to_bytes_be()),
+            felt!("0x5c8c8683596c732541a59e03007b2d30dbbbb873556fe65b5fb63c16688f941")
+        );
+    }
+
+    #[test]
+    fn derive_private_keys_from_mnemonic() {
+        let mnemonic = DevMnemonic::new(MNEMONIC, DEFAULT_DEV_ACCOUNT_DERIVATION_PATH).unwrap();
+
+        assert_eq!(
+            mnemonic.private_key(0).unwrap(),
+            felt!("0x6a9c4ecd67b5a868c4e1ff108da4735c573881681e83aae0d2c9382a410857f")
+        );
+        assert_eq!(
+            mnemonic.private_key(1).unwrap(),
+            felt!("0x5ba0239ac05e7b4e08723ea1b14adcdedebc722c108320abe030f66957b212a")
+        );
+
+        assert!(DevMnemonic::new("not a mnemonic", DEFAULT_DEV_ACCOUNT_DERIVATION_PATH).is_err());
+        assert!(DevMnemonic::new(MNEMONIC, "not a path").is_err());
+    }
+
+    #[test]
+    fn argent_account_class() {
+        let class = DevAccountClass::Argent.genesis_class().unwrap();
+        let sierra = DEFAULT_ARGENT_ACCOUNT_CONTRACT.clone();
+        assert_eq!(sierra.class_hash().unwrap(), DEFAULT_ARGENT_ACCOUNT_CONTRACT_CLASS_HASH);
+        assert!(class.sierra.is_some());
+
+        // the owner public key is stored in the `_signer` storage variable
+        assert_eq!(
+            ARGENT_ACCOUNT_CONTRACT_SIGNER_STORAGE_SLOT,
+            get_storage_var_address("_signer", &[]).unwrap()
+        );
+    }
+
+    #[test]
+    fn generate_accounts_with_classes() {
+        let mnemonic = DevMnemonic::new(MNEMONIC, DEFAULT_DEV_ACCOUNT_DERIVATION_PATH).unwrap();
+        let accounts = DevAllocationsGenerator::new(3)
+            .with_mnemonic(mnemonic.clone())
+            .with_classes(vec![DevAccountClass::OpenZeppelin, DevAccountClass::Argent])
+            .generate();
+
+        for index in 0..3 {
+            let private_key = mnemonic.private_key(index).unwrap();
+            let (_, account) = accounts.iter().find(|(_, a)| a.private_key == private_key).unwrap();
+
+            if index == 0 {
+                assert_eq!(account.class_hash, DEFAULT_OZ_ACCOUNT_CONTRACT_CLASS_HASH);
+                assert_eq!(account.storage, None);
+            } else {
+                let signer = (ARGENT_ACCOUNT_CONTRACT_SIGNER_STORAGE_SLOT, account.public_key);
+                assert_eq!(account.class_hash, DEFAULT_ARGENT_ACCOUNT_CONTRACT_CLASS_HASH);
+                assert_eq!(account.storage, Some(HashMap::from([signer])));
+            }
+        }
+    }
+}
@@ -2,9 +2,12 @@ use lazy_static::lazy_static;
 use starknet::core::utils::get_storage_var_address;
 
 use crate::contract::{
-    ClassHash, CompiledClassHash, CompiledContractClass, ContractAddress, SierraClass, StorageKey,
+    ClassHash, CompiledClassHash, CompiledContractClass, ContractAddress, LegacyClassAbi,
+    SierraClass, StorageKey,
+};
+use crate::utils::class::{
+    compute_compiled_class_hash, parse_compiled_class, parse_legacy_class_abi, parse_sierra_class,
 };
-use crate::utils::class::{parse_compiled_class, parse_sierra_class};
 use crate::FieldElement;
 
 /// The default universal deployer contract address.
@@ -25,6 +28,16 @@ pub const DEFAULT_FEE_TOKEN_ADDRESS: ContractAddress = ContractAddress(FieldElem
     418961398025637529,
 ]));
 
+/// The default STRK fee token contract address, used to pay the fees of V3 transactions.
+/// Corresponds to 0x04718f5a0fc34cc1af16a1cdee98ffb20c31f5cd61d6ab07201858f4287c938d
+pub const DEFAULT_STRK_FEE_TOKEN_ADDRESS: ContractAddress =
+    ContractAddress(FieldElement::from_mont([
+        16432072983745651214,
+        1325769094487018516,
+        5134018303144032807,
+        468300854463065062,
+    ]));
+
 /// The standard storage address for `public key` in OpenZeppelin account contract.
 /// Corresponds to keccak("Account_public_key") ==
 /// 0x1379ac0624b939ceb9dede92211d7db5ee174fe28be72245b0a1a2abd81c98f
@@ -35,6 +48,16 @@ pub const OZ_ACCOUNT_CONTRACT_PUBKEY_STORAGE_SLOT: StorageKey = FieldElement::fr
     333410775162302292,
 ]);
 
+/// The storage address of the `_signer` in the Argent account contract, ie the owner public key.
+/// Corresponds to keccak("_signer") ==
+/// 0x01ccc09c8a19948e048de7add6929589945e25f22059c7345aaf7837188d8d05
+pub const ARGENT_ACCOUNT_CONTRACT_SIGNER_STORAGE_SLOT: StorageKey = FieldElement::from_mont([
+    3020502144143979919,
+    7883968182803675788,
+    15090611222105687024,
+    505126942629870904,
+]);
+
 /// The standard storage address for `ERC20_name` in ERC20 contract.
 /// Corresponds to keccak("ERC20_name") ==
 /// 0x0341c1bdfd89f69748aa00b5742b03adbffd79b8e80cab5c50d91cd8c2a79be1
@@ -75,6 +98,10 @@ pub const ERC20_TOTAL_SUPPLY_STORAGE_SLOT: StorageKey = FieldElement::from_mont(
     218835885563775175,
 ]);
 
+/// The default derivation path of the dev account keys derived from a mnemonic. The index of the
+/// account is appended to it. This is the path used by the Starknet wallets.
+pub const DEFAULT_DEV_ACCOUNT_DERIVATION_PATH: &str = "m/44'/9004'/0'/0";
+
 /// The default fee token balance for dev accounts at genesis.
 pub const DEFAULT_PREFUNDED_ACCOUNT_BALANCE: u128 = 10 * u128::pow(10, 21);
 
@@ -122,25 +149,41 @@ pub const DEFAULT_OZ_ACCOUNT_CONTRACT_COMPILED_CLASS_HASH: CompiledClassHash =
         190499602541245794,
     ]);
 
+/// The class hash of DEFAULT_ARGENT_ACCOUNT_CONTRACT, the Argent account 0.3.1.
+/// Corresponds to 0x029927c8af6bccf3f6fda035981e765a7bdbf18a2dc0d630494f8758aa908e2b
+pub const DEFAULT_ARGENT_ACCOUNT_CONTRACT_CLASS_HASH: ClassHash = FieldElement::from_mont([
+    8854263092256612902,
+    17170262515536243964,
+    14906149393200614104,
+    416097215623145143,
+]);
+
 // Pre-compiled contract classes
 lazy_static! {
 
     // Default fee token contract
     pub static ref DEFAULT_LEGACY_ERC20_CONTRACT_CASM: CompiledContractClass = parse_compiled_class(include_str!("../../contracts/compiled/erc20.json")).unwrap();
+    pub static ref DEFAULT_LEGACY_ERC20_CONTRACT_ABI: LegacyClassAbi = parse_legacy_class_abi(include_str!("../../contracts/compiled/erc20.json")).unwrap();
 
     // Default universal deployer
     pub static ref DEFAULT_LEGACY_UDC_CASM: CompiledContractClass = parse_compiled_class(include_str!("../../contracts/compiled/universal_deployer.json")).unwrap();
+    pub static ref DEFAULT_LEGACY_UDC_ABI: LegacyClassAbi = parse_legacy_class_abi(include_str!("../../contracts/compiled/universal_deployer.json")).unwrap();
 
     // Default account contract
     pub static ref DEFAULT_OZ_ACCOUNT_CONTRACT: SierraClass = parse_sierra_class(include_str!("../../contracts/compiled/oz_account_080.json")).unwrap();
     pub static ref DEFAULT_OZ_ACCOUNT_CONTRACT_CASM: CompiledContractClass = parse_compiled_class(include_str!("../../contracts/compiled/oz_account_080.json")).unwrap();
 
+    // Argent account contract, available for the dev accounts
+    pub static ref DEFAULT_ARGENT_ACCOUNT_CONTRACT: SierraClass = parse_sierra_class(include_str!("../../contracts/compiled/argent_ArgentAccount_0.3.1.json")).unwrap();
+    pub static ref DEFAULT_ARGENT_ACCOUNT_CONTRACT_CASM: CompiledContractClass = parse_compiled_class(include_str!("../../contracts/compiled/argent_ArgentAccount_0.3.1.json")).unwrap();
+    pub static ref DEFAULT_ARGENT_ACCOUNT_CONTRACT_COMPILED_CLASS_HASH: CompiledClassHash = compute_compiled_class_hash(include_str!("../../contracts/compiled/argent_ArgentAccount_0.3.1.json")).unwrap();
+
 }
 
 /// A helper function to get the base storage address for the fee token balance of a given account.
 ///
 /// This is to compute the base storage address of the balance because the fee token balance is
 /// stored as a U256 value and as such has to be split into two U128 values (low and high).
-pub(super) fn get_fee_token_balance_base_storage_address(address: ContractAddress) -> FieldElement {
+pub fn get_fee_token_balance_base_storage_address(address: ContractAddress) -> FieldElement {
     get_storage_var_address("ERC20_balances", &[address.into()]).unwrap()
 }
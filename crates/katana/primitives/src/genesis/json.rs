@@ -1,8 +1,8 @@
 //! JSON representation of the genesis configuration. Used to deserialize the genesis configuration
 //! from a JSON file.
 
-use std::collections::{hash_map, BTreeMap, HashMap};
-use std::fs::File;
+use std::collections::{hash_map, BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fs::{self, File};
 use std::io::{
     BufReader, {self},
 };
@@ -23,18 +23,21 @@ use serde_json::{Map, Value};
 use starknet::core::types::contract::legacy::LegacyContractClass;
 use starknet::core::types::contract::{ComputeClassHashError, JsonError};
 use starknet::core::types::FromByteArrayError;
+use starknet::core::utils::{cairo_short_string_to_felt, CairoShortStringToFeltError};
 
 use super::allocation::{
     DevGenesisAccount, GenesisAccount, GenesisAccountAlloc, GenesisContractAlloc,
 };
 use super::constant::{
-    DEFAULT_FEE_TOKEN_ADDRESS, DEFAULT_LEGACY_ERC20_CONTRACT_CASM,
-    DEFAULT_LEGACY_ERC20_CONTRACT_CLASS_HASH, DEFAULT_LEGACY_ERC20_CONTRACT_COMPILED_CLASS_HASH,
+    DEFAULT_FEE_TOKEN_ADDRESS, DEFAULT_LEGACY_ERC20_CONTRACT_ABI,
+    DEFAULT_LEGACY_ERC20_CONTRACT_CASM, DEFAULT_LEGACY_ERC20_CONTRACT_CLASS_HASH,
+    DEFAULT_LEGACY_ERC20_CONTRACT_COMPILED_CLASS_HASH, DEFAULT_LEGACY_UDC_ABI,
     DEFAULT_LEGACY_UDC_CASM, DEFAULT_LEGACY_UDC_CLASS_HASH, DEFAULT_LEGACY_UDC_COMPILED_CLASS_HASH,
     DEFAULT_OZ_ACCOUNT_CONTRACT, DEFAULT_OZ_ACCOUNT_CONTRACT_CASM,
     DEFAULT_OZ_ACCOUNT_CONTRACT_CLASS_HASH, DEFAULT_OZ_ACCOUNT_CONTRACT_COMPILED_CLASS_HASH,
-    DEFAULT_UDC_ADDRESS,
+    DEFAULT_STRK_FEE_TOKEN_ADDRESS, DEFAULT_UDC_ADDRESS,
 };
+use super::token::{Erc20Token, Erc721Token, GenesisToken};
 use super::{FeeTokenConfig, Genesis, GenesisAllocation, UniversalDeployerConfig};
 use crate::block::{BlockHash, BlockNumber, GasPrices};
 use crate::contract::{
@@ -42,10 +45,15 @@ use crate::contract::{
     ContractAddress, SierraClass, StorageKey, StorageValue,
 };
 use crate::genesis::GenesisClass;
+use crate::utils::class::legacy_class_abi_of_artifact;
 use crate::FieldElement;
 
 type Object = Map<String, Value>;
 
+/// The name of the directory, relative to the JSON file, in which the class artifacts are written
+/// by [GenesisJson::save].
+pub const CLASS_ARTIFACTS_DIR: &str = "classes";
+
 /// Represents the path to the class artifact or the full JSON artifact itself.
 #[derive(Debug, Clone, Serialize, PartialEq, Eq, derive_more::From)]
 #[serde(untagged)]
@@ -101,6 +109,27 @@ pub struct GenesisClassJson {
     pub class_hash: Option<ClassHash>,
 }
 
+impl GenesisClassJson {
+    /// Computes the hash of the class from its artifact, regardless of the provided `class_hash`.
+    /// The artifact path must be resolved first.
+    pub fn compute_class_hash(&self) -> Result<ClassHash, GenesisJsonError> {
+        let artifact = match &self.class {
+            PathOrFullArtifact::Artifact(artifact) => artifact,
+            PathOrFullArtifact::Path(path) => {
+                return Err(GenesisJsonError::UnresolvedClassPath(path.clone()));
+            }
+        };
+
+        match serde_json::from_value::<SierraClass>(artifact.clone()) {
+            Ok(sierra) => Ok(sierra.class_hash()?),
+            Err(_) => {
+                let class: LegacyContractClass = serde_json::from_value(artifact.clone())?;
+                Ok(class.class_hash()?)
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct FeeTokenConfigJson {
@@ -127,6 +156,44 @@ pub struct UniversalDeployerConfigJson {
     pub storage: Option<HashMap<StorageKey, StorageValue>>,
 }
 
+/// A token contract deployed in the genesis block, other than the fee token.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum GenesisTokenJson {
+    Erc20(Erc20TokenJson),
+    Erc721(Erc721TokenJson),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Erc20TokenJson {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    /// The class hash of the token contract.
+    /// If not provided, the default fee token class is used.
+    pub class: Option<ClassHash>,
+    /// The amount of tokens allocated to each account.
+    #[serde(default)]
+    pub balances: HashMap<ContractAddress, U256>,
+    /// To initialize the token contract storage
+    pub storage: Option<HashMap<StorageKey, StorageValue>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Erc721TokenJson {
+    pub name: String,
+    pub symbol: String,
+    /// The class hash of the token contract.
+    pub class: ClassHash,
+    /// The ids of the tokens owned by each account.
+    #[serde(default)]
+    pub owners: HashMap<ContractAddress, Vec<U256>>,
+    /// To initialize the token contract storage
+    pub storage: Option<HashMap<StorageKey, StorageValue>>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct GenesisContractJson {
@@ -154,6 +221,9 @@ pub enum GenesisJsonError {
     #[error("Failed to read class file at path {path}: {source}")]
     FileNotFound { source: io::Error, path: PathBuf },
 
+    #[error("Failed to write file at path {path}: {source}")]
+    FileWrite { source: io::Error, path: PathBuf },
+
     #[error(transparent)]
     ParsingError(#[from] serde_json::Error),
 
@@ -175,6 +245,18 @@ pub enum GenesisJsonError {
     #[error("Failed to flatten Sierra contract: {0}")]
     FlattenSierraClass(#[from] JsonError),
 
+    #[error("Invalid name or symbol of token {address}: {source}")]
+    InvalidTokenMetadata { source: CairoShortStringToFeltError, address: ContractAddress },
+
+    #[error("Token id {token_id} of token {address} is owned by more than one account")]
+    DuplicateTokenId { token_id: U256, address: ContractAddress },
+
+    #[error("Total supply of token {0} overflows")]
+    TokenSupplyOverflow(ContractAddress),
+
+    #[error("Token {0} is deployed at the address of an allocation")]
+    TokenAllocationConflict(ContractAddress),
+
     #[error("Unresolved class artifact path {0}")]
     UnresolvedClassPath(PathBuf),
 
@@ -213,11 +295,17 @@ pub struct GenesisJson {
     #[serde(default)]
     pub classes: Vec<GenesisClassJson>,
     pub fee_token: FeeTokenConfigJson,
+    /// The STRK fee token, used to pay the fees of V3 transactions. The default STRK token is
+    /// deployed if not provided.
+    #[serde(default)]
+    pub strk_fee_token: Option<FeeTokenConfigJson>,
     pub universal_deployer: Option<UniversalDeployerConfigJson>,
     #[serde(default)]
     pub accounts: HashMap<ContractAddress, GenesisAccountJson>,
     #[serde(default)]
     pub contracts: HashMap<ContractAddress, GenesisContractJson>,
+    #[serde(default)]
+    pub tokens: HashMap<ContractAddress, GenesisTokenJson>,
 }
 
 impl GenesisJson {
@@ -259,6 +347,57 @@ impl GenesisJson {
         }
         Ok(())
     }
+
+    /// Writes the genesis configuration to a JSON file at the given `path`. The embedded class
+    /// artifacts are written to separate files (see [GenesisJson::extract_class_artifacts]) so
+    /// that the file can be loaded back using [GenesisJson::load].
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), GenesisJsonError> {
+        let path = path.as_ref();
+        let base_path = path.parent().unwrap_or(Path::new(""));
+
+        let mut genesis = self.clone();
+        genesis.extract_class_artifacts(base_path)?;
+
+        let content = serde_json::to_vec_pretty(&genesis)?;
+        fs::write(path, content)
+            .map_err(|source| GenesisJsonError::FileWrite { source, path: path.to_path_buf() })
+    }
+
+    /// Writes the embedded class artifacts to files in the [CLASS_ARTIFACTS_DIR] directory under
+    /// `base_path`, and replaces them with the paths of the files relative to `base_path`. This is
+    /// the inverse of [GenesisJson::resolve_class_artifacts].
+    ///
+    /// The files are named after the class hashes, or after the position of the classes in the
+    /// list if their hashes are not provided.
+    pub fn extract_class_artifacts(
+        &mut self,
+        base_path: impl AsRef<Path>,
+    ) -> Result<(), GenesisJsonError> {
+        let base_path = base_path.as_ref();
+        let dir = base_path.join(CLASS_ARTIFACTS_DIR);
+
+        for (i, entry) in self.classes.iter_mut().enumerate() {
+            if let PathOrFullArtifact::Artifact(artifact) = &entry.class {
+                fs::create_dir_all(&dir)
+                    .map_err(|source| GenesisJsonError::FileWrite { source, path: dir.clone() })?;
+
+                let file_name = match entry.class_hash {
+                    Some(hash) => format!("{hash:#x}.json"),
+                    None => format!("class-{i}.json"),
+                };
+
+                let rel_path = Path::new(CLASS_ARTIFACTS_DIR).join(file_name);
+                let path = base_path.join(&rel_path);
+
+                fs::write(&path, serde_json::to_vec(artifact)?)
+                    .map_err(|source| GenesisJsonError::FileWrite { source, path })?;
+
+                entry.class = PathOrFullArtifact::Path(rel_path);
+            }
+        }
+
+        Ok(())
+    }
 }
 
 impl TryFrom<GenesisJson> for Genesis {
@@ -278,78 +417,23 @@ impl TryFrom<GenesisJson> for Genesis {
                     }
                 };
 
-                let sierra = serde_json::from_value::<SierraClass>(artifact.clone());
-
-                let (class_hash, compiled_class_hash, sierra, casm) = match sierra {
-                    Ok(sierra) => {
-                        let casm: ContractClass = serde_json::from_value(artifact)?;
-                        let casm = CasmContractClass::from_contract_class(casm, true)?;
-
-                        // check if the class hash is provided, otherwise compute it from the
-                        // artifacts
-                        let class_hash = class_hash.unwrap_or(sierra.class_hash()?);
-                        let compiled_hash = casm.compiled_class_hash().to_be_bytes();
-
-                        (
-                            class_hash,
-                            FieldElement::from_bytes_be(&compiled_hash)?,
-                            Some(Arc::new(sierra.flatten()?)),
-                            Arc::new(CompiledContractClass::V1(CompiledContractClassV1::try_from(
-                                casm,
-                            )?)),
-                        )
-                    }
-
-                    // if the artifact is not a sierra contract, we check if it's a legacy contract
-                    Err(_) => {
-                        let casm: CompiledContractClassV0 =
-                            serde_json::from_value(artifact.clone())?;
-
-                        let class_hash = if let Some(class_hash) = class_hash {
-                            class_hash
-                        } else {
-                            let casm: LegacyContractClass =
-                                serde_json::from_value(artifact.clone())?;
-                            casm.class_hash()?
-                        };
-
-                        (class_hash, class_hash, None, Arc::new(CompiledContractClass::V0(casm)))
-                    }
-                };
-
-                Ok((class_hash, GenesisClass { compiled_class_hash, sierra, casm }))
+                class_from_artifact(artifact, class_hash)
             })
             .collect::<Result<_, GenesisJsonError>>()?;
 
-        let mut fee_token = FeeTokenConfig {
-            name: value.fee_token.name,
-            symbol: value.fee_token.symbol,
-            total_supply: U256::zero(),
-            decimals: value.fee_token.decimals,
-            address: value.fee_token.address.unwrap_or(DEFAULT_FEE_TOKEN_ADDRESS),
-            class_hash: value.fee_token.class.unwrap_or(DEFAULT_LEGACY_ERC20_CONTRACT_CLASS_HASH),
-            storage: value.fee_token.storage,
-        };
-
-        match value.fee_token.class {
-            Some(hash) => {
-                if !classes.contains_key(&hash) {
-                    return Err(GenesisJsonError::MissingClass(hash));
-                }
-            }
+        let mut fee_token =
+            fee_token_from_json(value.fee_token, DEFAULT_FEE_TOKEN_ADDRESS, &mut classes)?;
 
-            // if no class hash is provided, use the default fee token class
-            None => {
-                let _ = classes.insert(
-                    DEFAULT_LEGACY_ERC20_CONTRACT_CLASS_HASH,
-                    GenesisClass {
-                        sierra: None,
-                        casm: Arc::new(DEFAULT_LEGACY_ERC20_CONTRACT_CASM.clone()),
-                        compiled_class_hash: DEFAULT_LEGACY_ERC20_CONTRACT_COMPILED_CLASS_HASH,
-                    },
-                );
-            }
-        };
+        let strk_fee_token = value.strk_fee_token.unwrap_or_else(|| FeeTokenConfigJson {
+            name: "Starknet Token".into(),
+            symbol: "STRK".into(),
+            address: None,
+            decimals: 18,
+            class: None,
+            storage: None,
+        });
+        let mut strk_fee_token =
+            fee_token_from_json(strk_fee_token, DEFAULT_STRK_FEE_TOKEN_ADDRESS, &mut classes)?;
 
         let universal_deployer = if let Some(config) = value.universal_deployer {
             match config.class {
@@ -377,6 +461,7 @@ impl TryFrom<GenesisJson> for Genesis {
                             sierra: None,
                             casm: Arc::new(DEFAULT_LEGACY_UDC_CASM.clone()),
                             compiled_class_hash: DEFAULT_LEGACY_UDC_COMPILED_CLASS_HASH,
+                            legacy_abi: Some(DEFAULT_LEGACY_UDC_ABI.clone().into()),
                         },
                     );
 
@@ -387,6 +472,93 @@ impl TryFrom<GenesisJson> for Genesis {
             None
         };
 
+        let mut tokens: BTreeMap<ContractAddress, GenesisToken> = BTreeMap::new();
+
+        for (address, token) in value.tokens {
+            let token = match token {
+                GenesisTokenJson::Erc20(token) => {
+                    let class_hash = match token.class {
+                        Some(hash) => {
+                            if !classes.contains_key(&hash) {
+                                return Err(GenesisJsonError::MissingClass(hash));
+                            }
+                            hash
+                        }
+
+                        // if no class hash is provided, use the default fee token class
+                        None => {
+                            classes.entry(DEFAULT_LEGACY_ERC20_CONTRACT_CLASS_HASH).or_insert_with(
+                                || GenesisClass {
+                                    sierra: None,
+                                    casm: Arc::new(DEFAULT_LEGACY_ERC20_CONTRACT_CASM.clone()),
+                                    compiled_class_hash:
+                                        DEFAULT_LEGACY_ERC20_CONTRACT_COMPILED_CLASS_HASH,
+                                    legacy_abi: Some(
+                                        DEFAULT_LEGACY_ERC20_CONTRACT_ABI.clone().into(),
+                                    ),
+                                },
+                            );
+                            DEFAULT_LEGACY_ERC20_CONTRACT_CLASS_HASH
+                        }
+                    };
+
+                    let token = Erc20Token {
+                        name: token.name,
+                        symbol: token.symbol,
+                        decimals: token.decimals,
+                        class_hash,
+                        balances: token.balances.into_iter().collect(),
+                        storage: token.storage,
+                    };
+
+                    if token.total_supply().is_none() {
+                        return Err(GenesisJsonError::TokenSupplyOverflow(address));
+                    }
+
+                    GenesisToken::Erc20(token)
+                }
+
+                GenesisTokenJson::Erc721(token) => {
+                    if !classes.contains_key(&token.class) {
+                        return Err(GenesisJsonError::MissingClass(token.class));
+                    }
+
+                    // check that every token is owned by a single account
+                    let mut token_ids = HashSet::new();
+                    for token_id in token.owners.values().flatten() {
+                        if !token_ids.insert(*token_id) {
+                            let token_id = *token_id;
+                            return Err(GenesisJsonError::DuplicateTokenId { token_id, address });
+                        }
+                    }
+
+                    GenesisToken::Erc721(Erc721Token {
+                        name: token.name,
+                        symbol: token.symbol,
+                        class_hash: token.class,
+                        owners: token
+                            .owners
+                            .into_iter()
+                            .map(|(owner, ids)| (owner, BTreeSet::from_iter(ids)))
+                            .collect(),
+                        storage: token.storage,
+                    })
+                }
+            };
+
+            let (name, symbol) = match &token {
+                GenesisToken::Erc20(token) => (&token.name, &token.symbol),
+                GenesisToken::Erc721(token) => (&token.name, &token.symbol),
+            };
+
+            // the metadata are stored as short strings in the token storage
+            cairo_short_string_to_felt(name)
+                .and_then(|_| cairo_short_string_to_felt(symbol))
+                .map_err(|source| GenesisJsonError::InvalidTokenMetadata { source, address })?;
+
+            tokens.insert(address, token);
+        }
+
         let mut allocations: BTreeMap<ContractAddress, GenesisAllocation> = BTreeMap::new();
 
         for (address, account) in value.accounts {
@@ -413,6 +585,7 @@ impl TryFrom<GenesisJson> for Genesis {
                                 DEFAULT_OZ_ACCOUNT_CONTRACT.clone().flatten().unwrap(),
                             )),
                             compiled_class_hash: DEFAULT_OZ_ACCOUNT_CONTRACT_COMPILED_CLASS_HASH,
+                            legacy_abi: None,
                         });
                     }
 
@@ -420,9 +593,10 @@ impl TryFrom<GenesisJson> for Genesis {
                 }
             };
 
-            // increase the total supply of the fee token if balance is given
+            // increase the total supply of the fee tokens if balance is given
             if let Some(balance) = account.balance {
                 fee_token.total_supply += balance;
+                strk_fee_token.total_supply += balance;
             }
 
             match account.private_key {
@@ -462,9 +636,10 @@ impl TryFrom<GenesisJson> for Genesis {
                 }
             }
 
-            // increase the total supply of the fee token if balance is given
+            // increase the total supply of the fee tokens if balance is given
             if let Some(balance) = contract.balance {
                 fee_token.total_supply += balance;
+                strk_fee_token.total_supply += balance;
             }
 
             allocations.insert(
@@ -478,10 +653,17 @@ impl TryFrom<GenesisJson> for Genesis {
             );
         }
 
+        // the storage of a token would be overwritten by the one of the allocation
+        if let Some(address) = tokens.keys().find(|address| allocations.contains_key(address)) {
+            return Err(GenesisJsonError::TokenAllocationConflict(*address));
+        }
+
         Ok(Genesis {
             classes,
             fee_token,
+            strk_fee_token,
             allocations,
+            tokens,
             universal_deployer,
             number: value.number,
             sequencer_address: value.sequencer_address,
@@ -493,6 +675,45 @@ impl TryFrom<GenesisJson> for Genesis {
     }
 }
 
+/// Converts the configuration of a fee token, deployed at `default_address` if no address is
+/// provided. The default fee token class is declared if no class hash is provided.
+fn fee_token_from_json(
+    token: FeeTokenConfigJson,
+    default_address: ContractAddress,
+    classes: &mut HashMap<ClassHash, GenesisClass>,
+) -> Result<FeeTokenConfig, GenesisJsonError> {
+    match token.class {
+        Some(hash) => {
+            if !classes.contains_key(&hash) {
+                return Err(GenesisJsonError::MissingClass(hash));
+            }
+        }
+
+        // if no class hash is provided, use the default fee token class
+        None => {
+            let _ = classes.insert(
+                DEFAULT_LEGACY_ERC20_CONTRACT_CLASS_HASH,
+                GenesisClass {
+                    sierra: None,
+                    casm: Arc::new(DEFAULT_LEGACY_ERC20_CONTRACT_CASM.clone()),
+                    compiled_class_hash: DEFAULT_LEGACY_ERC20_CONTRACT_COMPILED_CLASS_HASH,
+                    legacy_abi: Some(DEFAULT_LEGACY_ERC20_CONTRACT_ABI.clone().into()),
+                },
+            );
+        }
+    };
+
+    Ok(FeeTokenConfig {
+        name: token.name,
+        symbol: token.symbol,
+        total_supply: U256::zero(),
+        decimals: token.decimals,
+        address: token.address.unwrap_or(default_address),
+        class_hash: token.class.unwrap_or(DEFAULT_LEGACY_ERC20_CONTRACT_CLASS_HASH),
+        storage: token.storage,
+    })
+}
+
 impl FromStr for GenesisJson {
     type Err = GenesisJsonError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -534,6 +755,54 @@ pub fn from_base64(data: &[u8]) -> Result<GenesisJson, GenesisJsonError> {
     Ok(serde_json::from_slice::<GenesisJson>(&decoded)?)
 }
 
+/// Parses a class artifact into a [GenesisClass], along with its class hash. The artifact can
+/// either be a Sierra class, which is then compiled, or a legacy class. The class hash is computed
+/// from the artifact if it's not provided.
+pub fn class_from_artifact(
+    artifact: Value,
+    class_hash: Option<ClassHash>,
+) -> Result<(ClassHash, GenesisClass), GenesisJsonError> {
+    let sierra = serde_json::from_value::<SierraClass>(artifact.clone());
+
+    let (class_hash, compiled_class_hash, sierra, casm, legacy_abi) = match sierra {
+        Ok(sierra) => {
+            let casm: ContractClass = serde_json::from_value(artifact)?;
+            let casm = CasmContractClass::from_contract_class(casm, true)?;
+
+            // check if the class hash is provided, otherwise compute it from the
+            // artifacts
+            let class_hash = class_hash.unwrap_or(sierra.class_hash()?);
+            let compiled_hash = casm.compiled_class_hash().to_be_bytes();
+
+            (
+                class_hash,
+                FieldElement::from_bytes_be(&compiled_hash)?,
+                Some(Arc::new(sierra.flatten()?)),
+                Arc::new(CompiledContractClass::V1(CompiledContractClassV1::try_from(casm)?)),
+                None,
+            )
+        }
+
+        // if the artifact is not a sierra contract, we check if it's a legacy contract
+        Err(_) => {
+            let casm: CompiledContractClassV0 = serde_json::from_value(artifact.clone())?;
+            let abi = legacy_class_abi_of_artifact(&artifact)?;
+
+            let class_hash = if let Some(class_hash) = class_hash {
+                class_hash
+            } else {
+                let casm: LegacyContractClass = serde_json::from_value(artifact.clone())?;
+                casm.class_hash()?
+            };
+
+            let casm = Arc::new(CompiledContractClass::V0(casm));
+            (class_hash, class_hash, None, casm, Some(Arc::new(abi)))
+        }
+    };
+
+    Ok((class_hash, GenesisClass { compiled_class_hash, sierra, casm, legacy_abi }))
+}
+
 fn class_artifact_at_path(
     base_path: PathBuf,
     relative_path: &PathBuf,
@@ -561,21 +830,26 @@ mod tests {
     use ethers::types::U256;
     use starknet::macros::felt;
 
-    use super::{from_base64, GenesisClassJson, GenesisJson};
+    use super::{
+        from_base64, GenesisClassJson, GenesisJson, GenesisJsonError, GenesisTokenJson,
+        PathOrFullArtifact,
+    };
     use crate::block::GasPrices;
     use crate::genesis::allocation::{
         DevGenesisAccount, GenesisAccount, GenesisAccountAlloc, GenesisContractAlloc,
     };
     use crate::genesis::constant::{
-        DEFAULT_FEE_TOKEN_ADDRESS, DEFAULT_LEGACY_ERC20_CONTRACT_CASM,
-        DEFAULT_LEGACY_ERC20_CONTRACT_CLASS_HASH,
-        DEFAULT_LEGACY_ERC20_CONTRACT_COMPILED_CLASS_HASH, DEFAULT_LEGACY_UDC_CASM,
-        DEFAULT_LEGACY_UDC_CLASS_HASH, DEFAULT_LEGACY_UDC_COMPILED_CLASS_HASH,
-        DEFAULT_OZ_ACCOUNT_CONTRACT, DEFAULT_OZ_ACCOUNT_CONTRACT_CASM,
-        DEFAULT_OZ_ACCOUNT_CONTRACT_CLASS_HASH, DEFAULT_OZ_ACCOUNT_CONTRACT_COMPILED_CLASS_HASH,
+        DEFAULT_FEE_TOKEN_ADDRESS, DEFAULT_LEGACY_ERC20_CONTRACT_ABI,
+        DEFAULT_LEGACY_ERC20_CONTRACT_CASM, DEFAULT_LEGACY_ERC20_CONTRACT_CLASS_HASH,
+        DEFAULT_LEGACY_ERC20_CONTRACT_COMPILED_CLASS_HASH, DEFAULT_LEGACY_UDC_ABI,
+        DEFAULT_LEGACY_UDC_CASM, DEFAULT_LEGACY_UDC_CLASS_HASH,
+        DEFAULT_LEGACY_UDC_COMPILED_CLASS_HASH, DEFAULT_OZ_ACCOUNT_CONTRACT,
+        DEFAULT_OZ_ACCOUNT_CONTRACT_CASM, DEFAULT_OZ_ACCOUNT_CONTRACT_CLASS_HASH,
+        DEFAULT_OZ_ACCOUNT_CONTRACT_COMPILED_CLASS_HASH, DEFAULT_STRK_FEE_TOKEN_ADDRESS,
         DEFAULT_UDC_ADDRESS,
     };
     use crate::genesis::json::to_base64;
+    use crate::genesis::token::GenesisToken;
     use crate::genesis::{
         ContractAddress, FeeTokenConfig, Genesis, GenesisAllocation, GenesisClass,
         UniversalDeployerConfig,
@@ -603,6 +877,14 @@ mod tests {
             Some(HashMap::from([(felt!("0x111"), felt!("0x1")), (felt!("0x222"), felt!("0x2"))]))
         );
 
+        let strk_fee_token = json.strk_fee_token.clone().unwrap();
+        assert_eq!(strk_fee_token.address, Some(ContractAddress::from(felt!("0x56"))));
+        assert_eq!(strk_fee_token.name, String::from("STARK"));
+        assert_eq!(strk_fee_token.symbol, String::from("STRK"));
+        assert_eq!(strk_fee_token.class, Some(felt!("0x8")));
+        assert_eq!(strk_fee_token.decimals, 18);
+        assert_eq!(strk_fee_token.storage, None);
+
         assert_eq!(
             json.universal_deployer.clone().unwrap().address,
             Some(ContractAddress::from(felt!(
@@ -767,6 +1049,7 @@ mod tests {
                     compiled_class_hash: felt!(
                         "0x07b3e05f48f0c69e4a65ce5e076a66271a527aff2c34ce1083ec6e1526997a69"
                     ),
+                    legacy_abi: Some(DEFAULT_LEGACY_UDC_ABI.clone().into()),
                 },
             ),
             (
@@ -775,6 +1058,7 @@ mod tests {
                     sierra: None,
                     casm: DEFAULT_LEGACY_UDC_CASM.clone().into(),
                     compiled_class_hash: felt!("0x80085"),
+                    legacy_abi: Some(DEFAULT_LEGACY_UDC_ABI.clone().into()),
                 },
             ),
             (
@@ -783,6 +1067,7 @@ mod tests {
                     sierra: None,
                     compiled_class_hash: felt!("0x8"),
                     casm: DEFAULT_LEGACY_ERC20_CONTRACT_CASM.clone().into(),
+                    legacy_abi: Some(DEFAULT_LEGACY_ERC20_CONTRACT_ABI.clone().into()),
                 },
             ),
             (
@@ -791,6 +1076,7 @@ mod tests {
                     compiled_class_hash: DEFAULT_OZ_ACCOUNT_CONTRACT_COMPILED_CLASS_HASH,
                     casm: DEFAULT_OZ_ACCOUNT_CONTRACT_CASM.clone().into(),
                     sierra: Some(DEFAULT_OZ_ACCOUNT_CONTRACT.clone().flatten().unwrap().into()),
+                    legacy_abi: None,
                 },
             ),
             (
@@ -799,6 +1085,7 @@ mod tests {
                     compiled_class_hash: DEFAULT_OZ_ACCOUNT_CONTRACT_COMPILED_CLASS_HASH,
                     casm: DEFAULT_OZ_ACCOUNT_CONTRACT_CASM.clone().into(),
                     sierra: Some(DEFAULT_OZ_ACCOUNT_CONTRACT.clone().flatten().unwrap().into()),
+                    legacy_abi: None,
                 },
             ),
         ]);
@@ -816,6 +1103,16 @@ mod tests {
             ])),
         };
 
+        let expected_strk_fee_token = FeeTokenConfig {
+            address: ContractAddress::from(felt!("0x56")),
+            name: String::from("STARK"),
+            symbol: String::from("STRK"),
+            total_supply: U256::from_str("0xD3C21BCECCEDA1000000").unwrap() * 5,
+            decimals: 18,
+            class_hash: felt!("0x8"),
+            storage: None,
+        };
+
         let acc_1 = ContractAddress::from(felt!(
             "0x66efb28ac62686966ae85095ff3a772e014e7fbf56d4c5f6fac5606d4dde23a"
         ));
@@ -921,7 +1218,9 @@ mod tests {
             classes: expected_classes,
             number: 0,
             fee_token: expected_fee_token,
+            strk_fee_token: expected_strk_fee_token,
             allocations: expected_allocations,
+            tokens: BTreeMap::new(),
             timestamp: 5123512314u64,
             sequencer_address: ContractAddress::from(felt!("0x100")),
             state_root: felt!("0x99"),
@@ -949,6 +1248,18 @@ mod tests {
         assert_eq!(actual_genesis.fee_token.total_supply, expected_genesis.fee_token.total_supply);
         assert_eq!(actual_genesis.fee_token.class_hash, expected_genesis.fee_token.class_hash);
 
+        assert_eq!(actual_genesis.strk_fee_token.address, expected_genesis.strk_fee_token.address);
+        assert_eq!(actual_genesis.strk_fee_token.name, expected_genesis.strk_fee_token.name);
+        assert_eq!(actual_genesis.strk_fee_token.symbol, expected_genesis.strk_fee_token.symbol);
+        assert_eq!(
+            actual_genesis.strk_fee_token.total_supply,
+            expected_genesis.strk_fee_token.total_supply
+        );
+        assert_eq!(
+            actual_genesis.strk_fee_token.class_hash,
+            expected_genesis.strk_fee_token.class_hash
+        );
+
         assert_eq!(actual_genesis.universal_deployer, expected_genesis.universal_deployer);
 
         assert_eq!(actual_genesis.allocations.len(), expected_genesis.allocations.len());
@@ -1008,6 +1319,7 @@ mod tests {
                     sierra: None,
                     casm: DEFAULT_LEGACY_UDC_CASM.clone().into(),
                     compiled_class_hash: DEFAULT_LEGACY_UDC_COMPILED_CLASS_HASH,
+                    legacy_abi: Some(DEFAULT_LEGACY_UDC_ABI.clone().into()),
                 },
             ),
             (
@@ -1016,6 +1328,7 @@ mod tests {
                     sierra: None,
                     casm: DEFAULT_LEGACY_ERC20_CONTRACT_CASM.clone().into(),
                     compiled_class_hash: DEFAULT_LEGACY_ERC20_CONTRACT_COMPILED_CLASS_HASH,
+                    legacy_abi: Some(DEFAULT_LEGACY_ERC20_CONTRACT_ABI.clone().into()),
                 },
             ),
             (
@@ -1024,6 +1337,7 @@ mod tests {
                     compiled_class_hash: DEFAULT_OZ_ACCOUNT_CONTRACT_COMPILED_CLASS_HASH,
                     casm: DEFAULT_OZ_ACCOUNT_CONTRACT_CASM.clone().into(),
                     sierra: Some(DEFAULT_OZ_ACCOUNT_CONTRACT.clone().flatten().unwrap().into()),
+                    legacy_abi: None,
                 },
             ),
         ]);
@@ -1051,10 +1365,22 @@ mod tests {
             })),
         )]);
 
+        let strk_fee_token = FeeTokenConfig {
+            address: DEFAULT_STRK_FEE_TOKEN_ADDRESS,
+            name: String::from("Starknet Token"),
+            symbol: String::from("STRK"),
+            total_supply: U256::from_str("0xD3C21BCECCEDA1000000").unwrap(),
+            decimals: 18,
+            class_hash: DEFAULT_LEGACY_ERC20_CONTRACT_CLASS_HASH,
+            storage: None,
+        };
+
         let expected_genesis = Genesis {
             fee_token,
+            strk_fee_token,
             classes,
             allocations,
+            tokens: BTreeMap::new(),
             number: 0,
             timestamp: 5123512314u64,
             state_root: felt!("0x99"),
@@ -1112,4 +1438,120 @@ mod tests {
 
         assert_eq!(genesis, decoded);
     }
+
+    #[test]
+    fn save_and_load_genesis_file() {
+        let genesis = GenesisJson::load("./src/genesis/test-genesis.json").unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("genesis.json");
+        genesis.save(&path).unwrap();
+
+        let file = File::open(&path).unwrap();
+        let saved: GenesisJson = serde_json::from_reader(BufReader::new(file)).unwrap();
+        assert!(saved.classes.iter().all(|c| matches!(c.class, PathOrFullArtifact::Path(_))));
+
+        let loaded = GenesisJson::load(&path).unwrap();
+        assert_eq!(loaded, genesis);
+    }
+
+    #[test]
+    fn genesis_with_tokens_from_json() {
+        let mut json = GenesisJson::load("./src/genesis/test-genesis.json").unwrap();
+        json.tokens = serde_json::from_value(serde_json::json!({
+            "0x1000": {
+                "type": "erc20",
+                "name": "Gold",
+                "symbol": "GLD",
+                "decimals": 6,
+                "balances": { "0x1": "0x64", "0x2": "0x36" }
+            },
+            "0x2000": {
+                "type": "erc721",
+                "name": "Swords",
+                "symbol": "SWD",
+                "class": "0x8",
+                "owners": { "0x1": ["0x1", "0x2"], "0x2": ["0x3"] }
+            }
+        }))
+        .unwrap();
+
+        let genesis = Genesis::try_from(json.clone()).unwrap();
+        assert_eq!(genesis.tokens.len(), 2);
+
+        let Some(GenesisToken::Erc20(erc20)) =
+            genesis.tokens.get(&ContractAddress::from(felt!("0x1000")))
+        else {
+            panic!("expected an ERC20 token")
+        };
+        assert_eq!(erc20.class_hash, DEFAULT_LEGACY_ERC20_CONTRACT_CLASS_HASH);
+        assert_eq!(erc20.total_supply(), Some(U256::from(0x9a)));
+
+        let Some(GenesisToken::Erc721(erc721)) =
+            genesis.tokens.get(&ContractAddress::from(felt!("0x2000")))
+        else {
+            panic!("expected an ERC721 token")
+        };
+        assert_eq!(erc721.class_hash, felt!("0x8"));
+        assert_eq!(erc721.total_supply(), 3);
+
+        let states = genesis.state_updates();
+        assert_eq!(
+            states.state_updates.contract_updates.get(&ContractAddress::from(felt!("0x2000"))),
+            Some(&felt!("0x8"))
+        );
+
+        // a token owned by more than one account
+        let GenesisTokenJson::Erc721(token) =
+            json.tokens.get_mut(&ContractAddress::from(felt!("0x2000"))).unwrap()
+        else {
+            unreachable!()
+        };
+        token.owners.insert(ContractAddress::from(felt!("0x3")), vec![U256::from(1)]);
+
+        assert!(matches!(
+            Genesis::try_from(json).unwrap_err(),
+            GenesisJsonError::DuplicateTokenId { .. }
+        ));
+    }
+
+    #[test]
+    fn genesis_with_invalid_tokens_from_json() {
+        let mut json = GenesisJson::load("./src/genesis/test-genesis.json").unwrap();
+        let account = *json.accounts.keys().next().unwrap();
+
+        // a token whose total supply overflows
+        json.tokens = serde_json::from_value(serde_json::json!({
+            "0x1000": {
+                "type": "erc20",
+                "name": "Gold",
+                "symbol": "GLD",
+                "decimals": 6,
+                "balances": { "0x1": format!("{:#x}", U256::MAX), "0x2": "0x1" }
+            }
+        }))
+        .unwrap();
+
+        assert!(matches!(
+            Genesis::try_from(json.clone()).unwrap_err(),
+            GenesisJsonError::TokenSupplyOverflow(address) if address == felt!("0x1000").into()
+        ));
+
+        // a token deployed at the address of an account
+        json.tokens = serde_json::from_value(serde_json::json!({
+            account.to_string(): {
+                "type": "erc721",
+                "name": "Swords",
+                "symbol": "SWD",
+                "class": "0x8",
+                "owners": { "0x1": ["0x1"] }
+            }
+        }))
+        .unwrap();
+
+        assert!(matches!(
+            Genesis::try_from(json).unwrap_err(),
+            GenesisJsonError::TokenAllocationConflict(address) if address == account
+        ));
+    }
 }
@@ -1,6 +1,7 @@
 pub mod allocation;
 pub mod constant;
 pub mod json;
+pub mod token;
 
 use std::collections::{BTreeMap, HashMap};
 use std::fmt::Debug;
@@ -14,19 +15,21 @@ use starknet::core::utils::cairo_short_string_to_felt;
 use self::allocation::{GenesisAccountAlloc, GenesisAllocation, GenesisContractAlloc};
 use self::constant::{
     get_fee_token_balance_base_storage_address, DEFAULT_FEE_TOKEN_ADDRESS,
-    DEFAULT_LEGACY_ERC20_CONTRACT_CASM, DEFAULT_LEGACY_ERC20_CONTRACT_CLASS_HASH,
-    DEFAULT_LEGACY_ERC20_CONTRACT_COMPILED_CLASS_HASH, DEFAULT_LEGACY_UDC_CASM,
-    DEFAULT_LEGACY_UDC_CLASS_HASH, DEFAULT_LEGACY_UDC_COMPILED_CLASS_HASH,
-    DEFAULT_OZ_ACCOUNT_CONTRACT, DEFAULT_OZ_ACCOUNT_CONTRACT_CASM,
-    DEFAULT_OZ_ACCOUNT_CONTRACT_CLASS_HASH, DEFAULT_OZ_ACCOUNT_CONTRACT_COMPILED_CLASS_HASH,
+    DEFAULT_LEGACY_ERC20_CONTRACT_ABI, DEFAULT_LEGACY_ERC20_CONTRACT_CASM,
+    DEFAULT_LEGACY_ERC20_CONTRACT_CLASS_HASH, DEFAULT_LEGACY_ERC20_CONTRACT_COMPILED_CLASS_HASH,
+    DEFAULT_LEGACY_UDC_ABI, DEFAULT_LEGACY_UDC_CASM, DEFAULT_LEGACY_UDC_CLASS_HASH,
+    DEFAULT_LEGACY_UDC_COMPILED_CLASS_HASH, DEFAULT_OZ_ACCOUNT_CONTRACT,
+    DEFAULT_OZ_ACCOUNT_CONTRACT_CASM, DEFAULT_OZ_ACCOUNT_CONTRACT_CLASS_HASH,
+    DEFAULT_OZ_ACCOUNT_CONTRACT_COMPILED_CLASS_HASH, DEFAULT_STRK_FEE_TOKEN_ADDRESS,
     DEFAULT_UDC_ADDRESS, ERC20_DECIMAL_STORAGE_SLOT, ERC20_NAME_STORAGE_SLOT,
     ERC20_SYMBOL_STORAGE_SLOT, ERC20_TOTAL_SUPPLY_STORAGE_SLOT,
     OZ_ACCOUNT_CONTRACT_PUBKEY_STORAGE_SLOT,
 };
+use self::token::GenesisToken;
 use crate::block::{Block, BlockHash, BlockNumber, GasPrices, Header};
 use crate::contract::{
     ClassHash, CompiledClassHash, CompiledContractClass, ContractAddress, FlattenedSierraClass,
-    StorageKey, StorageValue,
+    LegacyClassAbi, StorageKey, StorageValue,
 };
 use crate::state::StateUpdatesWithDeclaredClasses;
 use crate::utils::split_u256;
@@ -65,6 +68,9 @@ pub struct GenesisClass {
     /// The sierra class definition.
     #[serde(skip_serializing)]
     pub sierra: Option<Arc<FlattenedSierraClass>>,
+    /// The ABI of the legacy class definition, which isn't part of the casm class.
+    #[serde(skip_serializing)]
+    pub legacy_abi: Option<Arc<LegacyClassAbi>>,
 }
 
 /// The configuration of the universal deployer contract.
@@ -100,10 +106,15 @@ pub struct Genesis {
     pub classes: HashMap<ClassHash, GenesisClass>,
     /// The fee token configuration.
     pub fee_token: FeeTokenConfig,
+    /// The configuration of the STRK fee token, used to pay the fees of V3 transactions. The
+    /// allocations are given the same balance of both fee tokens.
+    pub strk_fee_token: FeeTokenConfig,
     /// The universal deployer (UDC) configuration.
     pub universal_deployer: Option<UniversalDeployerConfig>,
     /// The genesis contract allocations.
     pub allocations: BTreeMap<ContractAddress, GenesisAllocation>,
+    /// The token contracts deployed in the genesis block, other than the fee token.
+    pub tokens: BTreeMap<ContractAddress, GenesisToken>,
 }
 
 impl Genesis {
@@ -167,6 +178,10 @@ impl Genesis {
             if let Some(sierra) = &class.sierra {
                 states.declared_sierra_classes.insert(class_hash, sierra.as_ref().clone());
             }
+
+            if let Some(abi) = &class.legacy_abi {
+                states.declared_legacy_class_abis.insert(class_hash, abi.as_ref().clone());
+            }
         }
 
         for (address, alloc) in &self.allocations {
@@ -188,21 +203,44 @@ impl Genesis {
             states.state_updates.storage_updates.insert(address, storage);
         }
 
-        // TODO: put this in a separate function
+        for (address, token) in &self.tokens {
+            states.state_updates.contract_updates.insert(*address, token.class_hash());
+            states.state_updates.storage_updates.insert(*address, token.storage());
+        }
 
-        // insert fee token related data
-        let mut fee_token_storage = self.fee_token.storage.clone().unwrap_or_default();
+        // insert fee tokens related data
+        for token in [&self.fee_token, &self.strk_fee_token] {
+            let storage = self.fee_token_storage(token);
+            states.state_updates.contract_updates.insert(token.address, token.class_hash);
+            states.state_updates.storage_updates.insert(token.address, storage);
+        }
 
-        let name: FieldElement = cairo_short_string_to_felt(&self.fee_token.name).unwrap();
-        let symbol: FieldElement = cairo_short_string_to_felt(&self.fee_token.symbol).unwrap();
-        let decimals: FieldElement = self.fee_token.decimals.into();
-        let (total_supply_low, total_supply_high) = split_u256(self.fee_token.total_supply);
+        // insert universal deployer related data
+        if let Some(udc) = &self.universal_deployer {
+            let storage = udc.storage.clone().unwrap_or_default();
 
-        fee_token_storage.insert(ERC20_NAME_STORAGE_SLOT, name);
-        fee_token_storage.insert(ERC20_SYMBOL_STORAGE_SLOT, symbol);
-        fee_token_storage.insert(ERC20_DECIMAL_STORAGE_SLOT, decimals);
-        fee_token_storage.insert(ERC20_TOTAL_SUPPLY_STORAGE_SLOT, total_supply_low);
-        fee_token_storage.insert(ERC20_TOTAL_SUPPLY_STORAGE_SLOT + 1u8.into(), total_supply_high);
+            states.state_updates.contract_updates.insert(udc.address, udc.class_hash);
+            states.state_updates.storage_updates.insert(udc.address, storage);
+        }
+
+        states
+    }
+
+    /// Returns the storage of a fee token contract: its metadata and the balances of the
+    /// allocations, along with its configured storage which takes precedence over them.
+    fn fee_token_storage(&self, token: &FeeTokenConfig) -> HashMap<StorageKey, StorageValue> {
+        let mut storage = HashMap::new();
+
+        let name: FieldElement = cairo_short_string_to_felt(&token.name).unwrap();
+        let symbol: FieldElement = cairo_short_string_to_felt(&token.symbol).unwrap();
+        let decimals: FieldElement = token.decimals.into();
+        let (total_supply_low, total_supply_high) = split_u256(token.total_supply);
+
+        storage.insert(ERC20_NAME_STORAGE_SLOT, name);
+        storage.insert(ERC20_SYMBOL_STORAGE_SLOT, symbol);
+        storage.insert(ERC20_DECIMAL_STORAGE_SLOT, decimals);
+        storage.insert(ERC20_TOTAL_SUPPLY_STORAGE_SLOT, total_supply_low);
+        storage.insert(ERC20_TOTAL_SUPPLY_STORAGE_SLOT + 1u8.into(), total_supply_high);
 
         for (address, alloc) in &self.allocations {
             if let Some(balance) = alloc.balance() {
@@ -216,26 +254,13 @@ impl Genesis {
                 // the storage address of high u128 of the balance
                 let high_bal_storage_var = bal_base_storage_var + 1u8.into();
 
-                fee_token_storage.insert(low_bal_storage_var, low);
-                fee_token_storage.insert(high_bal_storage_var, high);
+                storage.insert(low_bal_storage_var, low);
+                storage.insert(high_bal_storage_var, high);
             }
         }
 
-        states
-            .state_updates
-            .contract_updates
-            .insert(self.fee_token.address, self.fee_token.class_hash);
-        states.state_updates.storage_updates.insert(self.fee_token.address, fee_token_storage);
-
-        // insert universal deployer related data
-        if let Some(udc) = &self.universal_deployer {
-            let storage = udc.storage.clone().unwrap_or_default();
-
-            states.state_updates.contract_updates.insert(udc.address, udc.class_hash);
-            states.state_updates.storage_updates.insert(udc.address, storage);
-        }
-
-        states
+        storage.extend(token.storage.clone().unwrap_or_default());
+        storage
     }
 }
 
@@ -254,6 +279,16 @@ impl Default for Genesis {
             storage: None,
         };
 
+        let strk_fee_token = FeeTokenConfig {
+            decimals: 18,
+            name: "Starknet Token".into(),
+            symbol: "STRK".into(),
+            total_supply: 0.into(),
+            address: DEFAULT_STRK_FEE_TOKEN_ADDRESS,
+            class_hash: DEFAULT_LEGACY_ERC20_CONTRACT_CLASS_HASH,
+            storage: None,
+        };
+
         let universal_deployer = UniversalDeployerConfig {
             address: DEFAULT_UDC_ADDRESS,
             class_hash: DEFAULT_LEGACY_UDC_CLASS_HASH,
@@ -267,6 +302,7 @@ impl Default for Genesis {
                     sierra: None,
                     casm: DEFAULT_LEGACY_ERC20_CONTRACT_CASM.clone().into(),
                     compiled_class_hash: DEFAULT_LEGACY_ERC20_CONTRACT_COMPILED_CLASS_HASH,
+                    legacy_abi: Some(DEFAULT_LEGACY_ERC20_CONTRACT_ABI.clone().into()),
                 },
             ),
             (
@@ -275,6 +311,7 @@ impl Default for Genesis {
                     sierra: None,
                     casm: DEFAULT_LEGACY_UDC_CASM.clone().into(),
                     compiled_class_hash: DEFAULT_LEGACY_UDC_COMPILED_CLASS_HASH,
+                    legacy_abi: Some(DEFAULT_LEGACY_UDC_ABI.clone().into()),
                 },
             ),
             (
@@ -283,6 +320,7 @@ impl Default for Genesis {
                     sierra: Some(DEFAULT_OZ_ACCOUNT_CONTRACT.clone().flatten().unwrap().into()),
                     casm: DEFAULT_OZ_ACCOUNT_CONTRACT_CASM.clone().into(),
                     compiled_class_hash: DEFAULT_OZ_ACCOUNT_CONTRACT_COMPILED_CLASS_HASH,
+                    legacy_abi: None,
                 },
             ),
         ]);
@@ -296,7 +334,9 @@ impl Default for Genesis {
             sequencer_address: FieldElement::ZERO.into(),
             classes,
             allocations: BTreeMap::new(),
+            tokens: BTreeMap::new(),
             fee_token,
+            strk_fee_token,
             universal_deployer: Some(universal_deployer),
         }
     }
@@ -309,12 +349,13 @@ mod tests {
     use starknet::macros::felt;
     use tests::allocation::GenesisAccount;
     use tests::constant::{
-        DEFAULT_FEE_TOKEN_ADDRESS, DEFAULT_LEGACY_ERC20_CONTRACT_CASM,
-        DEFAULT_LEGACY_ERC20_CONTRACT_CLASS_HASH,
-        DEFAULT_LEGACY_ERC20_CONTRACT_COMPILED_CLASS_HASH, DEFAULT_LEGACY_UDC_CASM,
-        DEFAULT_LEGACY_UDC_CLASS_HASH, DEFAULT_LEGACY_UDC_COMPILED_CLASS_HASH,
-        DEFAULT_OZ_ACCOUNT_CONTRACT, DEFAULT_OZ_ACCOUNT_CONTRACT_CASM,
-        DEFAULT_OZ_ACCOUNT_CONTRACT_CLASS_HASH, DEFAULT_OZ_ACCOUNT_CONTRACT_COMPILED_CLASS_HASH,
+        DEFAULT_FEE_TOKEN_ADDRESS, DEFAULT_LEGACY_ERC20_CONTRACT_ABI,
+        DEFAULT_LEGACY_ERC20_CONTRACT_CASM, DEFAULT_LEGACY_ERC20_CONTRACT_CLASS_HASH,
+        DEFAULT_LEGACY_ERC20_CONTRACT_COMPILED_CLASS_HASH, DEFAULT_LEGACY_UDC_ABI,
+        DEFAULT_LEGACY_UDC_CASM, DEFAULT_LEGACY_UDC_CLASS_HASH,
+        DEFAULT_LEGACY_UDC_COMPILED_CLASS_HASH, DEFAULT_OZ_ACCOUNT_CONTRACT,
+        DEFAULT_OZ_ACCOUNT_CONTRACT_CASM, DEFAULT_OZ_ACCOUNT_CONTRACT_CLASS_HASH,
+        DEFAULT_OZ_ACCOUNT_CONTRACT_COMPILED_CLASS_HASH, DEFAULT_STRK_FEE_TOKEN_ADDRESS,
     };
 
     use super::*;
@@ -330,6 +371,7 @@ mod tests {
                     sierra: None,
                     casm: DEFAULT_LEGACY_UDC_CASM.clone().into(),
                     compiled_class_hash: DEFAULT_LEGACY_UDC_COMPILED_CLASS_HASH,
+                    legacy_abi: Some(DEFAULT_LEGACY_UDC_ABI.clone().into()),
                 },
             ),
             (
@@ -338,6 +380,7 @@ mod tests {
                     sierra: None,
                     casm: DEFAULT_LEGACY_ERC20_CONTRACT_CASM.clone().into(),
                     compiled_class_hash: DEFAULT_LEGACY_ERC20_CONTRACT_COMPILED_CLASS_HASH,
+                    legacy_abi: Some(DEFAULT_LEGACY_ERC20_CONTRACT_ABI.clone().into()),
                 },
             ),
             (
@@ -346,6 +389,7 @@ mod tests {
                     compiled_class_hash: DEFAULT_OZ_ACCOUNT_CONTRACT_COMPILED_CLASS_HASH,
                     casm: DEFAULT_OZ_ACCOUNT_CONTRACT_CASM.clone().into(),
                     sierra: Some(DEFAULT_OZ_ACCOUNT_CONTRACT.clone().flatten().unwrap().into()),
+                    legacy_abi: None,
                 },
             ),
         ]);
@@ -363,6 +407,16 @@ mod tests {
             ])),
         };
 
+        let strk_fee_token = FeeTokenConfig {
+            address: DEFAULT_STRK_FEE_TOKEN_ADDRESS,
+            name: String::from("Starknet Token"),
+            symbol: String::from("STRK"),
+            total_supply: fee_token.total_supply,
+            decimals: 18,
+            class_hash: DEFAULT_LEGACY_ERC20_CONTRACT_CLASS_HASH,
+            storage: None,
+        };
+
         let allocations = [
             (
                 ContractAddress::from(felt!("0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266")),
@@ -412,6 +466,7 @@ mod tests {
         let genesis = Genesis {
             classes,
             fee_token: fee_token.clone(),
+            strk_fee_token: strk_fee_token.clone(),
             allocations: BTreeMap::from(allocations.clone()),
             number: 0,
             timestamp: 5123512314u64,
@@ -420,6 +475,7 @@ mod tests {
             sequencer_address: ContractAddress(felt!("0x100")),
             gas_prices: GasPrices { eth: 1111, strk: 2222 },
             universal_deployer: Some(ud.clone()),
+            tokens: BTreeMap::new(),
         };
 
         // setup expected values
@@ -488,6 +544,10 @@ mod tests {
             actual_state_updates.declared_sierra_classes.len() == 1,
             "should be only 1 sierra class: oz account"
         );
+        assert!(
+            actual_state_updates.declared_legacy_class_abis.len() == 2,
+            "should be 2 legacy class abis: udc, erc20"
+        );
 
         assert_eq!(
             actual_state_updates.state_updates.declared_classes.get(&fee_token.class_hash),
@@ -565,8 +625,8 @@ mod tests {
 
         assert_eq!(
             actual_state_updates.state_updates.contract_updates.len(),
-            5,
-            "5 contracts should be created: fee token, universal deployer, and 3 allocations"
+            6,
+            "6 contracts should be created: fee tokens, universal deployer, and 3 allocations"
         );
 
         let alloc_1_addr = allocations[0].0;
@@ -678,6 +738,30 @@ mod tests {
             "total supply should match the total balances of all allocations"
         );
 
+        // check that the allocations are given the same balance of STRK
+
+        let strk_fee_token_storage = actual_state_updates
+            .state_updates
+            .storage_updates
+            .get(&strk_fee_token.address)
+            .unwrap();
+
+        assert_eq!(
+            actual_state_updates.state_updates.contract_updates.get(&strk_fee_token.address),
+            Some(&strk_fee_token.class_hash),
+            "The STRK fee token contract should be created"
+        );
+
+        for (address, alloc) in &allocations {
+            if let Some(balance) = alloc.balance() {
+                let (low, high) = split_u256(balance);
+                let base = get_fee_token_balance_base_storage_address(*address);
+
+                assert_eq!(strk_fee_token_storage.get(&base), Some(&low));
+                assert_eq!(strk_fee_token_storage.get(&(base + 1u8.into())), Some(&high));
+            }
+        }
+
         let udc_storage =
             actual_state_updates.state_updates.storage_updates.get(&ud.address).unwrap();
 
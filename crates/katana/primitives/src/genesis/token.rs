@@ -0,0 +1,220 @@
+//! Token contracts deployed in the genesis block, other than the fee token.
+//!
+//! The storage of the tokens is computed following the storage layout of the OpenZeppelin ERC20
+//! and ERC721 implementations, so the class of a token must be compatible with it.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use ethers::types::U256;
+use serde::{Deserialize, Serialize};
+use starknet::core::serde::unsigned_field_element::UfeHex;
+use starknet::core::utils::{cairo_short_string_to_felt, get_storage_var_address};
+
+use super::constant::{
+    get_fee_token_balance_base_storage_address, ERC20_DECIMAL_STORAGE_SLOT,
+    ERC20_NAME_STORAGE_SLOT, ERC20_SYMBOL_STORAGE_SLOT, ERC20_TOTAL_SUPPLY_STORAGE_SLOT,
+};
+use crate::contract::{ClassHash, ContractAddress, StorageKey, StorageValue};
+use crate::utils::split_u256;
+use crate::FieldElement;
+
+/// A token contract deployed in the genesis block.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum GenesisToken {
+    /// An ERC20 token.
+    Erc20(Erc20Token),
+    /// An ERC721 token collection.
+    Erc721(Erc721Token),
+}
+
+/// An ERC20 token and the amounts of tokens allocated to each account.
+#[serde_with::serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Erc20Token {
+    /// The name of the token.
+    pub name: String,
+    /// The symbol of the token.
+    pub symbol: String,
+    /// The decimals of the token.
+    pub decimals: u8,
+    /// The class hash of the token contract.
+    #[serde_as(as = "UfeHex")]
+    pub class_hash: ClassHash,
+    /// The amount of tokens allocated to each account.
+    pub balances: BTreeMap<ContractAddress, U256>,
+    /// To initialize the token contract storage
+    pub storage: Option<HashMap<StorageKey, StorageValue>>,
+}
+
+/// An ERC721 token collection and the tokens owned by each account.
+#[serde_with::serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Erc721Token {
+    /// The name of the collection.
+    pub name: String,
+    /// The symbol of the collection.
+    pub symbol: String,
+    /// The class hash of the token contract.
+    #[serde_as(as = "UfeHex")]
+    pub class_hash: ClassHash,
+    /// The ids of the tokens owned by each account.
+    pub owners: BTreeMap<ContractAddress, BTreeSet<U256>>,
+    /// To initialize the token contract storage
+    pub storage: Option<HashMap<StorageKey, StorageValue>>,
+}
+
+impl GenesisToken {
+    /// Get the class hash of the token contract.
+    pub fn class_hash(&self) -> ClassHash {
+        match self {
+            Self::Erc20(token) => token.class_hash,
+            Self::Erc721(token) => token.class_hash,
+        }
+    }
+
+    /// Get the storage of the token contract. The metadata and the balances of the token are
+    /// written on top of the storage values it's initialized with.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the name or the symbol of the token isn't a valid Cairo short string, or if the
+    /// total supply of an ERC20 token overflows.
+    pub fn storage(&self) -> HashMap<StorageKey, StorageValue> {
+        match self {
+            Self::Erc20(token) => token.storage(),
+            Self::Erc721(token) => token.storage(),
+        }
+    }
+}
+
+impl Erc20Token {
+    /// Get the total supply of the token, ie the sum of the allocated amounts. Returns `None` if
+    /// the sum overflows.
+    pub fn total_supply(&self) -> Option<U256> {
+        self.balances.values().try_fold(U256::zero(), |total, balance| total.checked_add(*balance))
+    }
+
+    fn storage(&self) -> HashMap<StorageKey, StorageValue> {
+        let mut storage = self.storage.clone().unwrap_or_default();
+
+        let name = cairo_short_string_to_felt(&self.name).unwrap();
+        let symbol = cairo_short_string_to_felt(&self.symbol).unwrap();
+        let total_supply = self.total_supply().expect("total supply overflow");
+        let (total_supply_low, total_supply_high) = split_u256(total_supply);
+
+        storage.insert(ERC20_NAME_STORAGE_SLOT, name);
+        storage.insert(ERC20_SYMBOL_STORAGE_SLOT, symbol);
+        storage.insert(ERC20_DECIMAL_STORAGE_SLOT, self.decimals.into());
+        storage.insert(ERC20_TOTAL_SUPPLY_STORAGE_SLOT, total_supply_low);
+        storage.insert(ERC20_TOTAL_SUPPLY_STORAGE_SLOT + 1u8.into(), total_supply_high);
+
+        for (address, balance) in &self.balances {
+            // the ERC20 balances are stored in the same storage variable as the fee token's
+            let base = get_fee_token_balance_base_storage_address(*address);
+            let (low, high) = split_u256(*balance);
+
+            storage.insert(base, low);
+            storage.insert(base + 1u8.into(), high);
+        }
+
+        storage
+    }
+}
+
+impl Erc721Token {
+    /// Get the total number of tokens in the collection.
+    pub fn total_supply(&self) -> usize {
+        self.owners.values().map(BTreeSet::len).sum()
+    }
+
+    fn storage(&self) -> HashMap<StorageKey, StorageValue> {
+        let mut storage = self.storage.clone().unwrap_or_default();
+
+        let name = cairo_short_string_to_felt(&self.name).unwrap();
+        let symbol = cairo_short_string_to_felt(&self.symbol).unwrap();
+
+        storage.insert(storage_var_address("ERC721_name", &[]), name);
+        storage.insert(storage_var_address("ERC721_symbol", &[]), symbol);
+
+        for (owner, token_ids) in &self.owners {
+            let base = storage_var_address("ERC721_balances", &[(*owner).into()]);
+            let (low, high) = split_u256(U256::from(token_ids.len()));
+
+            storage.insert(base, low);
+            storage.insert(base + 1u8.into(), high);
+
+            for token_id in token_ids {
+                let (low, high) = split_u256(*token_id);
+                storage.insert(storage_var_address("ERC721_owners", &[low, high]), (*owner).into());
+            }
+        }
+
+        storage
+    }
+}
+
+fn storage_var_address(name: &str, keys: &[FieldElement]) -> StorageKey {
+    get_storage_var_address(name, keys).expect("valid storage variable name")
+}
+
+#[cfg(test)]
+mod tests {
+    use starknet::macros::felt;
+
+    use super::*;
+
+    #[test]
+    fn erc20_token_storage() {
+        let holder = ContractAddress::from(felt!("0x1337"));
+        let token = Erc20Token {
+            name: "Gold".into(),
+            symbol: "GLD".into(),
+            decimals: 6,
+            class_hash: felt!("0x1"),
+            balances: BTreeMap::from([(holder, U256::from(100u8) << 128 | U256::from(5u8))]),
+            storage: Some(HashMap::from([(felt!("0x10"), felt!("0x20"))])),
+        };
+
+        let storage = GenesisToken::Erc20(token).storage();
+        let balance = get_fee_token_balance_base_storage_address(holder);
+
+        assert_eq!(storage.get(&felt!("0x10")), Some(&felt!("0x20")));
+        assert_eq!(storage.get(&ERC20_NAME_STORAGE_SLOT), Some(&felt!("0x476f6c64")));
+        assert_eq!(storage.get(&ERC20_SYMBOL_STORAGE_SLOT), Some(&felt!("0x474c44")));
+        assert_eq!(storage.get(&ERC20_DECIMAL_STORAGE_SLOT), Some(&felt!("0x6")));
+        assert_eq!(storage.get(&ERC20_TOTAL_SUPPLY_STORAGE_SLOT), Some(&felt!("0x5")));
+        assert_eq!(
+            storage.get(&(ERC20_TOTAL_SUPPLY_STORAGE_SLOT + 1u8.into())),
+            Some(&felt!("0x64"))
+        );
+        assert_eq!(storage.get(&balance), Some(&felt!("0x5")));
+        assert_eq!(storage.get(&(balance + 1u8.into())), Some(&felt!("0x64")));
+    }
+
+    #[test]
+    fn erc721_token_storage() {
+        let owner = ContractAddress::from(felt!("0x1337"));
+        let token = Erc721Token {
+            name: "Swords".into(),
+            symbol: "SWD".into(),
+            class_hash: felt!("0x1"),
+            owners: BTreeMap::from([(owner, BTreeSet::from([U256::from(1u8), U256::from(7u8)]))]),
+            storage: None,
+        };
+
+        assert_eq!(token.total_supply(), 2);
+
+        let storage = GenesisToken::Erc721(token).storage();
+        let balance = storage_var_address("ERC721_balances", &[owner.into()]);
+
+        assert_eq!(storage.len(), 6);
+        assert_eq!(storage.get(&balance), Some(&felt!("0x2")));
+        assert_eq!(storage.get(&(balance + 1u8.into())), Some(&FieldElement::ZERO));
+
+        for token_id in [1u8, 7u8] {
+            let slot = storage_var_address("ERC721_owners", &[token_id.into(), FieldElement::ZERO]);
+            assert_eq!(storage.get(&slot), Some(&owner.into()));
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use ethers::types::H256;
 
-use crate::contract::ContractAddress;
+use crate::contract::{ClassHash, ContractAddress};
+use crate::fee::PriceUnit;
 use crate::FieldElement;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -32,12 +33,16 @@ pub struct MessageToL1 {
 pub struct InvokeTxReceipt {
     /// Actual fee paid for the transaction.
     pub actual_fee: u128,
+    /// The unit of the actual fee, ie. the token it was paid in.
+    pub fee_unit: PriceUnit,
     /// Events emitted by contracts.
     pub events: Vec<Event>,
     /// Messages sent to L1.
     pub messages_sent: Vec<MessageToL1>,
     /// Revert error message if the transaction execution failed.
     pub revert_error: Option<String>,
+    /// The decoded revert error, if the transaction execution failed.
+    pub revert_reason: Option<RevertReason>,
     /// The execution resources used by the transaction.
     pub execution_resources: TxExecutionResources,
 }
@@ -48,12 +53,16 @@ pub struct InvokeTxReceipt {
 pub struct DeclareTxReceipt {
     /// Actual fee paid for the transaction.
     pub actual_fee: u128,
+    /// The unit of the actual fee, ie. the token it was paid in.
+    pub fee_unit: PriceUnit,
     /// Events emitted by contracts.
     pub events: Vec<Event>,
     /// Messages sent to L1.
     pub messages_sent: Vec<MessageToL1>,
     /// Revert error message if the transaction execution failed.
     pub revert_error: Option<String>,
+    /// The decoded revert error, if the transaction execution failed.
+    pub revert_reason: Option<RevertReason>,
     /// The execution resources used by the transaction.
     pub execution_resources: TxExecutionResources,
 }
@@ -72,6 +81,8 @@ pub struct L1HandlerTxReceipt {
     pub messages_sent: Vec<MessageToL1>,
     /// Revert error message if the transaction execution failed.
     pub revert_error: Option<String>,
+    /// The decoded revert error, if the transaction execution failed.
+    pub revert_reason: Option<RevertReason>,
     /// The execution resources used by the transaction.
     pub execution_resources: TxExecutionResources,
 }
@@ -82,12 +93,16 @@ pub struct L1HandlerTxReceipt {
 pub struct DeployAccountTxReceipt {
     /// Actual fee paid for the transaction.
     pub actual_fee: u128,
+    /// The unit of the actual fee, ie. the token it was paid in.
+    pub fee_unit: PriceUnit,
     /// Events emitted by contracts.
     pub events: Vec<Event>,
     /// Messages sent to L1.
     pub messages_sent: Vec<MessageToL1>,
     /// Revert error message if the transaction execution failed.
     pub revert_error: Option<String>,
+    /// The decoded revert error, if the transaction execution failed.
+    pub revert_reason: Option<RevertReason>,
     /// The execution resources used by the transaction.
     pub execution_resources: TxExecutionResources,
     /// Contract address of the deployed account contract.
@@ -117,6 +132,37 @@ impl Receipt {
         }
     }
 
+    /// Returns the fee charged for the transaction.
+    pub fn actual_fee(&self) -> u128 {
+        match self {
+            Receipt::Invoke(rct) => rct.actual_fee,
+            Receipt::Declare(rct) => rct.actual_fee,
+            Receipt::L1Handler(rct) => rct.actual_fee,
+            Receipt::DeployAccount(rct) => rct.actual_fee,
+        }
+    }
+
+    /// Returns the unit of the fee charged for the transaction. The fee of `L1Handler`
+    /// transactions is paid on L1, in ETH.
+    pub fn fee_unit(&self) -> PriceUnit {
+        match self {
+            Receipt::Invoke(rct) => rct.fee_unit,
+            Receipt::Declare(rct) => rct.fee_unit,
+            Receipt::L1Handler(_) => PriceUnit::Wei,
+            Receipt::DeployAccount(rct) => rct.fee_unit,
+        }
+    }
+
+    /// Returns the revert error of the transaction, if it is reverted.
+    pub fn revert_error(&self) -> Option<&str> {
+        match self {
+            Receipt::Invoke(rct) => rct.revert_error.as_deref(),
+            Receipt::Declare(rct) => rct.revert_error.as_deref(),
+            Receipt::L1Handler(rct) => rct.revert_error.as_deref(),
+            Receipt::DeployAccount(rct) => rct.revert_error.as_deref(),
+        }
+    }
+
     pub fn messages_sent(&self) -> &[MessageToL1] {
         match self {
             Receipt::Invoke(rct) => &rct.messages_sent,
@@ -126,6 +172,25 @@ impl Receipt {
         }
     }
 
+    /// Returns the decoded revert error if the transaction is reverted.
+    pub fn revert_reason(&self) -> Option<&RevertReason> {
+        match self {
+            Receipt::Invoke(rct) => rct.revert_reason.as_ref(),
+            Receipt::Declare(rct) => rct.revert_reason.as_ref(),
+            Receipt::L1Handler(rct) => rct.revert_reason.as_ref(),
+            Receipt::DeployAccount(rct) => rct.revert_reason.as_ref(),
+        }
+    }
+
+    pub fn execution_resources(&self) -> &TxExecutionResources {
+        match self {
+            Receipt::Invoke(rct) => &rct.execution_resources,
+            Receipt::Declare(rct) => &rct.execution_resources,
+            Receipt::L1Handler(rct) => &rct.execution_resources,
+            Receipt::DeployAccount(rct) => &rct.execution_resources,
+        }
+    }
+
     pub fn events(&self) -> &[Event] {
         match self {
             Receipt::Invoke(rct) => &rct.events,
@@ -136,6 +201,57 @@ impl Receipt {
     }
 }
 
+/// A human readable description of why the execution of a transaction, or a call, failed.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RevertReason {
+    /// The contract in which the failure originated.
+    pub contract_address: Option<ContractAddress>,
+    /// The selector of the entry point in which the failure originated.
+    pub selector: Option<FieldElement>,
+    /// The decoded failure reason.
+    pub reason: String,
+    /// The calls that led to the failure, from the outermost to the innermost one.
+    pub call_path: Vec<CallFrame>,
+}
+
+/// A single call in the call path of a [RevertReason].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CallFrame {
+    /// The address of the called contract.
+    pub contract_address: ContractAddress,
+    /// The selector of the called entry point, if known.
+    pub selector: Option<FieldElement>,
+    /// The program counter at which the call failed.
+    pub pc: Option<u64>,
+    /// The name of the Sierra function at `pc`, if it is an entry point of the class of the
+    /// contract. Not available for the other functions, nor for the legacy classes.
+    pub function: Option<String>,
+}
+
+impl std::fmt::Display for RevertReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.reason)?;
+
+        for frame in self.call_path.iter().rev() {
+            write!(f, "\n    at {}", frame.contract_address)?;
+
+            match (&frame.function, frame.selector) {
+                (Some(function), _) => write!(f, "::{function}")?,
+                (None, Some(selector)) => write!(f, " (selector {selector:#x})")?,
+                (None, None) => {}
+            }
+
+            if let Some(pc) = frame.pc {
+                write!(f, " [pc=0:{pc}]")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
 /// Transaction execution resources.
 ///
 /// The resources consumed by a transaction during its execution.
@@ -162,4 +278,26 @@ pub struct TxExecutionResources {
     pub keccak_builtin: Option<u64>,
 
     pub segment_arena_builtin: Option<u64>,
+    /// The amount of L1 gas used
+    pub l1_gas: Option<u64>,
+    /// The amount of L1 data gas used
+    pub l1_data_gas: Option<u64>,
+    /// The entry point calls made by the transaction, in execution order.
+    pub calls: Vec<CallProfile>,
+}
+
+/// The resources used by an entry point call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CallProfile {
+    /// The address of the called contract.
+    pub contract_address: ContractAddress,
+    /// The class hash of the called contract.
+    pub class_hash: Option<ClassHash>,
+    /// The selector of the called entry point.
+    pub selector: FieldElement,
+    /// The number of cairo steps used by the call itself, excluding its inner calls.
+    pub steps: u64,
+    /// The calls made by this call.
+    pub inner_calls: Vec<CallProfile>,
 }
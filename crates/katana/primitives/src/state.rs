@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use crate::contract::{
     ClassHash, CompiledClassHash, CompiledContractClass, ContractAddress, FlattenedSierraClass,
-    Nonce, StorageKey, StorageValue,
+    LegacyClassAbi, Nonce, StorageKey, StorageValue,
 };
 
 /// State updates.
@@ -30,4 +30,6 @@ pub struct StateUpdatesWithDeclaredClasses {
     pub declared_sierra_classes: HashMap<ClassHash, FlattenedSierraClass>,
     /// A mapping of class hashes to their compiled classes definition.
     pub declared_compiled_classes: HashMap<ClassHash, CompiledContractClass>,
+    /// A mapping of class hashes to the ABIs of their legacy classes definition.
+    pub declared_legacy_class_abis: HashMap<ClassHash, LegacyClassAbi>,
 }
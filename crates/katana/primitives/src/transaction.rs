@@ -5,8 +5,9 @@ use starknet::core::types::{DataAvailabilityMode, ResourceBoundsMapping};
 use crate::chain::ChainId;
 use crate::contract::{
     ClassHash, CompiledClassHash, CompiledContractClass, ContractAddress, FlattenedSierraClass,
-    Nonce,
+    LegacyClassAbi, Nonce,
 };
+use crate::fee::PriceUnit;
 use crate::utils::transaction::{
     compute_declare_v1_tx_hash, compute_declare_v2_tx_hash, compute_declare_v3_tx_hash,
     compute_deploy_account_v1_tx_hash, compute_deploy_account_v3_tx_hash,
@@ -18,6 +19,10 @@ use crate::{utils, FieldElement};
 pub type TxHash = FieldElement;
 /// The sequential number for all the transactions..
 pub type TxNumber = u64;
+/// The hash of a message sent from L1 to L2, as computed by the Starknet core contract.
+pub type L1MessageHash = H256;
+/// The hash of a transaction on the settlement chain.
+pub type L1TxHash = H256;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -28,6 +33,17 @@ pub enum Tx {
     DeployAccount(DeployAccountTx),
 }
 
+impl Tx {
+    pub fn tx_ref(&self) -> TxRef<'_> {
+        match self {
+            Tx::Invoke(tx) => TxRef::Invoke(tx),
+            Tx::Declare(tx) => TxRef::Declare(tx),
+            Tx::L1Handler(tx) => TxRef::L1Handler(tx),
+            Tx::DeployAccount(tx) => TxRef::DeployAccount(tx),
+        }
+    }
+}
+
 pub enum TxRef<'a> {
     Invoke(&'a InvokeTx),
     Declare(&'a DeclareTx),
@@ -35,6 +51,28 @@ pub enum TxRef<'a> {
     DeployAccount(&'a DeployAccountTx),
 }
 
+impl<'a> TxRef<'a> {
+    /// Returns the resource bounds of the transaction, only set for V3 transactions.
+    pub fn resource_bounds(&self) -> Option<&'a ResourceBoundsMapping> {
+        match self {
+            TxRef::Invoke(InvokeTx::V3(tx)) => Some(&tx.resource_bounds),
+            TxRef::Declare(DeclareTx::V3(tx)) => Some(&tx.resource_bounds),
+            TxRef::DeployAccount(DeployAccountTx::V3(tx)) => Some(&tx.resource_bounds),
+            _ => None,
+        }
+    }
+
+    /// Returns the unit of the fee charged for the transaction. V3 transactions pay their fee in
+    /// STRK, the others in ETH.
+    pub fn fee_unit(&self) -> PriceUnit {
+        if self.resource_bounds().is_some() {
+            PriceUnit::Fri
+        } else {
+            PriceUnit::Wei
+        }
+    }
+}
+
 impl<'a> From<TxRef<'a>> for Tx {
     fn from(value: TxRef<'a>) -> Self {
         match value {
@@ -104,6 +142,8 @@ pub struct DeclareTxWithClass {
     pub sierra_class: Option<FlattenedSierraClass>,
     /// The compiled contract class.
     pub compiled_class: CompiledContractClass,
+    /// The ABI of the class, if it's a legacy class.
+    pub legacy_abi: Option<LegacyClassAbi>,
     /// The raw transaction.
     #[deref]
     #[as_ref]
@@ -116,7 +156,7 @@ impl DeclareTxWithClass {
         sierra_class: FlattenedSierraClass,
         compiled_class: CompiledContractClass,
     ) -> Self {
-        Self { sierra_class: Some(sierra_class), compiled_class, transaction }
+        Self { sierra_class: Some(sierra_class), compiled_class, legacy_abi: None, transaction }
     }
 }
 
@@ -357,7 +397,7 @@ pub struct L1HandlerTx {
     pub chain_id: ChainId,
     pub paid_fee_on_l1: u128,
     pub version: FieldElement,
-    pub message_hash: H256,
+    pub message_hash: L1MessageHash,
     pub calldata: Vec<FieldElement>,
     pub contract_address: ContractAddress,
     pub entry_point_selector: FieldElement,
@@ -1,10 +1,23 @@
+use std::collections::HashMap;
+
 use anyhow::Result;
+use cairo_lang_sierra::extensions::gas::CostTokenType;
+use cairo_lang_sierra_to_casm::compiler::compile;
+use cairo_lang_sierra_to_casm::metadata::{calc_metadata, MetadataComputationConfig};
 use cairo_lang_starknet::casm_contract_class::CasmContractClass;
 use cairo_lang_starknet::contract_class::ContractClass;
+use starknet::core::utils::get_selector_from_name;
+use starknet_api::deprecated_contract_class::EntryPointType;
 
 use crate::contract::{
-    CompiledContractClass, CompiledContractClassV0, CompiledContractClassV1, SierraClass,
+    CompiledClassHash, CompiledContractClass, CompiledContractClassV0, CompiledContractClassV1,
+    FlattenedSierraClass, LegacyClassAbi, SierraClass,
 };
+use crate::FieldElement;
+
+/// The gas cost of an entry point. Must be the same as the one used when compiling the class in
+/// [`CasmContractClass::from_contract_class`] for the code offsets to match.
+const ENTRY_POINT_COST: i32 = 10000;
 
 /// Parse a [`str`] into a [`CompiledContractClass`].
 pub fn parse_compiled_class(class: &str) -> Result<CompiledContractClass> {
@@ -22,12 +35,225 @@ pub fn parse_compiled_class_v1(class: &str) -> Result<CompiledContractClassV1> {
     Ok(CompiledContractClassV1::try_from(class)?)
 }
 
+/// Compute the compiled class hash of a Sierra class from its [`str`] representation.
+pub fn compute_compiled_class_hash(class: &str) -> Result<CompiledClassHash> {
+    let class: ContractClass = serde_json::from_str(class)?;
+    let class = CasmContractClass::from_contract_class(class, true)?;
+    Ok(FieldElement::from_bytes_be(&class.compiled_class_hash().to_be_bytes())?)
+}
+
 /// Parse a [`str`] into a [`CompiledContractClassV0`].
 pub fn parse_compiled_class_v0(class: &str) -> Result<CompiledContractClassV0, serde_json::Error> {
     serde_json::from_str(class)
 }
 
+/// Parse the ABI of a legacy class from its artifact, with an empty ABI if it has none.
+pub fn parse_legacy_class_abi(class: &str) -> Result<LegacyClassAbi, serde_json::Error> {
+    let artifact: serde_json::Value = serde_json::from_str(class)?;
+    legacy_class_abi_of_artifact(&artifact)
+}
+
+/// Returns the ABI of a legacy class artifact, with an empty ABI if it has none.
+pub fn legacy_class_abi_of_artifact(
+    artifact: &serde_json::Value,
+) -> Result<LegacyClassAbi, serde_json::Error> {
+    match artifact.get("abi") {
+        Some(abi) => serde_json::from_value(abi.clone()),
+        None => Ok(LegacyClassAbi::new()),
+    }
+}
+
 /// Parse a [`str`] into a [`SierraClass`].
 pub fn parse_sierra_class(class: &str) -> Result<SierraClass, serde_json::Error> {
     serde_json::from_str(class)
 }
+
+/// Converts `starknet-rs` RPC [FlattenedSierraClass] type to Cairo's
+/// [ContractClass](cairo_lang_starknet::contract_class::ContractClass) type.
+pub fn rpc_to_cairo_contract_class(
+    contract_class: &FlattenedSierraClass,
+) -> Result<cairo_lang_starknet::contract_class::ContractClass, std::io::Error> {
+    let value = serde_json::to_value(contract_class)?;
+
+    Ok(cairo_lang_starknet::contract_class::ContractClass {
+        abi: serde_json::from_value(value["abi"].clone()).ok(),
+        sierra_program: serde_json::from_value(value["sierra_program"].clone())?,
+        entry_points_by_type: serde_json::from_value(value["entry_points_by_type"].clone())?,
+        contract_class_version: serde_json::from_value(value["contract_class_version"].clone())?,
+        sierra_program_debug_info: serde_json::from_value(
+            value["sierra_program_debug_info"].clone(),
+        )
+        .ok(),
+    })
+}
+
+/// Returns the names of the functions declared in the ABI of a class, indexed by their selector.
+pub fn entry_point_names(class: &FlattenedSierraClass) -> HashMap<FieldElement, String> {
+    fn collect(items: &[serde_json::Value], names: &mut HashMap<FieldElement, String>) {
+        for item in items {
+            match item["type"].as_str() {
+                Some("function" | "l1_handler" | "constructor") => {
+                    let Some(name) = item["name"].as_str() else { continue };
+                    if let Ok(selector) = get_selector_from_name(name) {
+                        names.insert(selector, name.to_string());
+                    }
+                }
+                Some("interface") => {
+                    if let Some(items) = item["items"].as_array() {
+                        collect(items, names);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let mut names = HashMap::new();
+    if let Ok(serde_json::Value::Array(items)) = serde_json::from_str(&class.abi) {
+        collect(&items, &mut names);
+    }
+    names
+}
+
+/// Returns true if the class implements the account interface, ie it has the `__validate__` and
+/// `__execute__` external entry points.
+pub fn is_account_class(class: &CompiledContractClass) -> bool {
+    let selectors: Vec<FieldElement> = match class {
+        CompiledContractClass::V0(class) => class
+            .entry_points_by_type
+            .get(&EntryPointType::External)
+            .into_iter()
+            .flatten()
+            .map(|entry| entry.selector.0.into())
+            .collect(),
+        CompiledContractClass::V1(class) => class
+            .entry_points_by_type
+            .get(&EntryPointType::External)
+            .into_iter()
+            .flatten()
+            .map(|entry| entry.selector.0.into())
+            .collect(),
+    };
+
+    ["__validate__", "__execute__"].into_iter().all(|name| {
+        get_selector_from_name(name).is_ok_and(|selector| selectors.contains(&selector))
+    })
+}
+
+/// Maps the program counters of a compiled Sierra class back to the Sierra functions.
+#[derive(Debug, Clone)]
+pub struct SierraFunctionMap {
+    /// The functions sorted by the code offset of their first instruction.
+    functions: Vec<SierraFunction>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SierraFunction {
+    /// The code offset of the first instruction of the function.
+    pub offset: usize,
+    /// The name of the function if it is an entry point of the class, as declared in its ABI. The
+    /// names of the other functions aren't known, as the declared classes don't include the
+    /// debug info of their Sierra program.
+    pub name: Option<String>,
+    /// The selector of the function if it is an entry point of the class.
+    pub selector: Option<FieldElement>,
+}
+
+impl SierraFunctionMap {
+    pub fn new(class: &FlattenedSierraClass) -> Result<Self> {
+        let names = entry_point_names(class);
+        let class = rpc_to_cairo_contract_class(class)?;
+        let program = class.extract_sierra_program()?;
+
+        let entry_points = class
+            .entry_points_by_type
+            .external
+            .iter()
+            .chain(&class.entry_points_by_type.l1_handler)
+            .chain(&class.entry_points_by_type.constructor)
+            .collect::<Vec<_>>();
+
+        let function_set_costs = entry_points
+            .iter()
+            .map(|entry_point| {
+                let costs = [(CostTokenType::Const, ENTRY_POINT_COST)].into_iter().collect();
+                (program.funcs[entry_point.function_idx].id.clone(), costs)
+            })
+            .collect();
+
+        let config = MetadataComputationConfig {
+            function_set_costs,
+            linear_gas_solver: true,
+            linear_ap_change_solver: true,
+        };
+
+        let metadata = calc_metadata(&program, config)?;
+        let cairo_program = compile(&program, &metadata, true)?;
+
+        let mut functions = program
+            .funcs
+            .iter()
+            .enumerate()
+            .map(|(idx, function)| {
+                let statement = &cairo_program.debug_info.sierra_statement_info;
+                let offset = statement[function.entry_point.0].code_offset;
+                let selector = entry_points
+                    .iter()
+                    .find(|entry_point| entry_point.function_idx == idx)
+                    .and_then(|entry_point| {
+                        FieldElement::from_byte_slice_be(&entry_point.selector.to_bytes_be()).ok()
+                    });
+                let name = selector.and_then(|selector| names.get(&selector).cloned());
+
+                SierraFunction { offset, name, selector }
+            })
+            .collect::<Vec<_>>();
+
+        functions.sort_by_key(|function| function.offset);
+        Ok(Self { functions })
+    }
+
+    /// Returns the functions of the class, sorted by their code offset.
+    pub fn functions(&self) -> &[SierraFunction] {
+        &self.functions
+    }
+
+    /// Returns the function that contains the instruction at `pc`.
+    pub fn function_at(&self, pc: u64) -> Option<&SierraFunction> {
+        let idx = self.functions.partition_point(|function| function.offset as u64 <= pc);
+        idx.checked_sub(1).map(|idx| &self.functions[idx])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use starknet::core::utils::get_selector_from_name;
+
+    use super::SierraFunctionMap;
+    use crate::genesis::constant::DEFAULT_OZ_ACCOUNT_CONTRACT;
+
+    #[test]
+    fn sierra_function_at_pc() {
+        let class = DEFAULT_OZ_ACCOUNT_CONTRACT.clone().flatten().unwrap();
+        let map = SierraFunctionMap::new(&class).unwrap();
+
+        assert!(map.functions.windows(2).all(|w| w[0].offset <= w[1].offset));
+        assert_eq!(map.function_at(0).map(|function| function.offset), Some(0));
+
+        // every instruction belongs to the last function starting at or before it
+        for (function, next) in map.functions.iter().zip(map.functions.iter().skip(1)) {
+            if function.offset == next.offset {
+                continue;
+            }
+            let last_pc = next.offset as u64 - 1;
+            assert_eq!(map.function_at(function.offset as u64).unwrap().offset, function.offset);
+            assert_eq!(map.function_at(last_pc).unwrap().offset, function.offset);
+        }
+
+        // the entry points are named after their ABI
+        let selector = get_selector_from_name("__execute__").unwrap();
+        let execute = map.functions.iter().find(|f| f.selector == Some(selector)).unwrap();
+        assert_eq!(execute.name.as_deref(), Some("__execute__"));
+        assert!(map.functions.iter().filter(|f| f.selector.is_none()).all(|f| f.name.is_none()));
+    }
+}
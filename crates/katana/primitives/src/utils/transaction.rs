@@ -1,4 +1,5 @@
 use ethers::types::H256;
+use ethers::utils::keccak256;
 use starknet::core::crypto::compute_hash_on_elements;
 use starknet::core::types::{DataAvailabilityMode, MsgToL1, ResourceBounds};
 use starknet_crypto::poseidon_hash_many;
@@ -274,6 +275,30 @@ pub fn compute_l1_message_hash(
     H256::from_slice(msg.hash().as_bytes())
 }
 
+/// Computes the hash of a L1 to L2 message.
+///
+/// The hash is computed the same way as the Starknet core contract does when the message is sent
+/// from L1, ie. `keccak256(from_address, to_address, nonce, selector, payload.len(), payload)`.
+/// As opposed to the hash of a L2 to L1 message, it includes the message nonce so identical
+/// messages sent more than once have different hashes.
+pub fn compute_l1_to_l2_message_hash(
+    from_address: FieldElement,
+    to_address: FieldElement,
+    selector: FieldElement,
+    payload: &[FieldElement],
+    nonce: FieldElement,
+) -> H256 {
+    let mut buf = Vec::with_capacity((5 + payload.len()) * 32);
+    buf.extend(from_address.to_bytes_be());
+    buf.extend(to_address.to_bytes_be());
+    buf.extend(nonce.to_bytes_be());
+    buf.extend(selector.to_bytes_be());
+    buf.extend(FieldElement::from(payload.len()).to_bytes_be());
+    payload.iter().for_each(|felt| buf.extend(felt.to_bytes_be()));
+
+    H256::from(keccak256(buf))
+}
+
 #[cfg(test)]
 mod tests {
     use starknet::core::chain_id;
@@ -1,6 +1,10 @@
 use jsonrpsee::core::RpcResult;
 use jsonrpsee::proc_macros::rpc;
+use katana_primitives::block::BlockIdOrTag;
 use katana_primitives::FieldElement;
+use katana_rpc_types::message::{MessageToL1WithStatus, MessageToL2Sent, MsgFromL1};
+use starknet::core::types::Hash256;
+use url::Url;
 
 #[cfg_attr(not(feature = "client"), rpc(server, namespace = "dev"))]
 #[cfg_attr(feature = "client", rpc(client, server, namespace = "dev"))]
@@ -17,6 +21,13 @@ pub trait DevApi {
     #[method(name = "increaseNextBlockTimestamp")]
     async fn increase_next_block_timestamp(&self, timestamp: u64) -> RpcResult<()>;
 
+    /// Drops the local chain and forks the network again, at `block` of the network at `url`. The
+    /// currently forked network and its latest block are used by default.
+    ///
+    /// The network has to be on the same chain as the one the node has been started with.
+    #[method(name = "resetFork")]
+    async fn reset_fork(&self, url: Option<Url>, block: Option<BlockIdOrTag>) -> RpcResult<()>;
+
     #[method(name = "setStorageAt")]
     async fn set_storage_at(
         &self,
@@ -24,4 +35,24 @@ pub trait DevApi {
         key: FieldElement,
         value: FieldElement,
     ) -> RpcResult<()>;
+
+    /// Sends a message from the simulated settlement chain to L2. The message is executed as a L1
+    /// handler transaction once gathered by the messaging service.
+    ///
+    /// `paid_fee_on_l1` is the fee paid on the settlement chain for the message, and defaults to
+    /// the estimated fee of the L1 handler transaction.
+    #[method(name = "sendMessageToL2")]
+    async fn send_message_to_l2(
+        &self,
+        message: MsgFromL1,
+        paid_fee_on_l1: Option<u128>,
+    ) -> RpcResult<MessageToL2Sent>;
+
+    /// Returns the messages sent from L2 to the simulated settlement chain.
+    #[method(name = "messagesToL1")]
+    async fn messages_to_l1(&self) -> RpcResult<Vec<MessageToL1WithStatus>>;
+
+    /// Consumes a message sent from L2 to the simulated settlement chain.
+    #[method(name = "consumeMessageToL1")]
+    async fn consume_message_to_l1(&self, message_hash: Hash256) -> RpcResult<()>;
 }
@@ -1,10 +1,87 @@
 use jsonrpsee::core::RpcResult;
 use jsonrpsee::proc_macros::rpc;
+use katana_primitives::block::BlockNumber;
+use katana_primitives::genesis::json::GenesisJson;
+use katana_primitives::receipt::{RevertReason, TxExecutionResources};
+use katana_primitives::FieldElement;
 use katana_rpc_types::account::Account;
+use katana_rpc_types::block::BlockHashAndNumber;
+use katana_rpc_types::message::{DeadLetterMessage, MessageStatus};
+use katana_rpc_types::{FeltAsHex, FunctionCall};
+use starknet::core::types::Hash256;
 
 #[cfg_attr(not(feature = "client"), rpc(server, namespace = "katana"))]
 #[cfg_attr(feature = "client", rpc(client, server, namespace = "katana"))]
 pub trait KatanaApi {
     #[method(name = "predeployedAccounts")]
     async fn predeployed_accounts(&self) -> RpcResult<Vec<Account>>;
+
+    /// Returns the execution resources used by a transaction, including the steps used by each
+    /// of the entry points it called.
+    #[method(name = "transactionExecutionResources")]
+    async fn transaction_execution_resources(
+        &self,
+        transaction_hash: FieldElement,
+    ) -> RpcResult<TxExecutionResources>;
+
+    /// Returns the reason why a transaction was reverted, with the contract, the selector and the
+    /// call path of the failure, or `None` if the transaction succeeded. The receipts of the
+    /// Starknet API only include the formatted reason.
+    #[method(name = "transactionRevertReason")]
+    async fn transaction_revert_reason(
+        &self,
+        transaction_hash: FieldElement,
+    ) -> RpcResult<Option<RevertReason>>;
+
+    /// Returns the fee paid on the settlement chain for an L1 handler transaction. The fee isn't
+    /// part of the transactions returned by the Starknet API, but is needed to execute them again.
+    #[method(name = "paidFeeOnL1")]
+    async fn paid_fee_on_l1(&self, transaction_hash: FieldElement) -> RpcResult<FeltAsHex>;
+
+    /// Returns the steps used by each of the entry points called by a transaction, in the folded
+    /// stack format consumed by flamegraph tools, eg. `inferno-flamegraph`.
+    #[method(name = "transactionFlamegraph")]
+    async fn transaction_flamegraph(&self, transaction_hash: FieldElement) -> RpcResult<String>;
+
+    /// Returns the status of the messages sent by a transaction on the settlement chain, in the
+    /// order they were sent. The status of the gathered messages is persisted in the database of
+    /// the node, so only the messages sent before the block the gathering started from are
+    /// unknown.
+    #[method(name = "getMessagesStatus")]
+    async fn get_messages_status(&self, transaction_hash: Hash256)
+        -> RpcResult<Vec<MessageStatus>>;
+
+    /// Returns the messages to L1 that couldn't be sent to the settlement chain after all the
+    /// retries.
+    #[method(name = "deadLetterMessages")]
+    async fn dead_letter_messages(&self) -> RpcResult<Vec<DeadLetterMessage>>;
+
+    /// Exports the state of the chain at the given block, or at the latest block if not provided,
+    /// as a genesis configuration with the class artifacts embedded. The returned configuration can
+    /// be loaded with `--genesis` once saved to a file.
+    #[method(name = "exportGenesis")]
+    async fn export_genesis(&self, block_number: Option<BlockNumber>) -> RpcResult<GenesisJson>;
+
+    /// Returns the hash and number of the last block mined at or before `timestamp`, in seconds
+    /// since the Unix epoch.
+    #[method(name = "blockIdByTimestamp")]
+    async fn block_id_by_timestamp(&self, timestamp: u64) -> RpcResult<BlockHashAndNumber>;
+
+    /// Calls a contract on the state of the last block mined at or before `timestamp`.
+    #[method(name = "callAtTimestamp")]
+    async fn call_at_timestamp(
+        &self,
+        request: FunctionCall,
+        timestamp: u64,
+    ) -> RpcResult<Vec<FeltAsHex>>;
+
+    /// Returns the value of a storage key of a contract on the state of the last block mined at or
+    /// before `timestamp`.
+    #[method(name = "getStorageAtTimestamp")]
+    async fn get_storage_at_timestamp(
+        &self,
+        contract_address: FieldElement,
+        key: FieldElement,
+        timestamp: u64,
+    ) -> RpcResult<FeltAsHex>;
 }
@@ -1,13 +1,54 @@
+use std::fmt;
+use std::str::FromStr;
+
 pub mod dev;
 pub mod katana;
 pub mod starknet;
 pub mod torii;
 
 /// List of APIs supported by Katana.
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum ApiKind {
     Starknet,
     Katana,
     Torii,
     Dev,
 }
+
+impl ApiKind {
+    /// All the supported APIs.
+    pub const ALL: [ApiKind; 4] =
+        [ApiKind::Starknet, ApiKind::Katana, ApiKind::Torii, ApiKind::Dev];
+
+    /// The namespace of the API methods.
+    pub fn namespace(&self) -> &'static str {
+        match self {
+            ApiKind::Starknet => "starknet",
+            ApiKind::Katana => "katana",
+            ApiKind::Torii => "torii",
+            ApiKind::Dev => "dev",
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("unknown api `{0}`, expected one of: starknet, katana, torii, dev")]
+pub struct UnknownApiKind(String);
+
+impl FromStr for ApiKind {
+    type Err = UnknownApiKind;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim();
+        Self::ALL
+            .into_iter()
+            .find(|api| api.namespace().eq_ignore_ascii_case(name))
+            .ok_or_else(|| UnknownApiKind(name.to_string()))
+    }
+}
+
+impl fmt::Display for ApiKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.namespace())
+    }
+}
@@ -4,7 +4,8 @@ use katana_primitives::block::{BlockIdOrTag, BlockNumber};
 use katana_primitives::transaction::TxHash;
 use katana_primitives::FieldElement;
 use katana_rpc_types::block::{
-    BlockHashAndNumber, BlockTxCount, MaybePendingBlockWithTxHashes, MaybePendingBlockWithTxs,
+    BlockHashAndNumber, BlockTxCount, MaybePendingBlockWithReceipts, MaybePendingBlockWithTxHashes,
+    MaybePendingBlockWithTxs,
 };
 use katana_rpc_types::event::{EventFilterWithPage, EventsPage};
 use katana_rpc_types::message::MsgFromL1;
@@ -15,7 +16,8 @@ use katana_rpc_types::transaction::{
     DeclareTxResult, DeployAccountTxResult, InvokeTxResult, Tx,
 };
 use katana_rpc_types::{
-    ContractClass, FeeEstimate, FeltAsHex, FunctionCall, SimulationFlags, SyncingStatus,
+    CompiledCasm, ContractClass, FeeEstimate, FeltAsHex, FunctionCall, SimulationFlags,
+    SyncingStatus,
 };
 use starknet::core::types::TransactionStatus;
 
@@ -43,6 +45,13 @@ pub trait StarknetApi {
     #[method(name = "getBlockWithTxs")]
     async fn block_with_txs(&self, block_id: BlockIdOrTag) -> RpcResult<MaybePendingBlockWithTxs>;
 
+    /// Get block information with full transactions and receipts given the block id.
+    #[method(name = "getBlockWithReceipts")]
+    async fn block_with_receipts(
+        &self,
+        block_id: BlockIdOrTag,
+    ) -> RpcResult<MaybePendingBlockWithReceipts>;
+
     /// Get the information about the result of executing the requested block.
     #[method(name = "getStateUpdate")]
     async fn state_update(&self, block_id: BlockIdOrTag) -> RpcResult<StateUpdate>;
@@ -105,6 +114,10 @@ pub trait StarknetApi {
         contract_address: FieldElement,
     ) -> RpcResult<ContractClass>;
 
+    /// Get the CASM of a Sierra contract class given its class hash.
+    #[method(name = "getCompiledCasm")]
+    async fn compiled_casm(&self, class_hash: FieldElement) -> RpcResult<CompiledCasm>;
+
     /// Get the number of transactions in a block given a block id.
     #[method(name = "getBlockTransactionCount")]
     async fn block_transaction_count(&self, block_id: BlockIdOrTag) -> RpcResult<BlockTxCount>;
@@ -1,7 +1,8 @@
 use katana_primitives::block::BlockHashOrNumber;
 use katana_provider::traits::block::{BlockHashProvider, BlockProvider, BlockStatusProvider};
+use katana_provider::traits::transaction::ReceiptProvider;
 use katana_provider::ProviderResult;
-use katana_rpc_types::block::{BlockWithTxHashes, BlockWithTxs};
+use katana_rpc_types::block::{BlockWithReceipts, BlockWithTxHashes, BlockWithTxs};
 
 /// A builder for building RPC block types.
 pub struct BlockBuilder<P> {
@@ -45,3 +46,23 @@ where
         Ok(Some(BlockWithTxHashes::new(hash, block, finality_status)))
     }
 }
+
+impl<P> BlockBuilder<P>
+where
+    P: BlockProvider + BlockHashProvider + ReceiptProvider,
+{
+    pub fn build_with_receipts(self) -> ProviderResult<Option<BlockWithReceipts>> {
+        let Some(hash) = BlockHashProvider::block_hash_by_id(&self.provider, self.block_id)? else {
+            return Ok(None);
+        };
+
+        let block = BlockProvider::block(&self.provider, self.block_id)?
+            .expect("should exist if block exists");
+        let finality_status = BlockStatusProvider::block_status(&self.provider, self.block_id)?
+            .expect("should exist if block exists");
+        let receipts = ReceiptProvider::receipts_by_block(&self.provider, self.block_id)?
+            .expect("should exist if block exists");
+
+        Ok(Some(BlockWithReceipts::new(hash, block, finality_status, receipts)))
+    }
+}
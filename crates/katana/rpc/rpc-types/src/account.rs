@@ -1,6 +1,6 @@
 use ethers::types::U256;
 use katana_primitives::contract::{ClassHash, ContractAddress};
-use katana_primitives::genesis::allocation::GenesisAccountAlloc;
+use katana_primitives::genesis::allocation::{DevAccountClass, GenesisAccountAlloc};
 use katana_primitives::FieldElement;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
@@ -18,6 +18,9 @@ pub struct Account {
     pub private_key: Option<FieldElement>,
     #[serde_as(as = "UfeHex")]
     pub class_hash: ClassHash,
+    /// The name of the account class if it's one of the built-in classes, eg `oz` or `argent`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub class_name: Option<String>,
     pub balance: U256,
 }
 
@@ -28,6 +31,9 @@ impl Account {
             public_key: account.public_key(),
             private_key: account.private_key(),
             class_hash: account.class_hash(),
+            class_name: DevAccountClass::from_class_hash(account.class_hash())
+                .name()
+                .map(String::from),
             balance: account.balance().unwrap_or_default(),
         }
     }
@@ -1,8 +1,15 @@
 use katana_primitives::block::{Block, BlockHash, BlockNumber, FinalityStatus, PartialHeader};
+use katana_primitives::receipt::Receipt;
 use katana_primitives::transaction::{TxHash, TxWithHash};
+use katana_primitives::FieldElement;
 use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use starknet::core::serde::unsigned_field_element::UfeHex;
 use starknet::core::types::{BlockStatus, ResourcePrice};
 
+use crate::receipt::{PendingTxReceipt, TxReceipt};
+use crate::transaction::Tx;
+
 pub type BlockTxCount = u64;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -131,6 +138,132 @@ pub enum MaybePendingBlockWithTxHashes {
     Block(BlockWithTxHashes),
 }
 
+/// A transaction along with its receipt.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TxWithReceipt<R> {
+    pub transaction: Tx,
+    pub receipt: R,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockWithReceipts {
+    pub status: BlockStatus,
+    #[serde(flatten)]
+    pub header: BlockHeader,
+    pub transactions: Vec<TxWithReceipt<TxReceipt>>,
+}
+
+impl BlockWithReceipts {
+    pub fn new(
+        block_hash: BlockHash,
+        block: Block,
+        finality_status: FinalityStatus,
+        receipts: Vec<Receipt>,
+    ) -> Self {
+        let Block { header, body } = block;
+
+        let transactions = body
+            .into_iter()
+            .zip(receipts)
+            .map(|(tx, receipt)| {
+                let receipt =
+                    TxReceipt::new(tx.hash, header.number, block_hash, finality_status, receipt);
+                TxWithReceipt { transaction: Tx::from(tx), receipt }
+            })
+            .collect();
+
+        let status = match finality_status {
+            FinalityStatus::AcceptedOnL1 => BlockStatus::AcceptedOnL1,
+            FinalityStatus::AcceptedOnL2 => BlockStatus::AcceptedOnL2,
+        };
+
+        let header = BlockHeader {
+            block_hash,
+            new_root: header.state_root,
+            timestamp: header.timestamp,
+            block_number: header.number,
+            parent_hash: header.parent_hash,
+            starknet_version: header.version.to_string(),
+            sequencer_address: header.sequencer_address.into(),
+            l1_gas_price: ResourcePrice {
+                price_in_wei: header.gas_prices.eth.into(),
+                price_in_fri: header.gas_prices.strk.into(),
+            },
+        };
+
+        Self { status, header, transactions }
+    }
+}
+
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockHeader {
+    #[serde_as(as = "UfeHex")]
+    pub block_hash: BlockHash,
+    #[serde_as(as = "UfeHex")]
+    pub parent_hash: BlockHash,
+    pub block_number: BlockNumber,
+    #[serde_as(as = "UfeHex")]
+    pub new_root: FieldElement,
+    pub timestamp: u64,
+    #[serde_as(as = "UfeHex")]
+    pub sequencer_address: FieldElement,
+    pub l1_gas_price: ResourcePrice,
+    pub starknet_version: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingBlockWithReceipts {
+    #[serde(flatten)]
+    pub header: PendingBlockHeader,
+    pub transactions: Vec<TxWithReceipt<PendingTxReceipt>>,
+}
+
+impl PendingBlockWithReceipts {
+    pub fn new(header: PartialHeader, transactions: Vec<(TxWithHash, Receipt)>) -> Self {
+        let transactions = transactions
+            .into_iter()
+            .map(|(tx, receipt)| {
+                let receipt = PendingTxReceipt::new(tx.hash, receipt);
+                TxWithReceipt { transaction: Tx::from(tx), receipt }
+            })
+            .collect();
+
+        let header = PendingBlockHeader {
+            timestamp: header.timestamp,
+            parent_hash: header.parent_hash,
+            starknet_version: header.version.to_string(),
+            sequencer_address: header.sequencer_address.into(),
+            l1_gas_price: ResourcePrice {
+                price_in_wei: header.gas_prices.eth.into(),
+                price_in_fri: header.gas_prices.strk.into(),
+            },
+        };
+
+        Self { header, transactions }
+    }
+}
+
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingBlockHeader {
+    #[serde_as(as = "UfeHex")]
+    pub parent_hash: BlockHash,
+    pub timestamp: u64,
+    #[serde_as(as = "UfeHex")]
+    pub sequencer_address: FieldElement,
+    pub l1_gas_price: ResourcePrice,
+    pub starknet_version: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum MaybePendingBlockWithReceipts {
+    // must be tried first, a block being a valid pending block as well
+    Block(BlockWithReceipts),
+    Pending(PendingBlockWithReceipts),
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(transparent)]
 pub struct BlockHashAndNumber(starknet::core::types::BlockHashAndNumber);
@@ -11,6 +11,20 @@ pub enum KatanaApiError {
     FailedToDumpState = 2,
     #[error("Failed to update storage.")]
     FailedToUpdateStorage = 3,
+    #[error("Messaging is not configured with a simulated settlement chain.")]
+    MessagingNotSimulated = 4,
+    #[error("Message not found or already consumed.")]
+    MessageToL1NotFound = 5,
+    #[error("No messages found for the L1 transaction.")]
+    L1TransactionNotFound = 6,
+    #[error("Failed to export genesis.")]
+    FailedToExportGenesis = 7,
+    #[error("The node is not forked from a network.")]
+    NotForked = 8,
+    #[error("Failed to reset the fork.")]
+    FailedToResetFork = 9,
+    #[error("The transaction is not an L1 handler transaction.")]
+    NotL1HandlerTransaction = 10,
 }
 
 impl From<KatanaApiError> for Error {
@@ -18,3 +32,20 @@ impl From<KatanaApiError> for Error {
         Error::Call(CallError::Custom(ErrorObject::owned(err as i32, err.to_string(), None::<()>)))
     }
 }
+
+impl KatanaApiError {
+    /// Returns the error with the cause of the failure as its data.
+    pub fn with_reason(self, reason: impl ToString) -> Error {
+        let data = FailureReason { reason: reason.to_string() };
+        Error::Call(CallError::Custom(ErrorObject::owned(
+            self as i32,
+            self.to_string(),
+            Some(data),
+        )))
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct FailureReason {
+    reason: String,
+}
@@ -2,8 +2,9 @@ use jsonrpsee::core::Error;
 use jsonrpsee::types::error::CallError;
 use jsonrpsee::types::ErrorObject;
 use katana_core::sequencer_error::SequencerError;
+use katana_executor::abstraction::ExecutorError;
+use katana_primitives::receipt::RevertReason;
 use katana_provider::error::ProviderError;
-use starknet::core::types::ContractErrorData;
 
 /// Possible list of errors that can be returned by the Starknet API according to the spec: <https://github.com/starkware-libs/starknet-specs>.
 #[derive(Debug, thiserror::Error, Clone)]
@@ -32,7 +33,7 @@ pub enum StarknetApiError {
     #[error("The supplied continuation token is invalid or unknown")]
     InvalidContinuationToken,
     #[error("Contract error")]
-    ContractError { revert_error: String },
+    ContractError { revert_error: String, revert_reason: Option<RevertReason> },
     #[error("Invalid contract class")]
     InvalidContractClass,
     #[error("Class already declared")]
@@ -105,6 +106,16 @@ impl StarknetApiError {
     }
 }
 
+/// The data of a [StarknetApiError::ContractError]. The `revert_error` is the one of the spec, and
+/// the structured `revert_reason`, with the failing contract, selector and call path, is added
+/// when available.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct ContractErrorData {
+    revert_error: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    revert_reason: Option<RevertReason>,
+}
+
 #[derive(serde::Serialize, serde::Deserialize)]
 struct UnexpectedError {
     reason: String,
@@ -122,9 +133,11 @@ impl From<StarknetApiError> for Error {
         let message = err.to_string();
 
         let err = match err {
-            StarknetApiError::ContractError { revert_error } => {
-                ErrorObject::owned(code, message, Some(ContractErrorData { revert_error }))
-            }
+            StarknetApiError::ContractError { revert_error, revert_reason } => ErrorObject::owned(
+                code,
+                message,
+                Some(ContractErrorData { revert_error, revert_reason }),
+            ),
 
             StarknetApiError::UnexpectedError { reason } => {
                 ErrorObject::owned(code, message, Some(UnexpectedError { reason }))
@@ -141,13 +154,25 @@ impl From<SequencerError> for StarknetApiError {
     fn from(value: SequencerError) -> Self {
         match value {
             SequencerError::TransactionExecution(e) => {
-                StarknetApiError::ContractError { revert_error: e.to_string() }
+                StarknetApiError::ContractError { revert_error: e.to_string(), revert_reason: None }
             }
             SequencerError::EntryPointExecution(e) => {
-                StarknetApiError::ContractError { revert_error: e.to_string() }
+                StarknetApiError::ContractError { revert_error: e.to_string(), revert_reason: None }
+            }
+            SequencerError::Executor(ExecutorError::ContractNotDeployed(_)) => {
+                StarknetApiError::ContractNotFound
+            }
+            SequencerError::Executor(ExecutorError::TransactionExecution(reason)) => {
+                StarknetApiError::ContractError {
+                    revert_error: reason.to_string(),
+                    revert_reason: Some(reason),
+                }
             }
             SequencerError::BlockNotFound(_) => StarknetApiError::BlockNotFound,
             SequencerError::ContractNotFound(_) => StarknetApiError::ContractNotFound,
+            SequencerError::InsufficientResourceBounds { .. } => {
+                StarknetApiError::InsufficientMaxFee
+            }
             err => StarknetApiError::UnexpectedError { reason: err.to_string() },
         }
     }
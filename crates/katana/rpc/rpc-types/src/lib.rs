@@ -49,6 +49,9 @@ pub type FeeEstimate = starknet::core::types::FeeEstimate;
 
 pub type ContractClass = starknet::core::types::ContractClass;
 
+/// The CASM of a Sierra class, in the format produced by the Sierra compiler.
+pub type CompiledCasm = serde_json::Value;
+
 pub type SimulationFlags = starknet::core::types::SimulationFlagForEstimateFee;
 
 pub type SyncingStatus = starknet::core::types::SyncStatusType;
@@ -1,26 +1,46 @@
 use katana_primitives::chain::ChainId;
 use katana_primitives::transaction::L1HandlerTx;
-use katana_primitives::utils::transaction::compute_l1_message_hash;
+use katana_primitives::utils::transaction::compute_l1_to_l2_message_hash;
 use katana_primitives::FieldElement;
 use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use starknet::core::serde::unsigned_field_element::UfeHex;
+use starknet::core::types::Hash256;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MsgFromL1(starknet::core::types::MsgFromL1);
 
+impl From<MsgFromL1> for starknet::core::types::MsgFromL1 {
+    fn from(value: MsgFromL1) -> Self {
+        value.0
+    }
+}
+
 impl MsgFromL1 {
+    /// Converts the message into the L1 handler transaction that processes it. As with the
+    /// messages gathered from the settlement chain, the sender of the message is prepended to the
+    /// payload in the transaction calldata.
     pub fn into_tx_with_chain_id(self, chain_id: ChainId) -> L1HandlerTx {
-        let message_hash = compute_l1_message_hash(
-            // This conversion will never fail bcs `from_address` is 20 bytes and the it will only
-            // fail if the slice is > 32 bytes
-            FieldElement::from_byte_slice_be(self.0.from_address.as_bytes()).unwrap(),
+        // This conversion will never fail bcs `from_address` is 20 bytes and the it will only
+        // fail if the slice is > 32 bytes
+        let from_address =
+            FieldElement::from_byte_slice_be(self.0.from_address.as_bytes()).unwrap();
+
+        let message_hash = compute_l1_to_l2_message_hash(
+            from_address,
             self.0.to_address,
+            self.0.entry_point_selector,
             &self.0.payload,
+            FieldElement::ZERO,
         );
 
+        let mut calldata = vec![from_address];
+        calldata.extend(self.0.payload);
+
         L1HandlerTx {
             chain_id,
             message_hash,
-            calldata: self.0.payload,
+            calldata,
             nonce: Default::default(),
             version: FieldElement::ZERO,
             paid_fee_on_l1: Default::default(),
@@ -29,3 +49,81 @@ impl MsgFromL1 {
         }
     }
 }
+
+/// A message sent to L2 from the simulated settlement chain.
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageToL2Sent {
+    /// The hash of the message.
+    pub message_hash: Hash256,
+    /// The hash of the L1 handler transaction that will process the message.
+    #[serde_as(as = "UfeHex")]
+    pub transaction_hash: FieldElement,
+    /// The hash of the simulated settlement chain transaction that sent the message, to be used
+    /// to look up the message status.
+    pub l1_transaction_hash: Hash256,
+}
+
+/// A message sent to the simulated settlement chain.
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageToL1WithStatus {
+    /// The hash of the message, used to consume it.
+    pub message_hash: Hash256,
+    /// The L2 contract address that sent the message.
+    #[serde_as(as = "UfeHex")]
+    pub from_address: FieldElement,
+    /// The L1 contract address that the message is sent to.
+    #[serde_as(as = "UfeHex")]
+    pub to_address: FieldElement,
+    /// The payload of the message.
+    #[serde_as(as = "Vec<UfeHex>")]
+    pub payload: Vec<FieldElement>,
+    /// Whether the message has been consumed.
+    pub consumed: bool,
+}
+
+/// A message to L1 that couldn't be sent to the settlement chain.
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeadLetterMessage {
+    /// The block in which the message was sent.
+    pub block_number: u64,
+    /// The L2 contract address that sent the message.
+    #[serde_as(as = "UfeHex")]
+    pub from_address: FieldElement,
+    /// The L1 contract address that the message is sent to.
+    #[serde_as(as = "UfeHex")]
+    pub to_address: FieldElement,
+    /// The payload of the message.
+    #[serde_as(as = "Vec<UfeHex>")]
+    pub payload: Vec<FieldElement>,
+    /// The number of times the sending of the message was attempted.
+    pub attempts: u32,
+    /// The error of the last attempt.
+    pub error: String,
+}
+
+/// The status of a message sent from L1, in the format of `starknet_getMessagesStatus`.
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageStatus {
+    /// The hash of the L1 handler transaction that processes the message.
+    #[serde_as(as = "UfeHex")]
+    pub transaction_hash: FieldElement,
+    /// The status of the L1 handler transaction.
+    pub finality_status: MessageFinalityStatus,
+    /// The reason of the failure, if the message was rejected or its transaction reverted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure_reason: Option<String>,
+}
+
+/// The status of the L1 handler transaction processing a message sent from L1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum MessageFinalityStatus {
+    Received,
+    Rejected,
+    AcceptedOnL2,
+    AcceptedOnL1,
+}
@@ -1,5 +1,5 @@
 use katana_primitives::block::{BlockHash, BlockNumber, FinalityStatus};
-use katana_primitives::receipt::{MessageToL1, Receipt, TxExecutionResources};
+use katana_primitives::receipt::{MessageToL1, Receipt, RevertReason, TxExecutionResources};
 use katana_primitives::transaction::TxHash;
 use serde::{Deserialize, Serialize};
 use starknet::core::types::{
@@ -40,13 +40,12 @@ impl TxReceipt {
                     messages_sent,
                     finality_status,
                     transaction_hash,
-                    actual_fee: FeePayment { amount: rct.actual_fee.into(), unit: PriceUnit::Wei },
-                    execution_resources: ExecutionResources::from(rct.execution_resources).0,
-                    execution_result: if let Some(reason) = rct.revert_error {
-                        ExecutionResult::Reverted { reason }
-                    } else {
-                        ExecutionResult::Succeeded
+                    actual_fee: FeePayment {
+                        amount: rct.actual_fee.into(),
+                        unit: rct.fee_unit.into(),
                     },
+                    execution_resources: ExecutionResources::from(rct.execution_resources).0,
+                    execution_result: execution_result(rct.revert_error, rct.revert_reason),
                 })
             }
 
@@ -62,13 +61,12 @@ impl TxReceipt {
                     messages_sent,
                     finality_status,
                     transaction_hash,
-                    actual_fee: FeePayment { amount: rct.actual_fee.into(), unit: PriceUnit::Wei },
-                    execution_resources: ExecutionResources::from(rct.execution_resources).0,
-                    execution_result: if let Some(reason) = rct.revert_error {
-                        ExecutionResult::Reverted { reason }
-                    } else {
-                        ExecutionResult::Succeeded
+                    actual_fee: FeePayment {
+                        amount: rct.actual_fee.into(),
+                        unit: rct.fee_unit.into(),
                     },
+                    execution_resources: ExecutionResources::from(rct.execution_resources).0,
+                    execution_result: execution_result(rct.revert_error, rct.revert_reason),
                 })
             }
 
@@ -87,11 +85,7 @@ impl TxReceipt {
                     actual_fee: FeePayment { amount: rct.actual_fee.into(), unit: PriceUnit::Wei },
                     execution_resources: ExecutionResources::from(rct.execution_resources).0,
                     message_hash: Hash256::from_bytes(rct.message_hash.to_fixed_bytes()),
-                    execution_result: if let Some(reason) = rct.revert_error {
-                        ExecutionResult::Reverted { reason }
-                    } else {
-                        ExecutionResult::Succeeded
-                    },
+                    execution_result: execution_result(rct.revert_error, rct.revert_reason),
                 })
             }
 
@@ -107,14 +101,13 @@ impl TxReceipt {
                     messages_sent,
                     finality_status,
                     transaction_hash,
-                    actual_fee: FeePayment { amount: rct.actual_fee.into(), unit: PriceUnit::Wei },
+                    actual_fee: FeePayment {
+                        amount: rct.actual_fee.into(),
+                        unit: rct.fee_unit.into(),
+                    },
                     contract_address: rct.contract_address.into(),
                     execution_resources: ExecutionResources::from(rct.execution_resources).0,
-                    execution_result: if let Some(reason) = rct.revert_error {
-                        ExecutionResult::Reverted { reason }
-                    } else {
-                        ExecutionResult::Succeeded
-                    },
+                    execution_result: execution_result(rct.revert_error, rct.revert_reason),
                 })
             }
         };
@@ -139,13 +132,12 @@ impl PendingTxReceipt {
                     transaction_hash,
                     events,
                     messages_sent,
-                    actual_fee: FeePayment { amount: rct.actual_fee.into(), unit: PriceUnit::Wei },
-                    execution_resources: ExecutionResources::from(rct.execution_resources).0,
-                    execution_result: if let Some(reason) = rct.revert_error {
-                        ExecutionResult::Reverted { reason }
-                    } else {
-                        ExecutionResult::Succeeded
+                    actual_fee: FeePayment {
+                        amount: rct.actual_fee.into(),
+                        unit: rct.fee_unit.into(),
                     },
+                    execution_resources: ExecutionResources::from(rct.execution_resources).0,
+                    execution_result: execution_result(rct.revert_error, rct.revert_reason),
                 })
             }
 
@@ -158,13 +150,12 @@ impl PendingTxReceipt {
                     events,
                     transaction_hash,
                     messages_sent,
-                    actual_fee: FeePayment { amount: rct.actual_fee.into(), unit: PriceUnit::Wei },
-                    execution_resources: ExecutionResources::from(rct.execution_resources).0,
-                    execution_result: if let Some(reason) = rct.revert_error {
-                        ExecutionResult::Reverted { reason }
-                    } else {
-                        ExecutionResult::Succeeded
+                    actual_fee: FeePayment {
+                        amount: rct.actual_fee.into(),
+                        unit: rct.fee_unit.into(),
                     },
+                    execution_resources: ExecutionResources::from(rct.execution_resources).0,
+                    execution_result: execution_result(rct.revert_error, rct.revert_reason),
                 })
             }
 
@@ -180,11 +171,7 @@ impl PendingTxReceipt {
                     actual_fee: FeePayment { amount: rct.actual_fee.into(), unit: PriceUnit::Wei },
                     execution_resources: ExecutionResources::from(rct.execution_resources).0,
                     message_hash: Hash256::from_bytes(rct.message_hash.0),
-                    execution_result: if let Some(reason) = rct.revert_error {
-                        ExecutionResult::Reverted { reason }
-                    } else {
-                        ExecutionResult::Succeeded
-                    },
+                    execution_result: execution_result(rct.revert_error, rct.revert_reason),
                 })
             }
 
@@ -197,14 +184,13 @@ impl PendingTxReceipt {
                     transaction_hash,
                     events,
                     messages_sent,
-                    actual_fee: FeePayment { amount: rct.actual_fee.into(), unit: PriceUnit::Wei },
+                    actual_fee: FeePayment {
+                        amount: rct.actual_fee.into(),
+                        unit: rct.fee_unit.into(),
+                    },
                     contract_address: rct.contract_address.into(),
                     execution_resources: ExecutionResources::from(rct.execution_resources).0,
-                    execution_result: if let Some(reason) = rct.revert_error {
-                        ExecutionResult::Reverted { reason }
-                    } else {
-                        ExecutionResult::Succeeded
-                    },
+                    execution_result: execution_result(rct.revert_error, rct.revert_reason),
                 })
             }
         };
@@ -268,3 +254,15 @@ impl From<TxExecutionResources> for ExecutionResources {
         })
     }
 }
+
+/// The execution result of a transaction. The revert reason is reported in its decoded form when
+/// available.
+fn execution_result(
+    revert_error: Option<String>,
+    revert_reason: Option<RevertReason>,
+) -> ExecutionResult {
+    match revert_reason.map(|reason| reason.to_string()).or(revert_error) {
+        Some(reason) => ExecutionResult::Reverted { reason },
+        None => ExecutionResult::Succeeded,
+    }
+}
@@ -98,6 +98,7 @@ impl BroadcastedDeclareTx {
                 Ok(DeclareTxWithClass {
                     compiled_class,
                     sierra_class: None,
+                    legacy_abi: Some(tx.contract_class.abi.clone().unwrap_or_default()),
                     transaction: DeclareTx::V1(DeclareTxV1 {
                         chain_id,
                         class_hash,
@@ -117,6 +118,7 @@ impl BroadcastedDeclareTx {
                 Ok(DeclareTxWithClass {
                     compiled_class,
                     sierra_class: Arc::into_inner(tx.contract_class),
+                    legacy_abi: None,
                     transaction: DeclareTx::V2(DeclareTxV2 {
                         chain_id,
                         class_hash,
@@ -137,6 +139,7 @@ impl BroadcastedDeclareTx {
                 Ok(DeclareTxWithClass {
                     compiled_class,
                     sierra_class: Arc::into_inner(tx.contract_class),
+                    legacy_abi: None,
                     transaction: DeclareTx::V3(DeclareTxV3 {
                         chain_id,
                         class_hash,
@@ -1,11 +1,29 @@
+use std::net::IpAddr;
+
 use katana_rpc_api::ApiKind;
 
+/// The default maximum size of a request or response body, in bytes.
+pub const DEFAULT_MAX_BODY_SIZE: u32 = 10 * 1024 * 1024;
+
 #[derive(Debug, Clone)]
 pub struct ServerConfig {
     pub port: u16,
     pub host: String,
     pub max_connections: u32,
     pub apis: Vec<ApiKind>,
+    /// Maximum size of a request body, in bytes.
+    pub max_request_body_size: u32,
+    /// Maximum size of a response body, in bytes.
+    pub max_response_body_size: u32,
+    /// Maximum number of calls in a batch request. Unlimited if `None`.
+    pub max_batch_size: Option<u32>,
+    /// Maximum number of calls per second from a single client IP. Unlimited if `None`.
+    ///
+    /// The requests are then served by a front server which only accepts plain HTTP requests.
+    pub rate_limit: Option<u32>,
+    /// The reverse proxies whose `X-Forwarded-For` and `X-Real-IP` headers are trusted to identify
+    /// the rate limited clients.
+    pub trusted_proxies: Vec<IpAddr>,
 }
 
 impl ServerConfig {
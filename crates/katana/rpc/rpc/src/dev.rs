@@ -1,10 +1,26 @@
 use std::sync::Arc;
 
 use jsonrpsee::core::{async_trait, Error};
+use katana_core::backend::fork::ForkError;
 use katana_core::sequencer::KatanaSequencer;
+#[cfg(feature = "messaging")]
+use katana_core::service::messaging::SimulatedMessaging;
+use katana_primitives::block::BlockIdOrTag;
+#[cfg(feature = "messaging")]
+use katana_primitives::transaction::{ExecutableTxWithHash, L1MessageHash};
 use katana_primitives::FieldElement;
+#[cfg(feature = "messaging")]
+use katana_provider::traits::messaging::MessagingCheckpointProvider;
 use katana_rpc_api::dev::DevApiServer;
 use katana_rpc_types::error::katana::KatanaApiError;
+#[cfg(feature = "messaging")]
+use katana_rpc_types::error::starknet::StarknetApiError;
+use katana_rpc_types::message::{MessageToL1WithStatus, MessageToL2Sent, MsgFromL1};
+#[cfg(feature = "messaging")]
+use starknet::core::types::BlockTag;
+use starknet::core::types::Hash256;
+use tracing::warn;
+use url::Url;
 
 pub struct DevApi {
     sequencer: Arc<KatanaSequencer>,
@@ -14,6 +30,13 @@ impl DevApi {
     pub fn new(sequencer: Arc<KatanaSequencer>) -> Self {
         Self { sequencer }
     }
+
+    #[cfg(feature = "messaging")]
+    fn simulated_messaging(&self) -> Result<&SimulatedMessaging, Error> {
+        self.sequencer
+            .simulated_messaging()
+            .ok_or_else(|| Error::from(KatanaApiError::MessagingNotSimulated))
+    }
 }
 
 #[async_trait]
@@ -40,6 +63,17 @@ impl DevApiServer for DevApi {
             .map_err(|_| Error::from(KatanaApiError::FailedToChangeNextBlockTimestamp))
     }
 
+    async fn reset_fork(&self, url: Option<Url>, block: Option<BlockIdOrTag>) -> Result<(), Error> {
+        match self.sequencer.reset_fork(url, block).await {
+            Ok(_) => Ok(()),
+            Err(ForkError::NotForked) => Err(KatanaApiError::NotForked.into()),
+            Err(err) => {
+                warn!(target: "rpc", error = %err, "Failed to reset the fork.");
+                Err(KatanaApiError::FailedToResetFork.into())
+            }
+        }
+    }
+
     async fn set_storage_at(
         &self,
         _contract_address: FieldElement,
@@ -52,4 +86,94 @@ impl DevApiServer for DevApi {
         //     .map_err(|_| Error::from(KatanaApiError::FailedToUpdateStorage))
         Ok(())
     }
+
+    #[cfg(feature = "messaging")]
+    async fn send_message_to_l2(
+        &self,
+        message: MsgFromL1,
+        paid_fee_on_l1: Option<u128>,
+    ) -> Result<MessageToL2Sent, Error> {
+        let messaging = self.simulated_messaging()?;
+        let provider = self.sequencer.backend().blockchain.provider();
+        let chain_id = self.sequencer.chain_id();
+
+        let paid_fee_on_l1 = match paid_fee_on_l1 {
+            Some(fee) => fee,
+            None => {
+                let tx = message.clone().into_tx_with_chain_id(chain_id);
+                let hash = tx.calculate_hash();
+                let tx = ExecutableTxWithHash { hash, transaction: tx.into() };
+
+                let estimate = self
+                    .sequencer
+                    .estimate_fee(vec![tx], BlockIdOrTag::Tag(BlockTag::Latest), false)
+                    .map_err(StarknetApiError::from)?
+                    .pop()
+                    .expect("should have estimate result");
+
+                u128::try_from(estimate.overall_fee).unwrap_or(u128::MAX)
+            }
+        };
+
+        let tx = messaging.send_message_to_l2(message.into(), paid_fee_on_l1, chain_id, |hash| {
+            // a failure to read the storage is treated as if the message was processed, the
+            // next nonce is then used
+            provider.l1_handler_tx_by_message_hash(hash).map_or(true, |tx| tx.is_some())
+        });
+
+        // each message is sent by its own simulated transaction, identified by the message hash
+        let message_hash = Hash256::from_bytes(tx.message_hash.to_fixed_bytes());
+
+        Ok(MessageToL2Sent {
+            message_hash,
+            transaction_hash: tx.calculate_hash(),
+            l1_transaction_hash: message_hash,
+        })
+    }
+
+    #[cfg(feature = "messaging")]
+    async fn messages_to_l1(&self) -> Result<Vec<MessageToL1WithStatus>, Error> {
+        let messages = self.simulated_messaging()?.messages_to_l1();
+
+        Ok(messages
+            .into_iter()
+            .map(|m| MessageToL1WithStatus {
+                message_hash: Hash256::from_bytes(m.hash.to_fixed_bytes()),
+                from_address: m.message.from_address.into(),
+                to_address: m.message.to_address,
+                payload: m.message.payload,
+                consumed: m.consumed,
+            })
+            .collect())
+    }
+
+    #[cfg(feature = "messaging")]
+    async fn consume_message_to_l1(&self, message_hash: Hash256) -> Result<(), Error> {
+        let hash = L1MessageHash::from(*message_hash.as_bytes());
+
+        if self.simulated_messaging()?.consume_message_to_l1(hash) {
+            Ok(())
+        } else {
+            Err(KatanaApiError::MessageToL1NotFound.into())
+        }
+    }
+
+    #[cfg(not(feature = "messaging"))]
+    async fn send_message_to_l2(
+        &self,
+        _message: MsgFromL1,
+        _paid_fee_on_l1: Option<u128>,
+    ) -> Result<MessageToL2Sent, Error> {
+        Err(KatanaApiError::MessagingNotSimulated.into())
+    }
+
+    #[cfg(not(feature = "messaging"))]
+    async fn messages_to_l1(&self) -> Result<Vec<MessageToL1WithStatus>, Error> {
+        Err(KatanaApiError::MessagingNotSimulated.into())
+    }
+
+    #[cfg(not(feature = "messaging"))]
+    async fn consume_message_to_l1(&self, _message_hash: Hash256) -> Result<(), Error> {
+        Err(KatanaApiError::MessagingNotSimulated.into())
+    }
 }
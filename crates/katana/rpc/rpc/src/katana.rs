@@ -1,9 +1,38 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use jsonrpsee::core::{async_trait, Error};
+use katana_core::backend::genesis::export_genesis;
 use katana_core::sequencer::KatanaSequencer;
+#[cfg(feature = "messaging")]
+use katana_core::service::messaging::GatheredMessageStatus;
+use katana_executor::abstraction::EntryPointCall;
+#[cfg(feature = "messaging")]
+use katana_primitives::block::FinalityStatus;
+use katana_primitives::block::{BlockIdOrTag, BlockNumber};
+use katana_primitives::contract::ClassHash;
+use katana_primitives::genesis::json::GenesisJson;
+use katana_primitives::receipt::{CallProfile, RevertReason, TxExecutionResources};
+#[cfg(feature = "messaging")]
+use katana_primitives::transaction::L1TxHash;
+use katana_primitives::transaction::Tx;
+use katana_primitives::utils::class::entry_point_names;
+use katana_primitives::FieldElement;
+use katana_provider::traits::state::StateProvider;
+use katana_provider::traits::transaction::TransactionProvider;
+#[cfg(feature = "messaging")]
+use katana_provider::traits::transaction::{ReceiptProvider, TransactionStatusProvider};
 use katana_rpc_api::katana::KatanaApiServer;
 use katana_rpc_types::account::Account;
+use katana_rpc_types::block::BlockHashAndNumber;
+use katana_rpc_types::error::katana::KatanaApiError;
+use katana_rpc_types::error::starknet::StarknetApiError;
+#[cfg(feature = "messaging")]
+use katana_rpc_types::message::MessageFinalityStatus;
+use katana_rpc_types::message::{DeadLetterMessage, MessageStatus};
+use katana_rpc_types::{FeltAsHex, FunctionCall};
+use katana_tasks::TokioTaskSpawner;
+use starknet::core::types::{BlockTag, Hash256};
 
 pub struct KatanaApi {
     sequencer: Arc<KatanaSequencer>,
@@ -13,6 +42,58 @@ impl KatanaApi {
     pub fn new(sequencer: Arc<KatanaSequencer>) -> Self {
         Self { sequencer }
     }
+
+    /// Returns the id of the last block mined at or before `timestamp`.
+    fn block_id_at_timestamp(&self, timestamp: u64) -> Result<BlockIdOrTag, Error> {
+        let (_, number) = self
+            .sequencer
+            .block_hash_and_number_at_timestamp(timestamp)
+            .map_err(StarknetApiError::from)?
+            .ok_or(StarknetApiError::BlockNotFound)?;
+
+        Ok(BlockIdOrTag::Number(number))
+    }
+
+    #[cfg(feature = "messaging")]
+    fn message_status(&self, status: GatheredMessageStatus) -> Result<MessageStatus, Error> {
+        let transaction_hash = match status {
+            GatheredMessageStatus::Accepted { transaction_hash } => transaction_hash,
+            GatheredMessageStatus::Rejected { transaction_hash, paid_fee_on_l1, estimated_fee } => {
+                return Ok(MessageStatus {
+                    transaction_hash,
+                    finality_status: MessageFinalityStatus::Rejected,
+                    failure_reason: Some(format!(
+                        "Insufficient fee paid on L1: paid {paid_fee_on_l1}, estimated \
+                         {estimated_fee}"
+                    )),
+                });
+            }
+            GatheredMessageStatus::Failed { transaction_hash, reason } => {
+                return Ok(MessageStatus {
+                    transaction_hash,
+                    finality_status: MessageFinalityStatus::Rejected,
+                    failure_reason: Some(reason),
+                });
+            }
+        };
+
+        let provider = self.sequencer.backend.blockchain.provider();
+        let status = TransactionStatusProvider::transaction_status(provider, transaction_hash)
+            .map_err(StarknetApiError::from)?;
+        let receipt = ReceiptProvider::receipt_by_hash(provider, transaction_hash)
+            .map_err(StarknetApiError::from)?;
+
+        let finality_status = match status {
+            Some(FinalityStatus::AcceptedOnL1) => MessageFinalityStatus::AcceptedOnL1,
+            Some(FinalityStatus::AcceptedOnL2) => MessageFinalityStatus::AcceptedOnL2,
+            None => MessageFinalityStatus::Received,
+        };
+
+        let failure_reason =
+            receipt.and_then(|receipt| receipt.revert_reason().map(|reason| reason.to_string()));
+
+        Ok(MessageStatus { transaction_hash, finality_status, failure_reason })
+    }
 }
 
 #[async_trait]
@@ -27,4 +108,228 @@ impl KatanaApiServer for KatanaApi {
             .map(|e| Account::new(*e.0, e.1))
             .collect())
     }
+
+    async fn transaction_execution_resources(
+        &self,
+        transaction_hash: FieldElement,
+    ) -> Result<TxExecutionResources, Error> {
+        let receipt = self
+            .sequencer
+            .receipt(&transaction_hash)
+            .map_err(StarknetApiError::from)?
+            .ok_or(StarknetApiError::TxnHashNotFound)?;
+
+        Ok(receipt.execution_resources().clone())
+    }
+
+    async fn transaction_revert_reason(
+        &self,
+        transaction_hash: FieldElement,
+    ) -> Result<Option<RevertReason>, Error> {
+        let receipt = self
+            .sequencer
+            .receipt(&transaction_hash)
+            .map_err(StarknetApiError::from)?
+            .ok_or(StarknetApiError::TxnHashNotFound)?;
+
+        // the receipts fetched from another node only have the formatted reason
+        let reason = receipt.revert_reason().cloned().or_else(|| {
+            let reason = receipt.revert_error()?.to_string();
+            Some(RevertReason { reason, ..Default::default() })
+        });
+
+        Ok(reason)
+    }
+
+    async fn paid_fee_on_l1(&self, transaction_hash: FieldElement) -> Result<FeltAsHex, Error> {
+        let tx = self
+            .sequencer
+            .transaction(&transaction_hash)
+            .map_err(StarknetApiError::from)?
+            .ok_or(StarknetApiError::TxnHashNotFound)?;
+
+        match tx.transaction {
+            Tx::L1Handler(tx) => Ok(FieldElement::from(tx.paid_fee_on_l1).into()),
+            _ => Err(KatanaApiError::NotL1HandlerTransaction.into()),
+        }
+    }
+
+    async fn transaction_flamegraph(
+        &self,
+        transaction_hash: FieldElement,
+    ) -> Result<String, Error> {
+        let resources = self.transaction_execution_resources(transaction_hash).await?;
+
+        // the classes of the called contracts may have been replaced since, so the names are
+        // resolved on the state of the block of the transaction
+        let provider = self.sequencer.backend().blockchain.provider();
+        let block = TransactionProvider::transaction_block_num_and_hash(provider, transaction_hash)
+            .map_err(StarknetApiError::from)?;
+        let block_id = match block {
+            Some((block_num, _)) => BlockIdOrTag::Number(block_num),
+            None => BlockIdOrTag::Tag(BlockTag::Pending),
+        };
+        let state = self.sequencer.state(&block_id).map_err(StarknetApiError::from)?;
+
+        Ok(folded_stacks(&resources.calls, state.as_ref()))
+    }
+
+    #[cfg(feature = "messaging")]
+    async fn get_messages_status(
+        &self,
+        transaction_hash: Hash256,
+    ) -> Result<Vec<MessageStatus>, Error> {
+        let hash = L1TxHash::from(*transaction_hash.as_bytes());
+        let messages = match self.sequencer.gathered_messages() {
+            Some(messages) => messages.by_l1_tx_hash(hash).map_err(StarknetApiError::from)?,
+            None => None,
+        };
+        let messages = messages.ok_or(KatanaApiError::L1TransactionNotFound)?;
+
+        messages.into_iter().map(|message| self.message_status(message.status)).collect()
+    }
+
+    #[cfg(not(feature = "messaging"))]
+    async fn get_messages_status(
+        &self,
+        _transaction_hash: Hash256,
+    ) -> Result<Vec<MessageStatus>, Error> {
+        Err(KatanaApiError::L1TransactionNotFound.into())
+    }
+
+    #[cfg(feature = "messaging")]
+    async fn dead_letter_messages(&self) -> Result<Vec<DeadLetterMessage>, Error> {
+        let Some(dead_letters) = self.sequencer.dead_letters() else {
+            return Ok(Vec::new());
+        };
+
+        Ok(dead_letters
+            .all()
+            .map_err(StarknetApiError::from)?
+            .into_iter()
+            .map(|letter| DeadLetterMessage {
+                block_number: letter.block_number,
+                from_address: letter.message.from_address.into(),
+                to_address: letter.message.to_address,
+                payload: letter.message.payload,
+                attempts: letter.attempts,
+                error: letter.error,
+            })
+            .collect())
+    }
+
+    #[cfg(not(feature = "messaging"))]
+    async fn dead_letter_messages(&self) -> Result<Vec<DeadLetterMessage>, Error> {
+        Ok(Vec::new())
+    }
+
+    async fn export_genesis(
+        &self,
+        block_number: Option<BlockNumber>,
+    ) -> Result<GenesisJson, Error> {
+        let latest = self.sequencer.block_number().map_err(StarknetApiError::from)?;
+        let block_number = block_number.unwrap_or(latest);
+
+        if block_number > latest {
+            return Err(StarknetApiError::BlockNotFound.into());
+        }
+
+        // replaying the state updates of all the blocks is slow, so it's done on a blocking task
+        let sequencer = self.sequencer.clone();
+        let failed = |reason: String| KatanaApiError::FailedToExportGenesis.with_reason(reason);
+        TokioTaskSpawner::new()
+            .map_err(|err| failed(err.to_string()))?
+            .spawn_blocking(move || {
+                let backend = sequencer.backend();
+                export_genesis(backend.blockchain.provider(), block_number, &backend.config.genesis)
+            })
+            .await
+            .map_err(|err| failed(err.to_string()))?
+            .map_err(|err| failed(format!("{err:#}")))
+    }
+
+    async fn block_id_by_timestamp(&self, timestamp: u64) -> Result<BlockHashAndNumber, Error> {
+        let hash_and_number = self
+            .sequencer
+            .block_hash_and_number_at_timestamp(timestamp)
+            .map_err(StarknetApiError::from)?
+            .ok_or(StarknetApiError::BlockNotFound)?;
+
+        Ok(hash_and_number.into())
+    }
+
+    async fn call_at_timestamp(
+        &self,
+        request: FunctionCall,
+        timestamp: u64,
+    ) -> Result<Vec<FeltAsHex>, Error> {
+        let block_id = self.block_id_at_timestamp(timestamp)?;
+        let request = EntryPointCall {
+            calldata: request.calldata,
+            contract_address: request.contract_address.into(),
+            entry_point_selector: request.entry_point_selector,
+        };
+
+        let retdata = self.sequencer.call(request, block_id).map_err(StarknetApiError::from)?;
+        Ok(retdata.into_iter().map(|v| v.into()).collect())
+    }
+
+    async fn get_storage_at_timestamp(
+        &self,
+        contract_address: FieldElement,
+        key: FieldElement,
+        timestamp: u64,
+    ) -> Result<FeltAsHex, Error> {
+        let block_id = self.block_id_at_timestamp(timestamp)?;
+        let value = self
+            .sequencer
+            .storage_at(contract_address.into(), key, block_id)
+            .map_err(StarknetApiError::from)?;
+
+        Ok(value.into())
+    }
+}
+
+/// Returns the folded stacks of the calls, one line per call, in the order they were made.
+fn folded_stacks(calls: &[CallProfile], state: &dyn StateProvider) -> String {
+    let mut names = HashMap::new();
+    let mut lines = Vec::new();
+
+    for call in calls {
+        fold_call(call, &mut Vec::new(), state, &mut names, &mut lines);
+    }
+
+    lines.join("\n")
+}
+
+/// Appends the folded stack of `call`, and of all its inner calls, to `lines`. Each line contains
+/// the semicolon-separated path to a call followed by the steps used by the call itself.
+fn fold_call(
+    call: &CallProfile,
+    stack: &mut Vec<String>,
+    state: &dyn StateProvider,
+    names: &mut HashMap<ClassHash, HashMap<FieldElement, String>>,
+    lines: &mut Vec<String>,
+) {
+    let class_hash = call
+        .class_hash
+        .or_else(|| state.class_hash_of_contract(call.contract_address).ok().flatten());
+
+    let name = class_hash.and_then(|hash| {
+        let names = names.entry(hash).or_insert_with(|| {
+            let class = state.sierra_class(hash).ok().flatten();
+            class.map(|class| entry_point_names(&class)).unwrap_or_default()
+        });
+        names.get(&call.selector).cloned()
+    });
+
+    let name = name.unwrap_or_else(|| format!("{:#x}", call.selector));
+    stack.push(format!("{}::{name}", call.contract_address));
+    lines.push(format!("{} {}", stack.join(";"), call.steps));
+
+    for inner_call in &call.inner_calls {
+        fold_call(inner_call, stack, state, names, lines);
+    }
+
+    stack.pop();
 }
@@ -1,21 +1,24 @@
 pub mod config;
 pub mod dev;
 pub mod katana;
+pub mod logger;
+pub mod middleware;
+pub mod node;
+mod proxy;
 pub mod starknet;
 pub mod torii;
 
 use std::net::SocketAddr;
 use std::sync::Arc;
-use std::time::{Duration, Instant};
+use std::time::Duration;
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use config::ServerConfig;
 use hyper::Method;
-use jsonrpsee::server::logger::{Logger, MethodKind, TransportProtocol};
+use jsonrpsee::core::Error;
 use jsonrpsee::server::middleware::proxy_get_request::ProxyGetRequestLayer;
 use jsonrpsee::server::{AllowHosts, ServerBuilder, ServerHandle};
-use jsonrpsee::tracing::debug;
-use jsonrpsee::types::Params;
+use jsonrpsee::types::error::CallError;
 use jsonrpsee::RpcModule;
 use katana_core::sequencer::KatanaSequencer;
 use katana_rpc_api::dev::DevApiServer;
@@ -27,12 +30,15 @@ use tower_http::cors::{Any, CorsLayer};
 
 use crate::dev::DevApi;
 use crate::katana::KatanaApi;
+use crate::logger::RpcLogger;
+use crate::middleware::{BatchLimitLayer, RateLimiter};
 use crate::starknet::StarknetApi;
 use crate::torii::ToriiApi;
 
 pub async fn spawn(sequencer: Arc<KatanaSequencer>, config: ServerConfig) -> Result<NodeHandle> {
     let mut methods = RpcModule::new(());
-    methods.register_method("health", |_, _| Ok(serde_json::json!({ "health": true })))?;
+    let health_sequencer = sequencer.clone();
+    methods.register_method("health", move |_, _| health(&health_sequencer))?;
 
     for api in &config.apis {
         match api {
@@ -51,6 +57,8 @@ pub async fn spawn(sequencer: Arc<KatanaSequencer>, config: ServerConfig) -> Res
         }
     }
 
+    let methods = logger::trace_calls(methods)?;
+
     let cors = CorsLayer::new()
             // Allow `POST` when accessing the resource
             .allow_methods([Method::POST, Method::GET])
@@ -61,72 +69,50 @@ pub async fn spawn(sequencer: Arc<KatanaSequencer>, config: ServerConfig) -> Res
     let middleware = tower::ServiceBuilder::new()
         .layer(cors)
         .layer(ProxyGetRequestLayer::new("/", "health")?)
+        .option_layer(
+            config
+                .max_batch_size
+                .map(|size| BatchLimitLayer::new(size, config.max_request_body_size)),
+        )
         .timeout(Duration::from_secs(20));
 
     let server = ServerBuilder::new()
-        .set_logger(RpcLogger)
+        .set_logger(RpcLogger::new(methods.method_names()))
         .set_host_filtering(AllowHosts::Any)
         .set_middleware(middleware)
         .max_connections(config.max_connections)
-        .build(config.addr())
+        .max_request_body_size(config.max_request_body_size)
+        .max_response_body_size(config.max_response_body_size)
+        // the rate limiting front server is the one listening on the configured address
+        .build(if config.rate_limit.is_some() { "127.0.0.1:0".into() } else { config.addr() })
         .await?;
 
-    let addr = server.local_addr()?;
+    let mut addr = server.local_addr()?;
     let handle = server.start(methods)?;
 
+    if let Some(limit) = config.rate_limit {
+        let limiter =
+            RateLimiter::new(limit, config.trusted_proxies.clone(), config.max_request_body_size);
+        addr = proxy::spawn(&config.addr(), addr, limiter, handle.clone().stopped())?;
+    }
+
     Ok(NodeHandle { config, handle, addr })
 }
 
+/// A replica whose chain diverged from the one of the followed node doesn't sync new blocks
+/// anymore, so it is reported unhealthy.
+fn health(sequencer: &KatanaSequencer) -> Result<serde_json::Value, Error> {
+    match sequencer.follower_status().and_then(|status| status.diverged()) {
+        Some(reason) => Err(Error::Call(CallError::Failed(anyhow!(
+            "Stopped following the upstream node: {reason}"
+        )))),
+        None => Ok(serde_json::json!({ "health": true })),
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct NodeHandle {
     pub addr: SocketAddr,
     pub config: ServerConfig,
     pub handle: ServerHandle,
 }
-
-#[derive(Debug, Clone)]
-pub struct RpcLogger;
-
-impl Logger for RpcLogger {
-    type Instant = std::time::Instant;
-
-    fn on_connect(
-        &self,
-        _remote_addr: std::net::SocketAddr,
-        _request: &jsonrpsee::server::logger::HttpRequest,
-        _t: TransportProtocol,
-    ) {
-    }
-
-    fn on_request(&self, _transport: TransportProtocol) -> Self::Instant {
-        Instant::now()
-    }
-
-    fn on_call(
-        &self,
-        method_name: &str,
-        _params: Params<'_>,
-        _kind: MethodKind,
-        _transport: TransportProtocol,
-    ) {
-        debug!(target: "server", method = ?method_name);
-    }
-
-    fn on_result(
-        &self,
-        _method_name: &str,
-        _success: bool,
-        _started_at: Self::Instant,
-        _transport: TransportProtocol,
-    ) {
-    }
-
-    fn on_response(
-        &self,
-        _result: &str,
-        _started_at: Self::Instant,
-        _transport: TransportProtocol,
-    ) {
-    }
-    fn on_disconnect(&self, _remote_addr: std::net::SocketAddr, _transport: TransportProtocol) {}
-}
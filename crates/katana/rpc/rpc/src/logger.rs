@@ -0,0 +1,243 @@
+//! Logging and metrics of the RPC calls.
+
+use std::collections::HashSet;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use jsonrpsee::core::server::rpc_module::Methods;
+use jsonrpsee::core::traits::ToRpcParams;
+use jsonrpsee::core::Error;
+use jsonrpsee::server::logger::{HttpRequest, Logger, MethodKind, TransportProtocol};
+use jsonrpsee::types::Params;
+use jsonrpsee::RpcModule;
+use metrics::{
+    decrement_gauge, describe_counter, describe_gauge, describe_histogram, histogram,
+    increment_counter, increment_gauge, Unit,
+};
+use serde_json::value::RawValue;
+use serde_json::Value;
+use tracing::field::Empty;
+use tracing::{debug, debug_span, trace, warn, Instrument, Span};
+
+/// Calls taking longer than this are logged as warnings.
+const SLOW_CALL_THRESHOLD: Duration = Duration::from_secs(1);
+
+/// Label of the calls to methods that aren't registered, to not create a new time series for every
+/// method name sent by the clients.
+const UNKNOWN_METHOD: &str = "unknown";
+
+/// Counts the RPC calls and their errors.
+///
+/// The metrics recorded are:
+/// - `rpc.calls`: the number of calls per method.
+/// - `rpc.call_errors`: the number of failed calls per method.
+/// - `rpc.errors`: the number of errors returned per error code.
+///
+/// The logger isn't told when a call is dropped before it completes, nor which call of a batch
+/// request completes, so the latency of the calls is recorded by the methods wrapped with
+/// [trace_calls].
+#[derive(Debug, Clone)]
+pub struct RpcLogger {
+    methods: Arc<HashSet<&'static str>>,
+}
+
+impl RpcLogger {
+    /// Creates a logger for a server serving the given methods.
+    pub fn new(methods: impl IntoIterator<Item = &'static str>) -> Self {
+        describe_counter!("rpc.calls", "Number of calls per method");
+        describe_counter!("rpc.call_errors", "Number of failed calls per method");
+        describe_counter!("rpc.errors", "Number of errors returned per error code");
+
+        Self { methods: Arc::new(methods.into_iter().collect()) }
+    }
+
+    fn method_label(&self, method_name: &str) -> &'static str {
+        self.methods.get(method_name).copied().unwrap_or(UNKNOWN_METHOD)
+    }
+}
+
+impl Logger for RpcLogger {
+    type Instant = Instant;
+
+    fn on_connect(&self, _remote_addr: SocketAddr, _request: &HttpRequest, _t: TransportProtocol) {}
+
+    fn on_request(&self, _transport: TransportProtocol) -> Self::Instant {
+        Instant::now()
+    }
+
+    fn on_call(
+        &self,
+        method_name: &str,
+        _params: Params<'_>,
+        _kind: MethodKind,
+        _transport: TransportProtocol,
+    ) {
+        increment_counter!("rpc.calls", "method" => self.method_label(method_name));
+    }
+
+    fn on_result(
+        &self,
+        method_name: &str,
+        success: bool,
+        _started_at: Self::Instant,
+        _transport: TransportProtocol,
+    ) {
+        if !success {
+            increment_counter!("rpc.call_errors", "method" => self.method_label(method_name));
+        }
+    }
+
+    fn on_response(&self, result: &str, _started_at: Self::Instant, _transport: TransportProtocol) {
+        for code in error_codes(result) {
+            trace!(target: "rpc", code, "Error returned.");
+            increment_counter!("rpc.errors", "code" => code.to_string());
+        }
+    }
+
+    fn on_disconnect(&self, _remote_addr: SocketAddr, _transport: TransportProtocol) {}
+}
+
+/// Wraps every method of a module so that each call is handled in its own span, which the logs of
+/// the handler are attached to, and is tracked until its handler completes or is dropped, eg. when
+/// the client disconnects.
+///
+/// The metrics recorded are:
+/// - `rpc.call_duration_seconds`: the latency of the calls per method.
+/// - `rpc.calls_in_flight`: the number of calls being handled per method.
+pub fn trace_calls(methods: RpcModule<()>) -> Result<RpcModule<Methods>, Error> {
+    describe_histogram!("rpc.call_duration_seconds", Unit::Seconds, "Latency of the calls");
+    describe_gauge!("rpc.calls_in_flight", "Number of calls being handled per method");
+
+    let names = methods.method_names().collect::<Vec<_>>();
+    let mut traced = RpcModule::new(Methods::from(methods));
+
+    for name in names {
+        traced.register_async_method(name, move |params, methods| async move {
+            let call = TracedCall::start(name);
+            let result = methods
+                .call::<_, Box<RawValue>>(name, RawParams(params))
+                .instrument(call.span.clone())
+                .await;
+            call.finish(result.is_ok());
+            result
+        })?;
+    }
+
+    Ok(traced)
+}
+
+/// The params of a call, forwarded as is to the wrapped method.
+struct RawParams(Params<'static>);
+
+impl ToRpcParams for RawParams {
+    fn to_rpc_params(self) -> Result<Option<Box<RawValue>>, serde_json::Error> {
+        self.0.as_str().map(|params| RawValue::from_string(params.to_string())).transpose()
+    }
+}
+
+/// A call being handled. The call has been cancelled if it's dropped before being finished.
+struct TracedCall {
+    method: &'static str,
+    span: Span,
+    started_at: Instant,
+    finished: bool,
+}
+
+impl TracedCall {
+    fn start(method: &'static str) -> Self {
+        increment_gauge!("rpc.calls_in_flight", 1.0, "method" => method);
+        let span = debug_span!(target: "rpc", "call", method, success = Empty, elapsed_ms = Empty);
+        Self { method, span, started_at: Instant::now(), finished: false }
+    }
+
+    fn finish(mut self, success: bool) {
+        self.finished = true;
+
+        let elapsed = self.started_at.elapsed();
+        histogram!("rpc.call_duration_seconds", elapsed, "method" => self.method);
+
+        self.span.record("success", success);
+        self.span.record("elapsed_ms", elapsed.as_millis() as u64);
+        self.span.in_scope(|| {
+            if elapsed >= SLOW_CALL_THRESHOLD {
+                warn!(target: "rpc", "Slow call.");
+            } else {
+                debug!(target: "rpc", "Call handled.");
+            }
+        });
+    }
+}
+
+impl Drop for TracedCall {
+    fn drop(&mut self) {
+        decrement_gauge!("rpc.calls_in_flight", 1.0, "method" => self.method);
+        if !self.finished {
+            self.span.in_scope(|| debug!(target: "rpc", "Call cancelled."));
+        }
+    }
+}
+
+/// Returns the codes of the errors of a response, which is either a single response or the
+/// responses of a batch request.
+fn error_codes(response: &str) -> Vec<i64> {
+    // avoid parsing the successful responses, which can be large
+    if !response.contains(r#""error":"#) {
+        return Vec::new();
+    }
+
+    let code = |response: &Value| response.get("error")?.get("code")?.as_i64();
+    match serde_json::from_str::<Value>(response) {
+        Ok(Value::Array(responses)) => responses.iter().filter_map(code).collect(),
+        Ok(response) => code(&response).into_iter().collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use jsonrpsee::core::Error;
+    use jsonrpsee::types::error::{CallError, ErrorObject};
+    use jsonrpsee::types::Params;
+    use jsonrpsee::RpcModule;
+
+    use super::{error_codes, trace_calls, RawParams};
+
+    #[test]
+    fn error_codes_of_responses() {
+        let success = r#"{"jsonrpc":"2.0","result":"0x1","id":1}"#;
+        let error = r#"{"jsonrpc":"2.0","error":{"code":24,"message":"Block not found"},"id":2}"#;
+
+        assert!(error_codes(success).is_empty());
+        assert_eq!(error_codes(error), vec![24]);
+        assert_eq!(error_codes(&format!("[{success},{error},{error}]")), vec![24, 24]);
+    }
+
+    #[tokio::test]
+    async fn traced_calls_return_the_responses_of_the_methods() {
+        let mut methods = RpcModule::new(());
+        methods.register_method("double", |params, _| Ok(params.one::<u64>()? * 2)).unwrap();
+        methods
+            .register_async_method("block", |_, _| async {
+                let error = ErrorObject::owned(24, "Block not found", None::<()>);
+                Err::<(), _>(Error::Call(CallError::Custom(error)))
+            })
+            .unwrap();
+
+        let traced = trace_calls(methods).unwrap();
+        let call = |method: &'static str, params: Option<&'static str>| {
+            traced.call::<_, u64>(method, RawParams(Params::new(params)))
+        };
+
+        assert_eq!(call("double", Some("[21]")).await.unwrap(), 42);
+        assert_matches!(
+            call("block", None).await,
+            Err(Error::Call(CallError::Custom(error))) if error.code() == 24
+        );
+        assert_matches!(
+            call("double", Some(r#"["0x1"]"#)).await,
+            Err(Error::Call(CallError::Custom(error))) if error.code() == -32602
+        );
+    }
+}
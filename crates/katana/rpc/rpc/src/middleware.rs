@@ -0,0 +1,457 @@
+//! HTTP middlewares limiting the load a single client can put on the RPC server.
+
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv6Addr};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use futures::future::BoxFuture;
+use hyper::body::{Bytes, HttpBody};
+use hyper::header::{HeaderValue, CONTENT_TYPE};
+use hyper::{Body, Request, Response, StatusCode};
+use jsonrpsee::types::error::INVALID_REQUEST_CODE;
+use serde::de::IgnoredAny;
+use tower::{Layer, Service};
+
+/// Error code of the requests rejected because a limit has been exceeded, as defined by EIP-1474.
+const LIMIT_EXCEEDED_CODE: i32 = -32005;
+
+/// Maximum number of clients tracked within a single window. The requests of any other client are
+/// rejected until the window elapses.
+const MAX_CLIENTS: usize = 64 * 1024;
+
+/// Limits the number of calls per second of every client, the calls of a batch request being
+/// counted individually.
+///
+/// The client is identified by the IP address of the peer of the connection. The
+/// `X-Forwarded-For` and `X-Real-IP` headers are only honoured when the peer is one of the trusted
+/// proxies, as any client could otherwise get a fresh limit by making up a new address. IPv6
+/// clients are identified by their /64 prefix, which is usually assigned to a single host.
+#[derive(Debug)]
+pub struct RateLimiter {
+    limit: u32,
+    window: Duration,
+    trusted_proxies: Vec<IpAddr>,
+    max_body_size: u32,
+    state: Mutex<Windows>,
+}
+
+#[derive(Debug)]
+struct Windows {
+    clients: HashMap<IpAddr, Window>,
+    last_prune: Instant,
+}
+
+#[derive(Debug)]
+struct Window {
+    start: Instant,
+    count: u32,
+}
+
+impl RateLimiter {
+    pub fn new(calls_per_second: u32, trusted_proxies: Vec<IpAddr>, max_body_size: u32) -> Self {
+        Self::with_window(calls_per_second, Duration::from_secs(1), trusted_proxies, max_body_size)
+    }
+
+    fn with_window(
+        limit: u32,
+        window: Duration,
+        trusted_proxies: Vec<IpAddr>,
+        max_body_size: u32,
+    ) -> Self {
+        let state = Windows { clients: HashMap::new(), last_prune: Instant::now() };
+        Self { limit, window, trusted_proxies, max_body_size, state: Mutex::new(state) }
+    }
+
+    /// Returns the layer limiting the requests received from `peer`.
+    pub fn layer(self: &Arc<Self>, peer: IpAddr) -> RateLimitLayer {
+        RateLimitLayer { limiter: Arc::clone(self), peer }
+    }
+
+    /// Records `calls` calls of `client` made at `now`, returns whether they're within the limit.
+    /// The calls aren't recorded if they exceed it.
+    fn try_acquire(&self, client: IpAddr, calls: u32, now: Instant) -> bool {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+
+        // the windows which have elapsed don't carry any information, so they are dropped once per
+        // window to keep the map bounded by the number of clients seen within a single window
+        if now.saturating_duration_since(state.last_prune) >= self.window {
+            state.clients.retain(|_, window| now.duration_since(window.start) < self.window);
+            state.last_prune = now;
+        }
+
+        let client = client_key(client);
+        if state.clients.len() >= MAX_CLIENTS && !state.clients.contains_key(&client) {
+            return false;
+        }
+
+        let window = state.clients.entry(client).or_insert(Window { start: now, count: 0 });
+        if now.duration_since(window.start) >= self.window {
+            *window = Window { start: now, count: 0 };
+        }
+
+        match window.count.checked_add(calls) {
+            Some(count) if count <= self.limit => {
+                window.count = count;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns the address of the client which made `request` through `peer`.
+    ///
+    /// When the peer is a trusted proxy, the client is the last address of `X-Forwarded-For`
+    /// which isn't itself a trusted proxy, as the addresses before it are set by the client.
+    fn client_ip(&self, peer: IpAddr, request: &Request<Body>) -> IpAddr {
+        if !self.trusted_proxies.contains(&peer) {
+            return peer;
+        }
+
+        let header = |name: &str| request.headers().get(name).and_then(|v| v.to_str().ok());
+
+        if let Some(addrs) = header("x-forwarded-for") {
+            for addr in addrs.rsplit(',') {
+                match addr.trim().parse::<IpAddr>() {
+                    Ok(addr) if self.trusted_proxies.contains(&addr) => continue,
+                    Ok(addr) => return addr,
+                    Err(_) => return peer,
+                }
+            }
+            return peer;
+        }
+
+        header("x-real-ip").and_then(|addr| addr.trim().parse().ok()).unwrap_or(peer)
+    }
+}
+
+/// Identifies IPv6 clients by their /64 prefix.
+fn client_key(ip: IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V6(ip) if ip.to_ipv4_mapped().is_none() => {
+            let prefix = u128::from(ip) & !(u128::from(u64::MAX));
+            IpAddr::V6(Ipv6Addr::from(prefix))
+        }
+        ip => ip,
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RateLimitLayer {
+    limiter: Arc<RateLimiter>,
+    peer: IpAddr,
+}
+
+impl<S> Layer<S> for RateLimitLayer {
+    type Service = RateLimit<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RateLimit { inner, limiter: Arc::clone(&self.limiter), peer: self.peer }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RateLimit<S> {
+    inner: S,
+    limiter: Arc<RateLimiter>,
+    peer: IpAddr,
+}
+
+impl<S> Service<Request<Body>> for RateLimit<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response<Body>;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        // the service which has been polled ready is the one to be called
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let limiter = Arc::clone(&self.limiter);
+        let client = limiter.client_ip(self.peer, &request);
+
+        Box::pin(async move {
+            let (parts, body) = request.into_parts();
+
+            let body = match read_body(body, limiter.max_body_size as usize).await {
+                Ok(body) => body,
+                Err(response) => return Ok(response),
+            };
+
+            // a single call or an empty batch, which is rejected as a whole, both count as one
+            let calls = batch_size(&body).unwrap_or(1).max(1);
+            let calls = u32::try_from(calls).unwrap_or(u32::MAX);
+
+            if !limiter.try_acquire(client, calls, Instant::now()) {
+                return Ok(error_response(
+                    StatusCode::TOO_MANY_REQUESTS,
+                    LIMIT_EXCEEDED_CODE,
+                    "Too many requests",
+                ));
+            }
+
+            inner.call(Request::from_parts(parts, Body::from(body))).await
+        })
+    }
+}
+
+/// Rejects the batch requests made of more than a given number of calls.
+///
+/// The body of the requests has to be buffered to count the calls, so the layer also rejects the
+/// bodies larger than the maximum request body size of the server.
+#[derive(Debug, Clone, Copy)]
+pub struct BatchLimitLayer {
+    max_batch_size: u32,
+    max_body_size: u32,
+}
+
+impl BatchLimitLayer {
+    pub fn new(max_batch_size: u32, max_body_size: u32) -> Self {
+        Self { max_batch_size, max_body_size }
+    }
+}
+
+impl<S> Layer<S> for BatchLimitLayer {
+    type Service = BatchLimit<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        BatchLimit { inner, limits: *self }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BatchLimit<S> {
+    inner: S,
+    limits: BatchLimitLayer,
+}
+
+impl<S> Service<Request<Body>> for BatchLimit<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response<Body>;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        // the service which has been polled ready is the one to be called
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let BatchLimitLayer { max_batch_size, max_body_size } = self.limits;
+
+        Box::pin(async move {
+            let (parts, body) = request.into_parts();
+
+            let body = match read_body(body, max_body_size as usize).await {
+                Ok(body) => body,
+                Err(response) => return Ok(response),
+            };
+
+            if batch_size(&body).is_some_and(|size| size > max_batch_size as usize) {
+                let message = format!("Batch request exceeds the limit of {max_batch_size} calls");
+                return Ok(error_response(StatusCode::BAD_REQUEST, INVALID_REQUEST_CODE, &message));
+            }
+
+            inner.call(Request::from_parts(parts, Body::from(body))).await
+        })
+    }
+}
+
+async fn read_body(mut body: Body, limit: usize) -> Result<Bytes, Response<Body>> {
+    let mut buffer = Vec::new();
+
+    while let Some(chunk) = body.data().await {
+        let Ok(chunk) = chunk else {
+            let message = "Failed to read request body";
+            return Err(error_response(StatusCode::BAD_REQUEST, INVALID_REQUEST_CODE, message));
+        };
+
+        if buffer.len() + chunk.len() > limit {
+            let message = "Request body too large";
+            return Err(error_response(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                LIMIT_EXCEEDED_CODE,
+                message,
+            ));
+        }
+
+        buffer.extend_from_slice(&chunk);
+    }
+
+    Ok(buffer.into())
+}
+
+/// Returns the number of calls of the request if it's a batch request.
+fn batch_size(body: &[u8]) -> Option<usize> {
+    let first = body.iter().find(|b| !b.is_ascii_whitespace())?;
+    if *first != b'[' {
+        return None;
+    }
+    serde_json::from_slice::<Vec<IgnoredAny>>(body).ok().map(|calls| calls.len())
+}
+
+pub(crate) fn error_response(status: StatusCode, code: i32, message: &str) -> Response<Body> {
+    let body = serde_json::json!({
+        "jsonrpc": "2.0",
+        "error": { "code": code, "message": message },
+        "id": null,
+    });
+
+    let mut response = Response::new(Body::from(body.to_string()));
+    *response.status_mut() = status;
+    response.headers_mut().insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use super::*;
+
+    const MAX_BODY_SIZE: u32 = 1024;
+
+    fn limiter(limit: u32, trusted_proxies: Vec<IpAddr>) -> Arc<RateLimiter> {
+        Arc::new(RateLimiter::with_window(
+            limit,
+            Duration::from_secs(1),
+            trusted_proxies,
+            MAX_BODY_SIZE,
+        ))
+    }
+
+    fn request(body: &str, forwarded_for: Option<&str>) -> Request<Body> {
+        let mut request = Request::builder();
+        if let Some(addr) = forwarded_for {
+            request = request.header("x-forwarded-for", addr);
+        }
+        request.body(Body::from(body.to_string())).unwrap()
+    }
+
+    async fn status<S>(service: &mut S, request: Request<Body>) -> StatusCode
+    where
+        S: Service<Request<Body>, Response = Response<Body>, Error = Infallible>,
+    {
+        futures::future::poll_fn(|cx| service.poll_ready(cx)).await.unwrap();
+        service.call(request).await.unwrap().status()
+    }
+
+    #[test]
+    fn rate_limit_per_client() {
+        let limiter = limiter(2, Vec::new());
+        let alice = IpAddr::from([10, 0, 0, 1]);
+        let bob = IpAddr::from([10, 0, 0, 2]);
+        let now = Instant::now();
+
+        assert!(limiter.try_acquire(alice, 1, now));
+        assert!(limiter.try_acquire(alice, 1, now));
+        assert!(!limiter.try_acquire(alice, 1, now));
+        assert!(limiter.try_acquire(bob, 1, now));
+
+        // the limit is reset once the window has elapsed
+        let later = now + Duration::from_secs(1);
+        assert!(limiter.try_acquire(alice, 1, later));
+        // a batch is only accepted if all of its calls fit in the remaining limit
+        assert!(!limiter.try_acquire(alice, 2, later));
+        assert!(limiter.try_acquire(alice, 1, later));
+    }
+
+    #[test]
+    fn elapsed_windows_are_pruned() {
+        let limiter = limiter(1, Vec::new());
+        let now = Instant::now();
+
+        for i in 0..=255 {
+            assert!(limiter.try_acquire(IpAddr::from([10, 0, 0, i]), 1, now));
+        }
+
+        let later = now + Duration::from_secs(1);
+        assert!(limiter.try_acquire(IpAddr::from([10, 0, 1, 0]), 1, later));
+        assert_eq!(limiter.state.lock().unwrap().clients.len(), 1);
+    }
+
+    #[test]
+    fn ipv6_clients_share_their_prefix() {
+        let limiter = limiter(1, Vec::new());
+        let now = Instant::now();
+
+        assert!(limiter.try_acquire("2001:db8::1".parse().unwrap(), 1, now));
+        assert!(!limiter.try_acquire("2001:db8::2".parse().unwrap(), 1, now));
+        assert!(limiter.try_acquire("2001:db8:0:1::1".parse().unwrap(), 1, now));
+    }
+
+    #[test]
+    fn proxy_headers_only_from_trusted_proxies() {
+        let proxy = IpAddr::from([10, 0, 0, 1]);
+        let client = IpAddr::from([203, 0, 113, 7]);
+        let limiter = limiter(1, vec![proxy]);
+
+        // the addresses before the last untrusted one are set by the client
+        let forwarded = request("", Some("198.51.100.1, 203.0.113.7, 10.0.0.1"));
+        assert_eq!(limiter.client_ip(proxy, &forwarded), client);
+        assert_eq!(limiter.client_ip(client, &forwarded), client);
+
+        let real_ip =
+            Request::builder().header("x-real-ip", "203.0.113.7").body(Body::empty()).unwrap();
+        assert_eq!(limiter.client_ip(proxy, &real_ip), client);
+        assert_eq!(limiter.client_ip(proxy, &request("", Some("garbage"))), proxy);
+        assert_eq!(limiter.client_ip(proxy, &request("", None)), proxy);
+    }
+
+    #[tokio::test]
+    async fn spoofed_headers_do_not_bypass_the_limit() {
+        let limiter = limiter(2, Vec::new());
+        let inner =
+            tower::service_fn(|_| async { Ok::<_, Infallible>(Response::new(Body::empty())) });
+        let mut service = limiter.layer(IpAddr::from([203, 0, 113, 7])).layer(inner);
+
+        let call = r#"{"jsonrpc":"2.0","method":"starknet_chainId","id":1}"#;
+        for (i, expected) in
+            [StatusCode::OK, StatusCode::OK, StatusCode::TOO_MANY_REQUESTS].into_iter().enumerate()
+        {
+            let spoofed = format!("198.51.100.{i}");
+            assert_eq!(status(&mut service, request(call, Some(&spoofed))).await, expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn batched_calls_are_counted() {
+        let limiter = limiter(2, Vec::new());
+        let inner =
+            tower::service_fn(|_| async { Ok::<_, Infallible>(Response::new(Body::empty())) });
+        let peer = IpAddr::from([203, 0, 113, 7]);
+
+        let call = r#"{"jsonrpc":"2.0","method":"starknet_chainId","id":1}"#;
+        let batch = format!("[{call},{call},{call}]");
+        let mut service = limiter.layer(peer).layer(inner);
+        assert_eq!(
+            status(&mut service, request(&batch, None)).await,
+            StatusCode::TOO_MANY_REQUESTS
+        );
+
+        let batch = format!("[{call},{call}]");
+        assert_eq!(status(&mut service, request(&batch, None)).await, StatusCode::OK);
+        assert_eq!(status(&mut service, request(call, None)).await, StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    #[test]
+    fn batch_size_of_requests() {
+        let call = r#"{"jsonrpc":"2.0","method":"starknet_chainId","id":1}"#;
+        assert_eq!(batch_size(call.as_bytes()), None);
+        assert_eq!(batch_size(format!(" [{call},{call}]").as_bytes()), Some(2));
+        assert_eq!(batch_size(b"[]"), Some(0));
+    }
+}
@@ -0,0 +1,126 @@
+//! Embeddable Katana node.
+//!
+//! Starts a fully configured sequencer along with its JSON-RPC server in the current process, so
+//! that a Katana node can be used as a library (eg. in tests) instead of spawning the binary.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use anyhow::Result;
+use katana_core::backend::config::StarknetConfig;
+use katana_core::sequencer::{KatanaSequencer, SequencerConfig};
+use katana_primitives::contract::ContractAddress;
+use katana_primitives::genesis::allocation::GenesisAccountAlloc;
+use katana_rpc_api::ApiKind;
+use tracing::info;
+
+use crate::config::{ServerConfig, DEFAULT_MAX_BODY_SIZE};
+use crate::{spawn, NodeHandle};
+
+/// Builder for an in-process Katana [`Node`].
+///
+/// By default, the RPC server listens on a random available port of the loopback interface and
+/// exposes all the APIs, including the `dev` one.
+#[derive(Debug)]
+pub struct NodeBuilder {
+    sequencer_config: SequencerConfig,
+    starknet_config: StarknetConfig,
+    server_config: ServerConfig,
+}
+
+impl NodeBuilder {
+    pub fn new() -> Self {
+        Self {
+            sequencer_config: SequencerConfig::default(),
+            starknet_config: StarknetConfig::default(),
+            server_config: ServerConfig {
+                port: 0,
+                host: "127.0.0.1".into(),
+                max_connections: 100,
+                apis: ApiKind::ALL.to_vec(),
+                max_request_body_size: DEFAULT_MAX_BODY_SIZE,
+                max_response_body_size: DEFAULT_MAX_BODY_SIZE,
+                max_batch_size: None,
+                rate_limit: None,
+                trusted_proxies: Vec::new(),
+            },
+        }
+    }
+
+    pub fn sequencer_config(mut self, config: SequencerConfig) -> Self {
+        self.sequencer_config = config;
+        self
+    }
+
+    pub fn starknet_config(mut self, config: StarknetConfig) -> Self {
+        self.starknet_config = config;
+        self
+    }
+
+    pub fn server_config(mut self, config: ServerConfig) -> Self {
+        self.server_config = config;
+        self
+    }
+
+    /// Creates the sequencer and starts serving its RPC APIs.
+    pub async fn start(self) -> Result<Node> {
+        let sequencer =
+            Arc::new(KatanaSequencer::new(self.sequencer_config, self.starknet_config).await?);
+        let rpc = spawn(Arc::clone(&sequencer), self.server_config).await?;
+        Ok(Node { sequencer, rpc })
+    }
+}
+
+impl Default for NodeBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A running Katana node.
+pub struct Node {
+    sequencer: Arc<KatanaSequencer>,
+    rpc: NodeHandle,
+}
+
+impl Node {
+    pub fn builder() -> NodeBuilder {
+        NodeBuilder::new()
+    }
+
+    /// The address the RPC server is bound to.
+    pub fn addr(&self) -> SocketAddr {
+        self.rpc.addr
+    }
+
+    pub fn sequencer(&self) -> &Arc<KatanaSequencer> {
+        &self.sequencer
+    }
+
+    pub fn rpc(&self) -> &NodeHandle {
+        &self.rpc
+    }
+
+    /// Returns the accounts predeployed in the genesis.
+    pub fn accounts(&self) -> impl Iterator<Item = (&ContractAddress, &GenesisAccountAlloc)> {
+        self.sequencer.backend.config.genesis.accounts()
+    }
+
+    /// Stops the RPC server and the tasks of the sequencer, and waits for them to shut down.
+    ///
+    /// If the node is backed by a database, the pending block is sealed so that the transactions
+    /// which have already been executed aren't lost when the node is restarted from it.
+    pub async fn stop(self) -> Result<()> {
+        self.rpc.handle.stop()?;
+        self.rpc.handle.stopped().await;
+        self.sequencer.stop().await;
+
+        if self.sequencer.backend.config.db_dir.is_some() {
+            if let Some(outcome) = self.sequencer.block_producer().mine_pending().await? {
+                info!(target: "katana::node", block = %outcome.block_number, "Mined pending block");
+            }
+        }
+
+        Ok(())
+    }
+}
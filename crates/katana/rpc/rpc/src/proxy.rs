@@ -0,0 +1,81 @@
+//! Front server of the RPC server when the requests are rate limited.
+//!
+//! The middlewares of the RPC server aren't given the address of the peer of the connection, so
+//! the rate limiting is done by a server in front of it, which forwards the accepted requests to
+//! the RPC server listening on the loopback interface.
+
+use std::convert::Infallible;
+use std::future::Future;
+use std::net::{SocketAddr, TcpListener};
+use std::sync::Arc;
+
+use anyhow::Result;
+use hyper::client::HttpConnector;
+use hyper::header::UPGRADE;
+use hyper::server::conn::AddrStream;
+use hyper::service::make_service_fn;
+use hyper::{Body, Client, Request, Response, Server, StatusCode, Uri};
+use jsonrpsee::types::error::{INTERNAL_ERROR_CODE, INVALID_REQUEST_CODE};
+use tower::ServiceBuilder;
+use tracing::error;
+
+use crate::middleware::{error_response, RateLimiter};
+
+/// Starts serving on `addr` the requests accepted by `limiter`, forwarding them to the RPC server
+/// at `upstream`. The server is gracefully shut down once `shutdown` completes.
+pub(crate) fn spawn(
+    addr: &str,
+    upstream: SocketAddr,
+    limiter: RateLimiter,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) -> Result<SocketAddr> {
+    let listener = TcpListener::bind(addr)?;
+    listener.set_nonblocking(true)?;
+    let limiter = Arc::new(limiter);
+    let client = Client::new();
+
+    let make_service = make_service_fn(move |conn: &AddrStream| {
+        let peer = conn.remote_addr().ip();
+        let client = client.clone();
+        let service = ServiceBuilder::new()
+            .layer(limiter.layer(peer))
+            .service_fn(move |request| forward(client.clone(), upstream, request));
+        async move { Ok::<_, Infallible>(service) }
+    });
+
+    let server = Server::from_tcp(listener)?.serve(make_service);
+    let addr = server.local_addr();
+
+    tokio::spawn(async move {
+        if let Err(error) = server.with_graceful_shutdown(shutdown).await {
+            error!(target: "rpc", error = %error, "RPC front server failed.");
+        }
+    });
+
+    Ok(addr)
+}
+
+async fn forward(
+    client: Client<HttpConnector>,
+    upstream: SocketAddr,
+    mut request: Request<Body>,
+) -> Result<Response<Body>, Infallible> {
+    // the calls made over a WebSocket connection couldn't be counted once it's upgraded
+    if request.headers().contains_key(UPGRADE) {
+        let message = "WebSocket connections are not supported when rate limiting is enabled";
+        return Ok(error_response(StatusCode::BAD_REQUEST, INVALID_REQUEST_CODE, message));
+    }
+
+    let path = request.uri().path_and_query().map_or("/", |path| path.as_str());
+    let uri = format!("http://{upstream}{path}").parse::<Uri>();
+    *request.uri_mut() = uri.expect("valid uri");
+
+    match client.request(request).await {
+        Ok(response) => Ok(response),
+        Err(error) => {
+            error!(target: "rpc", error = %error, "Failed to forward request to the RPC server.");
+            let message = "Failed to forward request";
+            Ok(error_response(StatusCode::BAD_GATEWAY, INTERNAL_ERROR_CODE, message))
+        }
+    }
+}
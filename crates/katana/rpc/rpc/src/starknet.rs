@@ -3,9 +3,10 @@ use std::sync::Arc;
 use jsonrpsee::core::{async_trait, Error, RpcResult};
 use katana_core::backend::contract::StarknetContract;
 use katana_core::sequencer::KatanaSequencer;
-use katana_executor::blockifier::utils::EntryPointCall;
+use katana_executor::abstraction::EntryPointCall;
 use katana_primitives::block::{BlockHashOrNumber, BlockIdOrTag, FinalityStatus, PartialHeader};
-use katana_primitives::conversion::rpc::legacy_inner_to_rpc_class;
+use katana_primitives::contract::CompiledContractClass;
+use katana_primitives::conversion::rpc::{inner_to_casm_class, legacy_inner_to_rpc_class};
 use katana_primitives::transaction::{ExecutableTx, ExecutableTxWithHash, TxHash};
 use katana_primitives::version::CURRENT_STARKNET_VERSION;
 use katana_primitives::FieldElement;
@@ -15,8 +16,9 @@ use katana_provider::traits::transaction::{
 };
 use katana_rpc_api::starknet::StarknetApiServer;
 use katana_rpc_types::block::{
-    BlockHashAndNumber, MaybePendingBlockWithTxHashes, MaybePendingBlockWithTxs,
-    PendingBlockWithTxHashes, PendingBlockWithTxs,
+    BlockHashAndNumber, MaybePendingBlockWithReceipts, MaybePendingBlockWithTxHashes,
+    MaybePendingBlockWithTxs, PendingBlockWithReceipts, PendingBlockWithTxHashes,
+    PendingBlockWithTxs,
 };
 use katana_rpc_types::error::starknet::StarknetApiError;
 use katana_rpc_types::event::{EventFilterWithPage, EventsPage};
@@ -27,7 +29,9 @@ use katana_rpc_types::transaction::{
     BroadcastedDeclareTx, BroadcastedDeployAccountTx, BroadcastedInvokeTx, BroadcastedTx,
     DeclareTxResult, DeployAccountTxResult, InvokeTxResult, Tx,
 };
-use katana_rpc_types::{ContractClass, FeeEstimate, FeltAsHex, FunctionCall, SimulationFlags};
+use katana_rpc_types::{
+    CompiledCasm, ContractClass, FeeEstimate, FeltAsHex, FunctionCall, SimulationFlags,
+};
 use katana_rpc_types_builder::ReceiptBuilder;
 use katana_tasks::{BlockingTaskPool, TokioTaskSpawner};
 use starknet::core::types::{BlockTag, TransactionExecutionStatus, TransactionStatus};
@@ -279,6 +283,54 @@ impl StarknetApiServer for StarknetApi {
         .await
     }
 
+    async fn block_with_receipts(
+        &self,
+        block_id: BlockIdOrTag,
+    ) -> RpcResult<MaybePendingBlockWithReceipts> {
+        self.on_io_blocking_task(move |this| {
+            let provider = this.inner.sequencer.backend.blockchain.provider();
+
+            if BlockIdOrTag::Tag(BlockTag::Pending) == block_id {
+                if let Some(pending_state) = this.inner.sequencer.pending_state() {
+                    let block_env = pending_state.block_envs.read().0.clone();
+                    let latest_hash =
+                        BlockHashProvider::latest_hash(provider).map_err(StarknetApiError::from)?;
+
+                    let header = PartialHeader {
+                        parent_hash: latest_hash,
+                        version: CURRENT_STARKNET_VERSION,
+                        timestamp: block_env.timestamp,
+                        gas_prices: block_env.l1_gas_prices,
+                        sequencer_address: block_env.sequencer_address,
+                    };
+
+                    let transactions = pending_state
+                        .executed_txs
+                        .read()
+                        .iter()
+                        .map(|(tx, rct)| (tx.clone(), rct.clone()))
+                        .collect::<Vec<_>>();
+
+                    return Ok(MaybePendingBlockWithReceipts::Pending(
+                        PendingBlockWithReceipts::new(header, transactions),
+                    ));
+                }
+            }
+
+            let block_num = BlockIdReader::convert_block_id(provider, block_id)
+                .map_err(|e| StarknetApiError::UnexpectedError { reason: e.to_string() })?
+                .map(BlockHashOrNumber::Num)
+                .ok_or(StarknetApiError::BlockNotFound)?;
+
+            katana_rpc_types_builder::BlockBuilder::new(block_num, provider)
+                .build_with_receipts()
+                .map_err(|e| StarknetApiError::UnexpectedError { reason: e.to_string() })?
+                .map(MaybePendingBlockWithReceipts::Block)
+                .ok_or(Error::from(StarknetApiError::BlockNotFound))
+        })
+        .await
+    }
+
     async fn state_update(&self, block_id: BlockIdOrTag) -> RpcResult<StateUpdate> {
         self.on_io_blocking_task(move |this| {
             let provider = this.inner.sequencer.backend.blockchain.provider();
@@ -323,7 +375,7 @@ impl StarknetApiServer for StarknetApi {
                             .read()
                             .iter()
                             .find(|(tx, _)| tx.hash == transaction_hash)
-                            .map(|(_, rct)| rct.receipt.clone())
+                            .map(|(_, rct)| rct.clone())
                     });
 
                     let Some(pending_receipt) = pending_receipt else {
@@ -379,6 +431,27 @@ impl StarknetApiServer for StarknetApi {
         .await
     }
 
+    async fn compiled_casm(&self, class_hash: FieldElement) -> RpcResult<CompiledCasm> {
+        self.on_io_blocking_task(move |this| {
+            let class =
+                this.inner.sequencer.compiled_class(class_hash).map_err(StarknetApiError::from)?;
+
+            match class {
+                Some(CompiledContractClass::V1(class)) => {
+                    inner_to_casm_class(&class).map_err(|e| {
+                        StarknetApiError::UnexpectedError { reason: e.to_string() }.into()
+                    })
+                }
+                // legacy classes are executed as is, and so don't have a CASM
+                Some(CompiledContractClass::V0(_)) => {
+                    Err(StarknetApiError::UnsupportedContractClassVersion.into())
+                }
+                None => Err(StarknetApiError::ClassHashNotFound.into()),
+            }
+        })
+        .await
+    }
+
     async fn events(&self, filter: EventFilterWithPage) -> RpcResult<EventsPage> {
         self.on_io_blocking_task(move |this| {
             let from_block = filter.event_filter.from_block.unwrap_or(BlockIdOrTag::Number(0));
@@ -460,7 +533,7 @@ impl StarknetApiServer for StarknetApi {
             let tx = ExecutableTxWithHash::new(ExecutableTx::DeployAccount(tx));
             let tx_hash = tx.hash;
 
-            this.inner.sequencer.add_transaction_to_pool(tx);
+            this.inner.sequencer.add_transaction_to_pool(tx).map_err(StarknetApiError::from)?;
 
             Ok((tx_hash, contract_address).into())
         })
@@ -575,7 +648,7 @@ impl StarknetApiServer for StarknetApi {
             let tx = ExecutableTxWithHash::new(ExecutableTx::Declare(tx));
             let tx_hash = tx.hash;
 
-            this.inner.sequencer.add_transaction_to_pool(tx);
+            this.inner.sequencer.add_transaction_to_pool(tx).map_err(StarknetApiError::from)?;
 
             Ok((tx_hash, class_hash).into())
         })
@@ -597,7 +670,7 @@ impl StarknetApiServer for StarknetApi {
             let tx = ExecutableTxWithHash::new(ExecutableTx::Invoke(tx));
             let tx_hash = tx.hash;
 
-            this.inner.sequencer.add_transaction_to_pool(tx);
+            this.inner.sequencer.add_transaction_to_pool(tx).map_err(StarknetApiError::from)?;
 
             Ok(tx_hash.into())
         })
@@ -642,7 +715,7 @@ impl StarknetApiServer for StarknetApi {
             if let Some(is_reverted) = executed_txs
                 .iter()
                 .find(|(tx, _)| tx.hash == transaction_hash)
-                .map(|(_, rct)| rct.receipt.is_reverted())
+                .map(|(_, rct)| rct.is_reverted())
             {
                 let exec_status = if is_reverted {
                     TransactionExecutionStatus::Reverted
@@ -104,12 +104,12 @@ impl ToriiApiServer for ToriiApi {
                             .iter()
                             .skip(cursor.transaction_index as usize)
                             .take(remaining)
-                            .map(|(tx, info)| {
+                            .map(|(tx, receipt)| {
                                 (
                                     tx.clone(),
                                     MaybePendingTxReceipt::Pending(PendingTxReceipt::new(
                                         tx.hash,
-                                        info.receipt.clone(),
+                                        receipt.clone(),
                                     )),
                                 )
                             })
@@ -140,12 +140,12 @@ impl ToriiApiServer for ToriiApi {
                             .read()
                             .iter()
                             .take(remaining)
-                            .map(|(tx, info)| {
+                            .map(|(tx, receipt)| {
                                 (
                                     tx.clone(),
                                     MaybePendingTxReceipt::Pending(PendingTxReceipt::new(
                                         tx.hash,
-                                        info.receipt.clone(),
+                                        receipt.clone(),
                                     )),
                                 )
                             })
@@ -0,0 +1,149 @@
+use std::time::Duration;
+
+use dojo_test_utils::sequencer::{get_default_test_starknet_config, TestSequencer};
+use jsonrpsee::http_client::HttpClientBuilder;
+use katana_core::sequencer::SequencerConfig;
+use katana_primitives::contract::ContractAddress;
+use katana_primitives::genesis::constant::DEFAULT_FEE_TOKEN_ADDRESS;
+use katana_primitives::genesis::json::GenesisJson;
+use katana_primitives::genesis::Genesis;
+use katana_rpc_api::katana::KatanaApiClient;
+use starknet::accounts::{Account, Call};
+use starknet::core::types::FieldElement;
+use starknet::core::utils::get_selector_from_name;
+
+const WAIT_TX_DELAY_MILLIS: u64 = 1000;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_transaction_execution_profile() {
+    let sequencer =
+        TestSequencer::start(SequencerConfig::default(), get_default_test_starknet_config()).await;
+    let client = HttpClientBuilder::default().build(sequencer.url()).unwrap();
+    let account = sequencer.account();
+
+    let transfer = Call {
+        to: DEFAULT_FEE_TOKEN_ADDRESS.into(),
+        selector: get_selector_from_name("transfer").unwrap(),
+        calldata: vec![FieldElement::ONE, FieldElement::ONE, FieldElement::ZERO],
+    };
+    let res = account.execute(vec![transfer]).send().await.unwrap();
+
+    // wait for the tx to be mined
+    tokio::time::sleep(Duration::from_millis(WAIT_TX_DELAY_MILLIS)).await;
+
+    let resources = client.transaction_execution_resources(res.transaction_hash).await.unwrap();
+    let account_address = ContractAddress::from(account.address());
+    let fee_token = ContractAddress::from(DEFAULT_FEE_TOKEN_ADDRESS);
+
+    // the fee is disabled, so there is no fee transfer call
+    let [validate, execute] = resources.calls.as_slice() else {
+        panic!("expected the validate and execute calls, got {:?}", resources.calls);
+    };
+    assert_eq!(validate.contract_address, account_address);
+    assert_eq!(execute.contract_address, account_address);
+    assert_eq!(execute.inner_calls.len(), 1);
+    assert_eq!(execute.inner_calls[0].contract_address, fee_token);
+    assert_eq!(execute.inner_calls[0].selector, get_selector_from_name("transfer").unwrap());
+
+    let call_steps = validate.steps + execute.steps + execute.inner_calls[0].steps;
+    assert!(call_steps > 0);
+    assert!(call_steps <= resources.steps);
+
+    // one line per call, with the path to the call and the steps used by the call itself
+    let flamegraph = client.transaction_flamegraph(res.transaction_hash).await.unwrap();
+    let lines = flamegraph.lines().collect::<Vec<_>>();
+    let transfer = execute.inner_calls[0].selector;
+    assert_eq!(
+        lines,
+        vec![
+            format!("{account_address}::__validate__ {}", validate.steps),
+            format!("{account_address}::__execute__ {}", execute.steps),
+            format!(
+                "{account_address}::__execute__;{fee_token}::{transfer:#x} {}",
+                execute.inner_calls[0].steps
+            ),
+        ]
+    );
+
+    sequencer.stop().expect("failed to stop sequencer");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_export_genesis_after_transactions() {
+    let sequencer =
+        TestSequencer::start(SequencerConfig::default(), get_default_test_starknet_config()).await;
+    let client = HttpClientBuilder::default().build(sequencer.url()).unwrap();
+    let account = sequencer.account();
+
+    let transfer = Call {
+        to: DEFAULT_FEE_TOKEN_ADDRESS.into(),
+        selector: get_selector_from_name("transfer").unwrap(),
+        calldata: vec![FieldElement::ONE, FieldElement::ONE, FieldElement::ZERO],
+    };
+    account.execute(vec![transfer]).send().await.unwrap();
+
+    // wait for the tx to be mined
+    tokio::time::sleep(Duration::from_millis(WAIT_TX_DELAY_MILLIS)).await;
+
+    let exported = client.export_genesis(None).await.unwrap();
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("genesis.json");
+    exported.save(&path).unwrap();
+    let loaded = Genesis::try_from(GenesisJson::load(&path).unwrap()).unwrap();
+
+    let genesis = &sequencer.sequencer.backend.config.genesis;
+    let account_address = ContractAddress::from(account.address());
+    let allocation = loaded.allocations.get(&account_address).expect("account must be exported");
+
+    // the fee is disabled, so only the transferred amount is deducted from the balance
+    assert_eq!(allocation.nonce(), Some(FieldElement::ONE));
+    assert_eq!(
+        allocation.balance(),
+        genesis.allocations[&account_address].balance().map(|balance| balance - 1)
+    );
+    assert_eq!(loaded.allocations.len(), genesis.allocations.len());
+    assert_eq!(loaded.fee_token.class_hash, genesis.fee_token.class_hash);
+
+    let mut classes = loaded.classes.keys().collect::<Vec<_>>();
+    let mut expected = genesis.classes.keys().collect::<Vec<_>>();
+    classes.sort();
+    expected.sort();
+    assert_eq!(classes, expected);
+
+    // the fee token class is a legacy class, whose ABI is exported along with its definition
+    let fee_token_class = &loaded.classes[&loaded.fee_token.class_hash];
+    assert!(fee_token_class.legacy_abi.as_ref().is_some_and(|abi| !abi.is_empty()));
+
+    sequencer.stop().expect("failed to stop sequencer");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_transaction_revert_reason() {
+    let sequencer =
+        TestSequencer::start(SequencerConfig::default(), get_default_test_starknet_config()).await;
+    let client = HttpClientBuilder::default().build(sequencer.url()).unwrap();
+    let account = sequencer.account();
+
+    // more than the balance of the account
+    let transfer = Call {
+        to: DEFAULT_FEE_TOKEN_ADDRESS.into(),
+        selector: get_selector_from_name("transfer").unwrap(),
+        calldata: vec![FieldElement::ONE, FieldElement::ZERO, FieldElement::ONE],
+    };
+    // the fee isn't estimated, as the estimation fails for a reverting transaction
+    let res = account.execute(vec![transfer]).max_fee(FieldElement::ONE).send().await.unwrap();
+
+    // wait for the tx to be mined
+    tokio::time::sleep(Duration::from_millis(WAIT_TX_DELAY_MILLIS)).await;
+
+    let reason = client.transaction_revert_reason(res.transaction_hash).await.unwrap();
+    let reason = reason.expect("the transaction must be reverted");
+
+    let fee_token = ContractAddress::from(DEFAULT_FEE_TOKEN_ADDRESS);
+    assert_eq!(reason.contract_address, Some(fee_token));
+    assert_eq!(reason.call_path.last().map(|frame| frame.contract_address), Some(fee_token));
+    assert!(!reason.reason.is_empty());
+
+    sequencer.stop().expect("failed to stop sequencer");
+}
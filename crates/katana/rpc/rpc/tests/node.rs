@@ -0,0 +1,290 @@
+use std::time::Duration;
+
+use dojo_test_utils::sequencer::{
+    get_default_test_starknet_config, SequencerConfig, StarknetConfig, TestSequencer,
+};
+use jsonrpsee::http_client::HttpClientBuilder;
+use katana_primitives::genesis::constant::{
+    get_fee_token_balance_base_storage_address, DEFAULT_FEE_TOKEN_ADDRESS,
+    DEFAULT_PREFUNDED_ACCOUNT_BALANCE,
+};
+use katana_rpc::config::{ServerConfig, DEFAULT_MAX_BODY_SIZE};
+use katana_rpc::node::Node;
+use katana_rpc_api::dev::DevApiClient;
+use katana_rpc_api::katana::KatanaApiClient;
+use katana_rpc_api::ApiKind;
+use starknet::accounts::{Account, Call, ExecutionEncoding, SingleOwnerAccount};
+use starknet::core::chain_id;
+use starknet::core::types::{BlockId, BlockTag, FieldElement, MaybePendingBlockWithTxHashes};
+use starknet::core::utils::get_selector_from_name;
+use starknet::providers::jsonrpc::HttpTransport;
+use starknet::providers::{JsonRpcClient, Provider};
+use starknet::signers::{LocalWallet, SigningKey};
+use url::Url;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_start_and_stop_node() {
+    let node = Node::builder()
+        .starknet_config(get_default_test_starknet_config())
+        .start()
+        .await
+        .expect("failed to start node");
+
+    let url = Url::parse(&format!("http://{}", node.addr())).unwrap();
+    let client = JsonRpcClient::new(HttpTransport::new(url));
+
+    let chain_id = client.chain_id().await.unwrap();
+    assert_eq!(chain_id, node.sequencer().chain_id().into());
+
+    let (address, account) = node.accounts().next().expect("must have predeployed accounts");
+    let address = FieldElement::from(*address);
+    let class_hash =
+        client.get_class_hash_at(BlockId::Tag(BlockTag::Latest), address).await.unwrap();
+    assert_eq!(class_hash, account.class_hash());
+
+    node.stop().await.unwrap();
+    assert!(client.block_number().await.is_err(), "server must not respond once stopped");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_node_exposes_selected_apis_only() {
+    let server_config = ServerConfig {
+        port: 0,
+        host: "127.0.0.1".into(),
+        max_connections: 100,
+        apis: vec![ApiKind::Starknet],
+        max_request_body_size: DEFAULT_MAX_BODY_SIZE,
+        max_response_body_size: DEFAULT_MAX_BODY_SIZE,
+        max_batch_size: None,
+        rate_limit: None,
+        trusted_proxies: Vec::new(),
+    };
+
+    let node = Node::builder()
+        .starknet_config(get_default_test_starknet_config())
+        .server_config(server_config)
+        .start()
+        .await
+        .expect("failed to start node");
+
+    let url = Url::parse(&format!("http://{}", node.addr())).unwrap();
+    let client = HttpClientBuilder::default().build(&url).unwrap();
+
+    assert!(JsonRpcClient::new(HttpTransport::new(url)).chain_id().await.is_ok());
+    assert!(client.generate_block().await.is_err(), "dev api must not be exposed");
+
+    node.stop().await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_rate_limit_ignores_spoofed_client_ip() {
+    let server_config = ServerConfig {
+        port: 0,
+        host: "127.0.0.1".into(),
+        max_connections: 100,
+        apis: vec![ApiKind::Starknet],
+        max_request_body_size: DEFAULT_MAX_BODY_SIZE,
+        max_response_body_size: DEFAULT_MAX_BODY_SIZE,
+        max_batch_size: None,
+        rate_limit: Some(3),
+        trusted_proxies: Vec::new(),
+    };
+
+    let node = Node::builder()
+        .starknet_config(get_default_test_starknet_config())
+        .server_config(server_config)
+        .start()
+        .await
+        .expect("failed to start node");
+
+    let client = hyper::Client::new();
+    let call = r#"{"jsonrpc":"2.0","method":"starknet_chainId","params":[],"id":1}"#;
+    let send = |body: String, forwarded_for: &str| {
+        let request = hyper::Request::post(format!("http://{}", node.addr()))
+            .header(hyper::header::CONTENT_TYPE, "application/json")
+            .header("x-forwarded-for", forwarded_for)
+            .body(hyper::Body::from(body))
+            .unwrap();
+        client.request(request)
+    };
+
+    // the calls of a batch are counted individually
+    let response = send(format!("[{call},{call}]"), "198.51.100.1").await.unwrap();
+    assert_eq!(response.status(), hyper::StatusCode::OK);
+
+    let response = send(call.to_string(), "198.51.100.2").await.unwrap();
+    assert_eq!(response.status(), hyper::StatusCode::OK);
+
+    // the client can't get a fresh limit by making up a new address
+    let response = send(call.to_string(), "198.51.100.3").await.unwrap();
+    assert_eq!(response.status(), hyper::StatusCode::TOO_MANY_REQUESTS);
+
+    node.stop().await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_query_state_at_timestamp() {
+    let node = Node::builder()
+        .starknet_config(get_default_test_starknet_config())
+        .start()
+        .await
+        .expect("failed to start node");
+
+    let url = Url::parse(&format!("http://{}", node.addr())).unwrap();
+    let client = HttpClientBuilder::default().build(&url).unwrap();
+    let provider = JsonRpcClient::new(HttpTransport::new(url));
+
+    client.generate_block().await.unwrap();
+
+    let MaybePendingBlockWithTxHashes::Block(block) =
+        provider.get_block_with_tx_hashes(BlockId::Number(1)).await.unwrap()
+    else {
+        panic!("block 1 must be mined");
+    };
+
+    let block_id = client.block_id_by_timestamp(block.timestamp).await.unwrap();
+    assert_eq!(serde_json::to_value(&block_id).unwrap()["block_number"], 1);
+    let block_id = client.block_id_by_timestamp(u64::MAX).await.unwrap();
+    assert_eq!(serde_json::to_value(&block_id).unwrap()["block_number"], 1);
+
+    // the genesis block is mined at timestamp 0
+    let block_id = client.block_id_by_timestamp(0).await.unwrap();
+    assert_eq!(serde_json::to_value(&block_id).unwrap()["block_number"], 0);
+
+    let (address, _) = node.accounts().next().expect("must have predeployed accounts");
+    let key = get_fee_token_balance_base_storage_address(*address);
+    let balance =
+        client.get_storage_at_timestamp(DEFAULT_FEE_TOKEN_ADDRESS.into(), key, 0).await.unwrap();
+    assert_eq!(*balance, FieldElement::from(DEFAULT_PREFUNDED_ACCOUNT_BALANCE));
+
+    node.stop().await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_follow_node() {
+    let upstream =
+        TestSequencer::start(SequencerConfig::default(), get_default_test_starknet_config()).await;
+    let upstream_provider = JsonRpcClient::new(HttpTransport::new(upstream.url()));
+    let upstream_client = HttpClientBuilder::default().build(upstream.url()).unwrap();
+
+    let transfer = Call {
+        to: DEFAULT_FEE_TOKEN_ADDRESS.into(),
+        selector: get_selector_from_name("transfer").unwrap(),
+        calldata: vec![FieldElement::ONE, FieldElement::ONE, FieldElement::ZERO],
+    };
+    let res = upstream.account().execute(vec![transfer]).send().await.unwrap();
+    let tx_hash = res.transaction_hash;
+
+    // wait for the tx to be mined
+    tokio::time::sleep(Duration::from_millis(1000)).await;
+    let latest = upstream_provider.block_number().await.unwrap();
+    assert!(latest > 0, "the transaction must be mined");
+
+    let starknet_config = StarknetConfig {
+        follow_rpc_url: Some(upstream.url()),
+        ..get_default_test_starknet_config()
+    };
+    let replica = Node::builder()
+        .starknet_config(starknet_config)
+        .start()
+        .await
+        .expect("failed to start replica");
+
+    let url = Url::parse(&format!("http://{}", replica.addr())).unwrap();
+    let provider = JsonRpcClient::new(HttpTransport::new(url.clone()));
+    let client = HttpClientBuilder::default().build(&url).unwrap();
+
+    // the blocks are synced in the background, once the replica is already serving
+    let synced = async {
+        while provider.block_number().await.ok() != Some(latest) {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    };
+    tokio::time::timeout(Duration::from_secs(10), synced)
+        .await
+        .expect("replica must sync the blocks of the upstream node");
+
+    for block in 0..=latest {
+        let block = BlockId::Number(block);
+        assert_eq!(
+            provider.get_block_with_tx_hashes(block).await.unwrap(),
+            upstream_provider.get_block_with_tx_hashes(block).await.unwrap()
+        );
+    }
+
+    assert_eq!(
+        provider.get_transaction_receipt(tx_hash).await.unwrap(),
+        upstream_provider.get_transaction_receipt(tx_hash).await.unwrap()
+    );
+
+    // the data only exposed by the katana API of the upstream node is synced as well
+    let resources = client.transaction_execution_resources(tx_hash).await.unwrap();
+    assert!(!resources.calls.is_empty());
+    assert_eq!(resources, upstream_client.transaction_execution_resources(tx_hash).await.unwrap());
+
+    let status = replica.sequencer().follower_status().expect("replica must follow a node");
+    assert_eq!(status.diverged(), None);
+
+    replica.stop().await.unwrap();
+    upstream.stop().unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_reset_fork() {
+    let upstream =
+        TestSequencer::start(SequencerConfig::default(), get_default_test_starknet_config()).await;
+    let forked_block =
+        JsonRpcClient::new(HttpTransport::new(upstream.url())).block_number().await.unwrap();
+
+    let starknet_config =
+        StarknetConfig { fork_rpc_url: Some(upstream.url()), ..get_default_test_starknet_config() };
+    let node = Node::builder()
+        .sequencer_config(SequencerConfig { no_mining: true, ..Default::default() })
+        .starknet_config(starknet_config)
+        .start()
+        .await
+        .expect("failed to start forked node");
+
+    let url = Url::parse(&format!("http://{}", node.addr())).unwrap();
+    let provider = JsonRpcClient::new(HttpTransport::new(url.clone()));
+    let client = HttpClientBuilder::default().build(&url).unwrap();
+
+    // a local block, and a transaction left pending in the next one
+    client.generate_block().await.unwrap();
+    assert_eq!(provider.block_number().await.unwrap(), forked_block + 1);
+
+    let account = SingleOwnerAccount::new(
+        JsonRpcClient::new(HttpTransport::new(url.clone())),
+        LocalWallet::from_signing_key(SigningKey::from_secret_scalar(
+            upstream.raw_account().private_key,
+        )),
+        upstream.raw_account().account_address,
+        chain_id::TESTNET,
+        ExecutionEncoding::New,
+    );
+    let transfer = Call {
+        to: DEFAULT_FEE_TOKEN_ADDRESS.into(),
+        selector: get_selector_from_name("transfer").unwrap(),
+        calldata: vec![FieldElement::ONE, FieldElement::ONE, FieldElement::ZERO],
+    };
+    let res = account.execute(vec![transfer]).send().await.unwrap();
+
+    // wait for the tx to be executed in the pending block
+    tokio::time::sleep(Duration::from_millis(1000)).await;
+    assert!(provider.get_transaction_by_hash(res.transaction_hash).await.is_ok());
+
+    client.reset_fork(None, None).await.unwrap();
+
+    assert_eq!(provider.block_number().await.unwrap(), forked_block);
+    assert!(provider.get_block_with_tx_hashes(BlockId::Number(forked_block + 1)).await.is_err());
+    assert!(provider.get_transaction_by_hash(res.transaction_hash).await.is_err());
+
+    let pending = node.sequencer().pending_state().expect("must have a pending block");
+    assert!(pending.executed_txs.read().is_empty());
+    let nonce =
+        provider.get_nonce(BlockId::Tag(BlockTag::Pending), account.address()).await.unwrap();
+    assert_eq!(nonce, FieldElement::ZERO);
+
+    node.stop().await.unwrap();
+    upstream.stop().unwrap();
+}
@@ -3,16 +3,35 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 
-use dojo_test_utils::sequencer::{get_default_test_starknet_config, TestSequencer};
+use dojo_test_utils::sequencer::{
+    get_default_test_starknet_config, StarknetConfig, TestAccount, TestSequencer,
+};
+use jsonrpsee::http_client::HttpClientBuilder;
 use katana_core::sequencer::SequencerConfig;
+use katana_primitives::block::BlockIdOrTag;
+use katana_primitives::chain::ChainId;
+use katana_primitives::contract::ContractAddress;
+use katana_primitives::genesis::constant::{
+    get_fee_token_balance_base_storage_address, DEFAULT_FEE_TOKEN_ADDRESS,
+    DEFAULT_STRK_FEE_TOKEN_ADDRESS,
+};
+use katana_primitives::transaction::{InvokeTx, InvokeTxV3};
+use katana_rpc_api::dev::DevApiClient;
+use katana_rpc_api::starknet::StarknetApiClient;
+use katana_rpc_types::block::MaybePendingBlockWithReceipts;
 use starknet::accounts::{Account, Call, ConnectedAccount};
 use starknet::core::types::contract::legacy::LegacyContractClass;
+use starknet::core::types::contract::CompiledClass;
 use starknet::core::types::{
-    BlockId, BlockTag, DeclareTransactionReceipt, FieldElement, MaybePendingTransactionReceipt,
-    TransactionFinalityStatus, TransactionReceipt,
+    BlockId, BlockTag, BroadcastedInvokeTransaction, BroadcastedInvokeTransactionV3,
+    BroadcastedTransaction, DataAvailabilityMode, DeclareTransactionReceipt, FieldElement,
+    MaybePendingTransactionReceipt, PriceUnit, ResourceBounds, ResourceBoundsMapping,
+    SimulationFlagForEstimateFee, StarknetError, TransactionFinalityStatus, TransactionReceipt,
 };
 use starknet::core::utils::{get_contract_address, get_selector_from_name};
-use starknet::providers::Provider;
+use starknet::providers::jsonrpc::HttpTransport;
+use starknet::providers::{JsonRpcClient, Provider, ProviderError};
+use starknet::signers::SigningKey;
 
 use crate::common::prepare_contract_declaration_params;
 
@@ -175,3 +194,176 @@ async fn test_send_declare_and_deploy_legacy_contract() {
 
     sequencer.stop().expect("failed to stop sequencer");
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_get_block_with_receipts_and_compiled_casm() {
+    let sequencer = TestSequencer::start(
+        SequencerConfig { no_mining: true, ..Default::default() },
+        get_default_test_starknet_config(),
+    )
+    .await;
+
+    let client = HttpClientBuilder::default().build(sequencer.url()).unwrap();
+    let account = sequencer.account();
+
+    let path: PathBuf = PathBuf::from("tests/test_data/cairo1_contract.json");
+    let (contract, compiled_class_hash) = prepare_contract_declaration_params(&path).unwrap();
+    let class_hash = contract.class_hash();
+    let res = account.declare(Arc::new(contract), compiled_class_hash).send().await.unwrap();
+
+    let block = client.block_with_receipts(BlockIdOrTag::Tag(BlockTag::Pending)).await.unwrap();
+    let MaybePendingBlockWithReceipts::Pending(block) = block else {
+        panic!("expected pending block");
+    };
+    assert_eq!(block.transactions.len(), 1);
+
+    let casm = client.compiled_casm(class_hash).await.unwrap();
+    let casm: CompiledClass = serde_json::from_value(casm).unwrap();
+    assert_eq!(casm.class_hash().unwrap(), compiled_class_hash);
+
+    // Create block 1.
+    let _: () = client.generate_block().await.unwrap();
+
+    let block = client.block_with_receipts(BlockIdOrTag::Number(1)).await.unwrap();
+    let MaybePendingBlockWithReceipts::Block(block) = block else {
+        panic!("expected mined block");
+    };
+    assert_eq!(block.header.block_number, 1);
+    assert_eq!(block.transactions.len(), 1);
+
+    let receipt = serde_json::to_value(&block.transactions[0].receipt).unwrap();
+    let TransactionReceipt::Declare(receipt) = serde_json::from_value(receipt).unwrap() else {
+        panic!("expected declare receipt");
+    };
+    assert_eq!(receipt.transaction_hash, res.transaction_hash);
+    assert_eq!(receipt.block_hash, block.header.block_hash);
+
+    sequencer.stop().expect("failed to stop sequencer");
+}
+
+/// Builds a V3 invoke transaction of `account`, transferring 1 unit of the ETH fee token to the
+/// address `0x1`.
+fn transfer_v3(
+    account: &TestAccount,
+    nonce: FieldElement,
+    l1_gas: ResourceBounds,
+    is_query: bool,
+) -> BroadcastedInvokeTransaction {
+    let calldata = vec![
+        FieldElement::ONE,
+        DEFAULT_FEE_TOKEN_ADDRESS.into(),
+        get_selector_from_name("transfer").unwrap(),
+        FieldElement::THREE,
+        FieldElement::ONE,
+        FieldElement::ONE,
+        FieldElement::ZERO,
+    ];
+    let resource_bounds = ResourceBoundsMapping {
+        l1_gas,
+        l2_gas: ResourceBounds { max_amount: 0, max_price_per_unit: 0 },
+    };
+
+    let hash = InvokeTx::V3(InvokeTxV3 {
+        chain_id: ChainId::GOERLI,
+        sender_address: account.account_address.into(),
+        nonce,
+        calldata: calldata.clone(),
+        signature: vec![],
+        resource_bounds: resource_bounds.clone(),
+        tip: 0,
+        paymaster_data: vec![],
+        account_deployment_data: vec![],
+        nonce_data_availability_mode: DataAvailabilityMode::L1,
+        fee_data_availability_mode: DataAvailabilityMode::L1,
+    })
+    .calculate_hash(is_query);
+    let signature = SigningKey::from_secret_scalar(account.private_key).sign(&hash).unwrap();
+
+    BroadcastedInvokeTransaction::V3(BroadcastedInvokeTransactionV3 {
+        sender_address: account.account_address,
+        calldata,
+        signature: vec![signature.r, signature.s],
+        nonce,
+        resource_bounds,
+        tip: 0,
+        paymaster_data: vec![],
+        account_deployment_data: vec![],
+        nonce_data_availability_mode: DataAvailabilityMode::L1,
+        fee_data_availability_mode: DataAvailabilityMode::L1,
+        is_query,
+    })
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_v3_transactions_pay_fee_in_strk() {
+    let starknet_config =
+        StarknetConfig { disable_fee: false, ..get_default_test_starknet_config() };
+    let sequencer = TestSequencer::start(SequencerConfig::default(), starknet_config).await;
+    let provider = JsonRpcClient::new(HttpTransport::new(sequencer.url()));
+    let account = sequencer.raw_account();
+
+    let strk_gas_price = sequencer.sequencer.backend.config.env.gas_price.strk;
+    let nonce =
+        provider.get_nonce(BlockId::Tag(BlockTag::Pending), account.account_address).await.unwrap();
+
+    let balance_key = get_fee_token_balance_base_storage_address(account.account_address.into());
+    let balance = |token: ContractAddress| {
+        let provider = &provider;
+        async move {
+            provider
+                .get_storage_at(
+                    FieldElement::from(token),
+                    balance_key,
+                    BlockId::Tag(BlockTag::Latest),
+                )
+                .await
+                .unwrap()
+        }
+    };
+    let eth_balance = balance(DEFAULT_FEE_TOKEN_ADDRESS).await;
+    let strk_balance = balance(DEFAULT_STRK_FEE_TOKEN_ADDRESS).await;
+
+    // the fee is estimated in STRK, at the STRK gas price
+    let l1_gas = ResourceBounds { max_amount: 1_000_000, max_price_per_unit: strk_gas_price };
+    let query = transfer_v3(account, nonce, l1_gas.clone(), true);
+    let estimate = provider
+        .estimate_fee_single(
+            BroadcastedTransaction::Invoke(query),
+            Vec::<SimulationFlagForEstimateFee>::new(),
+            BlockId::Tag(BlockTag::Pending),
+        )
+        .await
+        .unwrap();
+    assert_eq!(estimate.unit, PriceUnit::Fri);
+    assert_eq!(estimate.gas_price, FieldElement::from(strk_gas_price));
+
+    // a price bound below the STRK gas price is rejected
+    let underpriced = ResourceBounds { max_price_per_unit: strk_gas_price - 1, ..l1_gas.clone() };
+    let res =
+        provider.add_invoke_transaction(transfer_v3(account, nonce, underpriced, false)).await;
+    assert!(
+        matches!(res, Err(ProviderError::StarknetError(StarknetError::InsufficientMaxFee))),
+        "unexpected result: {res:?}"
+    );
+
+    let res =
+        provider.add_invoke_transaction(transfer_v3(account, nonce, l1_gas, false)).await.unwrap();
+
+    // wait for the tx to be mined
+    tokio::time::sleep(Duration::from_millis(WAIT_TX_DELAY_MILLIS)).await;
+
+    let MaybePendingTransactionReceipt::Receipt(TransactionReceipt::Invoke(receipt)) =
+        provider.get_transaction_receipt(res.transaction_hash).await.unwrap()
+    else {
+        panic!("expected an invoke receipt");
+    };
+    assert_eq!(receipt.actual_fee.unit, PriceUnit::Fri);
+    assert!(receipt.actual_fee.amount > FieldElement::ZERO);
+
+    // the fee is charged in STRK, and only the transferred amount is taken from the ETH balance
+    let strk_paid = strk_balance - balance(DEFAULT_STRK_FEE_TOKEN_ADDRESS).await;
+    assert_eq!(strk_paid, receipt.actual_fee.amount);
+    assert_eq!(balance(DEFAULT_FEE_TOKEN_ADDRESS).await, eth_balance - FieldElement::ONE);
+
+    sequencer.stop().expect("failed to stop sequencer");
+}
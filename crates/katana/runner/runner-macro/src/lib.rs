@@ -1,21 +1,84 @@
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{parse_macro_input, parse_quote, Stmt};
+use syn::{parse_macro_input, parse_quote, Expr, Stmt};
+
+/// The arguments of the macros: the positional `n_accounts, with_blocks, executable`, optionally
+/// followed by `in_process` to start the node in-process instead of running `executable`, and by
+/// `genesis = "<path>"` or `db = "<path>"` to start it from a state fixture.
+struct Metadata {
+    n_accounts: u16,
+    with_blocks: bool,
+    executable: String,
+    in_process: bool,
+    fixture: Option<Expr>,
+}
+
+fn parse_metadata(metadata: String) -> Metadata {
+    let mut parsed = Metadata {
+        n_accounts: 2,
+        with_blocks: false,
+        executable: "katana".into(),
+        in_process: false,
+        fixture: None,
+    };
 
-fn parse_metadata(metadata: String) -> (u16, String, bool) {
     if metadata.is_empty() {
-        return (2, "katana".into(), false);
+        return parsed;
     }
-    let args = metadata.split(',').collect::<Vec<&str>>();
-    let n_accounts = if !args.is_empty() { args[0].parse::<u16>().unwrap() } else { 1 };
 
-    let with_blocks = if args.len() >= 2 { args[1].trim().parse::<bool>().unwrap() } else { false };
-
-    let executable = if args.len() >= 3 { args[2].trim() } else { "katana" };
-    let executable = executable.replace('"', "");
+    let mut positional = 0;
+    for arg in metadata.split(',').map(str::trim) {
+        if let Some((name, value)) = arg.split_once('=') {
+            let path = value.trim().replace('"', "");
+            parsed.fixture = Some(match name.trim() {
+                "genesis" => parse_quote!(katana_runner::StateFixture::Genesis(#path.into())),
+                "db" => parse_quote!(katana_runner::StateFixture::Database(#path.into())),
+                name => panic!("unknown katana_test argument `{name}`"),
+            });
+        } else if arg == "in_process" {
+            parsed.in_process = true;
+        } else {
+            match positional {
+                0 => parsed.n_accounts = arg.parse::<u16>().unwrap(),
+                1 => parsed.with_blocks = arg.parse::<bool>().unwrap(),
+                2 => parsed.executable = arg.replace('"', ""),
+                _ => {}
+            }
+            positional += 1;
+        }
+    }
 
     // plus one as the first account is used for deployment
-    (n_accounts + 1, executable, with_blocks)
+    parsed.n_accounts += 1;
+    parsed
+}
+
+impl Metadata {
+    /// Returns the expression starting the runner.
+    fn runner(&self, name: &str) -> Expr {
+        let Metadata { n_accounts, with_blocks, executable, .. } = self;
+
+        let program: Expr = if self.in_process {
+            parse_quote!(None)
+        } else {
+            parse_quote!(Some(#executable.into()))
+        };
+        let fixture: Expr = match &self.fixture {
+            Some(fixture) => parse_quote!(Some(#fixture)),
+            None => parse_quote!(None),
+        };
+
+        parse_quote! {
+            katana_runner::KatanaRunner::new_with_config(katana_runner::KatanaRunnerConfig {
+                program: #program,
+                name: #name.into(),
+                n_accounts: #n_accounts,
+                with_blocks: #with_blocks,
+                fixture: #fixture,
+                extra_args: Vec::new(),
+            })
+        }
+    }
 }
 
 #[proc_macro_attribute]
@@ -23,12 +86,10 @@ pub fn katana_test(metadata: TokenStream, input: TokenStream) -> TokenStream {
     let mut test_function = parse_macro_input!(input as syn::ItemFn);
     let function_name = test_function.sig.ident.to_string();
 
-    let (n_accounts, executable, with_blocks) = parse_metadata(metadata.to_string());
+    let runner = parse_metadata(metadata.to_string()).runner(&function_name);
 
     let header: Stmt = parse_quote! {
-        let runner =
-            katana_runner::KatanaRunner::new_with_args(#executable, #function_name, #n_accounts, #with_blocks)
-                .expect("failed to start katana");
+        let runner = #runner.expect("failed to start katana");
     };
 
     test_function.block.stmts.insert(0, header);
@@ -52,12 +113,11 @@ pub fn runner(metadata: TokenStream) -> TokenStream {
     let mut args = metadata.split(',').collect::<Vec<&str>>();
     let function_name = args.remove(0);
 
-    let (n_accounts, executable, with_blocks) = parse_metadata(args.join(","));
+    let runner = parse_metadata(args.join(",")).runner(function_name);
     TokenStream::from(quote! {
         lazy_static::lazy_static! {
             pub static ref RUNNER: std::sync::Arc<katana_runner::KatanaRunner> = std::sync::Arc::new(
-                katana_runner::KatanaRunner::new_with_args(#executable, #function_name, #n_accounts, #with_blocks)
-                    .expect("failed to start katana")
+                #runner.expect("failed to start katana")
             );
 
         }
@@ -0,0 +1,57 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use katana_primitives::genesis::json::GenesisJson;
+use katana_primitives::genesis::Genesis;
+use tempfile::TempDir;
+
+/// The state a [`KatanaRunner`](crate::KatanaRunner) starts the node from, so that the tests
+/// start with an already migrated world instead of setting it up themselves.
+#[derive(Debug, Clone)]
+pub enum StateFixture {
+    /// A genesis file, eg. one exported with `katana genesis export` from the database of a node
+    /// on which the world has been migrated. The accounts of the runner are the genesis accounts
+    /// whose private key is exposed.
+    Genesis(PathBuf),
+    /// The database directory of a node started with `--db-dir` and the same genesis as the
+    /// runner, ie. the same number of accounts, see
+    /// [`KatanaRunner::genesis`](crate::KatanaRunner::genesis). The database is copied to a
+    /// temporary directory so that the fixture isn't modified by the tests.
+    Database(PathBuf),
+}
+
+impl StateFixture {
+    pub(crate) fn load_genesis(path: &Path) -> Result<Genesis> {
+        let json = GenesisJson::load(path)
+            .with_context(|| format!("failed to load genesis fixture {}", path.display()))?;
+        Genesis::try_from(json).context("invalid genesis fixture")
+    }
+
+    /// Copies the database at `path` to a temporary directory, removed once dropped.
+    pub(crate) fn copy_database(path: &Path) -> Result<TempDir> {
+        let dir = tempfile::tempdir().context("failed to create database directory")?;
+        copy_dir(path, dir.path())
+            .with_context(|| format!("failed to copy database fixture {}", path.display()))?;
+        Ok(dir)
+    }
+}
+
+/// Recursively copies the content of the directory `from` to the existing directory `to`.
+fn copy_dir(from: &Path, to: &Path) -> Result<()> {
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+
+        if entry.file_type()?.is_dir() {
+            fs::create_dir(&target)?;
+            copy_dir(&entry.path(), &target)?;
+        } else if entry.file_name() != "mdbx.lck" {
+            // the lock file is recreated when the database is opened
+            fs::copy(entry.path(), &target)
+                .with_context(|| format!("failed to copy {}", entry.path().display()))?;
+        }
+    }
+
+    Ok(())
+}
@@ -1,32 +1,86 @@
+mod fixture;
 mod logs;
+mod node;
 mod prefunded;
 mod utils;
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::{Child, Command, Stdio};
 use std::sync::mpsc::{self};
 use std::thread;
 use std::time::Duration;
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
+pub use fixture::StateFixture;
+use katana_core::backend::config::{dev_genesis, Environment, StarknetConfig};
+use katana_core::sequencer::SequencerConfig;
 use katana_primitives::contract::ContractAddress;
-use katana_primitives::genesis::allocation::{DevAllocationsGenerator, DevGenesisAccount};
+use katana_primitives::genesis::allocation::{
+    DevAllocationsGenerator, DevGenesisAccount, GenesisAccountAlloc,
+};
+use katana_primitives::genesis::constant::DEFAULT_PREFUNDED_ACCOUNT_BALANCE;
+use katana_primitives::genesis::Genesis;
 use katana_primitives::FieldElement;
+use node::InProcessNode;
 pub use runner_macro::{katana_test, runner};
 use starknet::providers::jsonrpc::HttpTransport;
 use starknet::providers::JsonRpcClient;
+use tempfile::TempDir;
 use tokio::sync::Mutex;
 use url::Url;
 use utils::find_free_port;
 
 #[derive(Debug)]
 pub struct KatanaRunner {
-    child: Child,
+    backend: Backend,
     port: u16,
     provider: JsonRpcClient<HttpTransport>,
     accounts: Vec<(ContractAddress, DevGenesisAccount)>,
-    log_filename: PathBuf,
     contract: Mutex<Option<FieldElement>>,
+    /// The copy of the database fixture the node is started from, if any.
+    _db_dir: Option<TempDir>,
+}
+
+#[derive(Debug)]
+enum Backend {
+    /// The katana binary, whose logs are written to `log_filename`.
+    Process {
+        child: Child,
+        log_filename: PathBuf,
+    },
+    InProcess(InProcessNode),
+}
+
+/// Configuration of a [`KatanaRunner`].
+#[derive(Debug, Clone)]
+pub struct KatanaRunnerConfig {
+    /// The katana binary to spawn. The node is started in-process if `None`, in which case its
+    /// logs aren't recorded.
+    pub program: Option<String>,
+    /// Name of the runner, used for the log file of the binary.
+    pub name: String,
+    /// Number of prefunded accounts, ignored if the state is loaded from a genesis fixture.
+    pub n_accounts: u16,
+    /// Whether blocks are produced at an interval of [`BLOCK_TIME_IF_ENABLED`] instead of for
+    /// each transaction.
+    pub with_blocks: bool,
+    /// The state the node is started from. The node starts from the genesis only if `None`.
+    pub fixture: Option<StateFixture>,
+    /// Additional arguments passed to the binary. They can't be used with an in-process node.
+    pub extra_args: Vec<String>,
+}
+
+impl Default for KatanaRunnerConfig {
+    fn default() -> Self {
+        Self {
+            program: Some("katana".into()),
+            name: "katana".into(),
+            n_accounts: 2,
+            with_blocks: false,
+            fixture: None,
+            extra_args: Vec::new(),
+        }
+    }
 }
 
 pub const BLOCK_TIME_IF_ENABLED: u64 = 3000;
@@ -37,13 +91,8 @@ impl KatanaRunner {
     }
 
     pub fn new_with_name(name: &str) -> Result<Self> {
-        Self::new_with_port_and_filename(
-            "katana",
-            find_free_port(),
-            format!("logs/katana-{}.log", name),
-            2,
-            false,
-        )
+        let config = KatanaRunnerConfig { name: name.into(), ..Default::default() };
+        Self::start(config, find_free_port(), format!("logs/katana-{}.log", name))
     }
 
     pub fn new_with_args(
@@ -52,43 +101,146 @@ impl KatanaRunner {
         n_accounts: u16,
         with_blocks: bool,
     ) -> Result<Self> {
-        Self::new_with_port_and_filename(
-            program,
-            find_free_port(),
-            format!("katana-logs/{}.log", name),
+        Self::new_with_extra_args(program, name, n_accounts, with_blocks, &[])
+    }
+
+    /// Same as [`KatanaRunner::new_with_args`] but passes the additional `extra_args` to the
+    /// katana binary.
+    pub fn new_with_extra_args(
+        program: &str,
+        name: &str,
+        n_accounts: u16,
+        with_blocks: bool,
+        extra_args: &[&str],
+    ) -> Result<Self> {
+        Self::new_with_config(KatanaRunnerConfig {
+            program: Some(program.into()),
+            name: name.into(),
             n_accounts,
             with_blocks,
-        )
+            fixture: None,
+            extra_args: extra_args.iter().map(|arg| arg.to_string()).collect(),
+        })
     }
 
     pub fn new_with_port(port: u16) -> Result<Self> {
-        Self::new_with_port_and_filename(
-            "katana",
-            port,
-            format!("katana-logs/{}.log", port),
-            2,
-            false,
-        )
+        let config = KatanaRunnerConfig { name: port.to_string(), ..Default::default() };
+        Self::start(config, port, format!("katana-logs/{}.log", port))
+    }
+
+    /// Starts the node in-process, with `n_accounts` prefunded accounts.
+    pub fn new_in_process(name: &str, n_accounts: u16, with_blocks: bool) -> Result<Self> {
+        Self::new_with_config(KatanaRunnerConfig {
+            program: None,
+            name: name.into(),
+            n_accounts,
+            with_blocks,
+            ..Default::default()
+        })
+    }
+
+    pub fn new_with_config(config: KatanaRunnerConfig) -> Result<Self> {
+        let log_filename = format!("katana-logs/{}.log", config.name);
+        Self::start(config, find_free_port(), log_filename)
     }
 
-    fn new_with_port_and_filename(
+    fn start(config: KatanaRunnerConfig, port: u16, log_filename: String) -> Result<Self> {
+        if config.program.is_none() && !config.extra_args.is_empty() {
+            bail!(
+                "Extra arguments can only be passed to the katana binary, not to an in-process \
+                 node: {}",
+                config.extra_args.join(" ")
+            );
+        }
+
+        let genesis = match &config.fixture {
+            Some(StateFixture::Genesis(path)) => StateFixture::load_genesis(path)?,
+            _ => Self::genesis(config.n_accounts),
+        };
+
+        let db_dir = match &config.fixture {
+            Some(StateFixture::Database(path)) => Some(StateFixture::copy_database(path)?),
+            _ => None,
+        };
+
+        let accounts = genesis
+            .accounts()
+            .filter_map(|(address, account)| match account {
+                GenesisAccountAlloc::DevAccount(account) => Some((*address, account.clone())),
+                GenesisAccountAlloc::Account(_) => None,
+            })
+            .collect();
+
+        let (backend, port) = match &config.program {
+            Some(program) => {
+                let backend = Self::spawn(
+                    program,
+                    &config,
+                    port,
+                    log_filename,
+                    db_dir.as_ref().map(TempDir::path),
+                )?;
+                (backend, port)
+            }
+
+            None => {
+                let sequencer_config = SequencerConfig {
+                    block_time: config.with_blocks.then_some(BLOCK_TIME_IF_ENABLED),
+                    ..Default::default()
+                };
+
+                let starknet_config = StarknetConfig {
+                    db_dir: db_dir.as_ref().map(|dir| dir.path().to_path_buf()),
+                    genesis,
+                    ..Default::default()
+                };
+
+                let node = InProcessNode::start(sequencer_config, starknet_config)?;
+                let port = node.addr().port();
+                (Backend::InProcess(node), port)
+            }
+        };
+
+        let url =
+            Url::parse(&format!("http://127.0.0.1:{}/", port)).context("Failed to parse url")?;
+        let provider = JsonRpcClient::new(HttpTransport::new(url));
+        let contract = Mutex::new(Option::None);
+
+        Ok(KatanaRunner { backend, port, provider, accounts, contract, _db_dir: db_dir })
+    }
+
+    fn spawn(
         program: &str,
+        config: &KatanaRunnerConfig,
         port: u16,
         log_filename: String,
-        n_accounts: u16,
-        with_blocks: bool,
-    ) -> Result<Self> {
+        db_dir: Option<&Path>,
+    ) -> Result<Backend> {
         let mut command = Command::new(program);
         command
             .args(["-p", &port.to_string()])
             .args(["--json-log"])
-            .args(["--max-connections", &format!("{}", 10000)])
-            .args(["--accounts", &format!("{}", n_accounts)]);
+            .args(["--max-connections", &format!("{}", 10000)]);
 
-        if with_blocks {
+        match &config.fixture {
+            Some(StateFixture::Genesis(path)) => {
+                command.arg("--genesis").arg(path);
+            }
+            _ => {
+                command.args(["--accounts", &format!("{}", config.n_accounts)]);
+            }
+        }
+
+        if let Some(db_dir) = db_dir {
+            command.arg("--db-dir").arg(db_dir);
+        }
+
+        if config.with_blocks {
             command.args(["--block-time", &format!("{}", BLOCK_TIME_IF_ENABLED)]);
         }
 
+        command.args(&config.extra_args);
+
         let mut child =
             command.stdout(Stdio::piped()).spawn().context("failed to start subprocess")?;
 
@@ -105,20 +257,20 @@ impl KatanaRunner {
             .recv_timeout(Duration::from_secs(5))
             .context("timeout waiting for server to start")?;
 
-        let url =
-            Url::parse(&format!("http://127.0.0.1:{}/", port)).context("Failed to parse url")?;
-        let provider = JsonRpcClient::new(HttpTransport::new(url));
+        Ok(Backend::Process { child, log_filename })
+    }
 
+    /// The genesis of a node started by the runner with `n_accounts` prefunded accounts, ie. the
+    /// same as the one of the katana binary started with `--accounts <n_accounts>`.
+    pub fn genesis(n_accounts: u16) -> Genesis {
         let mut seed = [0; 32];
         seed[0] = 48;
+
         let accounts = DevAllocationsGenerator::new(n_accounts)
             .with_seed(seed)
-            .generate()
-            .into_iter()
-            .collect();
-        let contract = Mutex::new(Option::None);
+            .with_balance(DEFAULT_PREFUNDED_ACCOUNT_BALANCE);
 
-        Ok(KatanaRunner { child, port, provider, accounts, log_filename, contract })
+        dev_genesis(&accounts, Environment::default().gas_price, [])
     }
 
     pub fn provider(&self) -> &JsonRpcClient<HttpTransport> {
@@ -149,11 +301,14 @@ impl KatanaRunner {
 
 impl Drop for KatanaRunner {
     fn drop(&mut self) {
-        if let Err(e) = self.child.kill() {
-            eprintln!("Failed to kill katana subprocess: {}", e);
-        }
-        if let Err(e) = self.child.wait() {
-            eprintln!("Failed to wait for katana subprocess: {}", e);
+        // the in-process node is stopped when dropped
+        if let Backend::Process { child, .. } = &mut self.backend {
+            if let Err(e) = child.kill() {
+                eprintln!("Failed to kill katana subprocess: {}", e);
+            }
+            if let Err(e) = child.wait() {
+                eprintln!("Failed to wait for katana subprocess: {}", e);
+            }
         }
     }
 }
@@ -1,12 +1,13 @@
 use std::fs::File;
 use std::io::{BufRead, BufReader};
+use std::path::Path;
 use std::time::Duration;
 
 use chrono::prelude::*;
 use serde::{Deserialize, Serialize};
 use tokio::time::sleep;
 
-use crate::KatanaRunner;
+use crate::{Backend, KatanaRunner};
 
 #[derive(Serialize, Deserialize)]
 pub struct TimedLog<T> {
@@ -24,8 +25,15 @@ pub struct Message {
 pub type Log = TimedLog<Message>;
 
 impl KatanaRunner {
+    fn log_filename(&self) -> &Path {
+        match &self.backend {
+            Backend::Process { log_filename, .. } => log_filename,
+            Backend::InProcess(_) => panic!("the logs of an in-process node aren't recorded"),
+        }
+    }
+
     pub fn blocks(&self) -> Vec<Log> {
-        BufReader::new(File::open(&self.log_filename).unwrap())
+        BufReader::new(File::open(self.log_filename()).unwrap())
             .lines()
             .map_while(Result::ok)
             .filter_map(|line| match serde_json::from_str(&line) {
@@ -91,7 +99,7 @@ impl KatanaRunner {
 
     pub async fn steps(&self) -> Vec<u64> {
         let matching = "Transaction resource usage: Steps: ";
-        BufReader::new(File::open(&self.log_filename).unwrap())
+        BufReader::new(File::open(self.log_filename()).unwrap())
             .lines()
             .filter_map(|line| {
                 let line = line.unwrap();
@@ -0,0 +1,93 @@
+use std::net::SocketAddr;
+use std::sync::mpsc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+use katana_core::backend::config::StarknetConfig;
+use katana_core::sequencer::SequencerConfig;
+use katana_rpc::node::Node;
+use tokio::runtime;
+use tokio::sync::oneshot;
+
+/// A Katana node running in the test process.
+///
+/// The node has its own runtime on a dedicated thread, so that it can be used from both sync and
+/// async tests, and isn't tied to the runtime of the test which started it (eg. the runner of a
+/// `lazy_static`).
+#[derive(Debug)]
+pub(crate) struct InProcessNode {
+    addr: SocketAddr,
+    shutdown: Option<oneshot::Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl InProcessNode {
+    pub(crate) fn start(
+        sequencer_config: SequencerConfig,
+        starknet_config: StarknetConfig,
+    ) -> Result<Self> {
+        let (started_tx, started_rx) = mpsc::channel();
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+
+        let thread = thread::Builder::new()
+            .name("katana-node".into())
+            .spawn(move || {
+                let runtime = runtime::Builder::new_multi_thread()
+                    .enable_all()
+                    .build()
+                    .expect("failed to build katana runtime");
+
+                runtime.block_on(async move {
+                    let node = Node::builder()
+                        .sequencer_config(sequencer_config)
+                        .starknet_config(starknet_config)
+                        .start()
+                        .await;
+
+                    let node = match node {
+                        Ok(node) => {
+                            let _ = started_tx.send(Ok(node.addr()));
+                            node
+                        }
+                        Err(e) => {
+                            let _ = started_tx.send(Err(e));
+                            return;
+                        }
+                    };
+
+                    // the sender is dropped along with the runner if it isn't used
+                    let _ = shutdown_rx.await;
+
+                    if let Err(e) = node.stop().await {
+                        eprintln!("Failed to stop katana node: {}", e);
+                    }
+                });
+            })
+            .context("failed to spawn katana node thread")?;
+
+        let addr = started_rx
+            .recv_timeout(Duration::from_secs(5))
+            .map_err(|_| anyhow!("timeout waiting for node to start"))?
+            .context("failed to start katana node")?;
+
+        Ok(Self { addr, shutdown: Some(shutdown_tx), thread: Some(thread) })
+    }
+
+    pub(crate) fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+}
+
+impl Drop for InProcessNode {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+        if let Some(thread) = self.thread.take() {
+            if thread.join().is_err() {
+                eprintln!("Katana node thread panicked");
+            }
+        }
+    }
+}
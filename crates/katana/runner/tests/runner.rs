@@ -1,3 +1,6 @@
+use katana_core::backend::config::StarknetConfig;
+use katana_core::sequencer::SequencerConfig;
+use katana_rpc::node::Node;
 use katana_runner::*;
 use starknet::providers::Provider;
 
@@ -17,3 +20,64 @@ async fn test_run() {
 async fn basic_macro_usage() {
     let _block_number = runner.provider().block_number().await.unwrap();
 }
+
+#[katana_test(2, false, "katana", in_process)]
+async fn in_process_macro_usage() {
+    let _block_number = runner.provider().block_number().await.unwrap();
+    assert_eq!(runner.accounts_data().len(), 2);
+}
+
+#[katana_test(1, false, "katana", in_process, genesis = "tests/fixtures/genesis.json")]
+async fn genesis_fixture() {
+    use starknet::accounts::Account;
+    use starknet::core::types::{BlockId, BlockTag};
+
+    let account = runner.account(0);
+    let class_hash = runner
+        .provider()
+        .get_class_hash_at(BlockId::Tag(BlockTag::Latest), account.address())
+        .await
+        .expect("fixture account should be deployed");
+    assert_ne!(class_hash, Default::default());
+}
+
+#[tokio::test]
+async fn database_fixture() {
+    let fixture = tempfile::tempdir().unwrap();
+
+    // a node with the same genesis as the runner, which mines a block on top of it
+    let node = Node::builder()
+        .sequencer_config(SequencerConfig { no_mining: true, ..Default::default() })
+        .starknet_config(StarknetConfig {
+            db_dir: Some(fixture.path().to_path_buf()),
+            genesis: KatanaRunner::genesis(2),
+            ..Default::default()
+        })
+        .start()
+        .await
+        .unwrap();
+    node.sequencer().block_producer().force_mine();
+    node.stop().await.unwrap();
+
+    let config = KatanaRunnerConfig {
+        program: None,
+        name: "database_fixture".into(),
+        fixture: Some(StateFixture::Database(fixture.path().to_path_buf())),
+        ..Default::default()
+    };
+    let runner = KatanaRunner::new_with_config(config).unwrap();
+
+    assert_eq!(runner.provider().block_number().await.unwrap(), 1);
+    assert_eq!(runner.accounts_data().len(), 2);
+}
+
+#[test]
+fn in_process_node_rejects_extra_args() {
+    let config = KatanaRunnerConfig {
+        program: None,
+        name: "in_process_extra_args".into(),
+        extra_args: vec!["--disable-fee".into()],
+        ..Default::default()
+    };
+    assert!(KatanaRunner::new_with_config(config).is_err());
+}
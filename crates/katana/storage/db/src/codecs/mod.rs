@@ -2,7 +2,8 @@
 pub mod postcard;
 
 use katana_primitives::block::FinalityStatus;
-use katana_primitives::contract::{ContractAddress, FlattenedSierraClass};
+use katana_primitives::contract::{ContractAddress, FlattenedSierraClass, LegacyClassAbi};
+use katana_primitives::transaction::L1MessageHash;
 use katana_primitives::FieldElement;
 
 use crate::error::CodecError;
@@ -72,6 +73,23 @@ macro_rules! impl_encode_and_decode_for_felts {
 impl_encode_and_decode_for_uints!(u64);
 impl_encode_and_decode_for_felts!(FieldElement, ContractAddress);
 
+impl Encode for L1MessageHash {
+    type Encoded = [u8; 32];
+    fn encode(self) -> Self::Encoded {
+        self.to_fixed_bytes()
+    }
+}
+
+impl Decode for L1MessageHash {
+    fn decode<B: AsRef<[u8]>>(bytes: B) -> Result<Self, CodecError> {
+        let bytes: [u8; 32] = bytes
+            .as_ref()
+            .try_into()
+            .map_err(|_| CodecError::Decode("Invalid message hash length".into()))?;
+        Ok(Self::from(bytes))
+    }
+}
+
 impl Compress for FlattenedSierraClass {
     type Compressed = Vec<u8>;
     fn compress(self) -> Self::Compressed {
@@ -85,6 +103,19 @@ impl Decompress for FlattenedSierraClass {
     }
 }
 
+impl Compress for LegacyClassAbi {
+    type Compressed = Vec<u8>;
+    fn compress(self) -> Self::Compressed {
+        serde_json::to_vec(&self).unwrap()
+    }
+}
+
+impl Decompress for LegacyClassAbi {
+    fn decompress<B: AsRef<[u8]>>(bytes: B) -> Result<Self, CodecError> {
+        serde_json::from_slice(bytes.as_ref()).map_err(|e| CodecError::Decode(e.to_string()))
+    }
+}
+
 impl Compress for FinalityStatus {
     type Compressed = [u8; 1];
     fn compress(self) -> Self::Compressed {
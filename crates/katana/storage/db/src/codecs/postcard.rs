@@ -9,6 +9,7 @@ use crate::error::CodecError;
 use crate::models::block::StoredBlockBodyIndices;
 use crate::models::class::StoredContractClass;
 use crate::models::contract::ContractInfoChangeList;
+use crate::models::messaging::{DeadLetter, GatheredMessage};
 
 macro_rules! impl_compress_and_decompress_for_table_values {
     ($($name:ty),*) => {
@@ -40,5 +41,7 @@ impl_compress_and_decompress_for_table_values!(
     StoredContractClass,
     GenericContractInfo,
     StoredBlockBodyIndices,
-    ContractInfoChangeList
+    ContractInfoChangeList,
+    DeadLetter,
+    Vec<GatheredMessage>
 );
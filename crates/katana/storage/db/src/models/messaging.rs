@@ -0,0 +1,73 @@
+use katana_primitives::block::BlockNumber;
+use katana_primitives::receipt::MessageToL1;
+use katana_primitives::transaction::{L1MessageHash, TxHash};
+use serde::{Deserialize, Serialize};
+
+use crate::codecs::{Decode, Encode};
+use crate::error::CodecError;
+
+/// The cursors tracked by the messaging service.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum MessagingCheckpointId {
+    /// The block of the settlement chain from which messages will be gathered.
+    Gather = 0,
+    /// The local block from which messages will be sent to the settlement chain.
+    Send = 1,
+    /// The index of the next message of the [Send](Self::Send) block to send.
+    SendIndex = 2,
+}
+
+impl Encode for MessagingCheckpointId {
+    type Encoded = [u8; 1];
+    fn encode(self) -> Self::Encoded {
+        [self as u8]
+    }
+}
+
+impl Decode for MessagingCheckpointId {
+    fn decode<B: AsRef<[u8]>>(bytes: B) -> Result<Self, CodecError> {
+        match bytes.as_ref() {
+            [0] => Ok(Self::Gather),
+            [1] => Ok(Self::Send),
+            [2] => Ok(Self::SendIndex),
+            _ => Err(CodecError::Decode("Invalid messaging checkpoint id".into())),
+        }
+    }
+}
+
+/// A message to L1 that couldn't be sent to the settlement chain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeadLetter {
+    /// The local block in which the message was sent.
+    pub block_number: BlockNumber,
+    /// The message content.
+    pub message: MessageToL1,
+    /// The number of times the sending of the message was attempted.
+    pub attempts: u32,
+    /// The error of the last attempt.
+    pub error: String,
+}
+
+/// The status of a message sent from the settlement chain once gathered.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GatheredMessageStatus {
+    /// The L1 handler transaction of the message has been added to the pool, or the message had
+    /// already been processed by a previous L1 handler transaction.
+    Accepted { transaction_hash: TxHash },
+    /// The message has been rejected because the fee paid on the settlement chain doesn't cover
+    /// the execution of its L1 handler transaction.
+    Rejected { transaction_hash: TxHash, paid_fee_on_l1: u128, estimated_fee: u128 },
+    /// The L1 handler transaction of the message has been rejected by the block producer, as it
+    /// couldn't be executed.
+    Failed { transaction_hash: TxHash, reason: String },
+}
+
+/// A message gathered from the settlement chain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GatheredMessage {
+    /// The hash of the message.
+    pub message_hash: L1MessageHash,
+    /// The status of the message.
+    pub status: GatheredMessageStatus,
+}
@@ -1,15 +1,16 @@
 use katana_primitives::block::{BlockHash, BlockNumber, FinalityStatus, Header};
 use katana_primitives::contract::{
     ClassHash, CompiledClassHash, ContractAddress, FlattenedSierraClass, GenericContractInfo,
-    StorageKey,
+    LegacyClassAbi, StorageKey,
 };
 use katana_primitives::receipt::Receipt;
-use katana_primitives::transaction::{Tx, TxHash, TxNumber};
+use katana_primitives::transaction::{L1MessageHash, L1TxHash, Tx, TxHash, TxNumber};
 
 use crate::codecs::{Compress, Decode, Decompress, Encode};
 use crate::models::block::StoredBlockBodyIndices;
 use crate::models::class::StoredContractClass;
 use crate::models::contract::{ContractClassChange, ContractInfoChangeList, ContractNonceChange};
+use crate::models::messaging::{DeadLetter, GatheredMessage, MessagingCheckpointId};
 use crate::models::storage::{
     ContractStorageEntry, ContractStorageKey, StorageEntry, StorageEntryChangeList,
 };
@@ -47,7 +48,7 @@ pub enum TableType {
     DupSort,
 }
 
-pub const NUM_TABLES: usize = 22;
+pub const NUM_TABLES: usize = 28;
 
 /// Macro to declare `libmdbx` tables.
 #[macro_export]
@@ -169,7 +170,13 @@ define_tables_enum! {[
     (NonceChanges, TableType::DupSort),
     (ContractClassChanges, TableType::DupSort),
     (StorageChanges, TableType::DupSort),
-    (StorageChangeSet, TableType::DupSort)
+    (StorageChangeSet, TableType::DupSort),
+    (MessagingCheckpoints, TableType::Table),
+    (L1MessageTxs, TableType::Table),
+    (BlockTimestamps, TableType::Table),
+    (DeadLetters, TableType::Table),
+    (GatheredMessages, TableType::Table),
+    (LegacyClassAbis, TableType::Table)
 ]}
 
 tables! {
@@ -224,8 +231,26 @@ tables! {
     /// storage change set
     StorageChangeSet: (ContractAddress, StorageKey) => StorageEntryChangeList,
     /// Account storage change set
-    StorageChanges: (BlockNumber, ContractStorageKey) => ContractStorageEntry
+    StorageChanges: (BlockNumber, ContractStorageKey) => ContractStorageEntry,
 
+    /// Stores the cursors from which the messaging service resumes gathering / sending messages.
+    MessagingCheckpoints: (MessagingCheckpointId) => BlockNumber,
+    /// Stores the hash of the L1 handler transaction that processed a L1 message, according to
+    /// the message hash.
+    L1MessageTxs: (L1MessageHash) => TxHash,
+    /// Stores the number of the last block mined at a given timestamp, so that blocks can be
+    /// looked up by time. A block is indexed by the latest timestamp of the blocks up to it, which,
+    /// unlike the timestamps of the blocks, never decreases.
+    BlockTimestamps: (u64) => BlockNumber,
+    /// Stores the messages to L1 that couldn't be sent to the settlement chain, in the order they
+    /// were given up on.
+    DeadLetters: (u64) => DeadLetter,
+    /// Stores the messages gathered from the settlement chain and their status, according to the
+    /// hash of the settlement chain transaction that sent them.
+    GatheredMessages: (L1TxHash) => Vec<GatheredMessage>,
+    /// Stores the ABIs of the legacy classes, which aren't part of their compiled classes,
+    /// according to their class hash.
+    LegacyClassAbis: (ClassHash) => LegacyClassAbi
 }
 
 #[cfg(test)]
@@ -257,5 +282,11 @@ mod tests {
         assert_eq!(Tables::ALL[19].name(), ContractClassChanges::NAME);
         assert_eq!(Tables::ALL[20].name(), StorageChanges::NAME);
         assert_eq!(Tables::ALL[21].name(), StorageChangeSet::NAME);
+        assert_eq!(Tables::ALL[22].name(), MessagingCheckpoints::NAME);
+        assert_eq!(Tables::ALL[23].name(), L1MessageTxs::NAME);
+        assert_eq!(Tables::ALL[24].name(), BlockTimestamps::NAME);
+        assert_eq!(Tables::ALL[25].name(), DeadLetters::NAME);
+        assert_eq!(Tables::ALL[26].name(), GatheredMessages::NAME);
+        assert_eq!(Tables::ALL[27].name(), LegacyClassAbis::NAME);
     }
 }
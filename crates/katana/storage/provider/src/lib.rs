@@ -1,22 +1,24 @@
 use std::ops::{Range, RangeInclusive};
 
 use katana_db::models::block::StoredBlockBodyIndices;
+use katana_db::models::messaging::{DeadLetter, GatheredMessage};
 use katana_primitives::block::{
     Block, BlockHash, BlockHashOrNumber, BlockNumber, BlockWithTxHashes, FinalityStatus, Header,
     SealedBlockWithStatus,
 };
 use katana_primitives::contract::{
     ClassHash, CompiledClassHash, CompiledContractClass, ContractAddress, FlattenedSierraClass,
-    GenericContractInfo, StorageKey, StorageValue,
+    GenericContractInfo, LegacyClassAbi, StorageKey, StorageValue,
 };
 use katana_primitives::env::BlockEnv;
 use katana_primitives::receipt::Receipt;
 use katana_primitives::state::{StateUpdates, StateUpdatesWithDeclaredClasses};
-use katana_primitives::transaction::{TxHash, TxNumber, TxWithHash};
+use katana_primitives::transaction::{L1MessageHash, L1TxHash, TxHash, TxNumber, TxWithHash};
 use katana_primitives::FieldElement;
 use traits::block::{BlockIdReader, BlockStatusProvider, BlockWriter};
-use traits::contract::{ContractClassProvider, ContractClassWriter};
+use traits::contract::{ContractClassProvider, ContractClassWriter, LegacyClassAbiProvider};
 use traits::env::BlockEnvProvider;
+use traits::messaging::{MessagingCheckpointProvider, MessagingCheckpointWriter};
 use traits::state::{StateRootProvider, StateWriter};
 use traits::transaction::TransactionStatusProvider;
 
@@ -94,6 +96,10 @@ where
     fn block_number_by_hash(&self, hash: BlockHash) -> ProviderResult<Option<BlockNumber>> {
         self.provider.block_number_by_hash(hash)
     }
+
+    fn block_number_by_timestamp(&self, timestamp: u64) -> ProviderResult<Option<BlockNumber>> {
+        self.provider.block_number_by_timestamp(timestamp)
+    }
 }
 
 impl<Db> BlockHashProvider for BlockchainProvider<Db>
@@ -257,6 +263,15 @@ where
     }
 }
 
+impl<Db> LegacyClassAbiProvider for BlockchainProvider<Db>
+where
+    Db: LegacyClassAbiProvider,
+{
+    fn legacy_class_abi(&self, hash: ClassHash) -> ProviderResult<Option<LegacyClassAbi>> {
+        self.provider.legacy_class_abi(hash)
+    }
+}
+
 impl<Db> StateFactoryProvider for BlockchainProvider<Db>
 where
     Db: StateFactoryProvider,
@@ -363,3 +378,60 @@ where
         self.provider.block_env_at(id)
     }
 }
+
+impl<Db> MessagingCheckpointProvider for BlockchainProvider<Db>
+where
+    Db: MessagingCheckpointProvider,
+{
+    fn gather_from_block(&self) -> ProviderResult<Option<u64>> {
+        self.provider.gather_from_block()
+    }
+
+    fn send_from_block(&self) -> ProviderResult<Option<BlockNumber>> {
+        self.provider.send_from_block()
+    }
+
+    fn send_from_index(&self) -> ProviderResult<Option<u64>> {
+        self.provider.send_from_index()
+    }
+
+    fn l1_handler_tx_by_message_hash(&self, hash: L1MessageHash) -> ProviderResult<Option<TxHash>> {
+        self.provider.l1_handler_tx_by_message_hash(hash)
+    }
+
+    fn dead_letters(&self) -> ProviderResult<Vec<DeadLetter>> {
+        self.provider.dead_letters()
+    }
+
+    fn gathered_messages(
+        &self,
+        l1_tx_hash: L1TxHash,
+    ) -> ProviderResult<Option<Vec<GatheredMessage>>> {
+        self.provider.gathered_messages(l1_tx_hash)
+    }
+}
+
+impl<Db> MessagingCheckpointWriter for BlockchainProvider<Db>
+where
+    Db: MessagingCheckpointWriter,
+{
+    fn set_gather_from_block(&self, block: u64) -> ProviderResult<()> {
+        self.provider.set_gather_from_block(block)
+    }
+
+    fn set_send_from(&self, block: BlockNumber, index: u64) -> ProviderResult<()> {
+        self.provider.set_send_from(block, index)
+    }
+
+    fn insert_dead_letter(&self, letter: DeadLetter) -> ProviderResult<()> {
+        self.provider.insert_dead_letter(letter)
+    }
+
+    fn insert_gathered_message(
+        &self,
+        l1_tx_hash: L1TxHash,
+        message: GatheredMessage,
+    ) -> ProviderResult<()> {
+        self.provider.insert_gathered_message(l1_tx_hash, message)
+    }
+}
@@ -10,6 +10,7 @@ use katana_db::models::block::StoredBlockBodyIndices;
 use katana_db::models::contract::{
     ContractClassChange, ContractInfoChangeList, ContractNonceChange,
 };
+use katana_db::models::messaging::{DeadLetter, GatheredMessage, MessagingCheckpointId};
 use katana_db::models::storage::{
     ContractStorageEntry, ContractStorageKey, StorageEntry, StorageEntryChangeList,
 };
@@ -20,13 +21,13 @@ use katana_primitives::block::{
     SealedBlockWithStatus,
 };
 use katana_primitives::contract::{
-    ClassHash, CompiledClassHash, ContractAddress, GenericContractInfo, Nonce, StorageKey,
-    StorageValue,
+    ClassHash, CompiledClassHash, ContractAddress, GenericContractInfo, LegacyClassAbi, Nonce,
+    StorageKey, StorageValue,
 };
 use katana_primitives::env::BlockEnv;
 use katana_primitives::receipt::Receipt;
 use katana_primitives::state::{StateUpdates, StateUpdatesWithDeclaredClasses};
-use katana_primitives::transaction::{TxHash, TxNumber, TxWithHash};
+use katana_primitives::transaction::{L1MessageHash, L1TxHash, Tx, TxHash, TxNumber, TxWithHash};
 use katana_primitives::FieldElement;
 
 use crate::error::ProviderError;
@@ -34,7 +35,11 @@ use crate::traits::block::{
     BlockHashProvider, BlockNumberProvider, BlockProvider, BlockStatusProvider, BlockWriter,
     HeaderProvider,
 };
+use crate::traits::contract::LegacyClassAbiProvider;
 use crate::traits::env::BlockEnvProvider;
+use crate::traits::messaging::{
+    upsert_gathered_message, MessagingCheckpointProvider, MessagingCheckpointWriter,
+};
 use crate::traits::state::{StateFactoryProvider, StateProvider, StateRootProvider};
 use crate::traits::state_update::StateUpdateProvider;
 use crate::traits::transaction::{
@@ -90,6 +95,25 @@ impl BlockNumberProvider for DbProvider {
         Ok(block_num)
     }
 
+    fn block_number_by_timestamp(&self, timestamp: u64) -> ProviderResult<Option<BlockNumber>> {
+        let db_tx = self.0.tx()?;
+
+        let block_num = {
+            let mut cursor = db_tx.cursor::<tables::BlockTimestamps>()?;
+            // the cursor is positioned at the first timestamp greater than or equal to
+            // `timestamp`, so the block is either at that position or at the previous one
+            let entry = match cursor.seek(timestamp)? {
+                Some((key, num)) if key == timestamp => Some((key, num)),
+                Some(_) => cursor.prev()?,
+                None => cursor.last()?,
+            };
+            entry.map(|(_, num)| num)
+        };
+
+        db_tx.commit()?;
+        Ok(block_num)
+    }
+
     fn latest_number(&self) -> ProviderResult<BlockNumber> {
         let db_tx = self.0.tx()?;
         let res = db_tx.cursor::<tables::BlockHashes>()?.last()?.map(|(num, _)| num);
@@ -541,6 +565,106 @@ impl BlockEnvProvider for DbProvider {
     }
 }
 
+impl LegacyClassAbiProvider for DbProvider {
+    fn legacy_class_abi(&self, hash: ClassHash) -> ProviderResult<Option<LegacyClassAbi>> {
+        let db_tx = self.0.tx()?;
+        let abi = db_tx.get::<tables::LegacyClassAbis>(hash)?;
+        db_tx.commit()?;
+        Ok(abi)
+    }
+}
+
+impl MessagingCheckpointProvider for DbProvider {
+    fn gather_from_block(&self) -> ProviderResult<Option<u64>> {
+        let db_tx = self.0.tx()?;
+        let block = db_tx.get::<tables::MessagingCheckpoints>(MessagingCheckpointId::Gather)?;
+        db_tx.commit()?;
+        Ok(block)
+    }
+
+    fn send_from_block(&self) -> ProviderResult<Option<BlockNumber>> {
+        let db_tx = self.0.tx()?;
+        let block = db_tx.get::<tables::MessagingCheckpoints>(MessagingCheckpointId::Send)?;
+        db_tx.commit()?;
+        Ok(block)
+    }
+
+    fn send_from_index(&self) -> ProviderResult<Option<u64>> {
+        let db_tx = self.0.tx()?;
+        let index = db_tx.get::<tables::MessagingCheckpoints>(MessagingCheckpointId::SendIndex)?;
+        db_tx.commit()?;
+        Ok(index)
+    }
+
+    fn l1_handler_tx_by_message_hash(&self, hash: L1MessageHash) -> ProviderResult<Option<TxHash>> {
+        let db_tx = self.0.tx()?;
+        let tx_hash = db_tx.get::<tables::L1MessageTxs>(hash)?;
+        db_tx.commit()?;
+        Ok(tx_hash)
+    }
+
+    fn dead_letters(&self) -> ProviderResult<Vec<DeadLetter>> {
+        let db_tx = self.0.tx()?;
+        let letters = db_tx
+            .cursor::<tables::DeadLetters>()?
+            .walk(None)?
+            .map(|entry| entry.map(|(_, letter)| letter).map_err(ProviderError::from))
+            .collect::<ProviderResult<Vec<_>>>()?;
+        db_tx.commit()?;
+        Ok(letters)
+    }
+
+    fn gathered_messages(
+        &self,
+        l1_tx_hash: L1TxHash,
+    ) -> ProviderResult<Option<Vec<GatheredMessage>>> {
+        let db_tx = self.0.tx()?;
+        let messages = db_tx.get::<tables::GatheredMessages>(l1_tx_hash)?;
+        db_tx.commit()?;
+        Ok(messages)
+    }
+}
+
+impl MessagingCheckpointWriter for DbProvider {
+    fn set_gather_from_block(&self, block: u64) -> ProviderResult<()> {
+        self.0.update(|db_tx| {
+            db_tx.put::<tables::MessagingCheckpoints>(MessagingCheckpointId::Gather, block)
+        })??;
+        Ok(())
+    }
+
+    fn set_send_from(&self, block: BlockNumber, index: u64) -> ProviderResult<()> {
+        self.0.update(|db_tx| {
+            db_tx.put::<tables::MessagingCheckpoints>(MessagingCheckpointId::Send, block)?;
+            db_tx.put::<tables::MessagingCheckpoints>(MessagingCheckpointId::SendIndex, index)
+        })??;
+        Ok(())
+    }
+
+    fn insert_dead_letter(&self, letter: DeadLetter) -> ProviderResult<()> {
+        self.0.update(|db_tx| {
+            let mut cursor = db_tx.cursor::<tables::DeadLetters>()?;
+            let id = cursor.last()?.map_or(0, |(id, _)| id + 1);
+            cursor.append(id, letter)
+        })??;
+        Ok(())
+    }
+
+    fn insert_gathered_message(
+        &self,
+        l1_tx_hash: L1TxHash,
+        message: GatheredMessage,
+    ) -> ProviderResult<()> {
+        self.0.update(|db_tx| {
+            let mut messages =
+                db_tx.get::<tables::GatheredMessages>(l1_tx_hash)?.unwrap_or_default();
+            upsert_gathered_message(&mut messages, message);
+            db_tx.put::<tables::GatheredMessages>(l1_tx_hash, messages)
+        })??;
+        Ok(())
+    }
+}
+
 impl BlockWriter for DbProvider {
     fn insert_block_with_states_and_receipts(
         &self,
@@ -563,6 +687,12 @@ impl BlockWriter for DbProvider {
             db_tx.put::<tables::BlockNumbers>(block_hash, block_number)?;
             db_tx.put::<tables::BlockStatusses>(block_number, block.status)?;
 
+            // the timestamp of a block can be before the one of its parent, so the blocks are
+            // indexed by the latest timestamp up to them, which never decreases
+            let last_timestamp = db_tx.cursor::<tables::BlockTimestamps>()?.last()?.map(|(t, _)| t);
+            let timestamp = last_timestamp.unwrap_or_default().max(block_header.timestamp);
+            db_tx.put::<tables::BlockTimestamps>(timestamp, block_number)?;
+
             db_tx.put::<tables::Headers>(block_number, block_header)?;
             db_tx.put::<tables::BlockBodyIndices>(block_number, block_body_indices)?;
 
@@ -573,6 +703,11 @@ impl BlockWriter for DbProvider {
                 db_tx.put::<tables::TxHashes>(tx_number, tx_hash)?;
                 db_tx.put::<tables::TxNumbers>(tx_hash, tx_number)?;
                 db_tx.put::<tables::TxBlocks>(tx_number, block_number)?;
+
+                if let Tx::L1Handler(tx) = &transaction.transaction {
+                    db_tx.put::<tables::L1MessageTxs>(tx.message_hash, tx_hash)?;
+                }
+
                 db_tx.put::<tables::Transactions>(tx_number, transaction.transaction)?;
                 db_tx.put::<tables::Receipts>(tx_number, receipt)?;
             }
@@ -594,6 +729,10 @@ impl BlockWriter for DbProvider {
                 db_tx.put::<tables::SierraClasses>(class_hash, sierra_class)?;
             }
 
+            for (class_hash, abi) in states.declared_legacy_class_abis {
+                db_tx.put::<tables::LegacyClassAbis>(class_hash, abi)?;
+            }
+
             // insert storage changes
             {
                 let mut storage_cursor = db_tx.cursor::<tables::ContractStorage>()?;
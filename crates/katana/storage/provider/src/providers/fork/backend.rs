@@ -470,6 +470,9 @@ impl ContractClassProvider for SharedStateProvider {
                     ProviderError::ParsingError(e.to_string())
                 })?;
 
+                let abi = class.abi.unwrap_or_default();
+                self.0.shared_contract_classes.legacy_class_abis.write().entry(hash).or_insert(abi);
+
                 (hash, hash, compiled_class, None)
             }
 
@@ -5,17 +5,19 @@ use std::ops::{Range, RangeInclusive};
 use std::sync::Arc;
 
 use katana_db::models::block::StoredBlockBodyIndices;
+use katana_db::models::messaging::{DeadLetter, GatheredMessage};
 use katana_primitives::block::{
     Block, BlockHash, BlockHashOrNumber, BlockNumber, BlockWithTxHashes, FinalityStatus, Header,
     SealedBlockWithStatus,
 };
 use katana_primitives::contract::{
     ClassHash, CompiledClassHash, CompiledContractClass, ContractAddress, FlattenedSierraClass,
+    LegacyClassAbi,
 };
 use katana_primitives::env::BlockEnv;
 use katana_primitives::receipt::Receipt;
 use katana_primitives::state::{StateUpdates, StateUpdatesWithDeclaredClasses};
-use katana_primitives::transaction::{Tx, TxHash, TxNumber, TxWithHash};
+use katana_primitives::transaction::{L1MessageHash, L1TxHash, Tx, TxHash, TxNumber, TxWithHash};
 use parking_lot::RwLock;
 use starknet::providers::jsonrpc::HttpTransport;
 use starknet::providers::JsonRpcClient;
@@ -28,8 +30,11 @@ use crate::traits::block::{
     BlockHashProvider, BlockNumberProvider, BlockProvider, BlockStatusProvider, BlockWriter,
     HeaderProvider,
 };
-use crate::traits::contract::ContractClassWriter;
+use crate::traits::contract::{ContractClassWriter, LegacyClassAbiProvider};
 use crate::traits::env::BlockEnvProvider;
+use crate::traits::messaging::{
+    upsert_gathered_message, MessagingCheckpointProvider, MessagingCheckpointWriter,
+};
 use crate::traits::state::{StateFactoryProvider, StateProvider, StateRootProvider, StateWriter};
 use crate::traits::state_update::StateUpdateProvider;
 use crate::traits::transaction::{
@@ -40,7 +45,7 @@ use crate::ProviderResult;
 pub struct ForkedProvider {
     // TODO: insert `ForkedBackend` into `CacheDb`
     storage: RwLock<CacheDb<()>>,
-    state: Arc<ForkedStateDb>,
+    state: RwLock<Arc<ForkedStateDb>>,
     historical_states: RwLock<HistoricalStates>,
 }
 
@@ -53,11 +58,35 @@ impl ForkedProvider {
         let shared_provider = SharedStateProvider::new_with_backend(backend);
 
         let storage = RwLock::new(CacheDb::new(()));
-        let state = Arc::new(CacheStateDb::new(shared_provider));
+        let state = RwLock::new(Arc::new(CacheStateDb::new(shared_provider)));
         let historical_states = RwLock::new(HistoricalStates::default());
 
         Ok(Self { storage, state, historical_states })
     }
+
+    /// Forks the network of `provider` again at `block_id`, discarding all the blocks and states
+    /// stored so far.
+    ///
+    /// The state providers created before the reset keep reading from the previous fork.
+    pub fn reset(
+        &self,
+        provider: Arc<JsonRpcClient<HttpTransport>>,
+        block_id: BlockHashOrNumber,
+    ) -> Result<(), ForkedBackendError> {
+        let Self { storage, state, historical_states } = Self::new(provider, block_id)?;
+
+        // all the locks are held during the swap, so that the blocks and the states are never
+        // read from different forks. they are acquired in the same order as when inserting a block.
+        let mut storage_lock = self.storage.write();
+        let mut state_lock = self.state.write();
+        let mut historical_states_lock = self.historical_states.write();
+
+        *storage_lock = storage.into_inner();
+        *state_lock = state.into_inner();
+        *historical_states_lock = historical_states.into_inner();
+
+        Ok(())
+    }
 }
 
 impl BlockHashProvider for ForkedProvider {
@@ -78,6 +107,11 @@ impl BlockNumberProvider for ForkedProvider {
     fn block_number_by_hash(&self, hash: BlockHash) -> ProviderResult<Option<BlockNumber>> {
         Ok(self.storage.read().block_numbers.get(&hash).cloned())
     }
+
+    fn block_number_by_timestamp(&self, timestamp: u64) -> ProviderResult<Option<BlockNumber>> {
+        let storage = self.storage.read();
+        Ok(storage.block_timestamps.range(..=timestamp).next_back().map(|(_, num)| *num))
+    }
 }
 
 impl HeaderProvider for ForkedProvider {
@@ -382,7 +416,7 @@ impl StateUpdateProvider for ForkedProvider {
 
 impl StateFactoryProvider for ForkedProvider {
     fn latest(&self) -> ProviderResult<Box<dyn StateProvider>> {
-        Ok(Box::new(self::state::LatestStateProvider(Arc::clone(&self.state))))
+        Ok(Box::new(self::state::LatestStateProvider(Arc::clone(&self.state.read()))))
     }
 
     fn historical(
@@ -408,6 +442,64 @@ impl StateFactoryProvider for ForkedProvider {
     }
 }
 
+impl MessagingCheckpointProvider for ForkedProvider {
+    fn gather_from_block(&self) -> ProviderResult<Option<u64>> {
+        Ok(self.storage.read().messaging_gather_from_block)
+    }
+
+    fn send_from_block(&self) -> ProviderResult<Option<BlockNumber>> {
+        Ok(self.storage.read().messaging_send_from_block)
+    }
+
+    fn send_from_index(&self) -> ProviderResult<Option<u64>> {
+        Ok(self.storage.read().messaging_send_from_index)
+    }
+
+    fn l1_handler_tx_by_message_hash(&self, hash: L1MessageHash) -> ProviderResult<Option<TxHash>> {
+        Ok(self.storage.read().l1_message_txs.get(&hash).copied())
+    }
+
+    fn dead_letters(&self) -> ProviderResult<Vec<DeadLetter>> {
+        Ok(self.storage.read().dead_letters.clone())
+    }
+
+    fn gathered_messages(
+        &self,
+        l1_tx_hash: L1TxHash,
+    ) -> ProviderResult<Option<Vec<GatheredMessage>>> {
+        Ok(self.storage.read().gathered_messages.get(&l1_tx_hash).cloned())
+    }
+}
+
+impl MessagingCheckpointWriter for ForkedProvider {
+    fn set_gather_from_block(&self, block: u64) -> ProviderResult<()> {
+        self.storage.write().messaging_gather_from_block = Some(block);
+        Ok(())
+    }
+
+    fn set_send_from(&self, block: BlockNumber, index: u64) -> ProviderResult<()> {
+        let mut storage = self.storage.write();
+        storage.messaging_send_from_block = Some(block);
+        storage.messaging_send_from_index = Some(index);
+        Ok(())
+    }
+
+    fn insert_dead_letter(&self, letter: DeadLetter) -> ProviderResult<()> {
+        self.storage.write().dead_letters.push(letter);
+        Ok(())
+    }
+
+    fn insert_gathered_message(
+        &self,
+        l1_tx_hash: L1TxHash,
+        message: GatheredMessage,
+    ) -> ProviderResult<()> {
+        let mut storage = self.storage.write();
+        upsert_gathered_message(storage.gathered_messages.entry(l1_tx_hash).or_default(), message);
+        Ok(())
+    }
+}
+
 impl BlockWriter for ForkedProvider {
     fn insert_block_with_states_and_receipts(
         &self,
@@ -428,6 +520,12 @@ impl BlockWriter for ForkedProvider {
         let tx_offset = storage.transactions.len() as u64;
         let block_body_indices = StoredBlockBodyIndices { tx_offset, tx_count };
 
+        let l1_message_txs = txs.iter().filter_map(|tx| match &tx.transaction {
+            Tx::L1Handler(l1_tx) => Some((l1_tx.message_hash, tx.hash)),
+            _ => None,
+        });
+        storage.l1_message_txs.extend(l1_message_txs);
+
         let (txs_id, txs): (Vec<(TxNumber, TxHash)>, Vec<Tx>) = txs
             .into_iter()
             .enumerate()
@@ -440,6 +538,11 @@ impl BlockWriter for ForkedProvider {
         storage.latest_block_hash = block_hash;
         storage.latest_block_number = block_number;
 
+        // the timestamp of a block can be before the one of its parent, so the blocks are indexed
+        // by the latest timestamp up to them, which never decreases
+        let last_timestamp = storage.block_timestamps.last_key_value().map(|(t, _)| *t);
+        let timestamp = last_timestamp.unwrap_or_default().max(block_header.timestamp);
+        storage.block_timestamps.insert(timestamp, block_number);
         storage.block_numbers.insert(block_hash, block_number);
         storage.block_hashes.insert(block_number, block_hash);
         storage.block_headers.insert(block_number, block_header);
@@ -454,18 +557,27 @@ impl BlockWriter for ForkedProvider {
 
         storage.state_update.insert(block_number, states.state_updates.clone());
 
-        self.state.insert_updates(states);
+        let state = self.state.read();
+        state.insert_updates(states);
 
-        let snapshot = self.state.create_snapshot();
+        let snapshot = state.create_snapshot();
         self.historical_states.write().insert(block_number, Box::new(snapshot));
 
         Ok(())
     }
 }
 
+impl LegacyClassAbiProvider for ForkedProvider {
+    fn legacy_class_abi(&self, hash: ClassHash) -> ProviderResult<Option<LegacyClassAbi>> {
+        let state = self.state.read();
+        let abi = state.shared_contract_classes.legacy_class_abis.read().get(&hash).cloned();
+        Ok(abi)
+    }
+}
+
 impl ContractClassWriter for ForkedProvider {
     fn set_class(&self, hash: ClassHash, class: CompiledContractClass) -> ProviderResult<()> {
-        self.state.shared_contract_classes.compiled_classes.write().insert(hash, class);
+        self.state.read().shared_contract_classes.compiled_classes.write().insert(hash, class);
         Ok(())
     }
 
@@ -474,7 +586,7 @@ impl ContractClassWriter for ForkedProvider {
         hash: ClassHash,
         sierra: FlattenedSierraClass,
     ) -> ProviderResult<()> {
-        self.state.shared_contract_classes.sierra_classes.write().insert(hash, sierra);
+        self.state.read().shared_contract_classes.sierra_classes.write().insert(hash, sierra);
         Ok(())
     }
 
@@ -483,7 +595,7 @@ impl ContractClassWriter for ForkedProvider {
         hash: ClassHash,
         compiled_hash: CompiledClassHash,
     ) -> ProviderResult<()> {
-        self.state.compiled_class_hashes.write().insert(hash, compiled_hash);
+        self.state.read().compiled_class_hashes.write().insert(hash, compiled_hash);
         Ok(())
     }
 }
@@ -495,7 +607,13 @@ impl StateWriter for ForkedProvider {
         storage_key: katana_primitives::contract::StorageKey,
         storage_value: katana_primitives::contract::StorageValue,
     ) -> ProviderResult<()> {
-        self.state.storage.write().entry(address).or_default().insert(storage_key, storage_value);
+        self.state
+            .read()
+            .storage
+            .write()
+            .entry(address)
+            .or_default()
+            .insert(storage_key, storage_value);
         Ok(())
     }
 
@@ -504,7 +622,8 @@ impl StateWriter for ForkedProvider {
         address: ContractAddress,
         class_hash: ClassHash,
     ) -> ProviderResult<()> {
-        self.state.contract_state.write().entry(address).or_default().class_hash = class_hash;
+        self.state.read().contract_state.write().entry(address).or_default().class_hash =
+            class_hash;
         Ok(())
     }
 
@@ -513,7 +632,7 @@ impl StateWriter for ForkedProvider {
         address: ContractAddress,
         nonce: katana_primitives::contract::Nonce,
     ) -> ProviderResult<()> {
-        self.state.contract_state.write().entry(address).or_default().nonce = nonce;
+        self.state.read().contract_state.write().entry(address).or_default().nonce = nonce;
         Ok(())
     }
 }
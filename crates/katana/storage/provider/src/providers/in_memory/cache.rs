@@ -1,15 +1,16 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 
 use katana_db::models::block::StoredBlockBodyIndices;
+use katana_db::models::messaging::{DeadLetter, GatheredMessage};
 use katana_primitives::block::{BlockHash, BlockNumber, FinalityStatus, Header};
 use katana_primitives::contract::{
     ClassHash, CompiledClassHash, CompiledContractClass, ContractAddress, FlattenedSierraClass,
-    GenericContractInfo, StorageKey, StorageValue,
+    GenericContractInfo, LegacyClassAbi, StorageKey, StorageValue,
 };
 use katana_primitives::receipt::Receipt;
 use katana_primitives::state::{StateUpdates, StateUpdatesWithDeclaredClasses};
-use katana_primitives::transaction::{Tx, TxHash, TxNumber};
+use katana_primitives::transaction::{L1MessageHash, L1TxHash, Tx, TxHash, TxNumber};
 use parking_lot::RwLock;
 
 type ContractStorageMap = HashMap<ContractAddress, HashMap<StorageKey, StorageValue>>;
@@ -18,11 +19,13 @@ type ContractStateMap = HashMap<ContractAddress, GenericContractInfo>;
 type SierraClassesMap = HashMap<ClassHash, FlattenedSierraClass>;
 type CompiledClassesMap = HashMap<ClassHash, CompiledContractClass>;
 type CompiledClassHashesMap = HashMap<ClassHash, CompiledClassHash>;
+type LegacyClassAbisMap = HashMap<ClassHash, LegacyClassAbi>;
 
 #[derive(Default)]
 pub struct SharedContractClasses {
     pub(crate) sierra_classes: RwLock<SierraClassesMap>,
     pub(crate) compiled_classes: RwLock<CompiledClassesMap>,
+    pub(crate) legacy_class_abis: RwLock<LegacyClassAbisMap>,
 }
 
 pub struct CacheSnapshotWithoutClasses<Db> {
@@ -48,6 +51,7 @@ impl<Db> CacheStateDb<Db> {
         let mut compiled_class_hashes = self.compiled_class_hashes.write();
         let mut sierra_classes = self.shared_contract_classes.sierra_classes.write();
         let mut compiled_classes = self.shared_contract_classes.compiled_classes.write();
+        let mut legacy_class_abis = self.shared_contract_classes.legacy_class_abis.write();
 
         for (contract_address, nonce) in updates.state_updates.nonce_updates {
             let info = contract_state.entry(contract_address).or_default();
@@ -67,6 +71,7 @@ impl<Db> CacheStateDb<Db> {
         compiled_class_hashes.extend(updates.state_updates.declared_classes);
         sierra_classes.extend(updates.declared_sierra_classes);
         compiled_classes.extend(updates.declared_compiled_classes);
+        legacy_class_abis.extend(updates.declared_legacy_class_abis);
     }
 }
 
@@ -77,6 +82,7 @@ pub struct CacheDb<Db> {
     pub(crate) block_numbers: HashMap<BlockHash, BlockNumber>,
     pub(crate) block_statusses: HashMap<BlockNumber, FinalityStatus>,
     pub(crate) block_body_indices: HashMap<BlockNumber, StoredBlockBodyIndices>,
+    pub(crate) block_timestamps: BTreeMap<u64, BlockNumber>,
     pub(crate) latest_block_hash: BlockHash,
     pub(crate) latest_block_number: BlockNumber,
     pub(crate) state_update: HashMap<BlockNumber, StateUpdates>,
@@ -85,6 +91,12 @@ pub struct CacheDb<Db> {
     pub(crate) transaction_hashes: HashMap<TxNumber, TxHash>,
     pub(crate) transaction_numbers: HashMap<TxHash, TxNumber>,
     pub(crate) transaction_block: HashMap<TxNumber, BlockNumber>,
+    pub(crate) l1_message_txs: HashMap<L1MessageHash, TxHash>,
+    pub(crate) messaging_gather_from_block: Option<u64>,
+    pub(crate) messaging_send_from_block: Option<BlockNumber>,
+    pub(crate) messaging_send_from_index: Option<u64>,
+    pub(crate) dead_letters: Vec<DeadLetter>,
+    pub(crate) gathered_messages: HashMap<L1TxHash, Vec<GatheredMessage>>,
 }
 
 impl<Db> CacheStateDb<Db> {
@@ -113,9 +125,16 @@ impl<Db> CacheDb<Db> {
             transaction_block: HashMap::new(),
             transaction_hashes: HashMap::new(),
             block_body_indices: HashMap::new(),
+            block_timestamps: BTreeMap::new(),
             transaction_numbers: HashMap::new(),
+            l1_message_txs: HashMap::new(),
             latest_block_hash: Default::default(),
             latest_block_number: Default::default(),
+            messaging_gather_from_block: None,
+            messaging_send_from_block: None,
+            messaging_send_from_index: None,
+            dead_letters: Vec::new(),
+            gathered_messages: HashMap::new(),
         }
     }
 }
@@ -5,17 +5,19 @@ use std::ops::{Range, RangeInclusive};
 use std::sync::Arc;
 
 use katana_db::models::block::StoredBlockBodyIndices;
+use katana_db::models::messaging::{DeadLetter, GatheredMessage};
 use katana_primitives::block::{
     Block, BlockHash, BlockHashOrNumber, BlockNumber, BlockWithTxHashes, FinalityStatus, Header,
     SealedBlockWithStatus,
 };
 use katana_primitives::contract::{
     ClassHash, CompiledClassHash, CompiledContractClass, ContractAddress, FlattenedSierraClass,
+    LegacyClassAbi,
 };
 use katana_primitives::env::BlockEnv;
 use katana_primitives::receipt::Receipt;
 use katana_primitives::state::{StateUpdates, StateUpdatesWithDeclaredClasses};
-use katana_primitives::transaction::{Tx, TxHash, TxNumber, TxWithHash};
+use katana_primitives::transaction::{L1MessageHash, L1TxHash, Tx, TxHash, TxNumber, TxWithHash};
 use parking_lot::RwLock;
 
 use self::cache::CacheDb;
@@ -24,8 +26,11 @@ use crate::traits::block::{
     BlockHashProvider, BlockNumberProvider, BlockProvider, BlockStatusProvider, BlockWriter,
     HeaderProvider,
 };
-use crate::traits::contract::ContractClassWriter;
+use crate::traits::contract::{ContractClassWriter, LegacyClassAbiProvider};
 use crate::traits::env::BlockEnvProvider;
+use crate::traits::messaging::{
+    upsert_gathered_message, MessagingCheckpointProvider, MessagingCheckpointWriter,
+};
 use crate::traits::state::{StateFactoryProvider, StateProvider, StateRootProvider, StateWriter};
 use crate::traits::state_update::StateUpdateProvider;
 use crate::traits::transaction::{
@@ -72,6 +77,11 @@ impl BlockNumberProvider for InMemoryProvider {
     fn block_number_by_hash(&self, hash: BlockHash) -> ProviderResult<Option<BlockNumber>> {
         Ok(self.storage.read().block_numbers.get(&hash).cloned())
     }
+
+    fn block_number_by_timestamp(&self, timestamp: u64) -> ProviderResult<Option<BlockNumber>> {
+        let storage = self.storage.read();
+        Ok(storage.block_timestamps.range(..=timestamp).next_back().map(|(_, num)| *num))
+    }
 }
 
 impl HeaderProvider for InMemoryProvider {
@@ -402,6 +412,64 @@ impl StateRootProvider for InMemoryProvider {
     }
 }
 
+impl MessagingCheckpointProvider for InMemoryProvider {
+    fn gather_from_block(&self) -> ProviderResult<Option<u64>> {
+        Ok(self.storage.read().messaging_gather_from_block)
+    }
+
+    fn send_from_block(&self) -> ProviderResult<Option<BlockNumber>> {
+        Ok(self.storage.read().messaging_send_from_block)
+    }
+
+    fn send_from_index(&self) -> ProviderResult<Option<u64>> {
+        Ok(self.storage.read().messaging_send_from_index)
+    }
+
+    fn l1_handler_tx_by_message_hash(&self, hash: L1MessageHash) -> ProviderResult<Option<TxHash>> {
+        Ok(self.storage.read().l1_message_txs.get(&hash).copied())
+    }
+
+    fn dead_letters(&self) -> ProviderResult<Vec<DeadLetter>> {
+        Ok(self.storage.read().dead_letters.clone())
+    }
+
+    fn gathered_messages(
+        &self,
+        l1_tx_hash: L1TxHash,
+    ) -> ProviderResult<Option<Vec<GatheredMessage>>> {
+        Ok(self.storage.read().gathered_messages.get(&l1_tx_hash).cloned())
+    }
+}
+
+impl MessagingCheckpointWriter for InMemoryProvider {
+    fn set_gather_from_block(&self, block: u64) -> ProviderResult<()> {
+        self.storage.write().messaging_gather_from_block = Some(block);
+        Ok(())
+    }
+
+    fn set_send_from(&self, block: BlockNumber, index: u64) -> ProviderResult<()> {
+        let mut storage = self.storage.write();
+        storage.messaging_send_from_block = Some(block);
+        storage.messaging_send_from_index = Some(index);
+        Ok(())
+    }
+
+    fn insert_dead_letter(&self, letter: DeadLetter) -> ProviderResult<()> {
+        self.storage.write().dead_letters.push(letter);
+        Ok(())
+    }
+
+    fn insert_gathered_message(
+        &self,
+        l1_tx_hash: L1TxHash,
+        message: GatheredMessage,
+    ) -> ProviderResult<()> {
+        let mut storage = self.storage.write();
+        upsert_gathered_message(storage.gathered_messages.entry(l1_tx_hash).or_default(), message);
+        Ok(())
+    }
+}
+
 impl BlockWriter for InMemoryProvider {
     fn insert_block_with_states_and_receipts(
         &self,
@@ -422,6 +490,12 @@ impl BlockWriter for InMemoryProvider {
         let tx_offset = storage.transactions.len() as u64;
         let block_body_indices = StoredBlockBodyIndices { tx_offset, tx_count };
 
+        let l1_message_txs = txs.iter().filter_map(|tx| match &tx.transaction {
+            Tx::L1Handler(l1_tx) => Some((l1_tx.message_hash, tx.hash)),
+            _ => None,
+        });
+        storage.l1_message_txs.extend(l1_message_txs);
+
         let (txs_id, txs): (Vec<(TxNumber, TxHash)>, Vec<Tx>) = txs
             .into_iter()
             .enumerate()
@@ -434,6 +508,11 @@ impl BlockWriter for InMemoryProvider {
         storage.latest_block_hash = block_hash;
         storage.latest_block_number = block_number;
 
+        // the timestamp of a block can be before the one of its parent, so the blocks are indexed
+        // by the latest timestamp up to them, which never decreases
+        let last_timestamp = storage.block_timestamps.last_key_value().map(|(t, _)| *t);
+        let timestamp = last_timestamp.unwrap_or_default().max(block_header.timestamp);
+        storage.block_timestamps.insert(timestamp, block_number);
         storage.block_numbers.insert(block_hash, block_number);
         storage.block_hashes.insert(block_number, block_hash);
         storage.block_headers.insert(block_number, block_header);
@@ -457,6 +536,12 @@ impl BlockWriter for InMemoryProvider {
     }
 }
 
+impl LegacyClassAbiProvider for InMemoryProvider {
+    fn legacy_class_abi(&self, hash: ClassHash) -> ProviderResult<Option<LegacyClassAbi>> {
+        Ok(self.state.shared_contract_classes.legacy_class_abis.read().get(&hash).cloned())
+    }
+}
+
 impl ContractClassWriter for InMemoryProvider {
     fn set_class(&self, hash: ClassHash, class: CompiledContractClass) -> ProviderResult<()> {
         self.state.shared_contract_classes.compiled_classes.write().insert(hash, class);
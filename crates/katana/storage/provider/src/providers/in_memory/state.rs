@@ -102,6 +102,7 @@ impl Default for InMemoryStateDb {
             shared_contract_classes: Arc::new(SharedContractClasses {
                 sierra_classes: Default::default(),
                 compiled_classes: Default::default(),
+                legacy_class_abis: Default::default(),
             }),
             compiled_class_hashes: Default::default(),
         }
@@ -77,6 +77,13 @@ pub trait BlockNumberProvider: Send + Sync {
             BlockHashOrNumber::Hash(hash) => self.block_number_by_hash(hash),
         }
     }
+
+    /// Retrieves the number of the last block mined at or before `timestamp`, ie. the last block
+    /// such that neither it nor any of the blocks before it has a more recent timestamp. The
+    /// timestamps of the blocks can decrease, eg. when set with `dev_setNextBlockTimestamp`.
+    ///
+    /// Returns `None` if all the blocks are more recent than `timestamp`.
+    fn block_number_by_timestamp(&self, timestamp: u64) -> ProviderResult<Option<BlockNumber>>;
 }
 
 #[auto_impl::auto_impl(&, Box, Arc)]
@@ -1,6 +1,6 @@
 use katana_primitives::contract::{
     ClassHash, CompiledClassHash, CompiledContractClass, ContractAddress, FlattenedSierraClass,
-    GenericContractInfo,
+    GenericContractInfo, LegacyClassAbi,
 };
 
 use crate::ProviderResult;
@@ -27,6 +27,14 @@ pub trait ContractClassProvider: Send + Sync {
     fn sierra_class(&self, hash: ClassHash) -> ProviderResult<Option<FlattenedSierraClass>>;
 }
 
+/// A provider trait for retrieving the ABIs of the legacy classes, which aren't part of their
+/// compiled class definitions.
+#[auto_impl::auto_impl(&, Box, Arc)]
+pub trait LegacyClassAbiProvider: Send + Sync {
+    /// Returns the ABI of a legacy class given its class hash.
+    fn legacy_class_abi(&self, hash: ClassHash) -> ProviderResult<Option<LegacyClassAbi>>;
+}
+
 // TEMP: added mainly for compatibility reason. might be removed in the future.
 #[auto_impl::auto_impl(&, Box, Arc)]
 pub trait ContractClassWriter: Send + Sync {
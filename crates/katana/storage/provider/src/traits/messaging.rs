@@ -0,0 +1,70 @@
+use katana_db::models::messaging::{DeadLetter, GatheredMessage};
+use katana_primitives::block::BlockNumber;
+use katana_primitives::transaction::{L1MessageHash, L1TxHash, TxHash};
+
+use crate::ProviderResult;
+
+/// A provider for the progress of the messaging service, so that it can resume from where it
+/// stopped after a restart.
+#[auto_impl::auto_impl(&, Box, Arc)]
+pub trait MessagingCheckpointProvider: Send + Sync {
+    /// Returns the block of the settlement chain from which messages should be gathered.
+    fn gather_from_block(&self) -> ProviderResult<Option<u64>>;
+
+    /// Returns the local block from which messages should be sent to the settlement chain.
+    fn send_from_block(&self) -> ProviderResult<Option<BlockNumber>>;
+
+    /// Returns the index, among the messages of the block returned by
+    /// [send_from_block](Self::send_from_block), of the next message to send.
+    fn send_from_index(&self) -> ProviderResult<Option<u64>>;
+
+    /// Returns the hash of the L1 handler transaction that processed the given L1 message.
+    ///
+    /// The L1 messages are recorded when the block including their L1 handler transaction is
+    /// inserted.
+    fn l1_handler_tx_by_message_hash(&self, hash: L1MessageHash) -> ProviderResult<Option<TxHash>>;
+
+    /// Returns the messages that couldn't be sent to the settlement chain, in the order they were
+    /// dead-lettered.
+    fn dead_letters(&self) -> ProviderResult<Vec<DeadLetter>>;
+
+    /// Returns the messages sent by the given settlement chain transaction, in the order they were
+    /// gathered.
+    fn gathered_messages(
+        &self,
+        l1_tx_hash: L1TxHash,
+    ) -> ProviderResult<Option<Vec<GatheredMessage>>>;
+}
+
+#[auto_impl::auto_impl(&, Box, Arc)]
+pub trait MessagingCheckpointWriter: Send + Sync {
+    /// Sets the block of the settlement chain from which messages should be gathered.
+    fn set_gather_from_block(&self, block: u64) -> ProviderResult<()>;
+
+    /// Sets the local block from which messages should be sent to the settlement chain, along
+    /// with the index of the next message of that block to send.
+    fn set_send_from(&self, block: BlockNumber, index: u64) -> ProviderResult<()>;
+
+    /// Records a message that couldn't be sent to the settlement chain.
+    fn insert_dead_letter(&self, letter: DeadLetter) -> ProviderResult<()>;
+
+    /// Records a message gathered from the settlement chain, replacing the status of the message
+    /// if it was already gathered.
+    fn insert_gathered_message(
+        &self,
+        l1_tx_hash: L1TxHash,
+        message: GatheredMessage,
+    ) -> ProviderResult<()>;
+}
+
+/// Adds `message` to the messages of a settlement chain transaction, or replaces the existing
+/// message with the same hash.
+pub(crate) fn upsert_gathered_message(
+    messages: &mut Vec<GatheredMessage>,
+    message: GatheredMessage,
+) {
+    match messages.iter_mut().find(|m| m.message_hash == message.message_hash) {
+        Some(existing) => *existing = message,
+        None => messages.push(message),
+    }
+}
@@ -1,6 +1,7 @@
 pub mod block;
 pub mod contract;
 pub mod env;
+pub mod messaging;
 pub mod state;
 pub mod state_update;
 pub mod transaction;
@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use anyhow::Result;
 use katana_primitives::block::{
     Block, BlockHashOrNumber, BlockNumber, BlockWithTxHashes, FinalityStatus,
@@ -19,6 +21,9 @@ use katana_provider::traits::transaction::{
 };
 use katana_provider::BlockchainProvider;
 use rstest_reuse::{self, *};
+use starknet::providers::jsonrpc::HttpTransport;
+use starknet::providers::JsonRpcClient;
+use url::Url;
 
 mod fixtures;
 mod utils;
@@ -197,6 +202,104 @@ where
     Ok(())
 }
 
+#[rstest::rstest]
+fn block_number_by_timestamp_with_in_memory_provider(
+    #[from(in_memory_provider)] provider: BlockchainProvider<InMemoryProvider>,
+) -> Result<()> {
+    block_number_by_timestamp_test_impl(provider)
+}
+
+#[rstest::rstest]
+fn block_number_by_timestamp_with_db_provider(
+    #[from(db_provider)] provider: BlockchainProvider<DbProvider>,
+) -> Result<()> {
+    block_number_by_timestamp_test_impl(provider)
+}
+
+fn block_number_by_timestamp_test_impl<Db>(provider: BlockchainProvider<Db>) -> Result<()>
+where
+    Db: BlockNumberProvider + BlockWriter,
+{
+    // blocks 1 and 2 are mined at the same timestamp
+    let timestamps = [10, 20, 20, 30];
+    let blocks = generate_dummy_blocks_and_receipts(timestamps.len() as u64);
+
+    for ((mut block, receipts), timestamp) in blocks.into_iter().zip(timestamps) {
+        block.block.header.header.timestamp = timestamp;
+        provider.insert_block_with_states_and_receipts(block, Default::default(), receipts)?;
+    }
+
+    assert_eq!(provider.block_number_by_timestamp(5)?, None);
+    assert_eq!(provider.block_number_by_timestamp(10)?, Some(0));
+    assert_eq!(provider.block_number_by_timestamp(15)?, Some(0));
+    assert_eq!(provider.block_number_by_timestamp(20)?, Some(2));
+    assert_eq!(provider.block_number_by_timestamp(29)?, Some(2));
+    assert_eq!(provider.block_number_by_timestamp(30)?, Some(3));
+    assert_eq!(provider.block_number_by_timestamp(100)?, Some(3));
+
+    Ok(())
+}
+
+#[rstest::rstest]
+fn block_number_by_decreasing_timestamp_with_in_memory_provider(
+    #[from(in_memory_provider)] provider: BlockchainProvider<InMemoryProvider>,
+) -> Result<()> {
+    block_number_by_decreasing_timestamp_test_impl(provider)
+}
+
+#[rstest::rstest]
+fn block_number_by_decreasing_timestamp_with_db_provider(
+    #[from(db_provider)] provider: BlockchainProvider<DbProvider>,
+) -> Result<()> {
+    block_number_by_decreasing_timestamp_test_impl(provider)
+}
+
+fn block_number_by_decreasing_timestamp_test_impl<Db>(
+    provider: BlockchainProvider<Db>,
+) -> Result<()>
+where
+    Db: BlockNumberProvider + BlockWriter,
+{
+    // the timestamp of block 6 is set before the one of block 5
+    let timestamps = [10, 20, 30, 40, 50, 100, 50, 110];
+    let blocks = generate_dummy_blocks_and_receipts(timestamps.len() as u64);
+
+    for ((mut block, receipts), timestamp) in blocks.into_iter().zip(timestamps) {
+        block.block.header.header.timestamp = timestamp;
+        provider.insert_block_with_states_and_receipts(block, Default::default(), receipts)?;
+    }
+
+    assert_eq!(provider.block_number_by_timestamp(50)?, Some(4));
+    assert_eq!(provider.block_number_by_timestamp(99)?, Some(4));
+    assert_eq!(provider.block_number_by_timestamp(100)?, Some(6));
+    assert_eq!(provider.block_number_by_timestamp(109)?, Some(6));
+    assert_eq!(provider.block_number_by_timestamp(110)?, Some(7));
+
+    Ok(())
+}
+
+#[test]
+fn reset_fork_provider_drops_local_blocks() -> Result<()> {
+    let rpc = || {
+        let url = Url::parse("http://127.0.0.1:5050").unwrap();
+        Arc::new(JsonRpcClient::new(HttpTransport::new(url)))
+    };
+
+    let provider = ForkedProvider::new(rpc(), BlockHashOrNumber::Num(0))?;
+    for (block, receipts) in generate_dummy_blocks_and_receipts(3) {
+        provider.insert_block_with_states_and_receipts(block, Default::default(), receipts)?;
+    }
+    assert_eq!(provider.latest_number()?, 2);
+
+    provider.reset(rpc(), BlockHashOrNumber::Num(0))?;
+
+    assert_eq!(provider.latest_number()?, 0);
+    assert!(provider.block(BlockHashOrNumber::Num(1))?.is_none());
+    assert!(provider.transaction_in_range(0..10)?.is_empty());
+
+    Ok(())
+}
+
 #[template]
 #[rstest::rstest]
 #[case::insert_1_block(1)]
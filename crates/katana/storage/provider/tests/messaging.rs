@@ -0,0 +1,123 @@
+use anyhow::Result;
+use katana_db::models::messaging::{DeadLetter, GatheredMessage, GatheredMessageStatus};
+use katana_primitives::block::{Block, FinalityStatus, Header, SealedBlockWithStatus};
+use katana_primitives::receipt::{InvokeTxReceipt, MessageToL1, Receipt};
+use katana_primitives::transaction::{L1HandlerTx, L1MessageHash, L1TxHash, Tx, TxWithHash};
+use katana_primitives::FieldElement;
+use katana_provider::providers::db::DbProvider;
+use katana_provider::providers::in_memory::InMemoryProvider;
+use katana_provider::traits::block::BlockWriter;
+use katana_provider::traits::messaging::{MessagingCheckpointProvider, MessagingCheckpointWriter};
+use katana_provider::BlockchainProvider;
+use starknet::macros::felt;
+
+mod fixtures;
+
+use fixtures::{db_provider, in_memory_provider};
+
+#[rstest::rstest]
+fn messaging_checkpoints_with_in_memory_provider(
+    #[from(in_memory_provider)] provider: BlockchainProvider<InMemoryProvider>,
+) -> Result<()> {
+    messaging_checkpoints_test_impl(provider)
+}
+
+#[rstest::rstest]
+fn messaging_checkpoints_with_db_provider(
+    #[from(db_provider)] provider: BlockchainProvider<DbProvider>,
+) -> Result<()> {
+    messaging_checkpoints_test_impl(provider)
+}
+
+fn messaging_checkpoints_test_impl<Db>(provider: BlockchainProvider<Db>) -> Result<()>
+where
+    Db: BlockWriter + MessagingCheckpointProvider + MessagingCheckpointWriter,
+{
+    assert_eq!(provider.gather_from_block()?, None);
+    assert_eq!(provider.send_from_block()?, None);
+    assert_eq!(provider.send_from_index()?, None);
+
+    provider.set_gather_from_block(100)?;
+    provider.set_send_from(5, 2)?;
+    provider.set_gather_from_block(120)?;
+
+    assert_eq!(provider.gather_from_block()?, Some(120));
+    assert_eq!(provider.send_from_block()?, Some(5));
+    assert_eq!(provider.send_from_index()?, Some(2));
+
+    let message_hash = L1MessageHash::from_low_u64_be(1337);
+    let tx_hash = felt!("0x1");
+
+    let tx = TxWithHash {
+        hash: tx_hash,
+        transaction: Tx::L1Handler(L1HandlerTx {
+            message_hash,
+            nonce: FieldElement::ONE,
+            chain_id: Default::default(),
+            paid_fee_on_l1: 0,
+            version: FieldElement::ZERO,
+            calldata: vec![felt!("0x2")],
+            contract_address: felt!("0x3").into(),
+            entry_point_selector: felt!("0x4"),
+        }),
+    };
+
+    let header = Header { number: 0, ..Default::default() };
+    let block = Block { header, body: vec![tx] }.seal();
+    let block = SealedBlockWithStatus { block, status: FinalityStatus::AcceptedOnL2 };
+    let receipts = vec![Receipt::Invoke(InvokeTxReceipt::default())];
+
+    assert_eq!(provider.l1_handler_tx_by_message_hash(message_hash)?, None);
+    provider.insert_block_with_states_and_receipts(block, Default::default(), receipts)?;
+    assert_eq!(provider.l1_handler_tx_by_message_hash(message_hash)?, Some(tx_hash));
+
+    let letters = (0..3u64)
+        .map(|i| DeadLetter {
+            block_number: i,
+            message: MessageToL1 {
+                from_address: felt!("0x1").into(),
+                to_address: FieldElement::from(i),
+                payload: vec![felt!("0x2")],
+            },
+            attempts: 6,
+            error: format!("error {i}"),
+        })
+        .collect::<Vec<_>>();
+
+    assert!(provider.dead_letters()?.is_empty());
+    for letter in letters.clone() {
+        provider.insert_dead_letter(letter)?;
+    }
+    assert_eq!(provider.dead_letters()?, letters);
+
+    let l1_tx_hash = L1TxHash::from_low_u64_be(1);
+    let other_message_hash = L1MessageHash::from_low_u64_be(1338);
+
+    let rejected = GatheredMessageStatus::Rejected {
+        transaction_hash: tx_hash,
+        paid_fee_on_l1: 1,
+        estimated_fee: 2,
+    };
+    let accepted = GatheredMessageStatus::Accepted { transaction_hash: tx_hash };
+    let other = GatheredMessage {
+        message_hash: other_message_hash,
+        status: GatheredMessageStatus::Failed {
+            transaction_hash: felt!("0x2"),
+            reason: "x".into(),
+        },
+    };
+
+    assert_eq!(provider.gathered_messages(l1_tx_hash)?, None);
+    provider
+        .insert_gathered_message(l1_tx_hash, GatheredMessage { message_hash, status: rejected })?;
+    provider.insert_gathered_message(l1_tx_hash, other.clone())?;
+
+    // gathering a message again replaces its status, without changing the order of the messages
+    let regathered = GatheredMessage { message_hash, status: accepted };
+    provider.insert_gathered_message(l1_tx_hash, regathered.clone())?;
+
+    assert_eq!(provider.gathered_messages(l1_tx_hash)?, Some(vec![regathered, other]));
+    assert_eq!(provider.gathered_messages(L1TxHash::zero())?, None);
+
+    Ok(())
+}